@@ -0,0 +1,48 @@
+// turbo-cache-server is a self-hosted Remote Cache for turbo. It stores artifacts on
+// local disk and serves them over the same HTTP API that turbo's remote cache client uses.
+//
+//	TURBO_CACHE_SERVER_TOKENS=secret turbo-cache-server --addr :3000 --dir /var/cache/turbo
+//	turbo run build --api=http://localhost:3000 --token=secret --team=my-team
+package main
+
+import (
+	"fmt"
+	"net/http"
+	"os"
+	"strings"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/cacheserver"
+)
+
+func main() {
+	flags := pflag.NewFlagSet("turbo-cache-server", pflag.ExitOnError)
+	addr := flags.String("addr", ":3000", "The address to listen on")
+	dir := flags.String("dir", "turbo-cache", "The directory to store artifacts in")
+	tokens := flags.StringSlice("token", nil, "A bearer token that clients may authenticate with. Can be repeated, or set as a comma-separated list in TURBO_CACHE_SERVER_TOKENS. Without tokens, the cache is open to anyone who can reach it.")
+	verbose := flags.Bool("verbose", false, "Log every request")
+	_ = flags.Parse(os.Args[1:])
+
+	allowed := []string{}
+	for _, token := range append(*tokens, strings.Split(os.Getenv("TURBO_CACHE_SERVER_TOKENS"), ",")...) {
+		if token = strings.TrimSpace(token); token != "" {
+			allowed = append(allowed, token)
+		}
+	}
+	level := hclog.Info
+	if *verbose {
+		level = hclog.Debug
+	}
+	logger := hclog.New(&hclog.LoggerOptions{Name: "turbo-cache-server", Level: level})
+	if len(allowed) == 0 {
+		logger.Warn("no tokens are configured, so any client can read and write the cache")
+	}
+
+	server := cacheserver.New(cacheserver.Opts{Dir: *dir, Tokens: allowed, Logger: logger})
+	logger.Info("listening", "addr", *addr, "dir", *dir)
+	if err := http.ListenAndServe(*addr, server); err != nil {
+		fmt.Fprintln(os.Stderr, err)
+		os.Exit(1)
+	}
+}
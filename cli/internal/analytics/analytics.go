@@ -53,6 +53,7 @@ type worker struct {
 	doneSemaphore util.Semaphore
 	sessionID     uuid.UUID
 	sink          Sink
+	labels        map[string]string
 	wg            sync.WaitGroup
 	logger        hclog.Logger
 }
@@ -61,7 +62,7 @@ const bufferThreshold = 10
 const eventTimeout = 200 * time.Millisecond
 const noTimeout = 24 * time.Hour
 
-func newWorker(ctx context.Context, ch <-chan EventPayload, sink Sink, logger hclog.Logger) *worker {
+func newWorker(ctx context.Context, ch <-chan EventPayload, sink Sink, labels map[string]string, logger hclog.Logger) *worker {
 	buffer := []EventPayload{}
 	sessionID := uuid.New()
 	w := &worker{
@@ -71,6 +72,7 @@ func newWorker(ctx context.Context, ch <-chan EventPayload, sink Sink, logger hc
 		doneSemaphore: util.NewSemaphore(1),
 		sessionID:     sessionID,
 		sink:          sink,
+		labels:        labels,
 		logger:        logger,
 	}
 	w.doneSemaphore.Acquire()
@@ -79,10 +81,15 @@ func newWorker(ctx context.Context, ch <-chan EventPayload, sink Sink, logger hc
 }
 
 func NewClient(parent context.Context, sink Sink, logger hclog.Logger) Client {
+	return NewLabeledClient(parent, sink, logger, nil)
+}
+
+// NewLabeledClient creates a client that attaches the given labels to every event it sends
+func NewLabeledClient(parent context.Context, sink Sink, logger hclog.Logger, labels map[string]string) Client {
 	ch := make(chan EventPayload)
 	ctx, cancel := context.WithCancel(parent)
 	// creates and starts the worker
-	worker := newWorker(ctx, ch, sink, logger)
+	worker := newWorker(ctx, ch, sink, labels, logger)
 	s := &client{
 		ch:     ch,
 		cancel: cancel,
@@ -150,7 +157,7 @@ func (w *worker) flush() {
 func (w *worker) sendEvents(events []EventPayload) {
 	w.wg.Add(1)
 	go func() {
-		payload, err := addSessionID(w.sessionID.String(), events)
+		payload, err := addSessionID(w.sessionID.String(), w.labels, events)
 		if err != nil {
 			w.logger.Debug("failed to encode cache usage analytics", "error", err)
 		}
@@ -162,7 +169,7 @@ func (w *worker) sendEvents(events []EventPayload) {
 	}()
 }
 
-func addSessionID(sessionID string, events []EventPayload) (Events, error) {
+func addSessionID(sessionID string, labels map[string]string, events []EventPayload) (Events, error) {
 	eventMaps := []map[string]interface{}{}
 	err := mapstructure.Decode(events, &eventMaps)
 	if err != nil {
@@ -170,6 +177,9 @@ func addSessionID(sessionID string, events []EventPayload) (Events, error) {
 	}
 	for _, event := range eventMaps {
 		event["sessionId"] = sessionID
+		if len(labels) > 0 {
+			event["labels"] = labels
+		}
 	}
 	return eventMaps, nil
 }
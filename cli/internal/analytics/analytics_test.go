@@ -174,7 +174,7 @@ func Test_addSessionId(t *testing.T) {
 		arr[i] = event
 	}
 	sessionID := "my-uuid"
-	output, err := addSessionID(sessionID, arr)
+	output, err := addSessionID(sessionID, nil, arr)
 	if err != nil {
 		t.Errorf("failed to encode analytics events: %v", err)
 	}
@@ -188,5 +188,23 @@ func Test_addSessionId(t *testing.T) {
 		if event["sessionId"] != "my-uuid" {
 			t.Errorf("event %v sessionId got %v, want %v", i, event["sessionId"], sessionID)
 		}
+		if _, ok := event["labels"]; ok {
+			t.Errorf("event %v has labels, want none", i)
+		}
+	}
+}
+
+func Test_addSessionIdWithLabels(t *testing.T) {
+	arr := []interface{}{struct {
+		Foo string `mapstructure:"foo"`
+	}{Foo: "foo1"}}
+	labels := map[string]string{"team": "checkout"}
+	output, err := addSessionID("my-uuid", labels, arr)
+	if err != nil {
+		t.Errorf("failed to encode analytics events: %v", err)
+	}
+	eventLabels, ok := output[0]["labels"].(map[string]string)
+	if !ok || eventLabels["team"] != "checkout" {
+		t.Errorf("event labels got %v, want %v", output[0]["labels"], labels)
 	}
 }
@@ -0,0 +1,264 @@
+// Package boundaries finds imports that cross the boundaries between workspaces: imports
+// of workspaces that the importing workspace doesn't declare as dependencies, relative
+// imports that reach into another workspace's files, and imports that the tag rules in
+// turbo.json forbid.
+package boundaries
+
+import (
+	"errors"
+	"fmt"
+	"io/fs"
+	"io/ioutil"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/scm"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// The kinds of boundary violations
+const (
+	// KindUndeclared is an import of a workspace that isn't in the importing workspace's
+	// dependencies
+	KindUndeclared = "undeclared"
+	// KindRelative is a relative import of a file in another workspace
+	KindRelative = "relative"
+	// KindNotAllowed is an import of a workspace without any of the tags that one of the
+	// importing workspace's tags allows
+	KindNotAllowed = "not-allowed"
+	// KindDenied is an import of a workspace with a tag that one of the importing
+	// workspace's tags denies
+	KindDenied = "denied"
+)
+
+// Violation is an import that crosses a workspace boundary
+type Violation struct {
+	Kind string `json:"kind"`
+	// Package is the importing workspace
+	Package string `json:"package"`
+	// File is the repo-relative path of the importing file
+	File string `json:"file"`
+	Line int    `json:"line"`
+	// Import is the module specifier as written in the file
+	Import string `json:"import"`
+	// Imported is the workspace that is imported
+	Imported string `json:"imported"`
+	Message  string `json:"message"`
+}
+
+func (v Violation) String() string {
+	return fmt.Sprintf("%v:%v: %v", v.File, v.Line, v.Message)
+}
+
+// workspace is a workspace that imports are checked for or against
+type workspace struct {
+	name string
+	dir  string
+	pkg  *turbofs.PackageJSON
+	tags util.Set
+}
+
+// Check scans the source files of the given workspaces for imports that cross workspace
+// boundaries, and returns them sorted by file and line. The tag rules come from config,
+// which may be nil, and each workspace's tags from the "turbo" key of its package.json.
+func Check(repoRoot turbopath.AbsolutePath, packageInfos map[interface{}]*turbofs.PackageJSON, config *turbofs.BoundariesConfig, packages []string) ([]Violation, error) {
+	workspaces := make(map[string]*workspace, len(packageInfos))
+	// Longest directories first, so that a file belongs to the innermost workspace
+	// that contains it
+	byDir := []*workspace{}
+	for name, pkg := range packageInfos {
+		name := name.(string)
+		// The root workspace contains every other one, so it has no boundary to cross
+		if name == util.RootPkgName {
+			continue
+		}
+		w := &workspace{name: name, dir: pkg.Dir.ToString(), pkg: pkg, tags: make(util.Set)}
+		if pkg.LegacyTurboConfig != nil {
+			for _, tag := range pkg.LegacyTurboConfig.Tags {
+				w.tags.Add(tag)
+			}
+		}
+		workspaces[name] = w
+		byDir = append(byDir, w)
+	}
+	sort.Slice(byDir, func(i, j int) bool { return len(byDir[i].dir) > len(byDir[j].dir) })
+	owner := func(repoRelativePath string) *workspace {
+		for _, w := range byDir {
+			if repoRelativePath == w.dir || strings.HasPrefix(repoRelativePath, w.dir+string(filepath.Separator)) {
+				return w
+			}
+		}
+		return nil
+	}
+
+	// Outside of a git repository, repoSCM is nil or can't list files, and every file is
+	// scanned instead
+	repoSCM, _ := scm.FromInRepo(repoRoot.ToString())
+	violations := []Violation{}
+	for _, name := range packages {
+		importer, ok := workspaces[name]
+		if !ok {
+			continue
+		}
+		files, err := sourceFiles(repoRoot, repoSCM, importer.dir)
+		if err != nil {
+			return nil, fmt.Errorf("listing the files of %v: %w", name, err)
+		}
+		for _, file := range files {
+			// Files in a workspace nested in this one are checked as part of that one
+			if owner(file) != importer {
+				continue
+			}
+			contents, err := ioutil.ReadFile(repoRoot.Join(file).ToString())
+			if err != nil {
+				return nil, err
+			}
+			for _, ref := range findImports(contents) {
+				violations = append(violations, checkImport(importer, file, ref, workspaces, owner, config)...)
+			}
+		}
+	}
+	sort.SliceStable(violations, func(i, j int) bool {
+		if violations[i].File != violations[j].File {
+			return violations[i].File < violations[j].File
+		}
+		return violations[i].Line < violations[j].Line
+	})
+	return violations, nil
+}
+
+// checkImport returns the boundaries that an import in file crosses
+func checkImport(importer *workspace, file string, ref importRef, workspaces map[string]*workspace, owner func(string) *workspace, config *turbofs.BoundariesConfig) []Violation {
+	violation := Violation{
+		Package: importer.name,
+		File:    filepath.ToSlash(file),
+		Line:    ref.Line,
+		Import:  ref.Specifier,
+	}
+	var imported *workspace
+	violations := []Violation{}
+	if name, ok := packageName(ref.Specifier); ok {
+		imported, ok = workspaces[name]
+		if !ok || imported == importer {
+			return nil
+		}
+		if !declares(importer.pkg, name) {
+			v := violation
+			v.Kind = KindUndeclared
+			v.Imported = name
+			v.Message = fmt.Sprintf("imports %v, which isn't in the dependencies of %v", name, importer.name)
+			violations = append(violations, v)
+		}
+	} else if strings.HasPrefix(ref.Specifier, ".") {
+		target := filepath.Clean(filepath.Join(filepath.Dir(file), filepath.FromSlash(ref.Specifier)))
+		imported = owner(target)
+		if imported == nil || imported == importer {
+			return nil
+		}
+		v := violation
+		v.Kind = KindRelative
+		v.Imported = imported.name
+		v.Message = fmt.Sprintf("imports a file of %v by its path, import it by its package name instead", imported.name)
+		violations = append(violations, v)
+	} else {
+		return nil
+	}
+	if config == nil {
+		return violations
+	}
+
+	for _, tag := range importer.tags.UnsafeListOfStrings() {
+		rules, ok := config.Tags[tag]
+		if !ok {
+			continue
+		}
+		if len(rules.Allow) > 0 && !hasAny(imported.tags, rules.Allow) {
+			v := violation
+			v.Kind = KindNotAllowed
+			v.Imported = imported.name
+			v.Message = fmt.Sprintf("imports %v, but workspaces tagged %v may only import workspaces tagged %v", imported.name, tag, strings.Join(rules.Allow, ", "))
+			violations = append(violations, v)
+		}
+		for _, denied := range rules.Deny {
+			if imported.tags.Includes(denied) {
+				v := violation
+				v.Kind = KindDenied
+				v.Imported = imported.name
+				v.Message = fmt.Sprintf("imports %v, which is tagged %v, but workspaces tagged %v may not import workspaces tagged %v", imported.name, denied, tag, denied)
+				violations = append(violations, v)
+			}
+		}
+	}
+	return violations
+}
+
+// declares returns true if the workspace depends on the package in any of the ways
+// package.json allows
+func declares(pkg *turbofs.PackageJSON, name string) bool {
+	for _, deps := range []map[string]string{pkg.Dependencies, pkg.DevDependencies, pkg.OptionalDependencies, pkg.PeerDependencies} {
+		if _, ok := deps[name]; ok {
+			return true
+		}
+	}
+	return false
+}
+
+func hasAny(tags util.Set, wanted []string) bool {
+	for _, tag := range wanted {
+		if tags.Includes(tag) {
+			return true
+		}
+	}
+	return false
+}
+
+// sourceFiles returns the repo-relative paths of the JavaScript and TypeScript files in
+// the workspace at dir. Files that git ignores are left out, unless the repository
+// isn't a git repository, in which case everything outside of node_modules is scanned.
+func sourceFiles(repoRoot turbopath.AbsolutePath, repoSCM scm.SCM, dir string) ([]string, error) {
+	files := []string{}
+	add := func(relativePath string) {
+		if _sourceExtensions[filepath.Ext(relativePath)] && !strings.HasSuffix(relativePath, ".d.ts") {
+			files = append(files, filepath.Join(dir, relativePath))
+		}
+	}
+	root := repoRoot.Join(dir).ToString()
+	if repoSCM != nil {
+		listed, err := scm.ListFiles(repoSCM, root)
+		if err == nil {
+			for _, file := range listed {
+				add(file)
+			}
+			return files, nil
+		}
+		if !errors.Is(err, scm.ErrCannotListFiles) {
+			return nil, err
+		}
+	}
+
+	err := filepath.WalkDir(root, func(path string, entry fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if entry.IsDir() {
+			if path != root && (entry.Name() == "node_modules" || strings.HasPrefix(entry.Name(), ".")) {
+				return filepath.SkipDir
+			}
+			return nil
+		}
+		relativePath, err := filepath.Rel(root, path)
+		if err != nil {
+			return err
+		}
+		add(relativePath)
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(files)
+	return files, nil
+}
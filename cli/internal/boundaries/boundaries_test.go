@@ -0,0 +1,125 @@
+package boundaries
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+func TestFindImports(t *testing.T) {
+	source := `import React from "react";
+import { Button,
+  Card } from '@acme/ui/button';
+import type { Theme } from "@acme/theme";
+import "./styles.css";
+export * from "../utils";
+// import { old } from "@acme/legacy";
+/* const legacy = require("@acme/legacy"); */
+const url = "https://example.com/a";
+const config = require("@acme/config");
+const page = await import("./page");
+foo.import("not-an-import");
+`
+	imports := findImports([]byte(source))
+	assert.DeepEqual(t, imports, []importRef{
+		{Specifier: "react", Line: 1},
+		{Specifier: "@acme/ui/button", Line: 3},
+		{Specifier: "@acme/theme", Line: 4},
+		{Specifier: "./styles.css", Line: 5},
+		{Specifier: "../utils", Line: 6},
+		{Specifier: "@acme/config", Line: 10},
+		{Specifier: "./page", Line: 11},
+	})
+}
+
+func TestPackageName(t *testing.T) {
+	cases := map[string]string{
+		"react":           "react",
+		"lodash/get":      "lodash",
+		"@acme/ui":        "@acme/ui",
+		"@acme/ui/button": "@acme/ui",
+		"./button":        "",
+		"/abs/path":       "",
+		"node:fs":         "",
+		"@acme":           "",
+	}
+	for specifier, expected := range cases {
+		name, ok := packageName(specifier)
+		assert.Equal(t, name, expected, specifier)
+		assert.Equal(t, ok, expected != "", specifier)
+	}
+}
+
+func TestCheck(t *testing.T) {
+	repoRoot := turbopath.AbsolutePath(t.TempDir())
+	writeFile := func(path string, contents string) {
+		t.Helper()
+		path = filepath.Join(repoRoot.ToString(), filepath.FromSlash(path))
+		assert.NilError(t, os.MkdirAll(filepath.Dir(path), 0755))
+		assert.NilError(t, os.WriteFile(path, []byte(contents), 0644))
+	}
+	writeFile("apps/web/src/index.ts", `import { Button } from "@acme/ui";
+import { track } from "@acme/analytics";
+import { secret } from "../../admin/src/secret";
+`)
+	writeFile("apps/web/node_modules/@acme/ui/index.js", `import "@acme/admin";`)
+	writeFile("apps/admin/src/secret.ts", `export const secret = 1;`)
+	writeFile("packages/ui/src/index.tsx", `import { App } from "web";`)
+	writeFile("packages/analytics/index.js", `module.exports = {};`)
+
+	packageInfos := map[interface{}]*turbofs.PackageJSON{
+		"//": {Name: "root"},
+		"web": {
+			Name:              "web",
+			Dir:               turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web")),
+			Dependencies:      map[string]string{"@acme/ui": "*"},
+			LegacyTurboConfig: &turbofs.TurboJSON{Tags: []string{"app"}},
+		},
+		"@acme/admin": {
+			Name:              "@acme/admin",
+			Dir:               turbopath.AnchoredSystemPath(filepath.FromSlash("apps/admin")),
+			LegacyTurboConfig: &turbofs.TurboJSON{Tags: []string{"app"}},
+		},
+		"@acme/ui": {
+			Name:              "@acme/ui",
+			Dir:               turbopath.AnchoredSystemPath(filepath.FromSlash("packages/ui")),
+			DevDependencies:   map[string]string{"web": "*"},
+			LegacyTurboConfig: &turbofs.TurboJSON{Tags: []string{"shared"}},
+		},
+		"@acme/analytics": {
+			Name: "@acme/analytics",
+			Dir:  turbopath.AnchoredSystemPath(filepath.FromSlash("packages/analytics")),
+		},
+	}
+	config := &turbofs.BoundariesConfig{
+		Tags: map[string]turbofs.TagRules{
+			"app":    {Allow: []string{"shared"}},
+			"shared": {Deny: []string{"app"}},
+		},
+	}
+
+	violations, err := Check(repoRoot, packageInfos, config, []string{"web", "@acme/ui"})
+	assert.NilError(t, err)
+	kinds := []string{}
+	for _, v := range violations {
+		kinds = append(kinds, v.File+":"+v.Imported+":"+v.Kind)
+	}
+	assert.DeepEqual(t, kinds, []string{
+		"apps/web/src/index.ts:@acme/analytics:undeclared",
+		"apps/web/src/index.ts:@acme/analytics:not-allowed",
+		"apps/web/src/index.ts:@acme/admin:relative",
+		"apps/web/src/index.ts:@acme/admin:not-allowed",
+		"packages/ui/src/index.tsx:web:denied",
+	})
+	assert.Equal(t, violations[0].Line, 2)
+	assert.Equal(t, violations[0].String(), "apps/web/src/index.ts:2: imports @acme/analytics, which isn't in the dependencies of web")
+
+	// Without tag rules, only the imports that skip package.json are reported
+	violations, err = Check(repoRoot, packageInfos, nil, []string{"web", "@acme/ui"})
+	assert.NilError(t, err)
+	assert.Equal(t, len(violations), 2)
+}
@@ -0,0 +1,119 @@
+package boundaries
+
+import (
+	"regexp"
+	"sort"
+	"strings"
+)
+
+// _sourceExtensions are the files that are scanned for imports
+var _sourceExtensions = map[string]bool{
+	".js":  true,
+	".jsx": true,
+	".mjs": true,
+	".cjs": true,
+	".ts":  true,
+	".tsx": true,
+	".mts": true,
+	".cts": true,
+}
+
+var (
+	// _staticImport matches import and export declarations that name a module:
+	// import x from "a", import { x } from "a", import "a", export * from "a"
+	_staticImport = regexp.MustCompile(`(?:^|[^.\w$])(?:import|export)\s+(?:type\s+)?(?:[\w$*{}\s,]+?\s+from\s*)?["']([^"'\n]+)["']`)
+	// _callImport matches require("a") and dynamic import("a")
+	_callImport = regexp.MustCompile(`(?:^|[^.\w$])(?:require|import)\s*\(\s*["']([^"'\n]+)["']\s*\)`)
+)
+
+// importRef is a module that a source file imports
+type importRef struct {
+	Specifier string
+	// Line is where the import is in the file, starting at 1
+	Line int
+}
+
+// findImports returns the modules that a JavaScript or TypeScript source file imports,
+// in the order they appear. Imports in comments are ignored.
+func findImports(contents []byte) []importRef {
+	code := stripComments(contents)
+	type match struct {
+		offset    int
+		specifier string
+	}
+	matches := []match{}
+	for _, re := range []*regexp.Regexp{_staticImport, _callImport} {
+		for _, m := range re.FindAllSubmatchIndex(code, -1) {
+			matches = append(matches, match{offset: m[2], specifier: string(code[m[2]:m[3]])})
+		}
+	}
+	sort.Slice(matches, func(i, j int) bool { return matches[i].offset < matches[j].offset })
+
+	imports := make([]importRef, len(matches))
+	line, counted := 1, 0
+	for i, m := range matches {
+		line += strings.Count(string(code[counted:m.offset]), "\n")
+		counted = m.offset
+		imports[i] = importRef{Specifier: m.specifier, Line: line}
+	}
+	return imports
+}
+
+// stripComments replaces the comments in JavaScript source with spaces, keeping line
+// breaks so that positions in the result are on the same lines as in the source.
+// Comment markers inside string and template literals are left alone.
+func stripComments(contents []byte) []byte {
+	code := make([]byte, len(contents))
+	copy(code, contents)
+	var quote byte
+	for i := 0; i < len(code); i++ {
+		c := code[i]
+		switch {
+		case quote != 0:
+			if c == '\\' {
+				i++
+			} else if c == quote || (c == '\n' && quote != '`') {
+				quote = 0
+			}
+		case c == '"' || c == '\'' || c == '`':
+			quote = c
+		case c == '/' && i+1 < len(code) && code[i+1] == '/':
+			for ; i < len(code) && code[i] != '\n'; i++ {
+				code[i] = ' '
+			}
+		case c == '/' && i+1 < len(code) && code[i+1] == '*':
+			end := i + 2
+			for end < len(code) && !(code[end] == '*' && end+1 < len(code) && code[end+1] == '/') {
+				end++
+			}
+			end += 2
+			if end > len(code) {
+				end = len(code)
+			}
+			for ; i < end; i++ {
+				if code[i] != '\n' {
+					code[i] = ' '
+				}
+			}
+			i--
+		}
+	}
+	return code
+}
+
+// packageName returns the package that a bare module specifier refers to, such as
+// "@acme/ui" for "@acme/ui/button", or false for relative imports, absolute paths and
+// URLs like "node:fs"
+func packageName(specifier string) (string, bool) {
+	if specifier == "" || strings.HasPrefix(specifier, ".") || strings.HasPrefix(specifier, "/") || strings.Contains(specifier, ":") {
+		return "", false
+	}
+	parts := strings.SplitN(specifier, "/", 3)
+	if strings.HasPrefix(specifier, "@") {
+		if len(parts) < 2 || parts[1] == "" {
+			return "", false
+		}
+		return parts[0] + "/" + parts[1], true
+	}
+	return parts[0], true
+}
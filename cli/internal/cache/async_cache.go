@@ -48,8 +48,16 @@ func (c *asyncCache) Put(target string, key string, duration int, files []string
 	return nil
 }
 
-func (c *asyncCache) Fetch(target string, key string, files []string) (bool, []string, int, error) {
-	return c.realCache.Fetch(target, key, files)
+func (c *asyncCache) Fetch(target string, key string, restoreGlobs []string) (bool, []string, int, error) {
+	return c.realCache.Fetch(target, key, restoreGlobs)
+}
+
+func (c *asyncCache) Prefetch(hashes []string) {
+	Prefetch(c.realCache, hashes)
+}
+
+func (c *asyncCache) Duration(hash string) (int, bool) {
+	return Duration(c.realCache, hash)
 }
 
 func (c *asyncCache) Clean(target string) {
@@ -64,6 +72,7 @@ func (c *asyncCache) Shutdown() {
 	// fmt.Println("Shutting down cache workers...")
 	close(c.requests)
 	c.wg.Wait()
+	c.realCache.Shutdown()
 	// fmt.Println("Shut down all cache workers")
 }
 
@@ -8,10 +8,14 @@ package cache
 import (
 	"errors"
 	"fmt"
+	"path/filepath"
+	"strings"
 	"sync"
 
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/analytics"
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/ui"
@@ -22,9 +26,12 @@ import (
 // Cache is abstracted way to cache/fetch previously run tasks
 type Cache interface {
 	// Fetch returns true if there is a cache it. It is expected to move files
-	// into their correct position as a side effect
-	Fetch(target string, hash string, files []string) (bool, []string, int, error)
-	// Put caches files for a given hash
+	// into their correct position as a side effect, relative to the absolute directory
+	// target. If restoreGlobs is non-empty, only the files in the artifact matching one
+	// of those globs, relative to the same directory, are restored.
+	Fetch(target string, hash string, restoreGlobs []string) (bool, []string, int, error)
+	// Put caches files for a given hash. The files are relative to the absolute directory
+	// target, and must be inside of it.
 	Put(target string, hash string, duration int, files []string) error
 	Clean(target string)
 	CleanAll()
@@ -41,6 +48,19 @@ type CacheEvent struct {
 	Duration int    `mapstructure:"duration"`
 }
 
+// checkArtifactPaths returns an error if any of files, which are relative to the directory
+// an artifact is restored into, is outside of that directory. Caching such a file would
+// write it outside of the artifact.
+func checkArtifactPaths(files []string) error {
+	for _, file := range files {
+		clean := filepath.Clean(file)
+		if filepath.IsAbs(clean) || clean == ".." || strings.HasPrefix(clean, ".."+string(filepath.Separator)) {
+			return fmt.Errorf("cannot cache %v, it is outside of the directory the artifact is relative to", file)
+		}
+	}
+	return nil
+}
+
 // DefaultLocation returns the default filesystem cache location, given a repo root
 func DefaultLocation(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
 	return repoRoot.Join("node_modules", ".cache", "turbo")
@@ -52,7 +72,7 @@ func DefaultLocation(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
 type OnCacheRemoved = func(cache Cache, err error)
 
 // ErrNoCachesEnabled is returned when both the filesystem and http cache are unavailable
-var ErrNoCachesEnabled = errors.New("no caches are enabled")
+var ErrNoCachesEnabled = errcode.New(errcode.CacheNoCachesEnabled, "no caches are enabled")
 
 // Opts holds configuration options for the cache
 // TODO(gsoltis): further refactor this into fs cache opts and http cache opts
@@ -62,6 +82,12 @@ type Opts struct {
 	SkipFilesystem  bool
 	Workers         int
 	RemoteCacheOpts fs.RemoteCacheOptions
+	// SkipRemoteWrites keeps the remote cache from uploading artifacts, while still
+	// allowing them to be downloaded
+	SkipRemoteWrites bool
+	// LocalStore is how the filesystem cache stores new artifacts, StoreDirectory or
+	// StoreContent. Defaults to StoreDirectory.
+	LocalStore string
 }
 
 // ResolveCacheDir calculates the location turbo should use to cache artifacts,
@@ -76,12 +102,18 @@ func (o *Opts) ResolveCacheDir(repoRoot turbopath.AbsolutePath) turbopath.Absolu
 var _remoteOnlyHelp = `Ignore the local filesystem cache for all tasks. Only
 allow reading and caching artifacts using the remote cache.`
 
+var _cacheStoreHelp = `How the filesystem cache stores new artifacts: "directory"
+keeps a copy of each artifact's files, "content" stores the
+contents of each file once, shared between artifacts.
+(default "directory")`
+
 // AddFlags adds cache-related flags to the given FlagSet
 func AddFlags(opts *Opts, flags *pflag.FlagSet) {
 	// skipping remote caching not currently a flag
 	flags.BoolVar(&opts.SkipFilesystem, "remote-only", false, _remoteOnlyHelp)
 	flags.StringVar(&opts.OverrideDir, "cache-dir", "", "Override the filesystem cache directory.")
 	flags.IntVar(&opts.Workers, "cache-workers", 10, "Set the number of concurrent cache operations")
+	flags.StringVar(&opts.LocalStore, "cache-store", "", _cacheStoreHelp)
 }
 
 // New creates a new cache
@@ -125,7 +157,7 @@ func newSyncCache(opts Opts, repoRoot turbopath.AbsolutePath, client client, rec
 
 	if useHTTPCache {
 		fmt.Println(ui.Dim("• Remote computation caching enabled"))
-		implementation := newHTTPCache(opts, client, recorder, repoRoot)
+		implementation := newHTTPCache(opts, client, recorder)
 		cacheImplementations = append(cacheImplementations, implementation)
 	}
 
@@ -236,7 +268,7 @@ func (mplex *cacheMultiplexer) removeCache(removal *cacheRemoval) {
 	}
 }
 
-func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string) (bool, []string, int, error) {
+func (mplex *cacheMultiplexer) Fetch(target string, key string, restoreGlobs []string) (bool, []string, int, error) {
 	// Make a shallow copy of the caches, since storeUntil can call removeCache
 	mplex.mu.RLock()
 	caches := make([]Cache, len(mplex.caches))
@@ -246,7 +278,7 @@ func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string)
 	// Retrieve from caches sequentially; if we did them simultaneously we could
 	// easily write the same file from two goroutines at once.
 	for i, cache := range caches {
-		ok, actualFiles, duration, err := cache.Fetch(target, key, files)
+		ok, actualFiles, duration, err := cache.Fetch(target, key, restoreGlobs)
 		if err != nil {
 			cd := &util.CacheDisabledError{}
 			if errors.As(err, &cd) {
@@ -264,11 +296,106 @@ func (mplex *cacheMultiplexer) Fetch(target string, key string, files []string)
 			// Store this into other caches. We can ignore errors here because we know
 			// we have previously successfully stored in a higher-priority cache, and so the overall
 			// result is a success at fetching. Storing in lower-priority caches is an optimization.
-			_ = mplex.storeUntil(target, key, duration, actualFiles, i)
+			// A partial restore doesn't have the whole artifact, so it can't be stored.
+			if len(restoreGlobs) == 0 {
+				_ = mplex.storeUntil(target, key, duration, actualFiles, i)
+			}
 			return ok, actualFiles, duration, err
 		}
 	}
-	return false, files, 0, nil
+	return false, nil, 0, nil
+}
+
+// prefetcher is implemented by caches that can start retrieving artifacts before
+// they are fetched.
+type prefetcher interface {
+	Prefetch(hashes []string)
+}
+
+// Prefetch asks the given cache to start retrieving the artifacts for the given hashes
+// in the background, so that later Fetches for them complete sooner. Caches that don't
+// support prefetching ignore the request.
+func Prefetch(cache Cache, hashes []string) {
+	if p, ok := cache.(prefetcher); ok {
+		p.Prefetch(hashes)
+	}
+}
+
+// existenceChecker is implemented by caches that can tell whether they have an artifact
+// without restoring it, which is cheap for local caches.
+type existenceChecker interface {
+	Exists(hash string) bool
+}
+
+// Prefetch only asks each cache for the hashes that none of the caches before it already
+// have, since those are restored from the earlier caches.
+func (mplex *cacheMultiplexer) Prefetch(hashes []string) {
+	mplex.mu.RLock()
+	caches := make([]Cache, len(mplex.caches))
+	copy(caches, mplex.caches)
+	mplex.mu.RUnlock()
+	for _, cache := range caches {
+		if len(hashes) == 0 {
+			return
+		}
+		Prefetch(cache, hashes)
+		if checker, ok := cache.(existenceChecker); ok {
+			missing := []string{}
+			for _, hash := range hashes {
+				if !checker.Exists(hash) {
+					missing = append(missing, hash)
+				}
+			}
+			hashes = missing
+		}
+	}
+}
+
+// durationRecorder is implemented by caches that record how long the task that produced
+// an artifact took, and can report it without fetching the artifact.
+type durationRecorder interface {
+	Duration(hash string) (int, bool)
+}
+
+// Duration returns how long, in milliseconds, the task that produced the artifact for the
+// given hash took to execute, if the cache recorded it. That is how much time restoring the
+// artifact saves.
+func Duration(cache Cache, hash string) (int, bool) {
+	if r, ok := cache.(durationRecorder); ok {
+		return r.Duration(hash)
+	}
+	return 0, false
+}
+
+func (mplex *cacheMultiplexer) Duration(hash string) (int, bool) {
+	mplex.mu.RLock()
+	caches := make([]Cache, len(mplex.caches))
+	copy(caches, mplex.caches)
+	mplex.mu.RUnlock()
+	for _, cache := range caches {
+		if duration, ok := Duration(cache, hash); ok {
+			return duration, true
+		}
+	}
+	return 0, false
+}
+
+// shouldRestore returns true if the artifact file at repoRelativePath matches one of
+// restoreGlobs, or if restoreGlobs is empty and the whole artifact is being restored.
+func shouldRestore(restoreGlobs []string, repoRelativePath string) (bool, error) {
+	if len(restoreGlobs) == 0 {
+		return true, nil
+	}
+	name := filepath.ToSlash(repoRelativePath)
+	for _, glob := range restoreGlobs {
+		matches, err := doublestar.Match(filepath.ToSlash(glob), name)
+		if err != nil {
+			return false, fmt.Errorf("invalid restore glob %v: %w", glob, err)
+		} else if matches {
+			return true, nil
+		}
+	}
+	return false, nil
 }
 
 func (mplex *cacheMultiplexer) Clean(target string) {
@@ -5,11 +5,14 @@ package cache
 
 import (
 	"encoding/json"
+	"errors"
 	"fmt"
 	"io/ioutil"
+	"os"
 	"path/filepath"
 	"runtime"
 
+	"github.com/nightlyone/lockfile"
 	"github.com/vercel/turborepo/cli/internal/analytics"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
@@ -20,11 +23,19 @@ import (
 type fsCache struct {
 	cacheDirectory string
 	recorder       analytics.Recorder
-	repoRoot       turbopath.AbsolutePath
+	// store is how new artifacts are stored. Artifacts are read whichever way they were
+	// stored.
+	store string
 }
 
 // newFsCache creates a new filesystem cache
 func newFsCache(opts Opts, recorder analytics.Recorder, repoRoot turbopath.AbsolutePath) (*fsCache, error) {
+	store := opts.LocalStore
+	if store == "" {
+		store = StoreDirectory
+	} else if store != StoreDirectory && store != StoreContent {
+		return nil, fmt.Errorf("unknown cache store %q, expected %v or %v", store, StoreDirectory, StoreContent)
+	}
 	cacheDir := opts.ResolveCacheDir(repoRoot)
 	if err := cacheDir.MkdirAll(); err != nil {
 		return nil, err
@@ -32,22 +43,37 @@ func newFsCache(opts Opts, recorder analytics.Recorder, repoRoot turbopath.Absol
 	return &fsCache{
 		cacheDirectory: cacheDir.ToStringDuringMigration(),
 		recorder:       recorder,
-		repoRoot:       repoRoot,
+		store:          store,
 	}, nil
 }
 
+// ExistsLocally returns true if the filesystem cache configured by opts has an
+// artifact for the given hash. It doesn't restore anything.
+func ExistsLocally(opts Opts, repoRoot turbopath.AbsolutePath, hash string) bool {
+	return artifactExists(opts.ResolveCacheDir(repoRoot).ToString(), hash)
+}
+
 // Fetch returns true if items are cached. It moves them into position as a side effect.
-func (f *fsCache) Fetch(target, hash string, _unusedOutputGlobs []string) (bool, []string, int, error) {
+func (f *fsCache) Fetch(target, hash string, restoreGlobs []string) (bool, []string, int, error) {
 	cachedFolder := filepath.Join(f.cacheDirectory, hash)
 
-	// If it's not in the cache bail now
-	if !fs.PathExists(cachedFolder) {
-		f.logFetch(false, hash, 0)
-		return false, nil, 0, nil
+	var err error
+	if fs.PathExists(cachedFolder) {
+		// Copy it into position
+		if len(restoreGlobs) == 0 {
+			err = fs.RecursiveCopy(cachedFolder, target)
+		} else {
+			err = copyMatchingFiles(cachedFolder, target, restoreGlobs)
+		}
+	} else {
+		var hit bool
+		hit, err = f.fetchContent(target, hash, restoreGlobs)
+		if err == nil && !hit {
+			// If it's not in the cache bail now
+			f.logFetch(false, hash, 0)
+			return false, nil, 0, nil
+		}
 	}
-
-	// Otherwise, copy it into position
-	err := fs.RecursiveCopy(cachedFolder, target)
 	if err != nil {
 		// TODO: what event to log here?
 		return false, nil, 0, fmt.Errorf("error moving artifact from cache into %v: %w", target, err)
@@ -61,6 +87,40 @@ func (f *fsCache) Fetch(target, hash string, _unusedOutputGlobs []string) (bool,
 	return true, nil, meta.Duration, nil
 }
 
+// Exists returns true if the cache has an artifact for hash
+func (f *fsCache) Exists(hash string) bool {
+	return artifactExists(f.cacheDirectory, hash)
+}
+
+// Duration returns the duration recorded in the metadata of the artifact for hash
+func (f *fsCache) Duration(hash string) (int, bool) {
+	meta, err := ReadCacheMetaFile(filepath.Join(f.cacheDirectory, hash+"-meta.json"))
+	if err != nil {
+		return 0, false
+	}
+	return meta.Duration, true
+}
+
+// copyMatchingFiles copies the files in cachedFolder that match one of restoreGlobs into target
+func copyMatchingFiles(cachedFolder string, target string, restoreGlobs []string) error {
+	return fs.WalkMode(cachedFolder, func(name string, isDir bool, fileType os.FileMode) error {
+		if isDir {
+			return nil
+		}
+		repoRelativePath, err := filepath.Rel(cachedFolder, name)
+		if err != nil {
+			return err
+		}
+		if restore, err := shouldRestore(restoreGlobs, repoRelativePath); err != nil {
+			return err
+		} else if !restore {
+			return nil
+		}
+		// name is absolute, (originates from godirwalk)
+		return fs.CopyFile(&fs.LstatCachedFile{Path: fs.UnsafeToAbsolutePath(name)}, filepath.Join(target, repoRelativePath))
+	})
+}
+
 func (f *fsCache) logFetch(hit bool, hash string, duration int) {
 	var event string
 	if hit {
@@ -77,7 +137,35 @@ func (f *fsCache) logFetch(hit bool, hash string, duration int) {
 	f.recorder.LogEvent(payload)
 }
 
+// Put copies the files into the cache. The artifact is assembled in a temporary directory
+// and moved into place once it is complete, so that other turbo processes reading from the
+// same cache never see a partial artifact.
 func (f *fsCache) Put(target, hash string, duration int, files []string) error {
+	if err := checkArtifactPaths(files); err != nil {
+		return err
+	}
+	lock, err := lockArtifact(f.cacheDirectory, hash)
+	if errors.Is(err, lockfile.ErrBusy) {
+		// Another turbo process is writing the same artifact, which has the same contents
+		return nil
+	} else if err != nil {
+		return fmt.Errorf("error locking artifact %v: %w", hash, err)
+	}
+	defer func() { _ = lock.Unlock() }()
+	if f.store == StoreContent {
+		return f.putContent(target, hash, duration, files)
+	}
+
+	partialDir, err := ioutil.TempDir(f.cacheDirectory, hash+"-partial-")
+	if err != nil {
+		return fmt.Errorf("error creating artifact directory: %w", err)
+	}
+	defer func() { _ = os.RemoveAll(partialDir) }()
+	if err := os.Chmod(partialDir, 0755); err != nil {
+		return err
+	}
+
+	root := fs.AbsolutePathFromUpstream(target)
 	g := new(errgroup.Group)
 
 	numDigesters := runtime.NumCPU()
@@ -86,17 +174,17 @@ func (f *fsCache) Put(target, hash string, duration int, files []string) error {
 	for i := 0; i < numDigesters; i++ {
 		g.Go(func() error {
 			for file := range fileQueue {
-				statedFile := fs.LstatCachedFile{Path: f.repoRoot.Join(file)}
+				statedFile := fs.LstatCachedFile{Path: root.Join(file)}
 				fromType, err := statedFile.GetType()
 				if err != nil {
 					return fmt.Errorf("error stat'ing cache source %v: %v", file, err)
 				}
 				if !fromType.IsDir() {
-					if err := fs.EnsureDir(filepath.Join(f.cacheDirectory, hash, file)); err != nil {
+					if err := fs.EnsureDir(filepath.Join(partialDir, file)); err != nil {
 						return fmt.Errorf("error ensuring directory file from cache: %w", err)
 					}
 
-					if err := fs.CopyFile(&statedFile, filepath.Join(f.cacheDirectory, hash, file)); err != nil {
+					if err := fs.CopyFile(&statedFile, filepath.Join(partialDir, file)); err != nil {
 						return fmt.Errorf("error copying file from cache: %w", err)
 					}
 				}
@@ -114,12 +202,46 @@ func (f *fsCache) Put(target, hash string, duration int, files []string) error {
 		return err
 	}
 
-	WriteCacheMetaFile(filepath.Join(f.cacheDirectory, hash+"-meta.json"), &CacheMetadata{
+	// The metadata goes first, since Fetch expects it for any artifact it finds
+	if err := WriteCacheMetaFile(filepath.Join(f.cacheDirectory, hash+"-meta.json"), &CacheMetadata{
 		Duration: duration,
 		Hash:     hash,
-	})
+	}); err != nil {
+		return fmt.Errorf("error writing cache metadata: %w", err)
+	}
+	return replaceDir(partialDir, filepath.Join(f.cacheDirectory, hash))
+}
 
-	return nil
+// lockArtifact takes the lock for writing the artifact for hash. It fails with
+// lockfile.ErrBusy if another live process holds the lock.
+func lockArtifact(cacheDirectory string, hash string) (lockfile.Lockfile, error) {
+	// lockfile only accepts absolute paths
+	lockPath, err := filepath.Abs(filepath.Join(cacheDirectory, hash+".lock"))
+	if err != nil {
+		return "", err
+	}
+	lock, err := lockfile.New(lockPath)
+	if err != nil {
+		return "", err
+	}
+	if err := lock.TryLock(); err != nil {
+		return "", err
+	}
+	return lock, nil
+}
+
+// replaceDir moves src to dst, replacing anything already at dst. A reader of dst sees
+// either the old or the new contents, or briefly nothing, but never a mix of the two.
+func replaceDir(src string, dst string) error {
+	if !fs.PathExists(dst) {
+		return os.Rename(src, dst)
+	}
+	staleDir := dst + "-stale-" + filepath.Base(src)
+	if err := os.Rename(dst, staleDir); err != nil {
+		return err
+	}
+	defer func() { _ = os.RemoveAll(staleDir) }()
+	return os.Rename(src, dst)
 }
 
 func (f *fsCache) Clean(target string) {
@@ -139,15 +261,36 @@ type CacheMetadata struct {
 	Duration int    `json:"duration"`
 }
 
-// WriteCacheMetaFile writes cache metadata file at a path
+// WriteCacheMetaFile writes cache metadata file at a path. The file is replaced in one step,
+// so a concurrent reader never sees it partially written.
 func WriteCacheMetaFile(path string, config *CacheMetadata) error {
 	jsonBytes, marshalErr := json.Marshal(config)
 	if marshalErr != nil {
 		return marshalErr
 	}
-	writeFilErr := ioutil.WriteFile(path, jsonBytes, 0644)
-	if writeFilErr != nil {
-		return writeFilErr
+	return writeFileAtomic(path, jsonBytes)
+}
+
+// writeFileAtomic replaces the file at path with contents in one step, by writing them
+// to a temporary file next to it and renaming that over it
+func writeFileAtomic(path string, contents []byte) error {
+	tmpFile, err := ioutil.TempFile(filepath.Dir(path), filepath.Base(path)+"-partial-")
+	if err != nil {
+		return err
+	}
+	_, err = tmpFile.Write(contents)
+	if closeErr := tmpFile.Close(); err == nil {
+		err = closeErr
+	}
+	if err == nil {
+		err = os.Chmod(tmpFile.Name(), 0644)
+	}
+	if err == nil {
+		err = os.Rename(tmpFile.Name(), path)
+	}
+	if err != nil {
+		_ = os.Remove(tmpFile.Name())
+		return err
 	}
 	return nil
 }
@@ -0,0 +1,222 @@
+package cache
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+	"runtime"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"golang.org/x/sync/errgroup"
+)
+
+// The ways the local filesystem cache can store artifacts
+const (
+	// StoreDirectory keeps each artifact as a directory holding a copy of its files
+	StoreDirectory = "directory"
+	// StoreContent keeps each artifact as a manifest of the hashes of its files. The
+	// contents of each file are stored once, in a blob store that all artifacts share.
+	StoreContent = "content"
+)
+
+// _blobsDir is the directory in the cache directory that holds the contents of the
+// files of artifacts stored by content. Blobs are never removed, even once no manifest
+// refers to them any more, so clearing the cache means removing this directory too.
+const _blobsDir = "blobs"
+
+// artifactManifest lists the files of an artifact stored by content
+type artifactManifest struct {
+	Hash  string          `json:"hash"`
+	Files []manifestEntry `json:"files"`
+}
+
+// manifestEntry is a file in an artifact stored by content. Regular files refer to the
+// hash of their contents, and symlinks hold their target instead.
+type manifestEntry struct {
+	Path   string      `json:"path"`
+	Mode   os.FileMode `json:"mode"`
+	Blob   string      `json:"blob,omitempty"`
+	Target string      `json:"target,omitempty"`
+}
+
+func manifestPath(cacheDirectory string, hash string) string {
+	return filepath.Join(cacheDirectory, hash+"-manifest.json")
+}
+
+// artifactExists returns true if the cache directory has an artifact for hash, stored
+// either way
+func artifactExists(cacheDirectory string, hash string) bool {
+	return fs.PathExists(filepath.Join(cacheDirectory, hash)) || fs.FileExists(manifestPath(cacheDirectory, hash))
+}
+
+// blobPath returns where the contents of the file with the given hash are stored. Blobs
+// are spread over directories named after the start of their hash, to keep any one
+// directory from getting too large.
+func (f *fsCache) blobPath(blob string) string {
+	return filepath.Join(f.cacheDirectory, _blobsDir, blob[:2], blob)
+}
+
+// putContent stores the files, relative to root, as an artifact stored by content. The
+// caller must hold the lock for the artifact.
+func (f *fsCache) putContent(root string, hash string, duration int, files []string) error {
+	entries := make([]manifestEntry, len(files))
+	g := new(errgroup.Group)
+	numDigesters := runtime.NumCPU()
+	indexQueue := make(chan int, numDigesters)
+	for i := 0; i < numDigesters; i++ {
+		g.Go(func() error {
+			for index := range indexQueue {
+				file := files[index]
+				path := filepath.Join(root, file)
+				info, err := os.Lstat(path)
+				if err != nil {
+					return fmt.Errorf("error stat'ing cache source %v: %v", file, err)
+				}
+				entry := manifestEntry{Path: filepath.ToSlash(file), Mode: info.Mode()}
+				switch {
+				case info.IsDir():
+					// Directories are created as needed for the files in them, like they are
+					// for artifacts stored as directories
+					continue
+				case info.Mode()&os.ModeSymlink != 0:
+					entry.Target, err = os.Readlink(path)
+				default:
+					entry.Blob, err = f.storeBlob(path)
+				}
+				if err != nil {
+					return fmt.Errorf("error storing %v in the cache: %w", file, err)
+				}
+				entries[index] = entry
+			}
+			return nil
+		})
+	}
+	for i := range files {
+		indexQueue <- i
+	}
+	close(indexQueue)
+	if err := g.Wait(); err != nil {
+		return err
+	}
+
+	manifest := &artifactManifest{Hash: hash, Files: []manifestEntry{}}
+	for _, entry := range entries {
+		if entry.Path != "" {
+			manifest.Files = append(manifest.Files, entry)
+		}
+	}
+	// The metadata goes first, since Fetch expects it for any artifact it finds
+	if err := WriteCacheMetaFile(filepath.Join(f.cacheDirectory, hash+"-meta.json"), &CacheMetadata{
+		Duration: duration,
+		Hash:     hash,
+	}); err != nil {
+		return fmt.Errorf("error writing cache metadata: %w", err)
+	}
+	return writeManifest(manifestPath(f.cacheDirectory, hash), manifest)
+}
+
+// storeBlob adds the contents of the file at path to the blob store, unless they are
+// already there, and returns their hash
+func (f *fsCache) storeBlob(path string) (string, error) {
+	src, err := os.Open(path)
+	if err != nil {
+		return "", err
+	}
+	defer func() { _ = src.Close() }()
+	blobsDir := filepath.Join(f.cacheDirectory, _blobsDir)
+	if err := os.MkdirAll(blobsDir, fs.DirPermissions); err != nil {
+		return "", err
+	}
+	// The contents are hashed while they are copied, and the copy is only kept if the
+	// store doesn't have them yet
+	tmpFile, err := ioutil.TempFile(blobsDir, "partial-")
+	if err != nil {
+		return "", err
+	}
+	defer func() { _ = os.Remove(tmpFile.Name()) }()
+	h := sha256.New()
+	_, err = io.Copy(io.MultiWriter(tmpFile, h), src)
+	if closeErr := tmpFile.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return "", err
+	}
+	blob := hex.EncodeToString(h.Sum(nil))
+	dest := f.blobPath(blob)
+	if fs.FileExists(dest) {
+		return blob, nil
+	}
+	if err := os.MkdirAll(filepath.Dir(dest), fs.DirPermissions); err != nil {
+		return "", err
+	}
+	if err := os.Chmod(tmpFile.Name(), 0644); err != nil {
+		return "", err
+	}
+	// Another process may store the same contents at the same time, in which case
+	// either copy is fine
+	if err := os.Rename(tmpFile.Name(), dest); err != nil {
+		return "", err
+	}
+	return blob, nil
+}
+
+// fetchContent restores the files of an artifact stored by content that match one of
+// restoreGlobs, or all of them if there are none. It returns false if there is no such
+// artifact.
+func (f *fsCache) fetchContent(target string, hash string, restoreGlobs []string) (bool, error) {
+	contents, err := ioutil.ReadFile(manifestPath(f.cacheDirectory, hash))
+	if os.IsNotExist(err) {
+		return false, nil
+	} else if err != nil {
+		return false, err
+	}
+	var manifest artifactManifest
+	if err := json.Unmarshal(contents, &manifest); err != nil {
+		return false, fmt.Errorf("error reading the manifest of %v: %w", hash, err)
+	}
+	for _, entry := range manifest.Files {
+		path := filepath.FromSlash(entry.Path)
+		if restore, err := shouldRestore(restoreGlobs, path); err != nil {
+			return false, err
+		} else if !restore {
+			continue
+		}
+		dest := filepath.Join(target, path)
+		if err := fs.EnsureDir(dest); err != nil {
+			return false, err
+		}
+		if entry.Mode&os.ModeSymlink != 0 {
+			_ = os.Remove(dest)
+			if err := os.Symlink(entry.Target, dest); err != nil {
+				return false, err
+			}
+			continue
+		}
+		blobPath := f.blobPath(entry.Blob)
+		if !fs.FileExists(blobPath) {
+			return false, fmt.Errorf("the cache is missing the contents of %v for %v", entry.Path, hash)
+		}
+		if err := fs.CopyFile(&fs.LstatCachedFile{Path: fs.UnsafeToAbsolutePath(blobPath)}, dest); err != nil {
+			return false, err
+		}
+		if err := os.Chmod(dest, entry.Mode.Perm()); err != nil {
+			return false, err
+		}
+	}
+	return true, nil
+}
+
+// writeManifest replaces the manifest at path in one step
+func writeManifest(path string, manifest *artifactManifest) error {
+	jsonBytes, err := json.Marshal(manifest)
+	if err != nil {
+		return err
+	}
+	return writeFileAtomic(path, jsonBytes)
+}
@@ -0,0 +1,94 @@
+package cache
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestContentStore(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cacheDir := t.TempDir()
+	cache, err := newFsCache(Opts{OverrideDir: cacheDir, LocalStore: StoreContent}, &dummyRecorder{}, repoRoot)
+	assert.NilError(t, err, "newFsCache")
+
+	writeOutput := func(name string, contents string, mode os.FileMode) {
+		path := repoRoot.Join("dist", name)
+		assert.NilError(t, path.EnsureDir(), "EnsureDir")
+		assert.NilError(t, path.WriteFile([]byte(contents), mode), "WriteFile")
+		assert.NilError(t, os.Chmod(path.ToString(), mode), "Chmod")
+	}
+	writeOutput("shared.js", "unchanged between versions", 0644)
+	writeOutput("main.js", "version 1", 0755)
+	assert.NilError(t, os.Symlink("main.js", repoRoot.Join("dist", "latest.js").ToString()), "Symlink")
+	files := []string{"dist", filepath.Join("dist", "shared.js"), filepath.Join("dist", "main.js"), filepath.Join("dist", "latest.js")}
+	assert.NilError(t, cache.Put(repoRoot.ToString(), "v1", 100, files), "Put")
+
+	writeOutput("main.js", "version 2", 0755)
+	assert.NilError(t, cache.Put(repoRoot.ToString(), "v2", 100, files), "Put")
+
+	// The unchanged file is stored once for both artifacts
+	blobs := 0
+	assert.NilError(t, fs.Walk(filepath.Join(cacheDir, _blobsDir), func(name string, isDir bool) error {
+		if !isDir {
+			blobs++
+		}
+		return nil
+	}), "Walk")
+	assert.Equal(t, blobs, 3)
+	assert.Assert(t, !fs.PathExists(filepath.Join(cacheDir, "v1")), "artifacts stored by content have no directory")
+	assert.Assert(t, ExistsLocally(Opts{OverrideDir: cacheDir}, repoRoot, "v1"))
+
+	assert.NilError(t, repoRoot.Join("dist").RemoveAll(), "RemoveAll")
+	hit, _, duration, err := cache.Fetch(repoRoot.ToString(), "v1", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit)
+	assert.Equal(t, duration, 100)
+	contents, err := repoRoot.Join("dist", "main.js").ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "version 1")
+	info, err := os.Stat(repoRoot.Join("dist", "main.js").ToString())
+	assert.NilError(t, err, "Stat")
+	assert.Equal(t, info.Mode().Perm(), os.FileMode(0755))
+	target, err := os.Readlink(repoRoot.Join("dist", "latest.js").ToString())
+	assert.NilError(t, err, "Readlink")
+	assert.Equal(t, target, "main.js")
+
+	// Only the files matching the restore globs are restored
+	assert.NilError(t, repoRoot.Join("dist").RemoveAll(), "RemoveAll")
+	hit, _, _, err = cache.Fetch(repoRoot.ToString(), "v2", []string{"dist/shared.js"})
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit)
+	assert.Assert(t, repoRoot.Join("dist", "shared.js").FileExists())
+	assert.Assert(t, !repoRoot.Join("dist", "main.js").FileExists())
+
+	hit, _, _, err = cache.Fetch(repoRoot.ToString(), "v3", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, !hit)
+}
+
+func TestContentStoreReadsDirectories(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cacheDir := t.TempDir()
+	directoryCache, err := newFsCache(Opts{OverrideDir: cacheDir}, &dummyRecorder{}, repoRoot)
+	assert.NilError(t, err, "newFsCache")
+	output := repoRoot.Join("dist", "main.js")
+	assert.NilError(t, output.EnsureDir(), "EnsureDir")
+	assert.NilError(t, output.WriteFile([]byte("main"), 0644), "WriteFile")
+	assert.NilError(t, directoryCache.Put(repoRoot.ToString(), "the-hash", 0, []string{filepath.Join("dist", "main.js")}), "Put")
+
+	// Switching stores keeps the artifacts that are already cached
+	contentCache, err := newFsCache(Opts{OverrideDir: cacheDir, LocalStore: StoreContent}, &dummyRecorder{}, repoRoot)
+	assert.NilError(t, err, "newFsCache")
+	assert.NilError(t, output.Remove(), "Remove")
+	hit, _, _, err := contentCache.Fetch(repoRoot.ToString(), "the-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit)
+	assert.Assert(t, output.FileExists())
+
+	_, err = newFsCache(Opts{OverrideDir: cacheDir, LocalStore: "tarball"}, &dummyRecorder{}, repoRoot)
+	assert.ErrorContains(t, err, `unknown cache store "tarball"`)
+}
@@ -108,12 +108,11 @@ func TestPut(t *testing.T) {
 	cache := &fsCache{
 		cacheDirectory: dst,
 		recorder:       dr,
-		repoRoot:       defaultCwd,
 	}
 
 	hash := "the-hash"
 	duration := 0
-	err = cache.Put("unused", hash, duration, files)
+	err = cache.Put(defaultCwd.ToString(), hash, duration, files)
 	assert.NilError(t, err, "Put")
 
 	// Verify that we got the files that we're expecting
@@ -221,15 +220,9 @@ func TestFetch(t *testing.T) {
 
 	dr := &dummyRecorder{}
 
-	defaultCwd, err := fs.GetCwd()
-	if err != nil {
-		t.Fatalf("failed to get cwd: %v", err)
-	}
-
 	cache := &fsCache{
 		cacheDirectory: cacheDir,
 		recorder:       dr,
-		repoRoot:       defaultCwd,
 	}
 
 	dstOutputPath := "some-package"
@@ -275,3 +268,66 @@ func TestFetch(t *testing.T) {
 	assert.NilError(t, err, "ReadDir")
 	assert.Equal(t, len(entries), 0)
 }
+
+func TestPutReplacesArtifact(t *testing.T) {
+	src := subdirForTest(t)
+	aPath := filepath.Join(src, "a")
+	assert.NilError(t, ioutil.WriteFile(aPath, []byte("first"), 0644), "WriteFile")
+
+	dst := subdirForTest(t)
+	defaultCwd, err := fs.GetCwd()
+	assert.NilError(t, err, "GetCwd")
+	cache := &fsCache{
+		cacheDirectory: dst,
+		recorder:       &dummyRecorder{},
+	}
+
+	hash := "the-hash"
+	assert.NilError(t, cache.Put(defaultCwd.ToString(), hash, 0, []string{aPath}), "Put")
+	assert.NilError(t, ioutil.WriteFile(aPath, []byte("second"), 0644), "WriteFile")
+	assert.NilError(t, cache.Put(defaultCwd.ToString(), hash, 0, []string{aPath}), "Put")
+
+	contents, err := ioutil.ReadFile(filepath.Join(dst, hash, aPath))
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "second")
+
+	// Nothing but the artifact and its metadata should be left in the cache directory
+	entries, err := os.ReadDir(dst)
+	assert.NilError(t, err, "ReadDir")
+	names := []string{}
+	for _, entry := range entries {
+		names = append(names, entry.Name())
+	}
+	assert.DeepEqual(t, names, []string{hash, hash + "-meta.json"})
+}
+
+func TestPutRefusesPathsOutsideTarget(t *testing.T) {
+	dst := subdirForTest(t)
+	defaultCwd, err := fs.GetCwd()
+	assert.NilError(t, err, "GetCwd")
+	cache := &fsCache{
+		cacheDirectory: dst,
+		recorder:       &dummyRecorder{},
+	}
+
+	outside := filepath.Join("..", "design-system", "packages", "ui", "dist", "index.js")
+	err = cache.Put(defaultCwd.ToString(), "the-hash", 0, []string{outside})
+	assert.ErrorContains(t, err, "outside of the directory the artifact is relative to")
+	assert.Assert(t, !fs.PathExists(filepath.Join(dst, "the-hash")), "expected no artifact to be written")
+}
+
+func TestDuration(t *testing.T) {
+	cacheDir := t.TempDir()
+	cache := &fsCache{cacheDirectory: cacheDir, recorder: &dummyRecorder{}}
+	assert.NilError(t, WriteCacheMetaFile(filepath.Join(cacheDir, "the-hash-meta.json"), &CacheMetadata{Hash: "the-hash", Duration: 1500}))
+
+	mplex := &cacheMultiplexer{caches: []Cache{newNoopCache(), cache}}
+	duration, ok := Duration(mplex, "the-hash")
+	assert.Assert(t, ok)
+	assert.Equal(t, duration, 1500)
+
+	_, ok = Duration(mplex, "other-hash")
+	assert.Assert(t, !ok)
+	_, ok = Duration(newNoopCache(), "the-hash")
+	assert.Assert(t, !ok)
+}
@@ -16,15 +16,19 @@ import (
 	"os"
 	"path/filepath"
 	"strconv"
+	"sync"
 	"time"
 
 	"github.com/vercel/turborepo/cli/internal/analytics"
+	"github.com/vercel/turborepo/cli/internal/errcode"
+	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 )
 
 type client interface {
 	PutArtifact(hash string, body []byte, duration int, tag string) error
 	FetchArtifact(hash string) (*http.Response, error)
+	ArtifactsExist(hashes []string) (map[string]bool, error)
 	GetTeamID() string
 }
 
@@ -32,9 +36,41 @@ type httpCache struct {
 	writable       bool
 	client         client
 	requestLimiter limiter
-	recorder       analytics.Recorder
-	signerVerifier *ArtifactSignatureAuthentication
-	repoRoot       turbopath.AbsolutePath
+	// prefetchLimiter bounds the prefetch requests separately from requestLimiter, so
+	// that prefetching never holds up the Fetches of running tasks
+	prefetchLimiter limiter
+	recorder        analytics.Recorder
+	signerVerifier  *ArtifactSignatureAuthentication
+
+	mu         sync.Mutex
+	closed     bool
+	prefetched map[string]*prefetchedArtifact
+	// prefetchedBytes is the size of the prefetched artifacts waiting in temporary files
+	prefetchedBytes int64
+	// fetched are the hashes that Fetch was called for, which there's no point prefetching
+	fetched map[string]struct{}
+}
+
+// _prefetchConcurrency is how many prefetch requests can be in flight at once
+const _prefetchConcurrency = 8
+
+// _maxPrefetchedBytes is how much prefetched artifacts can take up in temporary files.
+// Artifacts beyond it are left for Fetch to download.
+const _maxPrefetchedBytes = 1 << 30
+
+// errPrefetchSkipped is the error of a prefetch that was given up on, which Fetch makes
+// up for by downloading the artifact itself
+var errPrefetchSkipped = errors.New("prefetch skipped, too many prefetched artifacts are waiting to be fetched")
+
+// prefetchedArtifact is an artifact that has been, or is being, downloaded ahead of
+// the Fetch call that will restore it. done is closed once the download completes.
+type prefetchedArtifact struct {
+	done   chan struct{}
+	found  bool
+	path   string
+	size   int64
+	header http.Header
+	err    error
 }
 
 type limiter chan struct{}
@@ -54,12 +90,17 @@ var mtime = time.Date(2000, time.January, 1, 0, 0, 0, 0, time.UTC)
 const nobody = 65534
 
 func (cache *httpCache) Put(target, hash string, duration int, files []string) error {
-	// if cache.writable {
+	if !cache.writable {
+		return nil
+	}
+	if err := checkArtifactPaths(files); err != nil {
+		return err
+	}
 	cache.requestLimiter.acquire()
 	defer cache.requestLimiter.release()
 
 	r, w := io.Pipe()
-	go cache.write(w, hash, files)
+	go cache.write(w, fs.AbsolutePathFromUpstream(target), hash, files)
 
 	// Read the entire artifact tar into memory so we can easily compute the signature.
 	// Note: retryablehttp.NewRequest reads the files into memory anyways so there's no
@@ -78,8 +119,8 @@ func (cache *httpCache) Put(target, hash string, duration int, files []string) e
 	return cache.client.PutArtifact(hash, artifactBody, duration, tag)
 }
 
-// write writes a series of files into the given Writer.
-func (cache *httpCache) write(w io.WriteCloser, hash string, files []string) {
+// write writes a series of files, relative to root, into the given Writer.
+func (cache *httpCache) write(w io.WriteCloser, root turbopath.AbsolutePath, hash string, files []string) {
 	defer w.Close()
 	gzw := gzip.NewWriter(w)
 	defer gzw.Close()
@@ -87,21 +128,22 @@ func (cache *httpCache) write(w io.WriteCloser, hash string, files []string) {
 	defer tw.Close()
 	for _, file := range files {
 		// log.Printf("caching file %v", file)
-		if err := cache.storeFile(tw, file); err != nil {
+		if err := cache.storeFile(tw, root, file); err != nil {
 			log.Printf("[ERROR] Error uploading artifact %s to HTTP cache due to: %s", file, err)
 			// TODO(jaredpalmer): How can we cancel the request at this point?
 		}
 	}
 }
 
-func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string) error {
-	info, err := os.Lstat(repoRelativePath)
+func (cache *httpCache) storeFile(tw *tar.Writer, root turbopath.AbsolutePath, repoRelativePath string) error {
+	path := root.Join(repoRelativePath)
+	info, err := path.Lstat()
 	if err != nil {
 		return err
 	}
 	target := ""
 	if info.Mode()&os.ModeSymlink != 0 {
-		target, err = os.Readlink(repoRelativePath)
+		target, err = path.Readlink()
 		if err != nil {
 			return err
 		}
@@ -126,7 +168,7 @@ func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string) error
 	} else if info.IsDir() || target != "" {
 		return nil // nothing to write
 	}
-	f, err := os.Open(repoRelativePath)
+	f, err := path.Open()
 	if err != nil {
 		return err
 	}
@@ -138,10 +180,8 @@ func (cache *httpCache) storeFile(tw *tar.Writer, repoRelativePath string) error
 	return err
 }
 
-func (cache *httpCache) Fetch(target, key string, _unusedOutputGlobs []string) (bool, []string, int, error) {
-	cache.requestLimiter.acquire()
-	defer cache.requestLimiter.release()
-	hit, files, duration, err := cache.retrieve(key)
+func (cache *httpCache) Fetch(target, key string, restoreGlobs []string) (bool, []string, int, error) {
+	hit, files, duration, err := cache.retrieve(fs.AbsolutePathFromUpstream(target), key, restoreGlobs)
 	if err != nil {
 		// TODO: analytics event?
 		return false, files, duration, fmt.Errorf("failed to retrieve files from HTTP cache: %w", err)
@@ -166,7 +206,191 @@ func (cache *httpCache) logFetch(hit bool, hash string, duration int) {
 	cache.recorder.LogEvent(payload)
 }
 
-func (cache *httpCache) retrieve(hash string) (bool, []string, int, error) {
+// Prefetch starts downloading the artifacts for the given hashes in the background, so
+// that later Fetches for them don't need to wait on the network. Existence is checked
+// for all of the hashes at once, and only the artifacts that exist are downloaded. Each
+// artifact is buffered in a temporary file until it is fetched or the cache shuts down,
+// up to _maxPrefetchedBytes. Hashes that were already fetched aren't prefetched.
+func (cache *httpCache) Prefetch(hashes []string) {
+	cache.mu.Lock()
+	if cache.closed {
+		cache.mu.Unlock()
+		return
+	}
+	if cache.prefetched == nil {
+		cache.prefetched = make(map[string]*prefetchedArtifact)
+	}
+	toCheck := []string{}
+	artifacts := []*prefetchedArtifact{}
+	for _, hash := range hashes {
+		if _, ok := cache.prefetched[hash]; ok {
+			continue
+		}
+		if _, ok := cache.fetched[hash]; ok {
+			continue
+		}
+		artifact := &prefetchedArtifact{done: make(chan struct{})}
+		cache.prefetched[hash] = artifact
+		toCheck = append(toCheck, hash)
+		artifacts = append(artifacts, artifact)
+	}
+	cache.mu.Unlock()
+	if len(toCheck) == 0 {
+		return
+	}
+
+	cache.prefetchLimiter.acquire()
+	exists, err := cache.client.ArtifactsExist(toCheck)
+	cache.prefetchLimiter.release()
+	for i, hash := range toCheck {
+		artifact := artifacts[i]
+		if err != nil {
+			// Fetch will retry the request and surface the error
+			artifact.err = err
+			close(artifact.done)
+		} else if !exists[hash] {
+			// A known miss, so Fetch doesn't need to make a request at all
+			close(artifact.done)
+		} else {
+			go func(hash string) {
+				defer close(artifact.done)
+				cache.prefetchLimiter.acquire()
+				defer cache.prefetchLimiter.release()
+				artifact.found, artifact.path, artifact.size, artifact.header, artifact.err = cache.download(hash)
+			}(hash)
+		}
+	}
+}
+
+// reserveSpool reserves size bytes of the temporary files for a prefetched artifact,
+// returning false if that would take them over _maxPrefetchedBytes
+func (cache *httpCache) reserveSpool(size int64) bool {
+	cache.mu.Lock()
+	defer cache.mu.Unlock()
+	if cache.closed || cache.prefetchedBytes+size > _maxPrefetchedBytes {
+		return false
+	}
+	cache.prefetchedBytes += size
+	return true
+}
+
+// releaseSpool gives back size bytes reserved with reserveSpool
+func (cache *httpCache) releaseSpool(size int64) {
+	cache.mu.Lock()
+	cache.prefetchedBytes -= size
+	cache.mu.Unlock()
+}
+
+// spoolWriter writes a prefetched artifact to its temporary file, reserving room for
+// what it writes beyond the bytes already reserved for it. A write that doesn't fit
+// fails with errPrefetchSkipped.
+type spoolWriter struct {
+	cache    *httpCache
+	w        io.Writer
+	reserved int64
+	written  int64
+}
+
+func (sw *spoolWriter) Write(p []byte) (int, error) {
+	if need := sw.written + int64(len(p)) - sw.reserved; need > 0 {
+		if !sw.cache.reserveSpool(need) {
+			return 0, errPrefetchSkipped
+		}
+		sw.reserved += need
+	}
+	n, err := sw.w.Write(p)
+	sw.written += int64(n)
+	return n, err
+}
+
+// download fetches the artifact for the given hash into a temporary file, returning
+// the path to that file, its size and the headers that accompanied the artifact. The
+// file's size is reserved in the temporary files up front when the response has a
+// Content-Length, and as it is written otherwise, and the download is given up on with
+// errPrefetchSkipped once there is no room left for it.
+func (cache *httpCache) download(hash string) (bool, string, int64, http.Header, error) {
+	resp, err := cache.client.FetchArtifact(hash)
+	if err != nil {
+		return false, "", 0, nil, err
+	}
+	defer func() { _ = resp.Body.Close() }()
+	if resp.StatusCode == http.StatusNotFound {
+		return false, "", 0, nil, nil
+	} else if resp.StatusCode != http.StatusOK {
+		b, _ := ioutil.ReadAll(resp.Body)
+		return false, "", 0, nil, fmt.Errorf("%s", string(b))
+	}
+	spool := &spoolWriter{cache: cache}
+	if resp.ContentLength > 0 {
+		if !cache.reserveSpool(resp.ContentLength) {
+			return false, "", 0, nil, errPrefetchSkipped
+		}
+		spool.reserved = resp.ContentLength
+	}
+	f, err := ioutil.TempFile("", "turbo-prefetch-"+hash+"-")
+	if err != nil {
+		cache.releaseSpool(spool.reserved)
+		return false, "", 0, nil, err
+	}
+	spool.w = f
+	_, err = io.Copy(spool, resp.Body)
+	closeErr := f.Close()
+	if err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		_ = os.Remove(f.Name())
+		cache.releaseSpool(spool.reserved)
+		return false, "", 0, nil, err
+	}
+	// Give back what a shorter body than its Content-Length didn't use
+	cache.releaseSpool(spool.reserved - spool.written)
+	return true, f.Name(), spool.written, resp.Header, nil
+}
+
+// takePrefetched removes and returns the prefetched artifact for the given hash, if any.
+// Either way, the hash is marked as fetched, so that it isn't prefetched after this.
+func (cache *httpCache) takePrefetched(hash string) *prefetchedArtifact {
+	cache.mu.Lock()
+	defer cache.mu.Unlock()
+	if cache.fetched == nil {
+		cache.fetched = make(map[string]struct{})
+	}
+	cache.fetched[hash] = struct{}{}
+	artifact, ok := cache.prefetched[hash]
+	if ok {
+		delete(cache.prefetched, hash)
+	}
+	return artifact
+}
+
+// releasePrefetched removes the temporary file of a prefetched artifact that was restored
+func (cache *httpCache) releasePrefetched(artifact *prefetchedArtifact) {
+	_ = os.Remove(artifact.path)
+	cache.releaseSpool(artifact.size)
+}
+
+func (cache *httpCache) retrieve(root turbopath.AbsolutePath, hash string, restoreGlobs []string) (bool, []string, int, error) {
+	if artifact := cache.takePrefetched(hash); artifact != nil {
+		// Wait for a pending prefetch rather than downloading the artifact twice
+		<-artifact.done
+		// If the prefetch failed, fall back to fetching the artifact again below
+		if artifact.err == nil {
+			if !artifact.found {
+				return false, nil, 0, nil // doesn't exist - not an error
+			}
+			defer cache.releasePrefetched(artifact)
+			f, err := os.Open(artifact.path)
+			if err != nil {
+				return false, nil, 0, err
+			}
+			defer func() { _ = f.Close() }()
+			return cache.restoreArtifact(root, hash, artifact.header, f, restoreGlobs)
+		}
+	}
+
+	cache.requestLimiter.acquire()
+	defer cache.requestLimiter.release()
 	resp, err := cache.client.FetchArtifact(hash)
 	if err != nil {
 		return false, nil, 0, err
@@ -178,10 +402,15 @@ func (cache *httpCache) retrieve(hash string) (bool, []string, int, error) {
 		b, _ := ioutil.ReadAll(resp.Body)
 		return false, nil, 0, fmt.Errorf("%s", string(b))
 	}
+	return cache.restoreArtifact(root, hash, resp.Header, resp.Body, restoreGlobs)
+}
+
+// restoreArtifact verifies, if necessary, and untars a downloaded artifact into root.
+func (cache *httpCache) restoreArtifact(root turbopath.AbsolutePath, hash string, header http.Header, body io.Reader, restoreGlobs []string) (bool, []string, int, error) {
 	// If present, extract the duration from the response.
 	duration := 0
-	if resp.Header.Get("x-artifact-duration") != "" {
-		intVar, err := strconv.Atoi(resp.Header.Get("x-artifact-duration"))
+	if header.Get("x-artifact-duration") != "" {
+		intVar, err := strconv.Atoi(header.Get("x-artifact-duration"))
 		if err != nil {
 			return false, nil, 0, fmt.Errorf("invalid x-artifact-duration header: %w", err)
 		}
@@ -189,31 +418,30 @@ func (cache *httpCache) retrieve(hash string) (bool, []string, int, error) {
 	}
 	var tarReader io.Reader
 
-	defer func() { _ = resp.Body.Close() }()
 	if cache.signerVerifier.isEnabled() {
-		expectedTag := resp.Header.Get("x-artifact-tag")
+		expectedTag := header.Get("x-artifact-tag")
 		if expectedTag == "" {
 			// If the verifier is enabled all incoming artifact downloads must have a signature
-			return false, nil, 0, errors.New("artifact verification failed: Downloaded artifact is missing required x-artifact-tag header")
+			return false, nil, 0, errcode.New(errcode.CacheVerificationFailed, "artifact verification failed: Downloaded artifact is missing required x-artifact-tag header")
 		}
-		b, err := ioutil.ReadAll(resp.Body)
+		b, err := ioutil.ReadAll(body)
 		if err != nil {
-			return false, nil, 0, fmt.Errorf("artifact verification failed: %w", err)
+			return false, nil, 0, errcode.Wrap(errcode.CacheVerificationFailed, fmt.Errorf("artifact verification failed: %w", err))
 		}
 		isValid, err := cache.signerVerifier.validate(hash, b, expectedTag)
 		if err != nil {
-			return false, nil, 0, fmt.Errorf("artifact verification failed: %w", err)
+			return false, nil, 0, errcode.Wrap(errcode.CacheVerificationFailed, fmt.Errorf("artifact verification failed: %w", err))
 		}
 		if !isValid {
-			err = fmt.Errorf("artifact verification failed: artifact tag does not match expected tag %s", expectedTag)
+			err = errcode.Wrap(errcode.CacheVerificationFailed, fmt.Errorf("artifact verification failed: artifact tag does not match expected tag %s", expectedTag))
 			return false, nil, 0, err
 		}
 		// The artifact has been verified and the body can be read and untarred
 		tarReader = bytes.NewReader(b)
 	} else {
-		tarReader = resp.Body
+		tarReader = body
 	}
-	files, err := restoreTar(cache.repoRoot, tarReader)
+	files, err := restoreTar(root, tarReader, restoreGlobs)
 	if err != nil {
 		return false, nil, 0, err
 	}
@@ -224,7 +452,8 @@ func (cache *httpCache) retrieve(hash string) (bool, []string, int, error) {
 // restored. In the future, these should likely be repo-relative system paths
 // so that they are suitable for being fed into cache.Put for other caches.
 // For now, I think this is working because windows also accepts /-delimited paths.
-func restoreTar(root turbopath.AbsolutePath, reader io.Reader) ([]string, error) {
+// If restoreGlobs is non-empty, entries that don't match any of them are skipped.
+func restoreTar(root turbopath.AbsolutePath, reader io.Reader, restoreGlobs []string) ([]string, error) {
 	files := []string{}
 	missingLinks := []*tar.Header{}
 	gzr, err := gzip.NewReader(reader)
@@ -248,6 +477,11 @@ func restoreTar(root turbopath.AbsolutePath, reader io.Reader) ([]string, error)
 			}
 			return nil, err
 		}
+		if restore, err := shouldRestore(restoreGlobs, hdr.Name); err != nil {
+			return nil, err
+		} else if !restore {
+			continue
+		}
 		// hdr.Name is always a posix-style path
 		// TODO: files should eventually be repo-relative system paths
 		files = append(files, hdr.Name)
@@ -327,20 +561,34 @@ func (cache *httpCache) CleanAll() {
 	// Also not possible.
 }
 
-func (cache *httpCache) Shutdown() {}
+func (cache *httpCache) Shutdown() {
+	cache.mu.Lock()
+	cache.closed = true
+	prefetched := cache.prefetched
+	cache.prefetched = nil
+	cache.prefetchedBytes = 0
+	cache.mu.Unlock()
+	// Clean up any prefetched artifacts that were never fetched
+	for _, artifact := range prefetched {
+		<-artifact.done
+		if artifact.path != "" {
+			_ = os.Remove(artifact.path)
+		}
+	}
+}
 
-func newHTTPCache(opts Opts, client client, recorder analytics.Recorder, repoRoot turbopath.AbsolutePath) *httpCache {
+func newHTTPCache(opts Opts, client client, recorder analytics.Recorder) *httpCache {
 	return &httpCache{
-		writable:       true,
-		client:         client,
-		requestLimiter: make(limiter, 20),
-		recorder:       recorder,
+		writable:        !opts.SkipRemoteWrites,
+		client:          client,
+		requestLimiter:  make(limiter, 20),
+		prefetchLimiter: make(limiter, _prefetchConcurrency),
+		recorder:        recorder,
 		signerVerifier: &ArtifactSignatureAuthentication{
 			// TODO(Gaspar): this should use RemoteCacheOptions.TeamId once we start
 			// enforcing team restrictions for repositories.
 			teamId:  client.GetTeamID(),
 			enabled: opts.RemoteCacheOpts.Signature,
 		},
-		repoRoot: repoRoot,
 	}
 }
@@ -5,7 +5,11 @@ import (
 	"bytes"
 	"compress/gzip"
 	"errors"
+	"io/ioutil"
 	"net/http"
+	"path/filepath"
+	"runtime"
+	"sync"
 	"testing"
 
 	"github.com/vercel/turborepo/cli/internal/fs"
@@ -25,6 +29,10 @@ func (sr *errorResp) FetchArtifact(hash string) (*http.Response, error) {
 	return nil, sr.err
 }
 
+func (sr *errorResp) ArtifactsExist(hashes []string) (map[string]bool, error) {
+	return nil, sr.err
+}
+
 func (sr *errorResp) GetTeamID() string {
 	return ""
 }
@@ -49,6 +57,151 @@ func TestRemoteCachingDisabled(t *testing.T) {
 	}
 }
 
+type artifactResp struct {
+	mu       sync.Mutex
+	body     []byte
+	exists   map[string]bool
+	requests int
+	// contentLength is sent as the Content-Length of artifacts when set
+	contentLength bool
+}
+
+func (ar *artifactResp) PutArtifact(hash string, body []byte, duration int, tag string) error {
+	return nil
+}
+
+func (ar *artifactResp) FetchArtifact(hash string) (*http.Response, error) {
+	ar.mu.Lock()
+	defer ar.mu.Unlock()
+	ar.requests++
+	contentLength := int64(-1)
+	if ar.contentLength {
+		contentLength = int64(len(ar.body))
+	}
+	return &http.Response{
+		StatusCode:    http.StatusOK,
+		Header:        http.Header{},
+		Body:          ioutil.NopCloser(bytes.NewReader(ar.body)),
+		ContentLength: contentLength,
+	}, nil
+}
+
+func (ar *artifactResp) ArtifactsExist(hashes []string) (map[string]bool, error) {
+	return ar.exists, nil
+}
+
+func (ar *artifactResp) GetTeamID() string {
+	return ""
+}
+
+func TestPrefetch(t *testing.T) {
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+	client := &artifactResp{
+		body:   makeValidTar(t).Bytes(),
+		exists: map[string]bool{"some-hash": true},
+	}
+	cache := &httpCache{
+		client:          client,
+		requestLimiter:  make(limiter, 20),
+		prefetchLimiter: make(limiter, _prefetchConcurrency),
+		recorder:        &nullRecorder{},
+		signerVerifier:  &ArtifactSignatureAuthentication{},
+	}
+
+	cache.Prefetch([]string{"some-hash", "missing-hash"})
+	// A second prefetch of the same hash should not make another request
+	cache.Prefetch([]string{"some-hash"})
+	hit, _, _, err := cache.Fetch(root.ToString(), "some-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit, "expected a cache hit")
+	// A known miss shouldn't need a request either
+	hit, _, _, err = cache.Fetch(root.ToString(), "missing-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, !hit, "expected a cache miss")
+	assert.Equal(t, client.requests, 1)
+
+	someFile := root.Join("my-pkg", "some-file")
+	contents, err := someFile.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.DeepEqual(t, contents, []byte("some-file-contents"))
+	assert.Equal(t, cache.prefetchedBytes, int64(0))
+
+	// A hash that was fetched before it was prefetched isn't downloaded again
+	client.exists["fetched-hash"] = true
+	_, _, _, err = cache.Fetch(root.ToString(), "fetched-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	cache.Prefetch([]string{"fetched-hash"})
+	assert.Equal(t, client.requests, 2)
+	cache.Shutdown()
+}
+
+func TestPrefetchSpoolLimit(t *testing.T) {
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+	client := &artifactResp{
+		body:   makeValidTar(t).Bytes(),
+		exists: map[string]bool{"some-hash": true},
+	}
+	cache := &httpCache{
+		client:          client,
+		requestLimiter:  make(limiter, 20),
+		prefetchLimiter: make(limiter, _prefetchConcurrency),
+		recorder:        &nullRecorder{},
+		signerVerifier:  &ArtifactSignatureAuthentication{},
+		prefetchedBytes: _maxPrefetchedBytes,
+	}
+
+	// With no room left for temporary files, Fetch downloads the artifact itself
+	cache.Prefetch([]string{"some-hash"})
+	hit, _, _, err := cache.Fetch(root.ToString(), "some-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit, "expected a cache hit")
+	assert.Equal(t, client.requests, 1)
+	cache.Shutdown()
+}
+
+func TestPrefetchSpoolReservation(t *testing.T) {
+	body := makeValidTar(t).Bytes()
+	for _, contentLength := range []bool{true, false} {
+		client := &artifactResp{
+			body:          body,
+			exists:        map[string]bool{"a": true, "b": true, "c": true},
+			contentLength: contentLength,
+		}
+		// There is only room for one of the artifacts
+		initial := _maxPrefetchedBytes - int64(len(body))*3/2
+		cache := &httpCache{
+			client:          client,
+			requestLimiter:  make(limiter, 20),
+			prefetchLimiter: make(limiter, _prefetchConcurrency),
+			recorder:        &nullRecorder{},
+			signerVerifier:  &ArtifactSignatureAuthentication{},
+			prefetchedBytes: initial,
+		}
+
+		cache.Prefetch([]string{"a", "b", "c"})
+		cache.mu.Lock()
+		artifacts := []*prefetchedArtifact{}
+		for _, artifact := range cache.prefetched {
+			artifacts = append(artifacts, artifact)
+		}
+		cache.mu.Unlock()
+		spooled := 0
+		for _, artifact := range artifacts {
+			<-artifact.done
+			if artifact.err == nil {
+				spooled++
+				continue
+			}
+			assert.ErrorIs(t, artifact.err, errPrefetchSkipped)
+			assert.Equal(t, artifact.path, "", "a skipped artifact shouldn't leave a temporary file")
+		}
+		assert.Equal(t, spooled, 1, "Content-Length %v", contentLength)
+		// Only what was spooled stays reserved, even with the downloads in flight at once
+		assert.Equal(t, cache.prefetchedBytes, initial+int64(len(body)), "Content-Length %v", contentLength)
+		cache.Shutdown()
+	}
+}
+
 func makeValidTar(t *testing.T) *bytes.Buffer {
 	// <repoRoot>
 	//   my-pkg/
@@ -181,7 +334,7 @@ func TestRestoreTar(t *testing.T) {
 		"my-pkg/link-to-extra-file",
 		"my-pkg/broken-link",
 	}
-	files, err := restoreTar(root, tar)
+	files, err := restoreTar(root, tar, nil)
 	assert.NilError(t, err, "readTar")
 
 	expectedSet := util.SetFromStrings(expectedFiles)
@@ -207,6 +360,26 @@ func TestRestoreTar(t *testing.T) {
 	assert.DeepEqual(t, contents, []byte("some-file-contents"))
 }
 
+func TestRestoreTarWithGlobs(t *testing.T) {
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+
+	tar := makeValidTar(t)
+
+	files, err := restoreTar(root, tar, []string{"my-pkg/some-*"})
+	assert.NilError(t, err, "readTar")
+	assert.DeepEqual(t, files, []string{"my-pkg/some-file"})
+
+	someFile := root.Join("my-pkg", "some-file")
+	contents, err := someFile.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.DeepEqual(t, contents, []byte("some-file-contents"))
+
+	extraFile := root.Join("extra-file")
+	if extraFile.FileExists() {
+		t.Errorf("expected %v to not be restored", extraFile)
+	}
+}
+
 func TestRestoreInvalidTar(t *testing.T) {
 	root := fs.AbsolutePathFromUpstream(t.TempDir())
 	expectedContents := []byte("important-data")
@@ -218,7 +391,7 @@ func TestRestoreInvalidTar(t *testing.T) {
 	// use a child directory so that blindly untarring will squash the file
 	// that we just wrote above.
 	repoRoot := root.Join("repo")
-	_, err = restoreTar(repoRoot, tar)
+	_, err = restoreTar(repoRoot, tar, nil)
 	if err == nil {
 		t.Error("expected error untarring invalid tar")
 	}
@@ -228,6 +401,43 @@ func TestRestoreInvalidTar(t *testing.T) {
 	assert.Equal(t, string(contents), string(expectedContents), "expected to not overwrite file")
 }
 
+type bufferWriteCloser struct {
+	bytes.Buffer
+}
+
+func (b *bufferWriteCloser) Close() error { return nil }
+
+func TestNonUTF8NameRoundTrip(t *testing.T) {
+	if runtime.GOOS != "linux" {
+		t.Skip("only some file systems allow names that aren't valid UTF-8")
+	}
+	src := fs.AbsolutePathFromUpstream(t.TempDir())
+	name := "caf\xe9"
+	assert.NilError(t, src.Join(name).WriteFile([]byte("some-file-contents"), 0644), "WriteFile")
+
+	cache := &httpCache{}
+	artifact := &bufferWriteCloser{}
+	cache.write(artifact, src, "some-hash", []string{name})
+
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+	files, err := restoreTar(root, &artifact.Buffer, nil)
+	assert.NilError(t, err, "restoreTar")
+	assert.DeepEqual(t, files, []string{filepath.ToSlash(name)})
+	contents, err := root.Join(name).ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.DeepEqual(t, contents, []byte("some-file-contents"))
+}
+
 // Note that testing Put will require mocking the filesystem and is not currently the most
 // interesting test. The current implementation directly returns the error from PutArtifact.
 // We should still add the test once feasible to avoid future breakage.
+
+func TestPutReadOnly(t *testing.T) {
+	client := &errorResp{err: errors.New("uploads aren't allowed")}
+	cache := &httpCache{
+		writable:       false,
+		client:         client,
+		requestLimiter: make(limiter, 20),
+	}
+	assert.NilError(t, cache.Put("unused-target", "some-hash", 5, []string{"a-file"}), "Put")
+}
@@ -9,7 +9,7 @@ func newNoopCache() *noopCache {
 func (c *noopCache) Put(target string, key string, duration int, files []string) error {
 	return nil
 }
-func (c *noopCache) Fetch(target string, key string, files []string) (bool, []string, int, error) {
+func (c *noopCache) Fetch(target string, key string, restoreGlobs []string) (bool, []string, int, error) {
 	return false, nil, 0, nil
 }
 func (c *noopCache) Clean(target string) {}
@@ -8,10 +8,11 @@ import (
 	"crypto/sha256"
 	"encoding/base64"
 	"encoding/json"
-	"errors"
 	"fmt"
 	"hash"
 	"os"
+
+	"github.com/vercel/turborepo/cli/internal/errcode"
 )
 
 type ArtifactSignatureAuthentication struct {
@@ -28,7 +29,7 @@ func (asa *ArtifactSignatureAuthentication) isEnabled() bool {
 func (asa *ArtifactSignatureAuthentication) secretKey() ([]byte, error) {
 	secret := os.Getenv("TURBO_REMOTE_CACHE_SIGNATURE_KEY")
 	if len(secret) == 0 {
-		return nil, errors.New("signature secret key not found. You must specify a secret key in the TURBO_REMOTE_CACHE_SIGNATURE_KEY environment variable")
+		return nil, errcode.New(errcode.CacheSignatureKeyMissing, "signature secret key not found. You must specify a secret key in the TURBO_REMOTE_CACHE_SIGNATURE_KEY environment variable")
 	}
 	return []byte(secret), nil
 }
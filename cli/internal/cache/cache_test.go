@@ -14,6 +14,7 @@ import (
 type testCache struct {
 	disabledErr *util.CacheDisabledError
 	entries     map[string][]string
+	prefetched  []string
 }
 
 func (tc *testCache) Fetch(target string, hash string, files []string) (bool, []string, int, error) {
@@ -36,6 +37,15 @@ func (tc *testCache) Put(target string, hash string, duration int, files []strin
 	return nil
 }
 
+func (tc *testCache) Exists(hash string) bool {
+	_, ok := tc.entries[hash]
+	return ok
+}
+
+func (tc *testCache) Prefetch(hashes []string) {
+	tc.prefetched = append(tc.prefetched, hashes...)
+}
+
 func (tc *testCache) Clean(target string) {}
 func (tc *testCache) CleanAll()           {}
 func (tc *testCache) Shutdown()           {}
@@ -115,6 +125,11 @@ func (*fakeClient) FetchArtifact(hash string) (*http.Response, error) {
 	panic("unimplemented")
 }
 
+// ArtifactsExist implements client
+func (*fakeClient) ArtifactsExist(hashes []string) (map[string]bool, error) {
+	panic("unimplemented")
+}
+
 // GetTeamID implements client
 func (*fakeClient) GetTeamID() string {
 	return "fake-team-id"
@@ -272,3 +287,18 @@ func TestNew(t *testing.T) {
 		})
 	}
 }
+
+func TestPrefetchSkipsArtifactsInEarlierCaches(t *testing.T) {
+	local := newEnabledCache()
+	local.entries["local-hash"] = []string{"a-file"}
+	remote := newEnabledCache()
+	mplex := &cacheMultiplexer{caches: []Cache{local, remote}}
+
+	mplex.Prefetch([]string{"local-hash", "remote-hash"})
+	if want := []string{"local-hash", "remote-hash"}; !reflect.DeepEqual(local.prefetched, want) {
+		t.Errorf("local cache prefetched %v, want %v", local.prefetched, want)
+	}
+	if want := []string{"remote-hash"}; !reflect.DeepEqual(remote.prefetched, want) {
+		t.Errorf("remote cache prefetched %v, want %v", remote.prefetched, want)
+	}
+}
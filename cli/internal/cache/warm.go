@@ -0,0 +1,156 @@
+package cache
+
+import (
+	"errors"
+	"fmt"
+	"io/ioutil"
+	"os"
+	"path/filepath"
+	"sort"
+	"sync"
+
+	"github.com/nightlyone/lockfile"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"golang.org/x/sync/errgroup"
+)
+
+// WarmResult is what Warm did with each of the hashes it was given
+type WarmResult struct {
+	// Downloaded are the artifacts that were downloaded into the local cache
+	Downloaded []string
+	// Local are the artifacts that were already in the local cache
+	Local []string
+	// Missing are the artifacts that the remote cache doesn't have
+	Missing []string
+}
+
+// Warm downloads the artifacts for the given hashes from the remote cache into the local
+// filesystem cache, without restoring any of them into the repository. Artifacts that are
+// already cached locally aren't downloaded again.
+func Warm(opts Opts, repoRoot turbopath.AbsolutePath, client client, hashes []string) (*WarmResult, error) {
+	// Nothing is fetched through these caches, so there are no cache events to record
+	local, err := newFsCache(opts, nil, repoRoot)
+	if err != nil {
+		return nil, err
+	}
+	remote := newHTTPCache(opts, client, nil)
+	result := &WarmResult{}
+	toCheck := []string{}
+	for _, hash := range hashes {
+		if artifactExists(local.cacheDirectory, hash) {
+			result.Local = append(result.Local, hash)
+		} else {
+			toCheck = append(toCheck, hash)
+		}
+	}
+	if len(toCheck) > 0 {
+		exists, err := client.ArtifactsExist(toCheck)
+		if err != nil {
+			return nil, err
+		}
+		mu := sync.Mutex{}
+		g := new(errgroup.Group)
+		for _, hash := range toCheck {
+			hash := hash
+			if !exists[hash] {
+				// Downloads that are already running also add to Missing
+				mu.Lock()
+				result.Missing = append(result.Missing, hash)
+				mu.Unlock()
+				continue
+			}
+			g.Go(func() error {
+				remote.requestLimiter.acquire()
+				defer remote.requestLimiter.release()
+				found, err := remote.warm(local, hash)
+				if err != nil {
+					return fmt.Errorf("failed to download artifact %v: %w", hash, err)
+				}
+				mu.Lock()
+				defer mu.Unlock()
+				if found {
+					result.Downloaded = append(result.Downloaded, hash)
+				} else {
+					result.Missing = append(result.Missing, hash)
+				}
+				return nil
+			})
+		}
+		if err := g.Wait(); err != nil {
+			return nil, err
+		}
+	}
+	sort.Strings(result.Downloaded)
+	sort.Strings(result.Local)
+	sort.Strings(result.Missing)
+	return result, nil
+}
+
+// warm downloads the artifact for hash into the local cache. It returns false if the remote
+// cache doesn't have the artifact.
+func (cache *httpCache) warm(local *fsCache, hash string) (bool, error) {
+	lock, err := lockArtifact(local.cacheDirectory, hash)
+	if errors.Is(err, lockfile.ErrBusy) {
+		// Another turbo process is already writing this artifact
+		return true, nil
+	} else if err != nil {
+		return false, err
+	}
+	defer func() { _ = lock.Unlock() }()
+	found, path, header, err := cache.download(hash)
+	if err != nil || !found {
+		return false, err
+	}
+	defer func() { _ = os.Remove(path) }()
+	f, err := os.Open(path)
+	if err != nil {
+		return false, err
+	}
+	defer func() { _ = f.Close() }()
+
+	// The artifact is extracted next to where it belongs and moved into place once it is
+	// complete, so that an interrupted download doesn't leave behind a partial artifact
+	// that looks like a cache hit
+	partialDir, err := ioutil.TempDir(local.cacheDirectory, hash+"-partial-")
+	if err != nil {
+		return false, err
+	}
+	defer func() { _ = os.RemoveAll(partialDir) }()
+	if err := os.Chmod(partialDir, 0755); err != nil {
+		return false, err
+	}
+	_, _, duration, err := cache.restoreArtifact(fs.AbsolutePathFromUpstream(partialDir), hash, header, f, nil)
+	if err != nil {
+		return false, err
+	}
+	if local.store == StoreContent {
+		files := []string{}
+		if err := fs.Walk(partialDir, func(name string, isDir bool) error {
+			if !isDir {
+				relativePath, err := filepath.Rel(partialDir, name)
+				if err != nil {
+					return err
+				}
+				files = append(files, relativePath)
+			}
+			return nil
+		}); err != nil {
+			return false, err
+		}
+		if err := local.putContent(partialDir, hash, duration, files); err != nil {
+			return false, err
+		}
+		return true, nil
+	}
+	if err := WriteCacheMetaFile(filepath.Join(local.cacheDirectory, hash+"-meta.json"), &CacheMetadata{
+		Hash:     hash,
+		Duration: duration,
+	}); err != nil {
+		return false, err
+	}
+	if err := replaceDir(partialDir, filepath.Join(local.cacheDirectory, hash)); err != nil {
+		return false, err
+	}
+	return true, nil
+}
@@ -0,0 +1,42 @@
+package cache
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestWarm(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	cacheDir := filepath.Join(t.TempDir(), "cache")
+	opts := Opts{OverrideDir: cacheDir}
+	assert.NilError(t, fs.EnsureDir(filepath.Join(cacheDir, "local-hash", "file")), "EnsureDir")
+	client := &artifactResp{
+		body:   makeValidTar(t).Bytes(),
+		exists: map[string]bool{"remote-hash": true},
+	}
+
+	result, err := Warm(opts, repoRoot, client, []string{"remote-hash", "local-hash", "missing-hash"})
+	assert.NilError(t, err, "Warm")
+	assert.DeepEqual(t, result, &WarmResult{
+		Downloaded: []string{"remote-hash"},
+		Local:      []string{"local-hash"},
+		Missing:    []string{"missing-hash"},
+	})
+	assert.Equal(t, client.requests, 1)
+
+	contents, err := fs.AbsolutePathFromUpstream(filepath.Join(cacheDir, "remote-hash", "my-pkg", "some-file")).ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.DeepEqual(t, contents, []byte("some-file-contents"))
+	// Nothing is restored into the repository
+	assert.Assert(t, !fs.PathExists(repoRoot.Join("my-pkg").ToString()), "expected my-pkg not to be restored")
+
+	// The warmed artifact can be fetched from the local cache
+	local, err := newFsCache(opts, &nullRecorder{}, repoRoot)
+	assert.NilError(t, err, "newFsCache")
+	hit, _, _, err := local.Fetch(repoRoot.ToString(), "remote-hash", nil)
+	assert.NilError(t, err, "Fetch")
+	assert.Assert(t, hit, "expected a local cache hit")
+}
@@ -0,0 +1,361 @@
+// Package cacheserver is a minimal implementation of the Remote Caching API that stores
+// artifacts on local disk. It backs the turbo-cache-server binary, for teams that host
+// their own cache, and gives the API client something real to be tested against.
+package cacheserver
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/ioutil"
+	"net/http"
+	"net/url"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strconv"
+	"strings"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+const _artifactsPrefix = "/v8/artifacts"
+
+// _maxArtifactSize caps the size of uploads, so that a misbehaving client can't fill the disk
+// with a single request
+const _maxArtifactSize = 1 << 30
+
+// _validName matches hashes and team names that are safe to use as file names
+var _validName = regexp.MustCompile(`^[A-Za-z0-9_-]+$`)
+
+// Opts configures a Server
+type Opts struct {
+	// Dir is where artifacts are stored. Each team gets its own directory inside of it.
+	Dir string
+	// Tokens are the bearer tokens that are accepted. If there are none, requests don't
+	// need to be authenticated.
+	Tokens []string
+	Logger hclog.Logger
+}
+
+// Server serves the Remote Caching API
+type Server struct {
+	dir    string
+	tokens util.Set
+	logger hclog.Logger
+}
+
+// metadata is what is stored next to each artifact
+type metadata struct {
+	// Duration is how long the task that produced the artifact took, in milliseconds
+	Duration int `json:"duration"`
+	// Tag is the artifact's signature, if the client signs artifacts
+	Tag  string            `json:"tag,omitempty"`
+	Tags map[string]string `json:"tags,omitempty"`
+	// CreatedAt is when the artifact was uploaded, in milliseconds since the epoch
+	CreatedAt int64 `json:"createdAt"`
+	Size      int64 `json:"size"`
+}
+
+// New creates a Server
+func New(opts Opts) *Server {
+	tokens := make(util.Set)
+	for _, token := range opts.Tokens {
+		if token != "" {
+			tokens.Add(token)
+		}
+	}
+	logger := opts.Logger
+	if logger == nil {
+		logger = hclog.NewNullLogger()
+	}
+	return &Server{dir: opts.Dir, tokens: tokens, logger: logger}
+}
+
+// ServeHTTP implements http.Handler
+func (s *Server) ServeHTTP(w http.ResponseWriter, r *http.Request) {
+	s.logger.Debug("request", "method", r.Method, "path", r.URL.Path)
+	if r.Method == http.MethodOptions {
+		// Pre-flight requests are answered without a token, like a browser would expect
+		w.Header().Set("Access-Control-Allow-Origin", "*")
+		w.Header().Set("Access-Control-Allow-Methods", "GET, HEAD, PUT, POST, OPTIONS")
+		w.Header().Set("Access-Control-Allow-Headers", "Content-Type, Authorization, User-Agent, x-artifact-duration, x-artifact-tag, x-artifact-tags")
+		w.WriteHeader(http.StatusOK)
+		return
+	}
+	if !s.authorized(r) {
+		writeError(w, http.StatusForbidden, "forbidden", "the token is missing or isn't allowed to access this cache")
+		return
+	}
+	team := r.URL.Query().Get("teamId")
+	if team == "" {
+		team = r.URL.Query().Get("slug")
+	}
+	if team != "" && !_validName.MatchString(team) {
+		writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid team %q", team))
+		return
+	}
+
+	path := strings.TrimSuffix(r.URL.Path, "/")
+	switch {
+	case path == _artifactsPrefix && r.Method == http.MethodPost:
+		s.queryHashes(w, r, team)
+	case path == _artifactsPrefix+"/status" && r.Method == http.MethodGet:
+		writeJSON(w, http.StatusOK, map[string]string{"status": "enabled"})
+	case path == _artifactsPrefix+"/events" && r.Method == http.MethodPost:
+		s.recordEvents(w, r)
+	case path == _artifactsPrefix+"/query" && r.Method == http.MethodGet:
+		s.queryTags(w, r, team)
+	case strings.HasPrefix(path, _artifactsPrefix+"/"):
+		hash := strings.TrimPrefix(path, _artifactsPrefix+"/")
+		if !_validName.MatchString(hash) {
+			writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid hash %q", hash))
+			return
+		}
+		switch r.Method {
+		case http.MethodGet, http.MethodHead:
+			s.getArtifact(w, r, team, hash)
+		case http.MethodPut:
+			s.putArtifact(w, r, team, hash)
+		default:
+			writeError(w, http.StatusMethodNotAllowed, "method_not_allowed", fmt.Sprintf("%v is not supported for artifacts", r.Method))
+		}
+	default:
+		writeError(w, http.StatusNotFound, "not_found", fmt.Sprintf("%v %v is not part of the Remote Caching API", r.Method, r.URL.Path))
+	}
+}
+
+func (s *Server) authorized(r *http.Request) bool {
+	if len(s.tokens) == 0 {
+		return true
+	}
+	token := strings.TrimPrefix(r.Header.Get("Authorization"), "Bearer ")
+	return s.tokens.Includes(token)
+}
+
+func (s *Server) teamDir(team string) string {
+	if team == "" {
+		return s.dir
+	}
+	return filepath.Join(s.dir, "teams", team)
+}
+
+func (s *Server) readMetadata(team string, hash string) (*metadata, error) {
+	contents, err := ioutil.ReadFile(filepath.Join(s.teamDir(team), hash+".json"))
+	if err != nil {
+		return nil, err
+	}
+	m := &metadata{}
+	if err := json.Unmarshal(contents, m); err != nil {
+		return nil, err
+	}
+	return m, nil
+}
+
+func (s *Server) getArtifact(w http.ResponseWriter, r *http.Request, team string, hash string) {
+	m, err := s.readMetadata(team, hash)
+	if os.IsNotExist(err) {
+		writeError(w, http.StatusNotFound, "not_found", "artifact not found")
+		return
+	} else if err != nil {
+		s.internalError(w, err)
+		return
+	}
+	f, err := os.Open(filepath.Join(s.teamDir(team), hash))
+	if os.IsNotExist(err) {
+		writeError(w, http.StatusNotFound, "not_found", "artifact not found")
+		return
+	} else if err != nil {
+		s.internalError(w, err)
+		return
+	}
+	defer func() { _ = f.Close() }()
+	w.Header().Set("Content-Type", "application/octet-stream")
+	w.Header().Set("x-artifact-duration", strconv.Itoa(m.Duration))
+	if m.Tag != "" {
+		w.Header().Set("x-artifact-tag", m.Tag)
+	}
+	// ServeContent handles HEAD requests and Content-Length
+	http.ServeContent(w, r, hash, time.UnixMilli(m.CreatedAt), f)
+}
+
+func (s *Server) putArtifact(w http.ResponseWriter, r *http.Request, team string, hash string) {
+	m := &metadata{
+		Tag:       r.Header.Get("x-artifact-tag"),
+		CreatedAt: time.Now().UnixMilli(),
+	}
+	if raw := r.Header.Get("x-artifact-duration"); raw != "" {
+		duration, err := strconv.Atoi(raw)
+		if err != nil {
+			writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid x-artifact-duration %q", raw))
+			return
+		}
+		m.Duration = duration
+	}
+	if raw := r.Header.Get("x-artifact-tags"); raw != "" {
+		tags, err := parseTags(raw)
+		if err != nil {
+			writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid x-artifact-tags %q", raw))
+			return
+		}
+		m.Tags = tags
+	}
+
+	dir := s.teamDir(team)
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		s.internalError(w, err)
+		return
+	}
+	// Write to a temporary file first, so that a concurrent GET never sees a partial artifact
+	tmp, err := ioutil.TempFile(dir, hash+".*.tmp")
+	if err != nil {
+		s.internalError(w, err)
+		return
+	}
+	defer func() { _ = os.Remove(tmp.Name()) }()
+	size, err := tmp.ReadFrom(http.MaxBytesReader(w, r.Body, _maxArtifactSize))
+	if closeErr := tmp.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("failed to read artifact: %v", err))
+		return
+	}
+	m.Size = size
+	contents, err := json.Marshal(m)
+	if err != nil {
+		s.internalError(w, err)
+		return
+	}
+	if err := os.Rename(tmp.Name(), filepath.Join(dir, hash)); err != nil {
+		s.internalError(w, err)
+		return
+	}
+	if err := ioutil.WriteFile(filepath.Join(dir, hash+".json"), contents, 0644); err != nil {
+		s.internalError(w, err)
+		return
+	}
+	writeJSON(w, http.StatusAccepted, map[string][]string{"urls": {_artifactsPrefix + "/" + hash}})
+}
+
+// queryHashes answers which of a batch of hashes have artifacts
+func (s *Server) queryHashes(w http.ResponseWriter, r *http.Request, team string) {
+	query := struct {
+		Hashes []string `json:"hashes"`
+	}{}
+	if err := json.NewDecoder(r.Body).Decode(&query); err != nil {
+		writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid query: %v", err))
+		return
+	}
+	type artifact struct {
+		Size           int64  `json:"size"`
+		TaskDurationMs int    `json:"taskDurationMs"`
+		Tag            string `json:"tag,omitempty"`
+	}
+	response := make(map[string]*artifact, len(query.Hashes))
+	for _, hash := range query.Hashes {
+		response[hash] = nil
+		if !_validName.MatchString(hash) {
+			continue
+		}
+		if m, err := s.readMetadata(team, hash); err == nil {
+			response[hash] = &artifact{Size: m.Size, TaskDurationMs: m.Duration, Tag: m.Tag}
+		}
+	}
+	writeJSON(w, http.StatusOK, response)
+}
+
+// queryTags lists the artifacts that have all of the given tags, newest first
+func (s *Server) queryTags(w http.ResponseWriter, r *http.Request, team string) {
+	wanted, err := parseTags(r.URL.Query().Get("tags"))
+	if err != nil {
+		writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid tags: %v", err))
+		return
+	}
+	limit := 0
+	if raw := r.URL.Query().Get("limit"); raw != "" {
+		limit, err = strconv.Atoi(raw)
+		if err != nil || limit < 0 {
+			writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid limit %q", raw))
+			return
+		}
+	}
+	type artifact struct {
+		Hash      string            `json:"hash"`
+		Tags      map[string]string `json:"tags"`
+		CreatedAt int64             `json:"createdAt"`
+	}
+	artifacts := []artifact{}
+	files, err := filepath.Glob(filepath.Join(s.teamDir(team), "*.json"))
+	if err != nil {
+		s.internalError(w, err)
+		return
+	}
+	for _, file := range files {
+		hash := strings.TrimSuffix(filepath.Base(file), ".json")
+		m, err := s.readMetadata(team, hash)
+		if err != nil {
+			continue
+		}
+		matches := true
+		for key, value := range wanted {
+			if m.Tags[key] != value {
+				matches = false
+				break
+			}
+		}
+		if matches {
+			artifacts = append(artifacts, artifact{Hash: hash, Tags: m.Tags, CreatedAt: m.CreatedAt})
+		}
+	}
+	sort.SliceStable(artifacts, func(i, j int) bool { return artifacts[i].CreatedAt > artifacts[j].CreatedAt })
+	if limit > 0 && len(artifacts) > limit {
+		artifacts = artifacts[:limit]
+	}
+	writeJSON(w, http.StatusOK, map[string]interface{}{"artifacts": artifacts})
+}
+
+// recordEvents accepts cache usage events. They are only logged, since this server has
+// nowhere to report them to.
+func (s *Server) recordEvents(w http.ResponseWriter, r *http.Request) {
+	events := []map[string]interface{}{}
+	if err := json.NewDecoder(r.Body).Decode(&events); err != nil {
+		writeError(w, http.StatusBadRequest, "bad_request", fmt.Sprintf("invalid events: %v", err))
+		return
+	}
+	for _, event := range events {
+		s.logger.Info("cache event", "event", event["event"], "source", event["source"], "hash", event["hash"], "duration", event["duration"])
+	}
+	w.WriteHeader(http.StatusOK)
+}
+
+func (s *Server) internalError(w http.ResponseWriter, err error) {
+	s.logger.Error("request failed", "error", err)
+	writeError(w, http.StatusInternalServerError, "internal_error", "the cache failed to handle the request")
+}
+
+// parseTags reads tags encoded as a query string, the way the client sends them
+func parseTags(raw string) (map[string]string, error) {
+	values, err := url.ParseQuery(raw)
+	if err != nil {
+		return nil, err
+	}
+	tags := make(map[string]string, len(values))
+	for key := range values {
+		tags[key] = values.Get(key)
+	}
+	return tags, nil
+}
+
+// writeError responds in the format the client reads from failed requests
+func writeError(w http.ResponseWriter, status int, code string, message string) {
+	writeJSON(w, status, map[string]string{"code": code, "message": message})
+}
+
+func writeJSON(w http.ResponseWriter, status int, body interface{}) {
+	w.Header().Set("Content-Type", "application/json")
+	w.WriteHeader(status)
+	_ = json.NewEncoder(w).Encode(body)
+}
@@ -0,0 +1,101 @@
+package cacheserver
+
+import (
+	"io/ioutil"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/client"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"gotest.tools/v3/assert"
+)
+
+func newClient(url string, token string) *client.ApiClient {
+	return client.NewClient(client.RemoteConfig{
+		TeamSlug: "my-team",
+		APIURL:   url,
+		Token:    token,
+	}, hclog.NewNullLogger(), "v1", client.Opts{})
+}
+
+func TestArtifacts(t *testing.T) {
+	dir := t.TempDir()
+	ts := httptest.NewServer(New(Opts{Dir: dir, Tokens: []string{"secret"}}))
+	defer ts.Close()
+	apiClient := newClient(ts.URL, "secret")
+	apiClient.SetArtifactTags(map[string]string{"branch": "main"})
+
+	status, err := apiClient.GetCachingStatus()
+	assert.NilError(t, err)
+	assert.Equal(t, status, util.CachingStatusEnabled)
+
+	exists, err := apiClient.ArtifactExists("abc123")
+	assert.NilError(t, err)
+	assert.Assert(t, !exists)
+
+	assert.NilError(t, apiClient.PutArtifact("abc123", []byte("artifact"), 1500, "signature"))
+	// Artifacts are kept per team
+	_, err = os.Stat(filepath.Join(dir, "teams", "my-team", "abc123"))
+	assert.NilError(t, err)
+
+	exists, err = apiClient.ArtifactExists("abc123")
+	assert.NilError(t, err)
+	assert.Assert(t, exists)
+
+	resp, err := apiClient.FetchArtifact("abc123")
+	assert.NilError(t, err)
+	defer func() { _ = resp.Body.Close() }()
+	assert.Equal(t, resp.StatusCode, http.StatusOK)
+	assert.Equal(t, resp.Header.Get("x-artifact-duration"), "1500")
+	assert.Equal(t, resp.Header.Get("x-artifact-tag"), "signature")
+	body, err := ioutil.ReadAll(resp.Body)
+	assert.NilError(t, err)
+	assert.Equal(t, string(body), "artifact")
+
+	found, err := apiClient.ArtifactsExist([]string{"abc123", "def456"})
+	assert.NilError(t, err)
+	assert.DeepEqual(t, found, map[string]bool{"abc123": true, "def456": false})
+
+	artifacts, err := apiClient.QueryArtifacts(map[string]string{"branch": "main"}, 0)
+	assert.NilError(t, err)
+	assert.Equal(t, len(artifacts), 1)
+	assert.Equal(t, artifacts[0].Hash, "abc123")
+	artifacts, err = apiClient.QueryArtifacts(map[string]string{"branch": "other"}, 0)
+	assert.NilError(t, err)
+	assert.Equal(t, len(artifacts), 0)
+
+	assert.NilError(t, apiClient.RecordAnalyticsEvents([]map[string]interface{}{
+		{"source": "REMOTE", "event": "HIT", "hash": "abc123", "duration": 1500},
+	}))
+}
+
+func TestTokens(t *testing.T) {
+	ts := httptest.NewServer(New(Opts{Dir: t.TempDir(), Tokens: []string{"secret"}}))
+	defer ts.Close()
+
+	_, err := newClient(ts.URL, "wrong").ArtifactExists("abc123")
+	assert.ErrorContains(t, err, "forbidden")
+
+	req, err := http.NewRequest(http.MethodGet, ts.URL+"/v8/artifacts/abc123", nil)
+	assert.NilError(t, err)
+	resp, err := http.DefaultClient.Do(req)
+	assert.NilError(t, err)
+	_ = resp.Body.Close()
+	assert.Equal(t, resp.StatusCode, http.StatusForbidden)
+}
+
+func TestInvalidHash(t *testing.T) {
+	ts := httptest.NewServer(New(Opts{Dir: t.TempDir()}))
+	defer ts.Close()
+
+	req, err := http.NewRequest(http.MethodGet, ts.URL+"/v8/artifacts/..%2fsecret", nil)
+	assert.NilError(t, err)
+	resp, err := http.DefaultClient.Do(req)
+	assert.NilError(t, err)
+	_ = resp.Body.Close()
+	assert.Equal(t, resp.StatusCode, http.StatusBadRequest)
+}
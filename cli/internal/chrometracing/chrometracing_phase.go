@@ -0,0 +1,124 @@
+package chrometracing
+
+import (
+	"encoding/json"
+	"fmt"
+	"os"
+	"sync"
+	"time"
+)
+
+// Phases are implemented in a separate file to keep a separation between custom
+// code and upstream from github.com/google/chrometracing.
+
+// completeEvent is a trace_event with both a start time and a duration ("ph": "X").
+// Unlike begin/end pairs, it can be named after the unit of work has finished.
+type completeEvent struct {
+	Name     string  `json:"name"`
+	Phase    string  `json:"ph"`
+	Time     float64 `json:"ts"`
+	Duration float64 `json:"dur"`
+	Pid      uint64  `json:"pid"`
+	Tid      uint64  `json:"tid"`
+}
+
+// trackNameEvent names a track ("ph": "M") in the trace viewer
+type trackNameEvent struct {
+	Name  string `json:"name"`
+	Phase string `json:"ph"`
+	Pid   uint64 `json:"pid"`
+	Tid   uint64 `json:"tid"`
+	Arg   struct {
+		Name string `json:"name"`
+	} `json:"args"`
+}
+
+var namedTracks = struct {
+	sync.Mutex
+	names map[uint64]bool
+}{
+	names: make(map[uint64]bool),
+}
+
+// A Phase is a unit of work nested inside a PendingEvent. It is drawn on the same
+// track as its parent, underneath it.
+type Phase struct {
+	name  string
+	tid   uint64
+	start time.Duration
+}
+
+// Phase starts a nested unit of work on pe's track. Since thread ids are reused
+// once their event is done, each track corresponds to one slot of concurrency.
+func (pe *PendingEvent) Phase(name string) *Phase {
+	if pe == nil || pe.name == "" || trace.file == nil {
+		return &Phase{}
+	}
+	nameTrack(pe.tid)
+	return &Phase{
+		name:  name,
+		tid:   pe.tid,
+		start: time.Since(trace.start),
+	}
+}
+
+// SetName changes the name the phase is recorded under, for phases whose nature
+// is only known once they have started
+func (p *Phase) SetName(name string) {
+	if p.name != "" {
+		p.name = name
+	}
+}
+
+// Done writes the trace event for this phase
+func (p *Phase) Done() {
+	if p == nil || p.name == "" || trace.file == nil {
+		return
+	}
+	now := time.Since(trace.start)
+	writeJSON(&completeEvent{
+		Name:     p.name,
+		Phase:    "X",
+		Time:     float64(p.start.Microseconds()),
+		Duration: float64((now - p.start).Microseconds()),
+		Pid:      trace.pid,
+		Tid:      p.tid,
+	})
+}
+
+// nameTrack labels a track the first time a phase is drawn on it
+func nameTrack(tid uint64) {
+	namedTracks.Lock()
+	alreadyNamed := namedTracks.names[tid]
+	namedTracks.names[tid] = true
+	namedTracks.Unlock()
+	if alreadyNamed {
+		return
+	}
+	ev := &trackNameEvent{
+		Name:  "thread_name",
+		Phase: "M",
+		Pid:   trace.pid,
+		Tid:   tid,
+	}
+	ev.Arg.Name = fmt.Sprintf("slot %d", tid)
+	writeJSON(ev)
+}
+
+func writeJSON(ev interface{}) {
+	b, err := json.Marshal(ev)
+	if err != nil {
+		fmt.Fprintf(os.Stderr, "%v\n", err)
+		return
+	}
+	trace.fileMu.Lock()
+	defer trace.fileMu.Unlock()
+	if _, err = trace.file.Write(b); err != nil {
+		fmt.Fprintf(os.Stderr, "%v\n", err)
+		return
+	}
+	if _, err = trace.file.Write([]byte{',', '\n'}); err != nil {
+		fmt.Fprintf(os.Stderr, "%v\n", err)
+		return
+	}
+}
@@ -11,6 +11,7 @@ import (
 	"net/http"
 	"net/url"
 	"runtime"
+	"sort"
 	"strings"
 	"sync/atomic"
 	"time"
@@ -35,6 +36,8 @@ type ApiClient struct {
 	teamSlug   string
 	// Whether or not to send preflight requests before uploads
 	usePreflight bool
+	// Tags attached to every artifact this client uploads
+	artifactTags map[string]string
 }
 
 // ErrTooManyFailures is returned from remote cache API methods after `maxRemoteFailCount` errors have occurred
@@ -49,6 +52,12 @@ func (c *ApiClient) SetToken(token string) {
 	c.token = token
 }
 
+// SetArtifactTags sets the tags attached to every artifact this client uploads, so that
+// they can be found later with QueryArtifacts
+func (c *ApiClient) SetArtifactTags(tags map[string]string) {
+	c.artifactTags = tags
+}
+
 // RemoteConfig holds the authentication and endpoint details for the API client
 type RemoteConfig struct {
 	Token    string
@@ -265,7 +274,7 @@ func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int,
 	requestURL := c.makeUrl("/v8/artifacts/" + hash + encoded)
 	allowAuth := true
 	if c.usePreflight {
-		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodPut, "Content-Type, x-artifact-duration, Authorization, User-Agent, x-artifact-tag")
+		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodPut, "Content-Type, x-artifact-duration, Authorization, User-Agent, x-artifact-tag, x-artifact-tags")
 		if err != nil {
 			return fmt.Errorf("pre-flight request failed before trying to store in HTTP cache: %w", err)
 		}
@@ -284,6 +293,9 @@ func (c *ApiClient) PutArtifact(hash string, artifactBody []byte, duration int,
 	if tag != "" {
 		req.Header.Set("x-artifact-tag", tag)
 	}
+	if len(c.artifactTags) > 0 {
+		req.Header.Set("x-artifact-tags", encodeTags(c.artifactTags))
+	}
 	if err != nil {
 		return fmt.Errorf("[WARNING] Invalid cache URL: %w", err)
 	}
@@ -345,6 +357,212 @@ func (c *ApiClient) FetchArtifact(hash string) (*http.Response, error) {
 	return resp, nil
 }
 
+// errBatchQueryUnsupported is returned when the Remote Cache doesn't implement the
+// endpoint for querying many artifacts at once
+var errBatchQueryUnsupported = errors.New("batched artifact queries are not supported")
+
+// ArtifactsExist reports which of the given hashes have an artifact in the Remote Cache.
+// It uses a single request for all of the hashes if the server supports it, and falls
+// back to checking each hash individually otherwise.
+func (c *ApiClient) ArtifactsExist(hashes []string) (map[string]bool, error) {
+	exists, err := c.queryArtifacts(hashes)
+	if !errors.Is(err, errBatchQueryUnsupported) {
+		return exists, err
+	}
+	exists = make(map[string]bool, len(hashes))
+	for _, hash := range hashes {
+		found, err := c.ArtifactExists(hash)
+		if err != nil {
+			return nil, err
+		}
+		exists[hash] = found
+	}
+	return exists, nil
+}
+
+// queryArtifacts asks the Remote Cache about many artifacts in a single request
+func (c *ApiClient) queryArtifacts(hashes []string) (map[string]bool, error) {
+	if err := c.okToRequest(); err != nil {
+		return nil, err
+	}
+	params := url.Values{}
+	c.addTeamParam(&params)
+	encoded := params.Encode()
+	if encoded != "" {
+		encoded = "?" + encoded
+	}
+	body, err := json.Marshal(map[string][]string{"hashes": hashes})
+	if err != nil {
+		return nil, err
+	}
+
+	requestURL := c.makeUrl("/v8/artifacts" + encoded)
+	allowAuth := true
+	if c.usePreflight {
+		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodPost, "Content-Type, Authorization, User-Agent")
+		if err != nil {
+			return nil, fmt.Errorf("pre-flight request failed before trying to query HTTP cache: %w", err)
+		}
+		requestURL = latestRequestURL
+		headers := resp.Header.Get("Access-Control-Allow-Headers")
+		allowAuth = strings.Contains(strings.ToLower(headers), strings.ToLower("Authorization"))
+	}
+
+	req, err := retryablehttp.NewRequest(http.MethodPost, requestURL, body)
+	if err != nil {
+		return nil, fmt.Errorf("invalid cache URL: %w", err)
+	}
+	req.Header.Set("Content-Type", "application/json")
+	if allowAuth {
+		req.Header.Set("Authorization", "Bearer "+c.token)
+	}
+	req.Header.Set("User-Agent", c.UserAgent())
+	resp, err := c.HttpClient.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("failed to query artifacts: %v", err)
+	}
+	defer func() { _ = resp.Body.Close() }()
+	switch resp.StatusCode {
+	case http.StatusOK:
+		// continue below
+	case http.StatusForbidden:
+		return nil, c.handle403(resp.Body)
+	case http.StatusNotFound, http.StatusMethodNotAllowed, http.StatusNotImplemented:
+		return nil, errBatchQueryUnsupported
+	default:
+		b, _ := ioutil.ReadAll(resp.Body)
+		return nil, fmt.Errorf("%s", string(b))
+	}
+	// Each hash maps to either a description of the artifact, an error, or null
+	artifacts := make(map[string]*struct {
+		Error *struct {
+			Message string `json:"message"`
+		} `json:"error"`
+	})
+	if err := json.NewDecoder(resp.Body).Decode(&artifacts); err != nil {
+		return nil, fmt.Errorf("failed to parse artifact query response: %w", err)
+	}
+	exists := make(map[string]bool, len(hashes))
+	for _, hash := range hashes {
+		artifact := artifacts[hash]
+		exists[hash] = artifact != nil && artifact.Error == nil
+	}
+	return exists, nil
+}
+
+// ArtifactMetadata describes an artifact in the Remote Cache
+type ArtifactMetadata struct {
+	Hash string            `json:"hash"`
+	Tags map[string]string `json:"tags"`
+	// CreatedAt is when the artifact was uploaded, in milliseconds since the epoch
+	CreatedAt int64 `json:"createdAt"`
+}
+
+// encodeTags renders tags as a query string, sorted by key
+func encodeTags(tags map[string]string) string {
+	values := url.Values{}
+	for key, value := range tags {
+		values.Set(key, value)
+	}
+	return values.Encode()
+}
+
+// QueryArtifacts lists the artifacts in the Remote Cache that have all of the given tags,
+// newest first. A limit of 0 leaves it to the server.
+func (c *ApiClient) QueryArtifacts(tags map[string]string, limit int) ([]ArtifactMetadata, error) {
+	if err := c.okToRequest(); err != nil {
+		return nil, err
+	}
+	params := url.Values{}
+	c.addTeamParam(&params)
+	params.Set("tags", encodeTags(tags))
+	if limit > 0 {
+		params.Set("limit", fmt.Sprintf("%v", limit))
+	}
+	req, err := retryablehttp.NewRequest(http.MethodGet, c.makeUrl("/v8/artifacts/query?"+params.Encode()), nil)
+	if err != nil {
+		return nil, fmt.Errorf("invalid cache URL: %w", err)
+	}
+	req.Header.Set("Authorization", "Bearer "+c.token)
+	req.Header.Set("User-Agent", c.UserAgent())
+	resp, err := c.HttpClient.Do(req)
+	if err != nil {
+		return nil, fmt.Errorf("failed to query artifacts: %v", err)
+	}
+	defer func() { _ = resp.Body.Close() }()
+	switch resp.StatusCode {
+	case http.StatusOK:
+		// continue below
+	case http.StatusForbidden:
+		return nil, c.handle403(resp.Body)
+	case http.StatusNotFound, http.StatusMethodNotAllowed, http.StatusNotImplemented:
+		return nil, errors.New("the Remote Cache doesn't support querying artifacts by tag")
+	default:
+		b, _ := ioutil.ReadAll(resp.Body)
+		return nil, fmt.Errorf("failed to query artifacts (%v): %s", resp.StatusCode, string(b))
+	}
+	queryResponse := struct {
+		Artifacts []ArtifactMetadata `json:"artifacts"`
+	}{}
+	if err := json.NewDecoder(resp.Body).Decode(&queryResponse); err != nil {
+		return nil, fmt.Errorf("failed to parse artifact query response: %w", err)
+	}
+	sort.SliceStable(queryResponse.Artifacts, func(i, j int) bool {
+		return queryResponse.Artifacts[i].CreatedAt > queryResponse.Artifacts[j].CreatedAt
+	})
+	return queryResponse.Artifacts, nil
+}
+
+// ArtifactExists checks whether the Remote Cache has an artifact for the given hash,
+// without downloading it
+func (c *ApiClient) ArtifactExists(hash string) (bool, error) {
+	if err := c.okToRequest(); err != nil {
+		return false, err
+	}
+	params := url.Values{}
+	c.addTeamParam(&params)
+	encoded := params.Encode()
+	if encoded != "" {
+		encoded = "?" + encoded
+	}
+
+	requestURL := c.makeUrl("/v8/artifacts/" + hash + encoded)
+	allowAuth := true
+	if c.usePreflight {
+		resp, latestRequestURL, err := c.doPreflight(requestURL, http.MethodHead, "Authorization, User-Agent")
+		if err != nil {
+			return false, fmt.Errorf("pre-flight request failed before trying to query HTTP cache: %w", err)
+		}
+		requestURL = latestRequestURL
+		headers := resp.Header.Get("Access-Control-Allow-Headers")
+		allowAuth = strings.Contains(strings.ToLower(headers), strings.ToLower("Authorization"))
+	}
+
+	req, err := retryablehttp.NewRequest(http.MethodHead, requestURL, nil)
+	if err != nil {
+		return false, fmt.Errorf("invalid cache URL: %w", err)
+	}
+	if allowAuth {
+		req.Header.Set("Authorization", "Bearer "+c.token)
+	}
+	req.Header.Set("User-Agent", c.UserAgent())
+	resp, err := c.HttpClient.Do(req)
+	if err != nil {
+		return false, fmt.Errorf("failed to query artifact: %v", err)
+	}
+	defer func() { _ = resp.Body.Close() }()
+	switch resp.StatusCode {
+	case http.StatusOK:
+		return true, nil
+	case http.StatusNotFound:
+		return false, nil
+	case http.StatusForbidden:
+		return false, c.handle403(resp.Body)
+	default:
+		return false, fmt.Errorf("unexpected status querying artifact %v: %v", hash, resp.Status)
+	}
+}
+
 func (c *ApiClient) RecordAnalyticsEvents(events []map[string]interface{}) error {
 	if err := c.okToRequest(); err != nil {
 		return err
@@ -558,18 +776,25 @@ type statusResponse struct {
 // GetCachingStatus returns the server's perspective on whether or not remove caching
 // requests will be allowed.
 func (c *ApiClient) GetCachingStatus() (util.CachingStatus, error) {
+	status, _, err := c.requestCachingStatus()
+	return status, err
+}
+
+// requestCachingStatus fetches the caching status, along with the HTTP status code of the
+// response, or 0 if there wasn't one
+func (c *ApiClient) requestCachingStatus() (util.CachingStatus, int, error) {
 	values := make(url.Values)
 	c.addTeamParam(&values)
 	req, err := retryablehttp.NewRequest(http.MethodGet, c.makeUrl("/v8/artifacts/status?"+values.Encode()), nil)
 	if err != nil {
-		return util.CachingStatusDisabled, err
+		return util.CachingStatusDisabled, 0, err
 	}
 	req.Header.Set("User-Agent", c.UserAgent())
 	req.Header.Set("Content-Type", "application/json")
 	req.Header.Set("Authorization", "Bearer "+c.token)
 	resp, err := c.HttpClient.Do(req)
 	if err != nil {
-		return util.CachingStatusDisabled, err
+		return util.CachingStatusDisabled, 0, err
 	}
 	// Explicitly ignore the error from closing the response body. We don't need
 	// to fail the method if we fail to close the response.
@@ -582,18 +807,79 @@ func (c *ApiClient) GetCachingStatus() (util.CachingStatus, error) {
 		} else {
 			responseText = string(b)
 		}
-		return util.CachingStatusDisabled, fmt.Errorf("failed to get caching status (%v): %s", resp.StatusCode, responseText)
+		return util.CachingStatusDisabled, resp.StatusCode, fmt.Errorf("failed to get caching status (%v): %s", resp.StatusCode, responseText)
 	}
 	body, err := ioutil.ReadAll(resp.Body)
 	if err != nil {
-		return util.CachingStatusDisabled, fmt.Errorf("failed to read JSN response: %v", err)
+		return util.CachingStatusDisabled, resp.StatusCode, fmt.Errorf("failed to read JSN response: %v", err)
 	}
 	statusResponse := statusResponse{}
 	err = json.Unmarshal(body, &statusResponse)
 	if err != nil {
-		return util.CachingStatusDisabled, fmt.Errorf("failed to read JSON response: %v", string(body))
+		return util.CachingStatusDisabled, resp.StatusCode, fmt.Errorf("failed to read JSON response: %v", string(body))
+	}
+	status, err := util.CachingStatusFromString(statusResponse.Status)
+	return status, resp.StatusCode, err
+}
+
+// CacheAccess is what the configured token is allowed to do with the remote cache
+type CacheAccess struct {
+	Read  bool
+	Write bool
+	// WriteUnknown is set if the token may be allowed to upload artifacts, but the API
+	// can't tell before one is uploaded. Uploads are still attempted.
+	WriteUnknown bool
+	// Reason explains why access is limited, if it is
+	Reason string
+}
+
+// String describes the access as "read, write", "read, write unknown", "read", or "none"
+func (ca CacheAccess) String() string {
+	switch {
+	case ca.Read && ca.Write:
+		return "read, write"
+	case ca.Read && ca.WriteUnknown:
+		return "read, write unknown"
+	case ca.Read:
+		return "read"
+	case ca.Write:
+		return "write"
+	default:
+		return "none"
+	}
+}
+
+// CheckCacheAccess validates the token and team once, so that a misconfiguration can be
+// reported before a run rather than as a failure for every artifact. The caching status
+// only tells whether the token can download artifacts, so whether it can upload them is
+// reported as unknown.
+func (c *ApiClient) CheckCacheAccess() CacheAccess {
+	status, statusCode, err := c.requestCachingStatus()
+	if err != nil {
+		switch statusCode {
+		case http.StatusUnauthorized:
+			return CacheAccess{Reason: "the token is invalid or has expired"}
+		case http.StatusForbidden:
+			return CacheAccess{Reason: fmt.Sprintf("the token doesn't have access to team %v", c.teamName())}
+		default:
+			return CacheAccess{Reason: fmt.Sprintf("could not check remote cache access: %v", err)}
+		}
+	}
+	switch status {
+	case util.CachingStatusDisabled:
+		return CacheAccess{Reason: fmt.Sprintf("remote caching is disabled for team %v", c.teamName())}
+	case util.CachingStatusOverLimit:
+		return CacheAccess{Reason: fmt.Sprintf("team %v is over its remote caching usage limit", c.teamName())}
 	}
-	return util.CachingStatusFromString(statusResponse.Status)
+	return CacheAccess{Read: true, WriteUnknown: true}
+}
+
+// teamName returns the team slug if there is one, or the team id otherwise
+func (c *ApiClient) teamName() string {
+	if c.teamSlug != "" {
+		return c.teamSlug
+	}
+	return c.teamID
 }
 
 type verificationResponse struct {
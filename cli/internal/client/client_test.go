@@ -157,3 +157,171 @@ func Test_FetchWhenCachingDisabled(t *testing.T) {
 		t.Errorf("response got %v, want <nil>", resp)
 	}
 }
+
+func Test_ArtifactsExist(t *testing.T) {
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		defer func() { _ = req.Body.Close() }()
+		if req.Method != http.MethodPost || req.URL.Path != "/v8/artifacts" {
+			t.Errorf("unexpected request %v %v", req.Method, req.URL.Path)
+		}
+		_, _ = w.Write([]byte(`{"hit": {"size": 10, "taskDurationMs": 500}, "miss": {"error": {"message": "not found"}}, "other": null}`))
+	}))
+	defer ts.Close()
+
+	remoteConfig := RemoteConfig{
+		TeamSlug: "my-team-slug",
+		APIURL:   ts.URL,
+		Token:    "my-token",
+	}
+	apiClient := NewClient(remoteConfig, hclog.Default(), "v1", Opts{})
+	exists, err := apiClient.ArtifactsExist([]string{"hit", "miss", "other", "absent"})
+	if err != nil {
+		t.Fatalf("ArtifactsExist: %v", err)
+	}
+	expected := map[string]bool{"hit": true, "miss": false, "other": false, "absent": false}
+	if !reflect.DeepEqual(exists, expected) {
+		t.Errorf("ArtifactsExist got %v, want %v", exists, expected)
+	}
+}
+
+func Test_ArtifactsExistFallback(t *testing.T) {
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		defer func() { _ = req.Body.Close() }()
+		switch {
+		case req.Method == http.MethodPost:
+			w.WriteHeader(http.StatusNotFound)
+		case req.Method == http.MethodHead && req.URL.Path == "/v8/artifacts/hit":
+			w.WriteHeader(http.StatusOK)
+		default:
+			w.WriteHeader(http.StatusNotFound)
+		}
+	}))
+	defer ts.Close()
+
+	remoteConfig := RemoteConfig{
+		TeamSlug: "my-team-slug",
+		APIURL:   ts.URL,
+		Token:    "my-token",
+	}
+	apiClient := NewClient(remoteConfig, hclog.Default(), "v1", Opts{})
+	exists, err := apiClient.ArtifactsExist([]string{"hit", "miss"})
+	if err != nil {
+		t.Fatalf("ArtifactsExist: %v", err)
+	}
+	expected := map[string]bool{"hit": true, "miss": false}
+	if !reflect.DeepEqual(exists, expected) {
+		t.Errorf("ArtifactsExist got %v, want %v", exists, expected)
+	}
+}
+
+func Test_CheckCacheAccess(t *testing.T) {
+	testCases := []struct {
+		name       string
+		statusCode int
+		status     string
+		want       string
+		wantReason string
+	}{
+		{
+			name:       "enabled",
+			statusCode: http.StatusOK,
+			status:     "enabled",
+			want:       "read, write unknown",
+		},
+		{
+			name:       "over limit",
+			statusCode: http.StatusOK,
+			status:     "over_limit",
+			want:       "none",
+			wantReason: "team my-team-slug is over its remote caching usage limit",
+		},
+		{
+			name:       "invalid token",
+			statusCode: http.StatusUnauthorized,
+			want:       "none",
+			wantReason: "the token is invalid or has expired",
+		},
+		{
+			name:       "wrong team",
+			statusCode: http.StatusForbidden,
+			want:       "none",
+			wantReason: "the token doesn't have access to team my-team-slug",
+		},
+	}
+	for _, tc := range testCases {
+		t.Run(tc.name, func(t *testing.T) {
+			ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+				defer func() { _ = req.Body.Close() }()
+				if req.Method != http.MethodGet {
+					t.Errorf("CheckCacheAccess sent a %v request to %v, it should only read the caching status", req.Method, req.URL.Path)
+				}
+				w.WriteHeader(tc.statusCode)
+				_, _ = w.Write([]byte("{\"status\": \"" + tc.status + "\"}"))
+			}))
+			defer ts.Close()
+
+			remoteConfig := RemoteConfig{
+				TeamSlug: "my-team-slug",
+				APIURL:   ts.URL,
+				Token:    "my-token",
+			}
+			apiClient := NewClient(remoteConfig, hclog.Default(), "v1", Opts{})
+			access := apiClient.CheckCacheAccess()
+			if access.String() != tc.want {
+				t.Errorf("CheckCacheAccess got %v, want %v", access, tc.want)
+			}
+			if access.Reason != tc.wantReason {
+				t.Errorf("CheckCacheAccess reason got %q, want %q", access.Reason, tc.wantReason)
+			}
+		})
+	}
+}
+
+func Test_ArtifactTags(t *testing.T) {
+	var uploadedTags string
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, req *http.Request) {
+		defer func() { _ = req.Body.Close() }()
+		switch {
+		case req.Method == http.MethodPut:
+			uploadedTags = req.Header.Get("x-artifact-tags")
+			w.WriteHeader(http.StatusOK)
+		case req.Method == http.MethodGet && req.URL.Path == "/v8/artifacts/query":
+			if got := req.URL.Query().Get("tags"); got != "branch=main" {
+				t.Errorf("queried tags got %v, want branch=main", got)
+			}
+			_, _ = w.Write([]byte(`{"artifacts": [
+				{"hash": "older", "tags": {"branch": "main"}, "createdAt": 1000},
+				{"hash": "newer", "tags": {"branch": "main"}, "createdAt": 2000}
+			]}`))
+		default:
+			w.WriteHeader(http.StatusNotFound)
+		}
+	}))
+	defer ts.Close()
+
+	remoteConfig := RemoteConfig{
+		TeamSlug: "my-team-slug",
+		APIURL:   ts.URL,
+		Token:    "my-token",
+	}
+	apiClient := NewClient(remoteConfig, hclog.Default(), "v1", Opts{})
+	apiClient.SetArtifactTags(map[string]string{"sha": "abc123", "branch": "main"})
+	if err := apiClient.PutArtifact("hash", []byte("artifact"), 500, ""); err != nil {
+		t.Fatalf("PutArtifact: %v", err)
+	}
+	if uploadedTags != "branch=main&sha=abc123" {
+		t.Errorf("uploaded tags got %v, want branch=main&sha=abc123", uploadedTags)
+	}
+
+	artifacts, err := apiClient.QueryArtifacts(map[string]string{"branch": "main"}, 0)
+	if err != nil {
+		t.Fatalf("QueryArtifacts: %v", err)
+	}
+	hashes := []string{}
+	for _, artifact := range artifacts {
+		hashes = append(hashes, artifact.Hash)
+	}
+	if !reflect.DeepEqual(hashes, []string{"newer", "older"}) {
+		t.Errorf("QueryArtifacts got %v, want [newer older]", hashes)
+	}
+}
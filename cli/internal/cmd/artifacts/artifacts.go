@@ -0,0 +1,90 @@
+// Package artifacts lists the artifacts in the Remote Cache
+package artifacts
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+	"time"
+
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/client"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+type opts struct {
+	tags  map[string]string
+	limit int
+	json  bool
+}
+
+// GetCmd returns the artifacts command for use with cobra
+func GetCmd(helper *cmdutil.Helper) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:           "artifacts",
+		Short:         "List the artifacts in the Remote Cache with the given tags",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			if !base.APIClient.IsLinked() {
+				err := errors.New("this repository isn't linked to a Remote Cache. Run \"turbo link\" first")
+				base.LogError("%v", err)
+				return err
+			}
+			if len(opts.tags) == 0 {
+				err := errors.New("at least one --tag is required")
+				base.LogError("%v", err)
+				return err
+			}
+			artifacts, err := base.APIClient.QueryArtifacts(opts.tags, opts.limit)
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			if opts.json {
+				bytes, err := json.MarshalIndent(&struct {
+					Artifacts []client.ArtifactMetadata `json:"artifacts"`
+				}{
+					Artifacts: artifacts,
+				}, "", "  ")
+				if err != nil {
+					base.LogError("failed to render JSON: %v", err)
+					return err
+				}
+				base.UI.Output(string(bytes))
+				return nil
+			}
+			if len(artifacts) == 0 {
+				base.UI.Output("No artifacts found")
+				return nil
+			}
+			for _, artifact := range artifacts {
+				createdAt := time.UnixMilli(artifact.CreatedAt).Format(time.RFC3339)
+				base.UI.Output(fmt.Sprintf("%v %v %v", artifact.Hash, ui.Dim(createdAt), formatTags(artifact.Tags)))
+			}
+			return nil
+		},
+	}
+	cmd.Flags().StringToStringVar(&opts.tags, "tag", nil, "Only list artifacts with this key=value tag. Can be passed multiple times")
+	cmd.Flags().IntVar(&opts.limit, "limit", 0, "The maximum number of artifacts to list")
+	cmd.Flags().BoolVar(&opts.json, "json", false, "Output the artifacts in JSON format")
+	return cmd
+}
+
+// formatTags renders tags as a comma-separated list of key=value pairs, sorted by key
+func formatTags(tags map[string]string) string {
+	pairs := make([]string, 0, len(tags))
+	for key, value := range tags {
+		pairs = append(pairs, fmt.Sprintf("%v=%v", key, value))
+	}
+	sort.Strings(pairs)
+	return strings.Join(pairs, ", ")
+}
@@ -0,0 +1,195 @@
+package info
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/fs"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"strings"
+
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	"github.com/vercel/turborepo/cli/internal/daemonclient"
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// Diagnostics describes the environment turbo is running in, for use in bug reports
+type Diagnostics struct {
+	Version        string           `json:"version"`
+	RepoRoot       string           `json:"repoRoot"`
+	PackageManager string           `json:"packageManager"`
+	Git            string           `json:"git"`
+	Daemon         string           `json:"daemon"`
+	Caches         []CacheInfo      `json:"caches"`
+	RemoteCache    string           `json:"remoteCache"`
+	Platform       string           `json:"platform"`
+	Config         []config.Setting `json:"config"`
+}
+
+// CacheInfo is the location and size on disk of one of turbo's caches
+type CacheInfo struct {
+	Name  string `json:"name"`
+	Path  string `json:"path"`
+	Bytes int64  `json:"bytes"`
+}
+
+// InfoCmd returns the Cobra info command
+func InfoCmd(helper *cmdutil.Helper) *cobra.Command {
+	var outputJSON bool
+	cmd := &cobra.Command{
+		Use:           "info",
+		Short:         "Print information about the environment turbo is running in, for bug reports",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			diagnostics := collectDiagnostics(cmd, base)
+			if outputJSON {
+				rendered, err := json.MarshalIndent(diagnostics, "", "  ")
+				if err != nil {
+					base.LogError("failed to render JSON: %w", err)
+					return err
+				}
+				base.UI.Output(string(rendered))
+				return nil
+			}
+			base.UI.Output(fmt.Sprintf("turbo version:   %v", diagnostics.Version))
+			base.UI.Output(fmt.Sprintf("Repository root: %v", diagnostics.RepoRoot))
+			base.UI.Output(fmt.Sprintf("Package manager: %v", diagnostics.PackageManager))
+			base.UI.Output(fmt.Sprintf("git:             %v", diagnostics.Git))
+			base.UI.Output(fmt.Sprintf("Daemon:          %v", diagnostics.Daemon))
+			for _, cache := range diagnostics.Caches {
+				base.UI.Output(fmt.Sprintf("%-16v %v (%v)", cache.Name+":", cache.Path, util.FormatBytes(cache.Bytes)))
+			}
+			base.UI.Output(fmt.Sprintf("Remote cache:    %v", diagnostics.RemoteCache))
+			base.UI.Output(fmt.Sprintf("Platform:        %v", diagnostics.Platform))
+			base.UI.Output("Configuration:")
+			for _, setting := range diagnostics.Config {
+				value := setting.Value
+				if value == "" {
+					value = "(not set)"
+				}
+				base.UI.Output(fmt.Sprintf("  %-15v %v (%v)", setting.Name+":", value, setting.Source))
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&outputJSON, "json", false, "Output the information in JSON format")
+	return cmd
+}
+
+// collectDiagnostics gathers as much information as it can. Anything that can't be
+// determined is described rather than failing the command, since it is most useful
+// precisely when something is broken.
+func collectDiagnostics(cmd *cobra.Command, base *cmdutil.CmdBase) *Diagnostics {
+	diagnostics := &Diagnostics{
+		Version:        base.TurboVersion,
+		RepoRoot:       base.RepoRoot.ToString(),
+		PackageManager: packageManagerInfo(base.RepoRoot, base.RepoConfig.PackageManager()),
+		Git:            commandVersion("git", "--version"),
+		Daemon:         daemonInfo(cmd, base),
+		RemoteCache:    remoteCacheInfo(base),
+		Platform:       fmt.Sprintf("%v/%v, %v CPUs", runtime.GOOS, runtime.GOARCH, runtime.NumCPU()),
+		Config:         base.RepoConfig.Settings(),
+	}
+	cacheOpts := cache.Opts{OverrideDir: base.RepoConfig.CacheDir()}
+	localCache := cacheOpts.ResolveCacheDir(base.RepoRoot)
+	diagnostics.Caches = append(diagnostics.Caches, CacheInfo{
+		Name:  "Local cache",
+		Path:  localCache.ToString(),
+		Bytes: dirSize(localCache),
+	})
+	return diagnostics
+}
+
+func packageManagerInfo(repoRoot turbopath.AbsolutePath, packageManagerOverride string) string {
+	rootPackageJSON, err := turbofs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return fmt.Sprintf("unknown (failed to read package.json: %v)", err)
+	}
+	pm, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON, packageManagerOverride)
+	if err != nil {
+		return fmt.Sprintf("unknown (%v)", err)
+	}
+	if rootPackageJSON.PackageManager != "" {
+		if _, version, err := packagemanager.ParsePackageManagerString(rootPackageJSON.PackageManager); err == nil {
+			return fmt.Sprintf("%v %v (from packageManager)", pm.Slug, version)
+		}
+	}
+	return fmt.Sprintf("%v %v", pm.Slug, commandVersion(pm.Command, "--version"))
+}
+
+// commandVersion runs the given command and returns the first line of its output
+func commandVersion(command string, args ...string) string {
+	out, err := exec.Command(command, args...).Output()
+	if err != nil {
+		return fmt.Sprintf("unavailable (%v)", err)
+	}
+	return strings.SplitN(strings.TrimSpace(string(out)), "\n", 2)[0]
+}
+
+func daemonInfo(cmd *cobra.Command, base *cmdutil.CmdBase) string {
+	client, err := daemon.GetClient(cmd.Context(), base.RepoRoot, base.Logger, base.TurboVersion, daemon.ClientOpts{
+		// Reporting on the daemon shouldn't start it
+		DontStart: true,
+	})
+	if errors.Is(err, connector.ErrDaemonNotRunning) {
+		return "not running"
+	} else if err != nil {
+		return fmt.Sprintf("unavailable (%v)", err)
+	}
+	defer func() { _ = client.Close() }()
+	status, err := daemonclient.New(client).Status(cmd.Context())
+	if err != nil {
+		return fmt.Sprintf("unavailable (%v)", err)
+	}
+	return fmt.Sprintf("running, log file %v", status.LogFile)
+}
+
+func remoteCacheInfo(base *cmdutil.CmdBase) string {
+	if !base.APIClient.IsLinked() {
+		return fmt.Sprintf("not linked (%v)", base.RemoteConfig.APIURL)
+	}
+	status, err := base.APIClient.GetCachingStatus()
+	if err != nil {
+		return fmt.Sprintf("unreachable at %v (%v)", base.RemoteConfig.APIURL, err)
+	}
+	switch status {
+	case util.CachingStatusEnabled:
+		return fmt.Sprintf("enabled at %v", base.RemoteConfig.APIURL)
+	case util.CachingStatusOverLimit:
+		return fmt.Sprintf("over limit at %v", base.RemoteConfig.APIURL)
+	default:
+		return fmt.Sprintf("disabled at %v", base.RemoteConfig.APIURL)
+	}
+}
+
+// dirSize returns the total size of the files under dir, or 0 if it doesn't exist
+func dirSize(dir turbopath.AbsolutePath) int64 {
+	var size int64
+	_ = filepath.WalkDir(dir.ToString(), func(path string, entry fs.DirEntry, err error) error {
+		if err != nil {
+			return nil
+		}
+		if !entry.IsDir() {
+			if info, err := entry.Info(); err == nil {
+				size += info.Size()
+			}
+		}
+		return nil
+	})
+	return size
+}
@@ -0,0 +1,18 @@
+package info
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func Test_dirSize(t *testing.T) {
+	dir := fs.AbsolutePathFromUpstream(t.TempDir())
+	assert.Equal(t, dirSize(dir.Join("missing")), int64(0))
+
+	assert.NilError(t, dir.Join("a").WriteFile([]byte("12345"), 0644), "WriteFile")
+	assert.NilError(t, dir.Join("nested").MkdirAll(), "MkdirAll")
+	assert.NilError(t, dir.Join("nested", "b").WriteFile([]byte("123"), 0644), "WriteFile")
+	assert.Equal(t, dirSize(dir), int64(8))
+}
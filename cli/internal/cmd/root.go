@@ -9,16 +9,21 @@ import (
 	"github.com/pkg/errors"
 	"github.com/spf13/cobra"
 	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/cmd/artifacts"
 	"github.com/vercel/turborepo/cli/internal/cmd/auth"
 	"github.com/vercel/turborepo/cli/internal/cmd/info"
 	"github.com/vercel/turborepo/cli/internal/cmdutil"
 	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/doctor"
+	"github.com/vercel/turborepo/cli/internal/generate"
 	"github.com/vercel/turborepo/cli/internal/login"
 	"github.com/vercel/turborepo/cli/internal/process"
 	"github.com/vercel/turborepo/cli/internal/prune"
 	"github.com/vercel/turborepo/cli/internal/run"
 	"github.com/vercel/turborepo/cli/internal/signals"
+	"github.com/vercel/turborepo/cli/internal/skew"
 	"github.com/vercel/turborepo/cli/internal/util"
+	"github.com/vercel/turborepo/cli/internal/validate"
 )
 
 type execOpts struct {
@@ -76,8 +81,28 @@ func RunWithArgs(args []string, turboVersion string) int {
 
 const _defaultCmd string = "run"
 
+// _completionCmd is the name of cobra's default shell completion command
+const _completionCmd string = "completion"
+
 // resolveArgs adds a default command to the supplied arguments if none exists.
 func resolveArgs(root *cobra.Command, args []string) []string {
+	if len(args) > 0 {
+		switch args[0] {
+		case _completionCmd:
+			// cobra only registers the completion command when executing, so
+			// Traverse can't find it. Don't mistake it for a task.
+			return args
+		case cobra.ShellCompRequestCmd, cobra.ShellCompNoDescRequestCmd:
+			// Shell completion requests carry the command line being completed.
+			// Once there is at least one complete word, it needs the same default
+			// command as a regular invocation so that `turbo build --filter` completes
+			// like `turbo run build --filter`.
+			if len(args) > 2 {
+				return append([]string{args[0]}, resolveArgs(root, args[1:])...)
+			}
+			return args
+		}
+	}
 	for _, arg := range args {
 		if arg == "--help" || arg == "-h" || arg == "--version" {
 			return args
@@ -139,10 +164,18 @@ func getCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Comma
 	cmd.AddCommand(login.NewLoginCommand(helper))
 	cmd.AddCommand(auth.LogoutCmd(helper))
 	cmd.AddCommand(auth.UnlinkCmd(helper))
+	cmd.AddCommand(artifacts.GetCmd(helper))
 	cmd.AddCommand(info.BinCmd(helper))
+	cmd.AddCommand(info.InfoCmd(helper))
 	cmd.AddCommand(daemon.GetCmd(helper, signalWatcher))
+	cmd.AddCommand(doctor.GetCmd(helper))
+	cmd.AddCommand(generate.GetCmd(helper))
+	cmd.AddCommand(generate.InitCmd(helper))
+	cmd.AddCommand(run.GetPrefetchCmd(helper, signalWatcher))
 	cmd.AddCommand(prune.GetCmd(helper))
 	cmd.AddCommand(run.GetCmd(helper, signalWatcher))
+	cmd.AddCommand(skew.GetCmd(helper))
+	cmd.AddCommand(validate.GetCmd(helper))
 	return cmd
 }
 
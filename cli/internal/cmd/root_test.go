@@ -44,6 +44,26 @@ func TestDefaultCmd(t *testing.T) {
 			args:         []string{"--heap", "my-heap-profile", "some-task", "--cpuprofile", "my-profile"},
 			defaultAdded: true,
 		},
+		{
+			name:         "completion",
+			args:         []string{"completion", "zsh"},
+			defaultAdded: false,
+		},
+		{
+			name:         "complete root",
+			args:         []string{"__complete", ""},
+			defaultAdded: false,
+		},
+		{
+			name:         "complete run",
+			args:         []string{"__complete", "run", "build", "--filter", ""},
+			defaultAdded: false,
+		},
+		{
+			name:         "complete implicit run",
+			args:         []string{"__complete", "build", "--filter", ""},
+			defaultAdded: true,
+		},
 	}
 	for _, tc := range testCases {
 		args := tc.args
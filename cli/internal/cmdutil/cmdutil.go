@@ -15,6 +15,7 @@ import (
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/client"
 	"github.com/vercel/turborepo/cli/internal/config"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/ui"
@@ -25,6 +26,11 @@ const (
 	_envLogLevel = "TURBO_LOG_LEVEL"
 )
 
+const (
+	_errorFormatText = "text"
+	_errorFormatJSON = "json"
+)
+
 // Helper is a struct used to hold configuration values passed via flag, env vars,
 // config files, etc. It is not intended for direct use by turbo commands, it drives
 // the creation of CmdBase, which is then used by the commands themselves.
@@ -37,6 +43,8 @@ type Helper struct {
 	noColor    bool
 	// for logging
 	verbosity int
+	// errorFormat is how errors are printed, as text or as one line of JSON per error
+	errorFormat string
 
 	rawRepoRoot string
 
@@ -46,6 +54,10 @@ type Helper struct {
 	// a user-specific config file, if one is present. Public
 	// to allow overrides in tests
 	UserConfigPath turbopath.AbsolutePath
+	// GlobalConfigPath is the path to the config file with the
+	// user's defaults for every repository. Public to allow
+	// overrides in tests
+	GlobalConfigPath turbopath.AbsolutePath
 
 	cleanupsMu sync.Mutex
 	cleanups   []io.Closer
@@ -129,6 +141,7 @@ func (h *Helper) AddFlags(flags *pflag.FlagSet) {
 	flags.BoolVar(&h.forceColor, "color", false, "Force color usage in the terminal")
 	flags.BoolVar(&h.noColor, "no-color", false, "Suppress color usage in the terminal")
 	flags.CountVarP(&h.verbosity, "verbosity", "v", "verbosity")
+	flags.StringVar(&h.errorFormat, "error-format", _errorFormatText, "How to print errors: text, or json for a line of JSON with the error's code, message and location")
 	flags.StringVar(&h.rawRepoRoot, "cwd", "", "The directory in which to run turbo")
 	client.AddFlags(&h.clientOpts, flags)
 	config.AddRepoConfigFlags(flags)
@@ -139,8 +152,9 @@ func (h *Helper) AddFlags(flags *pflag.FlagSet) {
 // turbo command.
 func NewHelper(turboVersion string) *Helper {
 	return &Helper{
-		TurboVersion:   turboVersion,
-		UserConfigPath: config.DefaultUserConfigPath(),
+		TurboVersion:     turboVersion,
+		UserConfigPath:   config.DefaultUserConfigPath(),
+		GlobalConfigPath: config.DefaultGlobalConfigPath(),
 	}
 }
 
@@ -148,6 +162,9 @@ func NewHelper(turboVersion string) *Helper {
 // It additionally returns a mechanism to set an error, so
 func (h *Helper) GetCmdBase(flags *pflag.FlagSet) (*CmdBase, error) {
 	terminal := h.getUI(flags)
+	if h.errorFormat != _errorFormatText && h.errorFormat != _errorFormatJSON {
+		return nil, fmt.Errorf("invalid error format %q, expected %v or %v", h.errorFormat, _errorFormatText, _errorFormatJSON)
+	}
 	logger, err := h.getLogger()
 	if err != nil {
 		return nil, err
@@ -161,7 +178,7 @@ func (h *Helper) GetCmdBase(flags *pflag.FlagSet) (*CmdBase, error) {
 	if err != nil {
 		return nil, err
 	}
-	repoConfig, err := config.ReadRepoConfigFile(config.GetRepoConfigPath(repoRoot), flags)
+	repoConfig, err := config.ReadRepoConfigFile(config.GetRepoConfigPath(repoRoot), h.GlobalConfigPath, flags)
 	if err != nil {
 		return nil, err
 	}
@@ -195,6 +212,7 @@ func (h *Helper) GetCmdBase(flags *pflag.FlagSet) (*CmdBase, error) {
 		UserConfig:   userConfig,
 		RemoteConfig: remoteConfig,
 		TurboVersion: h.TurboVersion,
+		errorFormat:  h.errorFormat,
 	}, nil
 }
 
@@ -208,11 +226,32 @@ type CmdBase struct {
 	UserConfig   *config.UserConfig
 	RemoteConfig client.RemoteConfig
 	TurboVersion string
+
+	errorFormat string
 }
 
-// LogError prints an error to the UI
+// LogError prints an error to the UI. With --error-format=json, it prints a line of JSON
+// that carries the code and span of the first error among args.
 func (b *CmdBase) LogError(format string, args ...interface{}) {
 	err := fmt.Errorf(format, args...)
 	b.Logger.Error("error", err)
+	if b.errorFormat == _errorFormatJSON {
+		b.UI.Error(errorJSON(err, args))
+		return
+	}
 	b.UI.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", err)))
 }
+
+// errorJSON renders err, the formatted message, as JSON, taking its code and span from
+// the first error among the args it was formatted from
+func errorJSON(err error, args []interface{}) string {
+	report := errcode.NewReport(err)
+	for _, arg := range args {
+		if cause, ok := arg.(error); ok {
+			report = errcode.NewReport(cause)
+			report.Message = err.Error()
+			break
+		}
+	}
+	return report.JSON()
+}
@@ -1,10 +1,12 @@
 package cmdutil
 
 import (
+	"fmt"
 	"os"
 	"testing"
 
 	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"gotest.tools/v3/assert"
 )
@@ -39,3 +41,10 @@ func TestTokenEnvVar(t *testing.T) {
 		})
 	}
 }
+
+func TestErrorJSON(t *testing.T) {
+	cause := errcode.New(errcode.RunLocked, "another turbo run is in progress")
+	err := fmt.Errorf("run failed: %v", cause)
+	assert.Equal(t, errorJSON(err, []interface{}{cause}), `{"code":"TURBO_RUN_001","message":"run failed: another turbo run is in progress"}`)
+	assert.Equal(t, errorJSON(err, []interface{}{"build"}), `{"code":"TURBO_UNKNOWN","message":"run failed: another turbo run is in progress"}`)
+}
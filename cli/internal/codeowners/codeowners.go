@@ -0,0 +1,128 @@
+// Package codeowners reads the CODEOWNERS file of a repository, so that the owners of
+// workspaces can be reported alongside them.
+package codeowners
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// _locations are where a CODEOWNERS file is looked for, in the order that GitHub checks them
+var _locations = [][]string{
+	{".github", "CODEOWNERS"},
+	{"CODEOWNERS"},
+	{"docs", "CODEOWNERS"},
+}
+
+// rule is a line of a CODEOWNERS file
+type rule struct {
+	// glob is the pattern of the line, as a doublestar glob relative to the repo root
+	glob   string
+	owners []string
+}
+
+// Owners are the rules of a CODEOWNERS file
+type Owners struct {
+	// Path is the repo-relative path of the file the rules were read from
+	Path  string
+	rules []rule
+}
+
+// Read loads the CODEOWNERS file of the repository. It returns nil, without an error, if
+// the repository doesn't have one.
+func Read(repoRoot turbopath.AbsolutePath) (*Owners, error) {
+	for _, location := range _locations {
+		path := repoRoot.Join(location...)
+		if !path.FileExists() {
+			continue
+		}
+		contents, err := path.ReadFile()
+		if err != nil {
+			return nil, err
+		}
+		owners, err := Parse(contents)
+		if err != nil {
+			return nil, fmt.Errorf("%v: %w", filepath.Join(location...), err)
+		}
+		owners.Path = filepath.ToSlash(filepath.Join(location...))
+		return owners, nil
+	}
+	return nil, nil
+}
+
+// Parse reads the rules of a CODEOWNERS file
+func Parse(contents []byte) (*Owners, error) {
+	owners := &Owners{}
+	scanner := bufio.NewScanner(bytes.NewReader(contents))
+	lineNumber := 0
+	for scanner.Scan() {
+		lineNumber++
+		line := strings.TrimSpace(scanner.Text())
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		// Everything after a # is a comment, including on lines with a pattern
+		if i := strings.Index(line, " #"); i >= 0 {
+			line = line[:i]
+		}
+		fields := strings.Fields(line)
+		glob := toGlob(fields[0])
+		if !doublestar.ValidatePattern(glob) {
+			return nil, fmt.Errorf("line %v: invalid pattern %q", lineNumber, fields[0])
+		}
+		// A pattern without owners unsets the owners of the files it matches
+		owners.rules = append(owners.rules, rule{glob: glob, owners: fields[1:]})
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	return owners, nil
+}
+
+// toGlob converts a gitignore-style CODEOWNERS pattern to a glob. Patterns that contain a
+// "/" anywhere but at the end are relative to the repo root, and any others match at
+// any depth.
+func toGlob(pattern string) string {
+	anchored := strings.Contains(strings.TrimSuffix(pattern, "/"), "/")
+	glob := strings.Trim(pattern, "/")
+	if !anchored && glob != "**" {
+		glob = "**/" + glob
+	}
+	return glob
+}
+
+// Of returns the owners of a repo-relative path. As in git, a pattern that matches a
+// directory applies to everything inside of it, and the last matching rule wins.
+func (o *Owners) Of(path string) []string {
+	if o == nil {
+		return nil
+	}
+	path = strings.Trim(filepath.ToSlash(path), "/")
+	for i := len(o.rules) - 1; i >= 0; i-- {
+		if o.rules[i].matches(path) {
+			return o.rules[i].owners
+		}
+	}
+	return nil
+}
+
+// matches returns true if the rule matches the path or one of the directories it's in
+func (r rule) matches(path string) bool {
+	for prefix := path; prefix != ""; {
+		if ok, _ := doublestar.Match(r.glob, prefix); ok {
+			return true
+		}
+		i := strings.LastIndex(prefix, "/")
+		if i < 0 {
+			break
+		}
+		prefix = prefix[:i]
+	}
+	return false
+}
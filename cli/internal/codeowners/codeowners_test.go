@@ -0,0 +1,62 @@
+package codeowners
+
+import (
+	"os"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+func TestOf(t *testing.T) {
+	owners, err := Parse([]byte(`# Default owners
+*                  @acme/platform
+
+apps/              @acme/apps
+/apps/web/         @acme/web @alice # the storefront
+packages/ui        @acme/design
+packages/ui/icons
+docs/              @acme/docs
+`))
+	assert.NilError(t, err)
+
+	cases := map[string][]string{
+		"README.md":               {"@acme/platform"},
+		"apps/admin":              {"@acme/apps"},
+		"apps/web":                {"@acme/web", "@alice"},
+		"apps/web/src/index.ts":   {"@acme/web", "@alice"},
+		"packages/ui":             {"@acme/design"},
+		"packages/ui/icons":       {},
+		"packages/config":         {"@acme/platform"},
+		"packages/config/docs":    {"@acme/docs"},
+		"packages/apps/something": {"@acme/apps"},
+	}
+	for path, expected := range cases {
+		assert.DeepEqual(t, owners.Of(path), expected)
+	}
+}
+
+func TestOfWithoutCodeowners(t *testing.T) {
+	var owners *Owners
+	assert.Assert(t, owners.Of("apps/web") == nil)
+}
+
+func TestParseInvalidPattern(t *testing.T) {
+	_, err := Parse([]byte("\n[ @acme/platform\n"))
+	assert.ErrorContains(t, err, "line 2")
+}
+
+func TestRead(t *testing.T) {
+	repoRoot := turbopath.AbsolutePath(t.TempDir())
+	owners, err := Read(repoRoot)
+	assert.NilError(t, err)
+	assert.Assert(t, owners == nil)
+
+	assert.NilError(t, os.MkdirAll(repoRoot.Join(".github").ToString(), 0755))
+	assert.NilError(t, os.WriteFile(repoRoot.Join(".github", "CODEOWNERS").ToString(), []byte("* @acme/platform\n"), 0644))
+	assert.NilError(t, os.WriteFile(repoRoot.Join("CODEOWNERS").ToString(), []byte("* @acme/other\n"), 0644))
+	owners, err = Read(repoRoot)
+	assert.NilError(t, err)
+	assert.Equal(t, owners.Path, ".github/CODEOWNERS")
+	assert.DeepEqual(t, owners.Of("apps/web"), []string{"@acme/platform"})
+}
@@ -1,6 +1,7 @@
 package config
 
 import (
+	"fmt"
 	"os"
 
 	"github.com/spf13/pflag"
@@ -14,11 +15,122 @@ import (
 type RepoConfig struct {
 	repoViper *viper.Viper
 	path      turbopath.AbsolutePath
+	// globalViper holds the user's defaults from the global config file, which apply
+	// beneath the repository's config file
+	globalViper *viper.Viper
+	globalPath  turbopath.AbsolutePath
+	flags       *pflag.FlagSet
+}
+
+// setting is a configuration key, along with the environment variable and flag that
+// override it, if any
+type setting struct {
+	key  string
+	name string
+	env  string
+	flag string
+	// global is true if the global config file can set a default for it
+	global bool
+}
+
+// _settings are the configuration values, in the order turbo info lists them
+var _settings = []setting{
+	{key: "apiurl", name: "apiUrl", env: "TURBO_API", global: true},
+	{key: "loginurl", name: "loginUrl", env: "TURBO_LOGIN", flag: "login", global: true},
+	{key: "teamslug", name: "teamSlug", env: "TURBO_TEAM"},
+	{key: "teamid", name: "teamId", env: "TURBO_TEAMID"},
+	{key: "packagemanager", name: "packageManager", env: "TURBO_PACKAGE_MANAGER", flag: "package-manager"},
+	{key: "cachedir", name: "cacheDir", global: true},
+	{key: "cachestore", name: "cacheStore", global: true},
+	{key: "outputlogs", name: "outputLogs", global: true},
+	{key: "telemetry", name: "telemetry", global: true},
+}
+
+// Setting is a configuration value and where it was set
+type Setting struct {
+	Name   string `json:"name"`
+	Value  string `json:"value"`
+	Source string `json:"source"`
+}
+
+// Settings returns every configuration value along with where it came from: a flag, an
+// environment variable, the repository's config file, the global config file or turbo's
+// defaults, in that order of precedence
+func (rc *RepoConfig) Settings() []Setting {
+	settings := make([]Setting, 0, len(_settings))
+	for _, s := range _settings {
+		v, source := rc.lookup(s)
+		settings = append(settings, Setting{Name: s.name, Value: v.GetString(s.key), Source: source})
+	}
+	return settings
+}
+
+// lookup returns the viper instance that holds the value for s, and a description of
+// where the value was set
+func (rc *RepoConfig) lookup(s setting) (*viper.Viper, string) {
+	if s.flag != "" && rc.flags != nil {
+		if flag := rc.flags.Lookup(s.flag); flag != nil && flag.Changed {
+			return rc.repoViper, fmt.Sprintf("--%v flag", s.flag)
+		}
+	}
+	if s.env != "" && os.Getenv(s.env) != "" {
+		return rc.repoViper, fmt.Sprintf("%v environment variable", s.env)
+	}
+	if rc.repoViper.InConfig(s.key) {
+		return rc.repoViper, rc.path.ToString()
+	}
+	if s.global && rc.globalViper != nil && rc.globalViper.InConfig(s.key) {
+		return rc.globalViper, rc.globalPath.ToString()
+	}
+	return rc.repoViper, "default"
+}
+
+// get returns the viper instance that holds the value for key
+func (rc *RepoConfig) get(key string) *viper.Viper {
+	for _, s := range _settings {
+		if s.key == key {
+			v, _ := rc.lookup(s)
+			return v
+		}
+	}
+	return rc.repoViper
+}
+
+func (rc *RepoConfig) getString(key string) string {
+	return rc.get(key).GetString(key)
 }
 
 // LoginURL returns the configured URL for authenticating the user
 func (rc *RepoConfig) LoginURL() string {
-	return rc.repoViper.GetString("loginurl")
+	return rc.getString("loginurl")
+}
+
+// PackageManager returns the package manager this repository is configured to use, if any.
+// It takes precedence over the packageManager field in package.json and over detection.
+func (rc *RepoConfig) PackageManager() string {
+	return rc.getString("packagemanager")
+}
+
+// CacheDir returns the configured directory for the local cache, if any. --cache-dir
+// takes precedence over it.
+func (rc *RepoConfig) CacheDir() string {
+	return rc.getString("cachedir")
+}
+
+// CacheStore returns the configured default for --cache-store, if any
+func (rc *RepoConfig) CacheStore() string {
+	return rc.getString("cachestore")
+}
+
+// OutputLogs returns the configured default for --output-logs, if any
+func (rc *RepoConfig) OutputLogs() string {
+	return rc.getString("outputlogs")
+}
+
+// Telemetry returns false if the user opted out of sending analytics events about cache
+// usage to the remote cache
+func (rc *RepoConfig) Telemetry() bool {
+	return rc.get("telemetry").GetBool("telemetry")
 }
 
 // SetTeamID sets the teamID and clears the slug, since it may have been from an old team
@@ -40,16 +152,26 @@ func (rc *RepoConfig) GetRemoteConfig(token string) client.RemoteConfig {
 		Token:    token,
 		TeamID:   rc.repoViper.GetString("teamid"),
 		TeamSlug: rc.repoViper.GetString("teamslug"),
-		APIURL:   rc.repoViper.GetString("apiurl"),
+		APIURL:   rc.getString("apiurl"),
 	}
 }
 
-// Internal call to save this config data to the user config file.
+// Internal call to save this config data to the repo config file. Only the values from the
+// file, and those merged into it, are written, so that defaults don't shadow the global
+// config file when the repo config is read again.
 func (rc *RepoConfig) write() error {
 	if err := rc.path.EnsureDir(); err != nil {
 		return err
 	}
-	return rc.repoViper.WriteConfig()
+	fileViper := viper.New()
+	fileViper.SetConfigFile(rc.path.ToString())
+	fileViper.SetConfigType("json")
+	for _, key := range rc.repoViper.AllKeys() {
+		if rc.repoViper.InConfig(key) {
+			fileViper.Set(key, rc.repoViper.Get(key))
+		}
+	}
+	return fileViper.WriteConfig()
 }
 
 // Delete deletes the config file. This repo config shouldn't be used
@@ -131,11 +253,17 @@ const (
 	_defaultLoginURL = "https://vercel.com"
 )
 
+// DefaultGlobalConfigPath returns where the user's defaults for every repository are kept
+func DefaultGlobalConfigPath() turbopath.AbsolutePath {
+	return fs.GetGlobalConfigDir().Join("config.json")
+}
+
 // ReadRepoConfigFile creates a RepoConfig using the
-// specified path as the repo config file. Note that the path or its
+// specified path as the repo config file, and globalPath as the file
+// with the user's defaults beneath it. Note that the paths or their
 // parents do not need to exist. On a write to this configuration, they
 // will be created.
-func ReadRepoConfigFile(path turbopath.AbsolutePath, flags *pflag.FlagSet) (*RepoConfig, error) {
+func ReadRepoConfigFile(path turbopath.AbsolutePath, globalPath turbopath.AbsolutePath, flags *pflag.FlagSet) (*RepoConfig, error) {
 	repoViper := viper.New()
 	repoViper.SetConfigFile(path.ToString())
 	repoViper.SetConfigType("json")
@@ -144,17 +272,31 @@ func ReadRepoConfigFile(path turbopath.AbsolutePath, flags *pflag.FlagSet) (*Rep
 	repoViper.MustBindEnv("loginurl", "TURBO_LOGIN")
 	repoViper.MustBindEnv("teamslug", "TURBO_TEAM")
 	repoViper.MustBindEnv("teamid")
+	repoViper.MustBindEnv("packagemanager", "TURBO_PACKAGE_MANAGER")
 	repoViper.SetDefault("apiurl", _defaultAPIURL)
 	repoViper.SetDefault("loginurl", _defaultLoginURL)
+	repoViper.SetDefault("telemetry", true)
 	if err := repoViper.BindPFlag("loginurl", flags.Lookup("login")); err != nil {
 		return nil, err
 	}
+	if err := repoViper.BindPFlag("packagemanager", flags.Lookup("package-manager")); err != nil {
+		return nil, err
+	}
 	if err := repoViper.ReadInConfig(); err != nil && !os.IsNotExist(err) {
 		return nil, err
 	}
+	globalViper := viper.New()
+	globalViper.SetConfigFile(globalPath.ToString())
+	globalViper.SetConfigType("json")
+	if err := globalViper.ReadInConfig(); err != nil && !os.IsNotExist(err) {
+		return nil, fmt.Errorf("%v: %w", globalPath, err)
+	}
 	return &RepoConfig{
-		repoViper: repoViper,
-		path:      path,
+		repoViper:   repoViper,
+		path:        path,
+		globalViper: globalViper,
+		globalPath:  globalPath,
+		flags:       flags,
 	}, nil
 }
 
@@ -163,6 +305,7 @@ func AddRepoConfigFlags(flags *pflag.FlagSet) {
 	flags.String("team", "", "Set the team slug for API calls")
 	flags.String("api", "", "Override the endpoint for API calls")
 	flags.String("login", "", "Override the login endpoint")
+	flags.String("package-manager", "", "Override the detected package manager, as a name like yarn or a name@version string")
 }
 
 // GetRepoConfigPath reads the user-specific configuration values
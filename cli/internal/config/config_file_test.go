@@ -2,19 +2,26 @@ package config
 
 import (
 	"fmt"
+	"strings"
 	"testing"
 
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"gotest.tools/v3/assert"
 )
 
+// noGlobalConfig returns the path of a global config file that doesn't exist
+func noGlobalConfig(t *testing.T) turbopath.AbsolutePath {
+	return fs.AbsolutePathFromUpstream(t.TempDir()).Join("turbo", "config.json")
+}
+
 func TestReadRepoConfigWhenMissing(t *testing.T) {
 	testDir := fs.AbsolutePathFromUpstream(t.TempDir()).Join("config.json")
 	flags := pflag.NewFlagSet("test-flags", pflag.ContinueOnError)
 	AddRepoConfigFlags(flags)
 
-	config, err := ReadRepoConfigFile(testDir, flags)
+	config, err := ReadRepoConfigFile(testDir, noGlobalConfig(t), flags)
 	if err != nil {
 		t.Errorf("got error reading non-existent config file: %v, want <nil>", err)
 	}
@@ -33,7 +40,7 @@ func TestRepoConfigIncludesDefaults(t *testing.T) {
 	assert.NilError(t, testConfigFile.EnsureDir(), "EnsureDir")
 	assert.NilError(t, testConfigFile.WriteFile([]byte(fmt.Sprintf(`{"teamSlug":"%v"}`, expectedTeam)), 0644), "WriteFile")
 
-	config, err := ReadRepoConfigFile(testConfigFile, flags)
+	config, err := ReadRepoConfigFile(testConfigFile, noGlobalConfig(t), flags)
 	if err != nil {
 		t.Errorf("ReadRepoConfigFile err got %v, want <nil>", err)
 	}
@@ -58,14 +65,14 @@ func TestWriteRepoConfig(t *testing.T) {
 	assert.NilError(t, testConfigFile.EnsureDir(), "EnsureDir")
 	assert.NilError(t, testConfigFile.WriteFile([]byte(fmt.Sprintf(`{"teamSlug":"%v"}`, expectedTeam)), 0644), "WriteFile")
 
-	initial, err := ReadRepoConfigFile(testConfigFile, flags)
+	initial, err := ReadRepoConfigFile(testConfigFile, noGlobalConfig(t), flags)
 	assert.NilError(t, err, "GetRepoConfig")
 	// setting the teamID should clear the slug, since it may have been from an old team
 	expectedTeamID := "my-team-id"
 	err = initial.SetTeamID(expectedTeamID)
 	assert.NilError(t, err, "SetTeamID")
 
-	config, err := ReadRepoConfigFile(testConfigFile, flags)
+	config, err := ReadRepoConfigFile(testConfigFile, noGlobalConfig(t), flags)
 	if err != nil {
 		t.Errorf("ReadRepoConfig err got %v, want <nil>", err)
 	}
@@ -118,3 +125,64 @@ func TestUserConfigFlags(t *testing.T) {
 	assert.Equal(t, userConfig.Token(), "my-token")
 	assert.Equal(t, userConfig.path, configPath)
 }
+
+func TestRepoConfigPackageManager(t *testing.T) {
+	testConfigFile := fs.AbsolutePathFromUpstream(t.TempDir()).Join("turborepo", "config.json")
+	flags := pflag.NewFlagSet("test-flags", pflag.ContinueOnError)
+	AddRepoConfigFlags(flags)
+
+	assert.NilError(t, testConfigFile.EnsureDir(), "EnsureDir")
+	assert.NilError(t, testConfigFile.WriteFile([]byte(`{"packageManager":"yarn"}`), 0644), "WriteFile")
+	config, err := ReadRepoConfigFile(testConfigFile, noGlobalConfig(t), flags)
+	assert.NilError(t, err, "ReadRepoConfigFile")
+	assert.Equal(t, config.PackageManager(), "yarn")
+
+	assert.NilError(t, flags.Set("package-manager", "pnpm@7.14.0"), "set flag")
+	config, err = ReadRepoConfigFile(testConfigFile, noGlobalConfig(t), flags)
+	assert.NilError(t, err, "ReadRepoConfigFile")
+	assert.Equal(t, config.PackageManager(), "pnpm@7.14.0")
+}
+
+func TestGlobalConfig(t *testing.T) {
+	repoConfigFile := fs.AbsolutePathFromUpstream(t.TempDir()).Join(".turbo", "config.json")
+	globalConfigFile := fs.AbsolutePathFromUpstream(t.TempDir()).Join("turbo", "config.json")
+	flags := pflag.NewFlagSet("test-flags", pflag.ContinueOnError)
+	AddRepoConfigFlags(flags)
+
+	assert.NilError(t, globalConfigFile.EnsureDir(), "EnsureDir")
+	assert.NilError(t, globalConfigFile.WriteFile([]byte(`{"apiUrl":"https://cache.example.com","cacheDir":"/mnt/big/turbo","telemetry":false,"teamSlug":"not-global"}`), 0644), "WriteFile")
+	assert.NilError(t, repoConfigFile.EnsureDir(), "EnsureDir")
+	assert.NilError(t, repoConfigFile.WriteFile([]byte(`{"cacheDir":"../cache"}`), 0644), "WriteFile")
+
+	config, err := ReadRepoConfigFile(repoConfigFile, globalConfigFile, flags)
+	assert.NilError(t, err, "ReadRepoConfigFile")
+	assert.Equal(t, config.GetRemoteConfig("").APIURL, "https://cache.example.com")
+	assert.Equal(t, config.CacheDir(), "../cache")
+	assert.Equal(t, config.Telemetry(), false)
+	// Settings that belong to a repository can't be set globally
+	assert.Equal(t, config.GetRemoteConfig("").TeamSlug, "")
+
+	sources := map[string]string{}
+	for _, setting := range config.Settings() {
+		sources[setting.Name] = setting.Source
+	}
+	assert.Equal(t, sources["apiUrl"], globalConfigFile.ToString())
+	assert.Equal(t, sources["cacheDir"], repoConfigFile.ToString())
+	assert.Equal(t, sources["loginUrl"], "default")
+
+	assert.NilError(t, flags.Set("login", "https://login.example.com"), "set flag")
+	config, err = ReadRepoConfigFile(repoConfigFile, globalConfigFile, flags)
+	assert.NilError(t, err, "ReadRepoConfigFile")
+	assert.Equal(t, config.LoginURL(), "https://login.example.com")
+	assert.Equal(t, config.Settings()[1], Setting{Name: "loginUrl", Value: "https://login.example.com", Source: "--login flag"})
+
+	// Writing the repo config keeps defaults and global values out of it
+	assert.NilError(t, config.SetTeamID("my-team-id"), "SetTeamID")
+	contents, err := repoConfigFile.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Assert(t, !strings.Contains(string(contents), "apiurl"), "repo config got %v", string(contents))
+	config, err = ReadRepoConfigFile(repoConfigFile, globalConfigFile, flags)
+	assert.NilError(t, err, "ReadRepoConfigFile")
+	assert.Equal(t, config.GetRemoteConfig("").APIURL, "https://cache.example.com")
+	assert.Equal(t, config.GetRemoteConfig("").TeamID, "my-team-id")
+}
@@ -29,6 +29,20 @@ type Context struct {
 	RootNode         string
 	Lockfile         lockfile.Lockfile
 	PackageManager   *packagemanager.PackageManager
+	// TypesOnlyDependencies maps a workspace to the workspace dependencies that it only
+	// consumes types from, as declared by "dependsOnTypesOnly" in its package.json
+	TypesOnlyDependencies map[string]util.Set
+	// InjectedDependencies maps a workspace to the workspace dependencies that pnpm installs
+	// into it as copies, as declared by "dependenciesMeta" in its package.json
+	InjectedDependencies map[string]util.Set
+	// Catalogs are the dependency versions that workspaces can reference with the
+	// "catalog:" protocol, as defined by pnpm-workspace.yaml
+	Catalogs packagemanager.Catalogs
+	// ExternalRepoRoots are the roots of the turborepos outside of this one whose workspaces
+	// are in the graph, as set by WithExternalRepos
+	ExternalRepoRoots []turbopath.AbsolutePath
+	// externalPackages maps the workspaces of the external repos to the repo they are in
+	externalPackages map[string]turbopath.AbsolutePath
 	// Used to arbitrate access to the graph. We parallelise most build operations
 	// and Go maps aren't natively threadsafe so this is needed.
 	mutex sync.Mutex
@@ -116,15 +130,27 @@ func isWorkspaceReference(packageVersion string, dependencyVersion string, cwd s
 	return constraint.Check(pkgVersion)
 }
 
+// WithExternalRepos adds the workspaces of the turborepos at the given roots to the package
+// graph that WithGraph builds, so it must come before it. Each repo's graph is resolved with
+// its own package manager and lockfile, and the workspaces of this repo that depend on them
+// get edges to them.
+func WithExternalRepos(roots []turbopath.AbsolutePath) Option {
+	return func(c *Context) error {
+		c.ExternalRepoRoots = roots
+		return nil
+	}
+}
+
 // WithGraph attaches information about the package dependency graph to the Context instance being
-// constructed.
-func WithGraph(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON, cacheDir turbopath.AbsolutePath) Option {
+// constructed. A non-empty packageManagerOverride replaces package manager detection.
+func WithGraph(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON, cacheDir turbopath.AbsolutePath, packageManagerOverride string) Option {
 	return func(c *Context) error {
 		rootpath := repoRoot.ToStringDuringMigration()
 		c.PackageInfos = make(map[interface{}]*fs.PackageJSON)
+		c.InjectedDependencies = make(map[string]util.Set)
 		c.RootNode = core.ROOT_NODE_NAME
 
-		if packageManager, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON); err != nil {
+		if packageManager, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON, packageManagerOverride); err != nil {
 			return err
 		} else {
 			c.PackageManager = packageManager
@@ -136,6 +162,12 @@ func WithGraph(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON,
 		}
 		c.Lockfile = lockfile
 
+		catalogs, err := c.PackageManager.GetCatalogs(repoRoot)
+		if err != nil {
+			return err
+		}
+		c.Catalogs = catalogs
+
 		if err := c.resolveWorkspaceRootDeps(rootPackageJSON); err != nil {
 			// TODO(Gaspar) was this the intended return error?
 			return fmt.Errorf("could not resolve workspaces: %w", err)
@@ -163,9 +195,19 @@ func WithGraph(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON,
 		if err := parseJSONWaitGroup.Wait(); err != nil {
 			return err
 		}
+		// The external repos' workspaces have to be known before ours are linked to them
+		c.externalPackages = make(map[string]turbopath.AbsolutePath)
+		for _, externalRoot := range c.ExternalRepoRoots {
+			if err := c.addExternalRepo(repoRoot, externalRoot, cacheDir); err != nil {
+				return fmt.Errorf("external repo %v: %w", externalRoot, err)
+			}
+		}
 		populateGraphWaitGroup := &errgroup.Group{}
 		for _, pkg := range c.PackageInfos {
 			pkg := pkg
+			if _, ok := c.externalPackages[pkg.Name]; ok {
+				continue
+			}
 			populateGraphWaitGroup.Go(func() error {
 				return c.populateTopologicGraphForPackageJSON(pkg, rootpath, pkg.Name)
 			})
@@ -183,10 +225,74 @@ func WithGraph(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON,
 		}
 		c.PackageInfos[util.RootPkgName] = rootPackageJSON
 
-		return nil
+		return c.resolveTypesOnlyDependencies()
 	}
 }
 
+// addExternalRepo resolves the package graph of the turborepo at externalRoot and merges its
+// workspaces into this one's, with their directories relative to repoRoot. Its root package
+// is left out.
+func (c *Context) addExternalRepo(repoRoot turbopath.AbsolutePath, externalRoot turbopath.AbsolutePath, cacheDir turbopath.AbsolutePath) error {
+	rootPackageJSON, err := fs.ReadPackageJSON(externalRoot.Join("package.json"))
+	if err != nil {
+		return fmt.Errorf("failed to read package.json: %w", err)
+	}
+	external, err := New(WithGraph(externalRoot, rootPackageJSON, cacheDir, ""))
+	if err != nil {
+		return err
+	}
+	for _, name := range external.PackageNames {
+		pkg := external.PackageInfos[name]
+		if _, ok := c.PackageInfos[name]; ok {
+			return fmt.Errorf("%v is a workspace of more than one repo", name)
+		}
+		for _, path := range []*turbopath.AnchoredSystemPath{&pkg.Dir, &pkg.PackageJSONPath} {
+			relativePath, err := repoRoot.PathTo(externalRoot.Join(path.ToStringDuringMigration()))
+			if err != nil {
+				return err
+			}
+			*path = turbopath.AnchoredSystemPathFromUpstream(relativePath)
+		}
+		c.TopologicalGraph.Add(name)
+		for _, dep := range pkg.InternalDeps {
+			c.TopologicalGraph.Add(dep)
+			c.TopologicalGraph.Connect(dag.BasicEdge(name, dep))
+		}
+		if len(pkg.InternalDeps) == 0 {
+			c.TopologicalGraph.Connect(dag.BasicEdge(name, core.ROOT_NODE_NAME))
+		}
+		if injected, ok := external.InjectedDependencies[name]; ok {
+			c.InjectedDependencies[name] = injected
+		}
+		c.PackageInfos[name] = pkg
+		c.PackageNames = append(c.PackageNames, name)
+		c.externalPackages[name] = externalRoot
+	}
+	return nil
+}
+
+// resolveTypesOnlyDependencies collects the "dependsOnTypesOnly" declarations of each workspace,
+// which must name workspaces that the workspace depends on
+func (c *Context) resolveTypesOnlyDependencies() error {
+	c.TypesOnlyDependencies = make(map[string]util.Set)
+	for name, pkg := range c.PackageInfos {
+		// The root's "turbo" key is the legacy location of turbo.json
+		if name == util.RootPkgName || pkg.LegacyTurboConfig == nil || len(pkg.LegacyTurboConfig.DependsOnTypesOnly) == 0 {
+			continue
+		}
+		internalDeps := util.SetFromStrings(pkg.InternalDeps)
+		typesOnly := make(util.Set)
+		for _, dep := range pkg.LegacyTurboConfig.DependsOnTypesOnly {
+			if !internalDeps.Includes(dep) {
+				return fmt.Errorf("%v: dependsOnTypesOnly lists %v, which is not a workspace dependency of %v", pkg.PackageJSONPath, dep, pkg.Name)
+			}
+			typesOnly.Add(dep)
+		}
+		c.TypesOnlyDependencies[pkg.Name] = typesOnly
+	}
+	return nil
+}
+
 func (c *Context) resolveWorkspaceRootDeps(rootPackageJSON *fs.PackageJSON) error {
 	seen := mapset.NewSet()
 	var lockfileWg sync.WaitGroup
@@ -202,6 +308,7 @@ func (c *Context) resolveWorkspaceRootDeps(rootPackageJSON *fs.PackageJSON) erro
 	for dep, version := range pkg.Dependencies {
 		pkg.UnresolvedExternalDeps[dep] = version
 	}
+	c.resolveCatalogVersions(pkg.UnresolvedExternalDeps)
 	if c.Lockfile != nil {
 		pkg.TransitiveDeps = []string{}
 		c.resolveDepGraph(&lockfileWg, pkg.UnresolvedExternalDeps, depSet, seen, pkg)
@@ -249,15 +356,35 @@ func (c *Context) populateTopologicGraphForPackageJSON(pkg *fs.PackageJSON, root
 		depMap[dep] = version
 	}
 
+	// Injected workspace dependencies are still internal, but they are installed as copies
+	// with lockfile entries of their own, which we resolve like external dependencies
+	injectedDeps := make(map[string]string)
+
 	// split out internal vs. external deps
 	for depName, depVersion := range depMap {
-		if item, ok := c.PackageInfos[depName]; ok && isWorkspaceReference(item.Version, depVersion, pkg.Dir.ToStringDuringMigration(), rootpath) {
+		// A catalog reference is compared against the workspace version like the range it refers to
+		version := c.Catalogs.Resolve(depName, depVersion)
+		depRootpath := rootpath
+		if externalRoot, ok := c.externalPackages[depName]; ok {
+			depRootpath = externalRoot.ToStringDuringMigration()
+		}
+		if item, ok := c.PackageInfos[depName]; ok && isWorkspaceReference(item.Version, version, pkg.Dir.ToStringDuringMigration(), depRootpath) {
 			internalDepsSet.Add(depName)
 			c.TopologicalGraph.Connect(dag.BasicEdge(vertexName, depName))
+			if pkg.DependenciesMeta[depName].Injected {
+				injectedDeps[depName] = depVersion
+			}
 		} else {
 			externalUnresolvedDepsSet.Add(depName)
 		}
 	}
+	if len(injectedDeps) > 0 {
+		injected := make(util.Set)
+		for depName := range injectedDeps {
+			injected.Add(depName)
+		}
+		c.InjectedDependencies[vertexName] = injected
+	}
 
 	for _, name := range externalUnresolvedDepsSet.List() {
 		name := name.(string)
@@ -273,11 +400,16 @@ func (c *Context) populateTopologicGraphForPackageJSON(pkg *fs.PackageJSON, root
 			pkg.UnresolvedExternalDeps[name] = item
 		}
 	}
+	c.resolveCatalogVersions(pkg.UnresolvedExternalDeps)
 
 	pkg.TransitiveDeps = []string{}
 	seen := mapset.NewSet()
 	var lockfileWg sync.WaitGroup
 	c.resolveDepGraph(&lockfileWg, pkg.UnresolvedExternalDeps, externalDepSet, seen, pkg)
+	// Only pnpm installs injected dependencies
+	if _, ok := c.Lockfile.(*lockfile.PnpmLockfile); ok {
+		c.resolveDepGraph(&lockfileWg, injectedDeps, externalDepSet, seen, pkg)
+	}
 	lockfileWg.Wait()
 
 	// when there are no internal dependencies, we need to still add these leafs to the graph
@@ -325,6 +457,26 @@ func (c *Context) parsePackageJSON(repoRoot turbopath.AbsolutePath, pkgJSONPath
 	return nil
 }
 
+// resolveCatalogVersions replaces the catalog references in deps with the version ranges they
+// refer to, unless the lockfile records the reference itself as the dependency's specifier
+func (c *Context) resolveCatalogVersions(deps map[string]string) {
+	if len(c.Catalogs) == 0 {
+		return
+	}
+	for name, version := range deps {
+		resolved := c.Catalogs.Resolve(name, version)
+		if resolved == version {
+			continue
+		}
+		if c.Lockfile != nil {
+			if _, _, ok := c.Lockfile.ResolvePackage(name, version); ok {
+				continue
+			}
+		}
+		deps[name] = resolved
+	}
+}
+
 func (c *Context) resolveDepGraph(wg *sync.WaitGroup, unresolvedDirectDeps map[string]string, resolvedDepsSet mapset.Set, seen mapset.Set, pkg *fs.PackageJSON) {
 	if c.Lockfile == nil {
 		return
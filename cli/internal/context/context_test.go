@@ -2,7 +2,12 @@ package context
 
 import (
 	"path/filepath"
+	"reflect"
 	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 )
 
 func Test_isWorkspaceReference(t *testing.T) {
@@ -121,3 +126,69 @@ func Test_isWorkspaceReference(t *testing.T) {
 		})
 	}
 }
+
+func Test_resolveCatalogVersions(t *testing.T) {
+	c := &Context{
+		Catalogs: packagemanager.Catalogs{
+			"default": {"react": "^18.2.0"},
+			"legacy":  {"react": "^17.0.2"},
+		},
+	}
+	deps := map[string]string{
+		"react":     "catalog:",
+		"react-dom": "catalog:",
+		"lodash":    "^4.17.21",
+	}
+	c.resolveCatalogVersions(deps)
+	want := map[string]string{
+		"react":     "^18.2.0",
+		"react-dom": "catalog:", // not in the catalog, so left for the lockfile to resolve
+		"lodash":    "^4.17.21",
+	}
+	if !reflect.DeepEqual(deps, want) {
+		t.Errorf("resolveCatalogVersions() got %v, want %v", deps, want)
+	}
+}
+
+func Test_WithExternalRepos(t *testing.T) {
+	dir := fs.AbsolutePathFromUpstream(t.TempDir())
+	repoRoot := dir.Join("monorepo")
+	externalRoot := dir.Join("design-system")
+	writeFile(t, repoRoot.Join("package.json"), `{"name": "monorepo", "packageManager": "npm@8.19.2", "workspaces": ["apps/*"]}`)
+	writeFile(t, repoRoot.Join("apps", "web", "package.json"), `{"name": "web", "dependencies": {"ui": "^1.0.0"}}`)
+	writeFile(t, externalRoot.Join("package.json"), `{"name": "design-system", "packageManager": "npm@8.19.2", "workspaces": ["packages/*"]}`)
+	writeFile(t, externalRoot.Join("packages", "ui", "package.json"), `{"name": "ui", "version": "1.2.0", "dependencies": {"icons": "*"}}`)
+	writeFile(t, externalRoot.Join("packages", "icons", "package.json"), `{"name": "icons"}`)
+
+	newContext := func() (*Context, error) {
+		rootPackageJSON, err := fs.ReadPackageJSON(repoRoot.Join("package.json"))
+		if err != nil {
+			t.Fatalf("failed to read package.json: %v", err)
+		}
+		return New(WithExternalRepos([]turbopath.AbsolutePath{externalRoot}), WithGraph(repoRoot, rootPackageJSON, repoRoot.Join("node_modules", ".cache", "turbo"), ""))
+	}
+	c, err := newContext()
+	if err != nil {
+		t.Fatalf("New() error: %v", err)
+	}
+	if got, want := c.PackageInfos["ui"].Dir.ToString(), filepath.FromSlash("../design-system/packages/ui"); got != want {
+		t.Errorf("ui Dir got %v, want %v", got, want)
+	}
+	if got, want := c.PackageInfos["ui"].PackageJSONPath.ToString(), filepath.FromSlash("../design-system/packages/ui/package.json"); got != want {
+		t.Errorf("ui PackageJSONPath got %v, want %v", got, want)
+	}
+	if got := c.PackageInfos["web"].InternalDeps; !reflect.DeepEqual(got, []string{"ui"}) {
+		t.Errorf("web InternalDeps got %v, want [ui]", got)
+	}
+	if !c.TopologicalGraph.DownEdges("web").Include("ui") || !c.TopologicalGraph.DownEdges("ui").Include("icons") {
+		t.Error("expected edges from web to ui and from ui to icons")
+	}
+	if _, ok := c.PackageInfos["design-system"]; ok {
+		t.Error("expected the external repo's root package to be left out")
+	}
+
+	writeFile(t, externalRoot.Join("packages", "web", "package.json"), `{"name": "web"}`)
+	if _, err := newContext(); err == nil {
+		t.Error("expected an error for a workspace in both repos")
+	}
+}
@@ -0,0 +1,240 @@
+package context
+
+import (
+	"crypto/sha256"
+	"encoding/hex"
+	"encoding/json"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"reflect"
+	"sort"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _snapshotVersion changes whenever Snapshot does, so that graphs stored by another
+// version of turbo are never restored
+const _snapshotVersion = "1"
+
+// Snapshot is what is kept of a resolved package graph, so that it can be restored without
+// reading the lockfile or resolving dependencies again. The package.json files themselves
+// are read again when restoring.
+type Snapshot struct {
+	Packages              map[string]PackageSnapshot `json:"packages"`
+	PackageNames          []string                   `json:"packageNames"`
+	Vertices              []string                   `json:"vertices"`
+	Edges                 [][2]string                `json:"edges"`
+	TypesOnlyDependencies map[string][]string        `json:"typesOnlyDependencies,omitempty"`
+	InjectedDependencies  map[string][]string        `json:"injectedDependencies,omitempty"`
+	Catalogs              packagemanager.Catalogs    `json:"catalogs,omitempty"`
+}
+
+// PackageSnapshot is the part of a package's information that is resolved from the rest of
+// the repository
+type PackageSnapshot struct {
+	PackageJSONPath        string            `json:"packageJSONPath"`
+	InternalDeps           []string          `json:"internalDeps"`
+	UnresolvedExternalDeps map[string]string `json:"unresolvedExternalDeps"`
+	ExternalDeps           []string          `json:"externalDeps"`
+	TransitiveDeps         []string          `json:"transitiveDeps"`
+	ExternalDepsHash       string            `json:"externalDepsHash"`
+}
+
+// GraphKey returns a hash of the files that the package graph is resolved from: the root
+// package.json, the package manager's workspace configuration, the package.json of every
+// workspace and the lockfile. If none of them change, neither does the graph.
+func GraphKey(repoRoot turbopath.AbsolutePath, packageManager *packagemanager.PackageManager) (string, error) {
+	workspaces, err := packageManager.GetWorkspaces(repoRoot)
+	if err != nil {
+		return "", fmt.Errorf("workspace configuration error: %w", err)
+	}
+	files := []turbopath.AbsolutePath{repoRoot.Join("package.json")}
+	if packageManager.WorkspaceConfigurationPath != "" {
+		files = append(files, repoRoot.Join(packageManager.WorkspaceConfigurationPath))
+	}
+	if packageManager.Lockfile != "" {
+		files = append(files, repoRoot.Join(packageManager.Lockfile))
+	}
+	sort.Strings(workspaces)
+	for _, workspace := range workspaces {
+		files = append(files, fs.UnsafeToAbsolutePath(workspace))
+	}
+
+	h := sha256.New()
+	_, _ = io.WriteString(h, _snapshotVersion+"\x00"+packageManager.Name+"\x00")
+	for _, file := range files {
+		contents, err := file.ReadFile()
+		if os.IsNotExist(err) {
+			// A missing file is part of the key too, as opposed to an empty one
+			_, _ = io.WriteString(h, file.ToString()+"\x00missing\x00")
+			continue
+		} else if err != nil {
+			return "", err
+		}
+		_, _ = io.WriteString(h, fmt.Sprintf("%v\x00%v\x00", file, len(contents)))
+		_, _ = h.Write(contents)
+	}
+	return hex.EncodeToString(h.Sum(nil)), nil
+}
+
+// Snapshot returns what is needed to restore the package graph with WithSnapshot
+func (c *Context) Snapshot() *Snapshot {
+	s := &Snapshot{
+		Packages:              make(map[string]PackageSnapshot, len(c.PackageInfos)),
+		PackageNames:          sortedCopy(c.PackageNames),
+		Vertices:              []string{},
+		Edges:                 [][2]string{},
+		TypesOnlyDependencies: setsToLists(c.TypesOnlyDependencies),
+		InjectedDependencies:  setsToLists(c.InjectedDependencies),
+		Catalogs:              c.Catalogs,
+	}
+	for name, pkg := range c.PackageInfos {
+		s.Packages[fmt.Sprintf("%v", name)] = PackageSnapshot{
+			PackageJSONPath:        pkg.PackageJSONPath.ToString(),
+			InternalDeps:           sortedCopy(pkg.InternalDeps),
+			UnresolvedExternalDeps: pkg.UnresolvedExternalDeps,
+			ExternalDeps:           sortedCopy(pkg.ExternalDeps),
+			TransitiveDeps:         sortedCopy(pkg.TransitiveDeps),
+			ExternalDepsHash:       pkg.ExternalDepsHash,
+		}
+	}
+	for _, v := range c.TopologicalGraph.Vertices() {
+		s.Vertices = append(s.Vertices, dag.VertexName(v))
+	}
+	sort.Strings(s.Vertices)
+	for _, edge := range c.TopologicalGraph.Edges() {
+		s.Edges = append(s.Edges, [2]string{dag.VertexName(edge.Source()), dag.VertexName(edge.Target())})
+	}
+	sort.Slice(s.Edges, func(i, j int) bool {
+		if s.Edges[i][0] != s.Edges[j][0] {
+			return s.Edges[i][0] < s.Edges[j][0]
+		}
+		return s.Edges[i][1] < s.Edges[j][1]
+	})
+	return s
+}
+
+// Encode serializes the snapshot
+func (s *Snapshot) Encode() ([]byte, error) {
+	return json.Marshal(s)
+}
+
+// DecodeSnapshot deserializes a snapshot created by Encode
+func DecodeSnapshot(data []byte) (*Snapshot, error) {
+	s := &Snapshot{}
+	if err := json.Unmarshal(data, s); err != nil {
+		return nil, err
+	}
+	return s, nil
+}
+
+// Diff describes how other differs from s, or returns nothing if they are the same
+func (s *Snapshot) Diff(other *Snapshot) []string {
+	diffs := []string{}
+	names := make(util.Set)
+	for name := range s.Packages {
+		names.Add(name)
+	}
+	for name := range other.Packages {
+		names.Add(name)
+	}
+	sortedNames := names.UnsafeListOfStrings()
+	sort.Strings(sortedNames)
+	for _, name := range sortedNames {
+		pkg, ok := s.Packages[name]
+		otherPkg, otherOk := other.Packages[name]
+		if !ok || !otherOk {
+			diffs = append(diffs, fmt.Sprintf("%v is only in one of the graphs", name))
+		} else if !reflect.DeepEqual(pkg, otherPkg) {
+			diffs = append(diffs, fmt.Sprintf("the dependencies of %v differ", name))
+		}
+	}
+	if !reflect.DeepEqual(s.Vertices, other.Vertices) || !reflect.DeepEqual(s.Edges, other.Edges) {
+		diffs = append(diffs, "the workspace dependency graphs differ")
+	}
+	if !reflect.DeepEqual(s.PackageNames, other.PackageNames) {
+		diffs = append(diffs, "the workspaces differ")
+	}
+	if !reflect.DeepEqual(s.TypesOnlyDependencies, other.TypesOnlyDependencies) || !reflect.DeepEqual(s.InjectedDependencies, other.InjectedDependencies) {
+		diffs = append(diffs, "the types-only or injected dependencies differ")
+	}
+	if !reflect.DeepEqual(s.Catalogs, other.Catalogs) {
+		diffs = append(diffs, "the catalogs differ")
+	}
+	return diffs
+}
+
+// WithSnapshot restores a package graph from a snapshot of it, instead of resolving it like
+// WithGraph. The lockfile isn't read, so the context's Lockfile is nil.
+func WithSnapshot(repoRoot turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON, packageManager *packagemanager.PackageManager, s *Snapshot) Option {
+	return func(c *Context) error {
+		c.PackageManager = packageManager
+		c.RootNode = core.ROOT_NODE_NAME
+		c.PackageInfos = make(map[interface{}]*fs.PackageJSON, len(s.Packages))
+		c.PackageNames = sortedCopy(s.PackageNames)
+		c.Catalogs = s.Catalogs
+		c.TypesOnlyDependencies = listsToSets(s.TypesOnlyDependencies)
+		c.InjectedDependencies = listsToSets(s.InjectedDependencies)
+		for name, pkgSnapshot := range s.Packages {
+			pkg := rootPackageJSON
+			if name != util.RootPkgName {
+				var err error
+				pkg, err = fs.ReadPackageJSON(repoRoot.Join(pkgSnapshot.PackageJSONPath))
+				if err != nil {
+					return fmt.Errorf("parsing %s: %w", pkgSnapshot.PackageJSONPath, err)
+				}
+				if pkg.Name != name {
+					return fmt.Errorf("%v is named %v, expected %v", pkgSnapshot.PackageJSONPath, pkg.Name, name)
+				}
+				pkg.PackageJSONPath = turbopath.AnchoredSystemPathFromUpstream(pkgSnapshot.PackageJSONPath)
+				pkg.Dir = turbopath.AnchoredSystemPathFromUpstream(filepath.Dir(pkgSnapshot.PackageJSONPath))
+			}
+			pkg.InternalDeps = pkgSnapshot.InternalDeps
+			pkg.UnresolvedExternalDeps = pkgSnapshot.UnresolvedExternalDeps
+			pkg.ExternalDeps = pkgSnapshot.ExternalDeps
+			pkg.TransitiveDeps = pkgSnapshot.TransitiveDeps
+			pkg.ExternalDepsHash = pkgSnapshot.ExternalDepsHash
+			c.PackageInfos[name] = pkg
+		}
+		for _, v := range s.Vertices {
+			c.TopologicalGraph.Add(v)
+		}
+		for _, edge := range s.Edges {
+			c.TopologicalGraph.Connect(dag.BasicEdge(edge[0], edge[1]))
+		}
+		return nil
+	}
+}
+
+func sortedCopy(list []string) []string {
+	sorted := make([]string, len(list))
+	copy(sorted, list)
+	sort.Strings(sorted)
+	return sorted
+}
+
+func setsToLists(sets map[string]util.Set) map[string][]string {
+	if len(sets) == 0 {
+		return nil
+	}
+	lists := make(map[string][]string, len(sets))
+	for name, set := range sets {
+		lists[name] = sortedCopy(set.UnsafeListOfStrings())
+	}
+	return lists
+}
+
+func listsToSets(lists map[string][]string) map[string]util.Set {
+	sets := make(map[string]util.Set, len(lists))
+	for name, list := range lists {
+		sets[name] = util.SetFromStrings(list)
+	}
+	return sets
+}
@@ -0,0 +1,129 @@
+package context
+
+import (
+	"reflect"
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func writeFile(t *testing.T, path turbopath.AbsolutePath, contents string) {
+	t.Helper()
+	if err := path.EnsureDir(); err != nil {
+		t.Fatalf("failed to create directory for %v: %v", path, err)
+	}
+	if err := path.WriteFile([]byte(contents), 0644); err != nil {
+		t.Fatalf("failed to write %v: %v", path, err)
+	}
+}
+
+func Test_SnapshotRoundTrip(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile(t, repoRoot.Join("apps", "web", "package.json"), `{"name": "web", "dependencies": {"ui": "*", "react": "^18.2.0"}}`)
+	writeFile(t, repoRoot.Join("packages", "ui", "package.json"), `{"name": "ui"}`)
+	rootPackageJSON := &fs.PackageJSON{Name: "monorepo"}
+
+	web, err := fs.ReadPackageJSON(repoRoot.Join("apps", "web", "package.json"))
+	if err != nil {
+		t.Fatalf("failed to read package.json: %v", err)
+	}
+	web.PackageJSONPath = turbopath.AnchoredSystemPathFromUpstream("apps/web/package.json")
+	web.Dir = turbopath.AnchoredSystemPathFromUpstream("apps/web")
+	web.InternalDeps = []string{"ui"}
+	web.UnresolvedExternalDeps = map[string]string{"react": "^18.2.0"}
+	web.ExternalDeps = []string{"react@18.2.0"}
+	web.TransitiveDeps = []string{"react@18.2.0", "loose-envify@1.4.0"}
+	web.ExternalDepsHash = "hash-of-web"
+	ui, err := fs.ReadPackageJSON(repoRoot.Join("packages", "ui", "package.json"))
+	if err != nil {
+		t.Fatalf("failed to read package.json: %v", err)
+	}
+	ui.PackageJSONPath = turbopath.AnchoredSystemPathFromUpstream("packages/ui/package.json")
+	ui.Dir = turbopath.AnchoredSystemPathFromUpstream("packages/ui")
+	ui.ExternalDepsHash = "hash-of-ui"
+
+	c := &Context{
+		PackageInfos: map[interface{}]*fs.PackageJSON{
+			"web":            web,
+			"ui":             ui,
+			util.RootPkgName: rootPackageJSON,
+		},
+		PackageNames:          []string{"web", "ui"},
+		RootNode:              core.ROOT_NODE_NAME,
+		TypesOnlyDependencies: map[string]util.Set{"web": util.SetFromStrings([]string{"ui"})},
+		InjectedDependencies:  map[string]util.Set{},
+	}
+	for _, v := range []string{"web", "ui", core.ROOT_NODE_NAME} {
+		c.TopologicalGraph.Add(v)
+	}
+	c.TopologicalGraph.Connect(dag.BasicEdge("web", "ui"))
+	c.TopologicalGraph.Connect(dag.BasicEdge("ui", core.ROOT_NODE_NAME))
+
+	data, err := c.Snapshot().Encode()
+	if err != nil {
+		t.Fatalf("failed to encode snapshot: %v", err)
+	}
+	snapshot, err := DecodeSnapshot(data)
+	if err != nil {
+		t.Fatalf("failed to decode snapshot: %v", err)
+	}
+	restored, err := New(WithSnapshot(repoRoot, rootPackageJSON, &packagemanager.PackageManager{Name: "nodejs-npm"}, snapshot))
+	if err != nil {
+		t.Fatalf("failed to restore snapshot: %v", err)
+	}
+	if diffs := c.Snapshot().Diff(restored.Snapshot()); len(diffs) > 0 {
+		t.Errorf("restored graph differs: %v", diffs)
+	}
+	restoredWeb := restored.PackageInfos["web"]
+	if restoredWeb.Dir != web.Dir || restoredWeb.Dependencies["react"] != "^18.2.0" {
+		t.Errorf("restored web package got %+v", restoredWeb)
+	}
+	if restored.PackageInfos[util.RootPkgName] != rootPackageJSON {
+		t.Error("expected the root package.json to be the one passed in")
+	}
+
+	ui.ExternalDepsHash = "changed"
+	if diffs, want := c.Snapshot().Diff(restored.Snapshot()), []string{"the dependencies of ui differ"}; !reflect.DeepEqual(diffs, want) {
+		t.Errorf("Diff() got %v, want %v", diffs, want)
+	}
+}
+
+func Test_GraphKey(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile(t, repoRoot.Join("package.json"), `{"name": "monorepo", "workspaces": ["packages/*"]}`)
+	writeFile(t, repoRoot.Join("packages", "ui", "package.json"), `{"name": "ui"}`)
+	packageManager, err := packagemanager.GetPackageManager(repoRoot, &fs.PackageJSON{PackageManager: "npm@8.19.2"}, "")
+	if err != nil {
+		t.Fatalf("failed to get package manager: %v", err)
+	}
+	key, err := GraphKey(repoRoot, packageManager)
+	if err != nil {
+		t.Fatalf("failed to get graph key: %v", err)
+	}
+	if again, _ := GraphKey(repoRoot, packageManager); again != key {
+		t.Errorf("graph key changed without changes to the repository")
+	}
+
+	writeFile(t, repoRoot.Join("packages", "ui", "package.json"), `{"name": "ui", "dependencies": {"react": "^18.2.0"}}`)
+	changed, err := GraphKey(repoRoot, packageManager)
+	if err != nil {
+		t.Fatalf("failed to get graph key: %v", err)
+	}
+	if changed == key {
+		t.Errorf("graph key didn't change with a workspace's package.json")
+	}
+
+	writeFile(t, repoRoot.Join("package-lock.json"), `{"lockfileVersion": 2}`)
+	withLockfile, err := GraphKey(repoRoot, packageManager)
+	if err != nil {
+		t.Fatalf("failed to get graph key: %v", err)
+	}
+	if withLockfile == changed {
+		t.Errorf("graph key didn't change with the lockfile")
+	}
+}
@@ -79,25 +79,81 @@ type ExecOpts struct {
 	Parallel bool
 	// Concurrency is the number of concurrent tasks that can be executed
 	Concurrency int
+	// Priority, if set, decides which of the tasks that are ready to run gets the
+	// next free slot. Higher priorities run first.
+	Priority func(taskID string) int64
+	// ConcurrencyChanges, if set, delivers new values for Concurrency while the walk
+	// is in progress. Running tasks are never interrupted when it goes down.
+	ConcurrencyChanges <-chan int
+	// Restore, if set, is called for each task before it takes one of the Concurrency
+	// slots. If it returns true, the task's outputs were restored from the cache and
+	// the visitor isn't called for it. Restores are limited by RestoreConcurrency
+	// instead, so that cache hits neither wait for nor hold up the tasks that run.
+	Restore func(taskID string) (bool, error)
+	// RestoreConcurrency is the number of tasks that Restore can be called for at once
+	RestoreConcurrency int
 }
 
 // Execute executes the pipeline, constructing an internal task graph and walking it accordingly.
 func (p *Scheduler) Execute(visitor Visitor, opts ExecOpts) []error {
-	var sema = util.NewSemaphore(opts.Concurrency)
+	acquire, release, stop := newSlots(opts)
+	defer stop()
+	var restoreSema util.Semaphore
+	if opts.Restore != nil {
+		restoreSema = util.NewSemaphore(opts.RestoreConcurrency)
+	}
 	return p.TaskGraph.Walk(func(v dag.Vertex) error {
 		// Always return if it is the root node
 		if strings.Contains(dag.VertexName(v), ROOT_NODE_NAME) {
 			return nil
 		}
+		if opts.Restore != nil {
+			restoreSema.Acquire()
+			restored, err := opts.Restore(dag.VertexName(v))
+			restoreSema.Release()
+			if err != nil {
+				return err
+			} else if restored {
+				return nil
+			}
+		}
 		// Acquire the semaphore unless parallel
 		if !opts.Parallel {
-			sema.Acquire()
-			defer sema.Release()
+			acquire(dag.VertexName(v))
+			defer release()
 		}
 		return visitor(dag.VertexName(v))
 	})
 }
 
+// newSlots returns functions to acquire and release one of the concurrency slots
+// that tasks run in, and a function to call once the walk is done
+func newSlots(opts ExecOpts) (func(taskID string), func(), func()) {
+	if opts.Priority == nil && opts.ConcurrencyChanges == nil {
+		sema := util.NewSemaphore(opts.Concurrency)
+		return func(string) { sema.Acquire() }, sema.Release, func() {}
+	}
+	sema := util.NewPrioritySemaphore(opts.Concurrency)
+	done := make(chan struct{})
+	if opts.ConcurrencyChanges != nil {
+		go func() {
+			for {
+				select {
+				case n := <-opts.ConcurrencyChanges:
+					sema.Resize(n)
+				case <-done:
+					return
+				}
+			}
+		}()
+	}
+	acquire := func(string) { sema.Acquire(0) }
+	if opts.Priority != nil {
+		acquire = func(taskID string) { sema.Acquire(opts.Priority(taskID)) }
+	}
+	return acquire, sema.Release, func() { close(done) }
+}
+
 func (p *Scheduler) getTaskDefinition(pkg string, taskName string, taskID string) (*Task, error) {
 	if task, ok := p.Tasks[taskID]; ok {
 		return task, nil
@@ -4,6 +4,7 @@ import (
 	"fmt"
 	"strings"
 	"testing"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/util"
 	"gotest.tools/v3/assert"
@@ -479,3 +480,49 @@ b#test
 c#test
   ___ROOT___
 `
+
+func TestExecuteRestore(t *testing.T) {
+	graph := &dag.AcyclicGraph{}
+	graph.Add("app1")
+	graph.Add("libA")
+
+	p := NewScheduler(graph)
+	p.AddTask(&Task{
+		Name:     "build",
+		TopoDeps: make(util.Set),
+		Deps:     make(util.Set),
+	})
+	err := p.Prepare(&SchedulerExecutionOptions{
+		Packages:  []string{"app1", "libA"},
+		TaskNames: []string{"build"},
+	})
+	assert.NilError(t, err, "Prepare")
+
+	// app1#build holds the only slot until libA#build is restored, which only finishes if
+	// restoring doesn't need a slot
+	restored := make(chan struct{})
+	var visited []string
+	errs := p.Execute(func(taskID string) error {
+		visited = append(visited, taskID)
+		select {
+		case <-restored:
+			return nil
+		case <-time.After(5 * time.Second):
+			return fmt.Errorf("%v wasn't restored while %v was running", "libA#build", taskID)
+		}
+	}, ExecOpts{
+		Concurrency:        1,
+		RestoreConcurrency: 2,
+		Restore: func(taskID string) (bool, error) {
+			if taskID == "libA#build" {
+				close(restored)
+				return true, nil
+			}
+			return false, nil
+		},
+	})
+	for _, err := range errs {
+		assert.NilError(t, err, "Execute")
+	}
+	assert.DeepEqual(t, visited, []string{"app1#build"})
+}
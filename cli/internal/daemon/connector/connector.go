@@ -11,6 +11,7 @@ import (
 	"github.com/hashicorp/go-hclog"
 	"github.com/nightlyone/lockfile"
 	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"google.golang.org/grpc"
@@ -77,6 +78,11 @@ func (ce *ConnectionError) Error() string {
 	You can also run without the daemon process by passing --no-daemon`, ce.PidPath, ce.PidPath, ce.LogPath, ce.SockPath)
 }
 
+// ErrorCode returns the code of the error
+func (ce *ConnectionError) ErrorCode() errcode.Code {
+	return errcode.DaemonConnection
+}
+
 // Unwrap allows a connection error to work with standard library "errors" and compatible packages
 func (ce *ConnectionError) Unwrap() error {
 	return ce.cause
@@ -17,6 +17,7 @@ import (
 	"github.com/spf13/cobra"
 	"github.com/vercel/turborepo/cli/internal/cmdutil"
 	"github.com/vercel/turborepo/cli/internal/daemon/connector"
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/server"
 	"github.com/vercel/turborepo/cli/internal/signals"
@@ -47,6 +48,11 @@ func getDaemonFileRoot(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
 	return tempDir.Join(hexHash)
 }
 
+// FileRoot returns the directory that holds the daemon's socket and pid file for a repository
+func FileRoot(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
+	return getDaemonFileRoot(repoRoot)
+}
+
 func getLogFilePath(repoRoot turbopath.AbsolutePath) (turbopath.AbsolutePath, error) {
 	hexHash := getRepoHash(repoRoot)
 	base := repoRoot.Base()
@@ -75,9 +81,13 @@ func (d *daemon) logError(err error) {
 // we do not need to read the log file.
 var _logFileFlags = os.O_WRONLY | os.O_APPEND | os.O_CREATE
 
+// _fileWatcherEnvVar selects the file watcher when --file-watcher isn't passed
+const _fileWatcherEnvVar = "TURBO_FILE_WATCHER"
+
 // GetCmd returns the root daemon command
 func GetCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Command {
 	var idleTimeout time.Duration
+	var fileWatcher string
 	cmd := &cobra.Command{
 		Use:           "daemon",
 		Short:         "Runs the Turborepo background daemon",
@@ -115,7 +125,12 @@ func GetCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Comma
 				timedOutCh: make(chan struct{}),
 			}
 			serverName := getRepoHash(base.RepoRoot)
-			turboServer, err := server.New(serverName, d.logger.Named("rpc server"), base.RepoRoot, base.TurboVersion, logFilePath)
+			// The daemon is usually started by another turbo command, which can only pass the
+			// file watcher along through the environment
+			if !cmd.Flags().Changed("file-watcher") && os.Getenv(_fileWatcherEnvVar) != "" {
+				fileWatcher = os.Getenv(_fileWatcherEnvVar)
+			}
+			turboServer, err := server.New(serverName, d.logger.Named("rpc server"), base.RepoRoot, base.TurboVersion, logFilePath, fileWatcher)
 			if err != nil {
 				d.logError(err)
 				return err
@@ -130,12 +145,14 @@ func GetCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Comma
 		},
 	}
 	cmd.Flags().DurationVar(&idleTimeout, "idle-time", 4*time.Hour, "Set the idle timeout for turbod")
+	cmd.Flags().StringVar(&fileWatcher, "file-watcher", filewatcher.BackendAuto, "How turbod watches files: auto, watchman or native. auto uses watchman if it's running. Can also be set with "+_fileWatcherEnvVar)
 	addDaemonSubcommands(cmd, helper)
 	return cmd
 }
 
 func addDaemonSubcommands(cmd *cobra.Command, helper *cmdutil.Helper) {
 	addStatusCmd(cmd, helper)
+	addMetricsCmd(cmd, helper)
 	addStartCmd(cmd, helper)
 	addStopCmd(cmd, helper)
 	addRestartCmd(cmd, helper)
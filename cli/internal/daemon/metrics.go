@@ -0,0 +1,51 @@
+package daemon
+
+import (
+	"context"
+	"strings"
+
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/daemonclient"
+)
+
+func addMetricsCmd(root *cobra.Command, helper *cmdutil.Helper) {
+	cmd := &cobra.Command{
+		Use:           "metrics",
+		Short:         "Prints statistics about the runs the turbo daemon has seen, in the Prometheus text format",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			l := &lifecycle{
+				base,
+			}
+			if err := l.metrics(cmd.Context()); err != nil {
+				l.logError(err)
+				return err
+			}
+			return nil
+		},
+	}
+	root.AddCommand(cmd)
+}
+
+func (l *lifecycle) metrics(ctx context.Context) error {
+	client, err := GetClient(ctx, l.base.RepoRoot, l.base.Logger, l.base.TurboVersion, ClientOpts{
+		// A daemon that isn't running hasn't seen any runs
+		DontStart: true,
+	})
+	if err != nil {
+		return err
+	}
+	defer func() { _ = client.Close() }()
+	metrics, err := daemonclient.New(client).Metrics(ctx)
+	if err != nil {
+		return err
+	}
+	l.base.UI.Output(strings.TrimSuffix(metrics, "\n"))
+	return nil
+}
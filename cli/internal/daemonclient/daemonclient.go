@@ -4,6 +4,7 @@ package daemonclient
 
 import (
 	"context"
+	"time"
 
 	"github.com/vercel/turborepo/cli/internal/daemon/connector"
 	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
@@ -23,6 +24,14 @@ type Status struct {
 	SockFile turbopath.AbsolutePath `json:"sockFile"`
 }
 
+// TaskStats describes how a single task in a run finished
+type TaskStats struct {
+	TaskID string
+	// Status is one of "built", "cached" or "failed"
+	Status   string
+	Duration time.Duration
+}
+
 // New creates a new instance of a DaemonClient.
 func New(client *connector.Client) *DaemonClient {
 	return &DaemonClient{
@@ -65,3 +74,47 @@ func (d *DaemonClient) Status(ctx context.Context) (*Status, error) {
 		SockFile: d.client.SockPath,
 	}, nil
 }
+
+// RecordRunStats reports the outcome of a run's tasks to the daemon, which aggregates them
+func (d *DaemonClient) RecordRunStats(ctx context.Context, tasks []TaskStats) error {
+	req := &turbodprotocol.RecordRunStatsRequest{}
+	for _, task := range tasks {
+		req.Tasks = append(req.Tasks, &turbodprotocol.TaskStats{
+			TaskId:       task.TaskID,
+			Status:       task.Status,
+			DurationMsec: uint64(task.Duration.Milliseconds()),
+		})
+	}
+	_, err := d.client.RecordRunStats(ctx, req)
+	return err
+}
+
+// Metrics returns the statistics the daemon has aggregated, in the Prometheus text format
+func (d *DaemonClient) Metrics(ctx context.Context) (string, error) {
+	resp, err := d.client.GetMetrics(ctx, &turbodprotocol.GetMetricsRequest{})
+	if err != nil {
+		return "", err
+	}
+	return resp.Metrics, nil
+}
+
+// GetPackageGraph returns the package graph stored under key, or nil if there isn't one
+func (d *DaemonClient) GetPackageGraph(ctx context.Context, key string) ([]byte, error) {
+	resp, err := d.client.GetPackageGraph(ctx, &turbodprotocol.GetPackageGraphRequest{Key: key})
+	if err != nil {
+		return nil, err
+	}
+	if !resp.Found {
+		return nil, nil
+	}
+	return resp.Graph, nil
+}
+
+// StorePackageGraph asks the daemon to keep graph under key for later runs
+func (d *DaemonClient) StorePackageGraph(ctx context.Context, key string, graph []byte) error {
+	_, err := d.client.StorePackageGraph(ctx, &turbodprotocol.StorePackageGraphRequest{
+		Key:   key,
+		Graph: graph,
+	})
+	return err
+}
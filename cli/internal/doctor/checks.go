@@ -0,0 +1,285 @@
+package doctor
+
+import (
+	"bytes"
+	"fmt"
+	"os"
+	"os/exec"
+	"regexp"
+	"runtime"
+	"sort"
+	"strings"
+
+	"github.com/Masterminds/semver"
+	"github.com/vercel/turborepo/cli/internal/daemon"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"github.com/vercel/turborepo/cli/internal/validate"
+)
+
+// _lockfileNames are the lockfiles that each package manager command writes
+var _lockfileNames = map[string]string{
+	"npm":  "package-lock.json",
+	"pnpm": "pnpm-lock.yaml",
+	"yarn": "yarn.lock",
+}
+
+// installedVersion returns the version of the given package manager command on the PATH
+func installedVersion(command string) (string, error) {
+	out, err := exec.Command(command, "--version").Output()
+	if err != nil {
+		return "", err
+	}
+	return strings.TrimSpace(string(out)), nil
+}
+
+// checkPackageManagerField reports a `packageManager` field in the root package.json
+// that is missing, or that disagrees with the lockfile or the installed package manager
+func checkPackageManagerField(dc *doctorContext) ([]Finding, error) {
+	field := dc.rootPackageJSON.PackageManager
+	if field == "" {
+		if dc.packageManager == nil {
+			return nil, nil
+		}
+		finding := Finding{
+			Diagnostic: validate.Diagnostic{
+				Code:     "package-manager-missing",
+				Severity: validate.SeverityWarning,
+				Message:  "package.json has no packageManager field, so turbo has to guess which package manager is in use",
+			},
+			Suggestion: fmt.Sprintf("add a \"packageManager\" field for %v to package.json", dc.packageManager.Command),
+		}
+		if version, err := dc.installedVersion(dc.packageManager.Command); err == nil {
+			value := fmt.Sprintf("%v@%v", dc.packageManager.Command, version)
+			finding.Suggestion = fmt.Sprintf("add \"packageManager\": %q to package.json", value)
+			finding.fix = func() error {
+				return addPackageManagerField(dc.repoRoot.Join("package.json"), value)
+			}
+		}
+		return []Finding{finding}, nil
+	}
+
+	manager, version, err := packagemanager.ParsePackageManagerString(field)
+	if err != nil {
+		return []Finding{{
+			Diagnostic: validate.Diagnostic{
+				Code:     "package-manager-invalid",
+				Severity: validate.SeverityError,
+				Message:  fmt.Sprintf("packageManager field %q is not of the form <manager>@<version>", field),
+			},
+			Suggestion: "set the packageManager field to e.g. \"npm@8.19.2\"",
+		}}, nil
+	}
+	findings := []Finding{}
+	if !dc.repoRoot.Join(_lockfileNames[manager]).FileExists() {
+		for _, other := range sortedKeys(_lockfileNames) {
+			if other != manager && dc.repoRoot.Join(_lockfileNames[other]).FileExists() {
+				findings = append(findings, Finding{
+					Diagnostic: validate.Diagnostic{
+						Code:     "package-manager-mismatch",
+						Severity: validate.SeverityError,
+						Message:  fmt.Sprintf("packageManager is %v, but the repository has a %v lockfile (%v)", field, other, _lockfileNames[other]),
+					},
+					Suggestion: fmt.Sprintf("set packageManager to %v, or run `%v install` and remove %v", other, manager, _lockfileNames[other]),
+				})
+				break
+			}
+		}
+	}
+	if installed, err := dc.installedVersion(manager); err == nil {
+		wanted, wantedErr := semver.NewVersion(version)
+		got, gotErr := semver.NewVersion(installed)
+		if wantedErr == nil && gotErr == nil && wanted.Major() != got.Major() {
+			findings = append(findings, Finding{
+				Diagnostic: validate.Diagnostic{
+					Code:     "package-manager-version-mismatch",
+					Severity: validate.SeverityWarning,
+					Message:  fmt.Sprintf("packageManager is %v, but %v %v is installed", field, manager, installed),
+				},
+				Suggestion: "run `corepack enable` to use the version from packageManager",
+			})
+		}
+	}
+	return findings, nil
+}
+
+// _firstKey matches the start of the first key in a JSON object, capturing its indentation
+var _firstKey = regexp.MustCompile(`^\{(\r?\n)([ \t]*)"`)
+
+// addPackageManagerField adds a packageManager field at the start of a package.json,
+// leaving the rest of the file, including its formatting, as it was
+func addPackageManagerField(path turbopath.AbsolutePath, value string) error {
+	contents, err := path.ReadFile()
+	if err != nil {
+		return err
+	}
+	updated, err := insertPackageManagerField(contents, value)
+	if err != nil {
+		return fmt.Errorf("%v: %w", path, err)
+	}
+	info, err := os.Stat(path.ToString())
+	if err != nil {
+		return err
+	}
+	return path.WriteFile(updated, info.Mode())
+}
+
+func insertPackageManagerField(contents []byte, value string) ([]byte, error) {
+	trimmed := bytes.TrimLeft(contents, " \t\r\n")
+	prefix := contents[:len(contents)-len(trimmed)]
+	match := _firstKey.FindSubmatch(trimmed)
+	if match == nil {
+		return nil, fmt.Errorf("expected a JSON object with one key per line")
+	}
+	newline, indent := match[1], match[2]
+	field := fmt.Sprintf("{%s%s\"packageManager\": %q,", newline, indent, value)
+	updated := append([]byte{}, prefix...)
+	updated = append(updated, field...)
+	return append(updated, trimmed[1:]...), nil
+}
+
+// checkWorkspacesInLockfile reports workspaces with dependencies that the lockfile
+// doesn't know about, which usually means the lockfile wasn't updated when they
+// were added
+func checkWorkspacesInLockfile(dc *doctorContext) ([]Finding, error) {
+	if dc.lockfile == nil || dc.packageManager == nil {
+		return nil, nil
+	}
+	findings := []Finding{}
+	for _, pkgName := range validate.SortedPackageNames(dc.packageInfos) {
+		if pkgName == util.RootPkgName {
+			continue
+		}
+		missing := []string{}
+		for dep, version := range dc.packageInfos[pkgName].UnresolvedExternalDeps {
+			if _, _, ok := dc.lockfile.ResolvePackage(dep, version); !ok {
+				missing = append(missing, dep+"@"+version)
+			}
+		}
+		if len(missing) == 0 {
+			continue
+		}
+		sort.Strings(missing)
+		findings = append(findings, Finding{
+			Diagnostic: validate.Diagnostic{
+				Code:     "workspace-not-in-lockfile",
+				Severity: validate.SeverityError,
+				Package:  pkgName,
+				Message:  fmt.Sprintf("%v does not have entries for %v", dc.packageManager.Lockfile, strings.Join(missing, ", ")),
+			},
+			Suggestion: fmt.Sprintf("run `%v install` to update %v", dc.packageManager.Command, dc.packageManager.Lockfile),
+		})
+	}
+	return findings, nil
+}
+
+// checkDependenciesWithoutScripts reports dependsOn entries that name a task that no
+// workspace has a script for, so depending on it never runs anything
+func checkDependenciesWithoutScripts(dc *doctorContext) ([]Finding, error) {
+	pipeline := dc.turboJSON.Pipeline
+	findings := []Finding{}
+	for _, taskName := range validate.SortedTaskNames(pipeline) {
+		taskDefinition := pipeline[taskName]
+		dependencies := append(append([]string{}, taskDefinition.TopologicalDependencies...), taskDefinition.TaskDependencies...)
+		for _, dependency := range dependencies {
+			if hasScript(dc.packageInfos, dependency) {
+				continue
+			}
+			script := dependency
+			if util.IsPackageTask(dependency) {
+				_, script = util.GetPackageTaskFromId(dependency)
+			}
+			findings = append(findings, Finding{
+				Diagnostic: validate.Diagnostic{
+					Code:     "dependency-without-script",
+					Severity: validate.SeverityWarning,
+					Task:     taskName,
+					Message:  fmt.Sprintf("depends on %v, but no workspace has a %v script, so the dependency does nothing", dependency, script),
+				},
+				Suggestion: fmt.Sprintf("add a %v script where it is needed, or remove %v from dependsOn", script, dependency),
+			})
+		}
+	}
+	return findings, nil
+}
+
+func hasScript(packageInfos map[interface{}]*fs.PackageJSON, task string) bool {
+	if util.IsPackageTask(task) {
+		pkgName, script := util.GetPackageTaskFromId(task)
+		pkg, ok := packageInfos[pkgName]
+		if !ok {
+			// Unknown workspaces are reported by `turbo validate`
+			return true
+		}
+		_, ok = pkg.Scripts[script]
+		return ok
+	}
+	for pkgName, pkg := range packageInfos {
+		if pkgName == util.RootPkgName {
+			continue
+		}
+		if _, ok := pkg.Scripts[task]; ok {
+			return true
+		}
+	}
+	return false
+}
+
+// checkDaemonPermissions reports a daemon directory that the daemon can't create its
+// socket in, or that other users could create a socket in
+func checkDaemonPermissions(dc *doctorContext) ([]Finding, error) {
+	if runtime.GOOS == "windows" {
+		return nil, nil
+	}
+	return checkDirPermissions(daemon.FileRoot(dc.repoRoot))
+}
+
+func checkDirPermissions(dir turbopath.AbsolutePath) ([]Finding, error) {
+	info, err := os.Stat(dir.ToString())
+	if os.IsNotExist(err) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	mode := info.Mode().Perm()
+	if mode&0700 != 0700 {
+		fixed := mode | 0700
+		return []Finding{{
+			Diagnostic: validate.Diagnostic{
+				Code:     "daemon-dir-not-writable",
+				Severity: validate.SeverityError,
+				Message:  fmt.Sprintf("the daemon directory %v has mode %v, so the daemon can't create its socket", dir, mode),
+			},
+			Suggestion: fmt.Sprintf("chmod %o %v", fixed, dir),
+			fix: func() error {
+				return os.Chmod(dir.ToString(), fixed)
+			},
+		}}, nil
+	}
+	if mode&0022 != 0 {
+		fixed := mode &^ 0022
+		return []Finding{{
+			Diagnostic: validate.Diagnostic{
+				Code:     "daemon-dir-shared",
+				Severity: validate.SeverityWarning,
+				Message:  fmt.Sprintf("the daemon directory %v has mode %v, so other users can replace the daemon's socket", dir, mode),
+			},
+			Suggestion: fmt.Sprintf("chmod %o %v", fixed, dir),
+			fix: func() error {
+				return os.Chmod(dir.ToString(), fixed)
+			},
+		}}, nil
+	}
+	return nil, nil
+}
+
+func sortedKeys(m map[string]string) []string {
+	keys := make([]string, 0, len(m))
+	for key := range m {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	return keys
+}
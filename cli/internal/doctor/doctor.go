@@ -0,0 +1,195 @@
+// Package doctor checks the health of a repository beyond its turbo configuration,
+// suggesting fixes for the problems it finds and applying the ones that are safe.
+package doctor
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+
+	"github.com/fatih/color"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/lockfile"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/validate"
+)
+
+// Finding is a single problem found in the repository, along with how to resolve it
+type Finding struct {
+	validate.Diagnostic
+	// Suggestion describes how to resolve the problem
+	Suggestion string `json:"suggestion,omitempty"`
+	// Fixable is set when Fix can resolve the problem without further input
+	Fixable bool `json:"fixable"`
+	// Fixed is set once Fix has resolved the problem
+	Fixed bool `json:"fixed,omitempty"`
+	fix   func() error
+}
+
+// doctorContext holds the parts of the repository that checks inspect
+type doctorContext struct {
+	repoRoot        turbopath.AbsolutePath
+	rootPackageJSON *fs.PackageJSON
+	turboJSON       *fs.TurboJSON
+	packageInfos    map[interface{}]*fs.PackageJSON
+	packageManager  *packagemanager.PackageManager
+	lockfile        lockfile.Lockfile
+	// installedVersion returns the version of a package manager command
+	installedVersion func(command string) (string, error)
+}
+
+// check inspects the repository and reports any problems it finds
+type check func(dc *doctorContext) ([]Finding, error)
+
+var _checks = []check{
+	checkPackageManagerField,
+	checkWorkspacesInLockfile,
+	checkDependenciesWithoutScripts,
+	checkDaemonPermissions,
+}
+
+// Diagnose runs every check against the repository at repoRoot
+func Diagnose(repoRoot turbopath.AbsolutePath, packageManagerOverride string) ([]Finding, error) {
+	rootPackageJSON, err := fs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read package.json: %w", err)
+	}
+	turboJSON, err := fs.ReadTurboConfig(repoRoot, rootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	ctx, err := context.New(context.WithGraph(repoRoot, rootPackageJSON, cache.DefaultLocation(repoRoot), packageManagerOverride))
+	if err != nil {
+		return nil, errors.Wrap(err, "could not construct graph")
+	}
+	dc := &doctorContext{
+		repoRoot:         repoRoot,
+		rootPackageJSON:  rootPackageJSON,
+		turboJSON:        turboJSON,
+		packageInfos:     ctx.PackageInfos,
+		packageManager:   ctx.PackageManager,
+		lockfile:         ctx.Lockfile,
+		installedVersion: installedVersion,
+	}
+	return runChecks(dc, _checks)
+}
+
+// Fix applies the fixes for every fixable finding, marking the ones that succeed as fixed
+func Fix(findings []Finding) error {
+	for i := range findings {
+		finding := &findings[i]
+		if !finding.Fixable || finding.Fixed {
+			continue
+		}
+		if err := finding.fix(); err != nil {
+			return fmt.Errorf("failed to fix %v: %w", finding.Code, err)
+		}
+		finding.Fixed = true
+	}
+	return nil
+}
+
+func runChecks(dc *doctorContext, checks []check) ([]Finding, error) {
+	findings := []Finding{}
+	for _, check := range checks {
+		found, err := check(dc)
+		if err != nil {
+			return nil, err
+		}
+		findings = append(findings, found...)
+	}
+	for i := range findings {
+		findings[i].Fixable = findings[i].fix != nil
+	}
+	sort.SliceStable(findings, func(i, j int) bool {
+		return findings[i].Less(findings[j].Diagnostic)
+	})
+	return findings, nil
+}
+
+type opts struct {
+	json bool
+	fix  bool
+}
+
+// GetCmd returns the doctor subcommand for use with cobra
+func GetCmd(helper *cmdutil.Helper) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:           "doctor",
+		Short:         "Check your repository for problems and suggest fixes",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			findings, err := Diagnose(base.RepoRoot, base.RepoConfig.PackageManager())
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			if opts.fix {
+				if err := Fix(findings); err != nil {
+					base.LogError("%v", err)
+					return err
+				}
+			}
+			if err := report(base, findings, opts.json); err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			for _, finding := range findings {
+				if finding.Severity == validate.SeverityError && !finding.Fixed {
+					return errors.New("repository has problems")
+				}
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&opts.json, "json", false, "Output findings in JSON format")
+	cmd.Flags().BoolVar(&opts.fix, "fix", false, "Apply the fixes that are safe to make automatically")
+	return cmd
+}
+
+func report(base *cmdutil.CmdBase, findings []Finding, asJSON bool) error {
+	if asJSON {
+		bytes, err := json.MarshalIndent(&struct {
+			Findings []Finding `json:"findings"`
+		}{
+			Findings: findings,
+		}, "", "  ")
+		if err != nil {
+			return errors.Wrap(err, "failed to render JSON")
+		}
+		base.UI.Output(string(bytes))
+		return nil
+	}
+	if len(findings) == 0 {
+		base.UI.Output("No problems found")
+		return nil
+	}
+	for _, finding := range findings {
+		if finding.Fixed {
+			base.UI.Output(validate.FormatDiagnostic(color.GreenString("fixed:"), finding.Message, finding.Diagnostic))
+			continue
+		}
+		validate.PrintDiagnostic(base, finding.Diagnostic)
+		if finding.Suggestion != "" {
+			suffix := ""
+			if finding.Fixable {
+				suffix = ui.Dim(" (run with --fix to apply)")
+			}
+			base.UI.Output(fmt.Sprintf("  %s%s", finding.Suggestion, suffix))
+		}
+	}
+	return nil
+}
@@ -0,0 +1,102 @@
+package doctor
+
+import (
+	"os"
+	"runtime"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"gotest.tools/v3/assert"
+)
+
+func findingCodes(findings []Finding) []string {
+	codes := []string{}
+	for _, finding := range findings {
+		codes = append(codes, finding.Code+" "+finding.Task)
+	}
+	return codes
+}
+
+func Test_insertPackageManagerField(t *testing.T) {
+	updated, err := insertPackageManagerField([]byte("{\n    \"name\": \"monorepo\",\n    \"private\": true\n}\n"), "pnpm@7.14.0")
+	assert.NilError(t, err, "insertPackageManagerField")
+	assert.Equal(t, string(updated), "{\n    \"packageManager\": \"pnpm@7.14.0\",\n    \"name\": \"monorepo\",\n    \"private\": true\n}\n")
+
+	_, err = insertPackageManagerField([]byte(`{"name": "monorepo"}`), "pnpm@7.14.0")
+	assert.ErrorContains(t, err, "one key per line")
+}
+
+func Test_checkPackageManagerField(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	assert.NilError(t, repoRoot.Join("package.json").WriteFile([]byte("{\n  \"name\": \"monorepo\"\n}\n"), 0644), "WriteFile")
+	assert.NilError(t, repoRoot.Join("yarn.lock").WriteFile([]byte{}, 0644), "WriteFile")
+	dc := &doctorContext{
+		repoRoot:        repoRoot,
+		rootPackageJSON: &fs.PackageJSON{},
+		packageManager:  &packagemanager.PackageManager{Command: "yarn", Lockfile: "yarn.lock"},
+		installedVersion: func(command string) (string, error) {
+			return "1.22.19", nil
+		},
+	}
+
+	findings, err := runChecks(dc, []check{checkPackageManagerField})
+	assert.NilError(t, err, "checkPackageManagerField")
+	assert.DeepEqual(t, findingCodes(findings), []string{"package-manager-missing "})
+	assert.Assert(t, findings[0].Fixable)
+	assert.NilError(t, Fix(findings), "Fix")
+	contents, err := repoRoot.Join("package.json").ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	assert.Equal(t, string(contents), "{\n  \"packageManager\": \"yarn@1.22.19\",\n  \"name\": \"monorepo\"\n}\n")
+
+	dc.rootPackageJSON = &fs.PackageJSON{PackageManager: "pnpm@7.14.0"}
+	findings, err = runChecks(dc, []check{checkPackageManagerField})
+	assert.NilError(t, err, "checkPackageManagerField")
+	assert.DeepEqual(t, findingCodes(findings), []string{
+		"package-manager-mismatch ",
+		"package-manager-version-mismatch ",
+	})
+}
+
+func Test_checkDependenciesWithoutScripts(t *testing.T) {
+	dc := &doctorContext{
+		turboJSON: &fs.TurboJSON{
+			Pipeline: fs.Pipeline{
+				"build": fs.TaskDefinition{TopologicalDependencies: []string{"build"}, TaskDependencies: []string{"codegen"}},
+				"test":  fs.TaskDefinition{TaskDependencies: []string{"build", "web#lint", "//#setup"}},
+			},
+		},
+		packageInfos: map[interface{}]*fs.PackageJSON{
+			util.RootPkgName: {Scripts: map[string]string{"codegen": "./codegen.sh", "setup": "./setup.sh"}},
+			"web":            {Scripts: map[string]string{"build": "next build", "test": "jest"}},
+		},
+	}
+	findings, err := runChecks(dc, []check{checkDependenciesWithoutScripts})
+	assert.NilError(t, err, "checkDependenciesWithoutScripts")
+	assert.DeepEqual(t, findingCodes(findings), []string{
+		"dependency-without-script build",
+		"dependency-without-script test",
+	})
+}
+
+func Test_checkDirPermissions(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("file modes aren't enforced on windows")
+	}
+	dir := fs.AbsolutePathFromUpstream(t.TempDir())
+	findings, err := checkDirPermissions(dir.Join("missing"))
+	assert.NilError(t, err, "checkDirPermissions")
+	assert.Equal(t, len(findings), 0)
+
+	assert.NilError(t, os.Chmod(dir.ToString(), 0777), "Chmod")
+	findings, err = runChecks(&doctorContext{}, []check{func(dc *doctorContext) ([]Finding, error) {
+		return checkDirPermissions(dir)
+	}})
+	assert.NilError(t, err, "checkDirPermissions")
+	assert.DeepEqual(t, findingCodes(findings), []string{"daemon-dir-shared "})
+	assert.NilError(t, Fix(findings), "Fix")
+	info, err := os.Stat(dir.ToString())
+	assert.NilError(t, err, "Stat")
+	assert.Equal(t, info.Mode().Perm(), os.FileMode(0755))
+}
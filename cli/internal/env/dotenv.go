@@ -0,0 +1,118 @@
+package env
+
+import (
+	"bufio"
+	"bytes"
+	"fmt"
+	"os"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// _modePlaceholder is replaced by the --env-mode of the run in env file names,
+// e.g. `.env.{mode}`
+const _modePlaceholder = "{mode}"
+
+// ExpandEnvFiles substitutes mode into the given env file names. Files that refer to
+// a mode are dropped when there is no mode, so that `.env.{mode}` is only loaded when
+// a mode has been selected.
+func ExpandEnvFiles(files []string, mode string) []string {
+	expanded := []string{}
+	for _, file := range files {
+		if strings.Contains(file, _modePlaceholder) {
+			if mode == "" {
+				continue
+			}
+			file = strings.ReplaceAll(file, _modePlaceholder, mode)
+		}
+		expanded = append(expanded, file)
+	}
+	return expanded
+}
+
+// LoadEnvFiles reads the given env files, relative to dir, in order. Variables in later
+// files override the same variables in earlier ones, and files that don't exist are skipped.
+func LoadEnvFiles(dir turbopath.AbsolutePath, files []string) (map[string]string, error) {
+	vars := make(map[string]string)
+	for _, file := range files {
+		contents, err := dir.Join(file).ReadFile()
+		if os.IsNotExist(err) {
+			continue
+		} else if err != nil {
+			return nil, err
+		}
+		fileVars, err := ParseEnvFile(contents)
+		if err != nil {
+			return nil, fmt.Errorf("%v: %w", file, err)
+		}
+		for k, v := range fileVars {
+			vars[k] = v
+		}
+	}
+	return vars, nil
+}
+
+// ParseEnvFile parses the contents of a dotenv file. Each line is a `KEY=value` pair,
+// optionally preceded by `export`. Values may be single quoted, which keeps them as is,
+// or double quoted, which expands `\n` and allows values to span lines. Unquoted values
+// end at a ` #` comment.
+func ParseEnvFile(contents []byte) (map[string]string, error) {
+	vars := make(map[string]string)
+	scanner := bufio.NewScanner(bytes.NewReader(contents))
+	lineNumber := 0
+	for scanner.Scan() {
+		lineNumber++
+		line := strings.TrimSpace(scanner.Text())
+		if line == "" || strings.HasPrefix(line, "#") {
+			continue
+		}
+		line = strings.TrimPrefix(line, "export ")
+		equals := strings.Index(line, "=")
+		if equals < 1 {
+			return nil, fmt.Errorf("line %v: expected KEY=value", lineNumber)
+		}
+		key := strings.TrimSpace(line[:equals])
+		value := strings.TrimSpace(line[equals+1:])
+		switch {
+		case strings.HasPrefix(value, "'"):
+			end := strings.Index(value[1:], "'")
+			if end == -1 {
+				return nil, fmt.Errorf("line %v: unterminated quoted value for %v", lineNumber, key)
+			}
+			value = value[1 : end+1]
+		case strings.HasPrefix(value, `"`):
+			// Double quoted values can continue onto the following lines
+			quoted := value[1:]
+			for !strings.Contains(quoted, `"`) {
+				if !scanner.Scan() {
+					return nil, fmt.Errorf("line %v: unterminated quoted value for %v", lineNumber, key)
+				}
+				lineNumber++
+				quoted += "\n" + scanner.Text()
+			}
+			quoted = quoted[:strings.Index(quoted, `"`)]
+			value = strings.ReplaceAll(quoted, `\n`, "\n")
+		default:
+			if comment := strings.Index(value, " #"); comment != -1 {
+				value = strings.TrimSpace(value[:comment])
+			}
+		}
+		vars[key] = value
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, err
+	}
+	return vars, nil
+}
+
+// EnvFilePairs returns the variables loaded from env files as sorted key=value pairs
+func EnvFilePairs(vars map[string]string) []string {
+	pairs := make([]string, 0, len(vars))
+	for k, v := range vars {
+		pairs = append(pairs, fmt.Sprintf("%v=%v", k, v))
+	}
+	sort.Strings(pairs)
+	return pairs
+}
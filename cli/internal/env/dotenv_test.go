@@ -0,0 +1,77 @@
+package env
+
+import (
+	"reflect"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestParseEnvFile(t *testing.T) {
+	contents := []byte(`# comment
+PLAIN=value
+export EXPORTED=exported
+SPACED = spaced value # trailing comment
+HASH=a#b
+SINGLE='literal \n # kept'
+DOUBLE="line one\nline two"
+MULTILINE="first
+second"
+EMPTY=
+`)
+	got, err := ParseEnvFile(contents)
+	if err != nil {
+		t.Fatalf("ParseEnvFile() error = %v", err)
+	}
+	want := map[string]string{
+		"PLAIN":     "value",
+		"EXPORTED":  "exported",
+		"SPACED":    "spaced value",
+		"HASH":      "a#b",
+		"SINGLE":    `literal \n # kept`,
+		"DOUBLE":    "line one\nline two",
+		"MULTILINE": "first\nsecond",
+		"EMPTY":     "",
+	}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("ParseEnvFile() got = %v, want %v", got, want)
+	}
+
+	for _, invalid := range []string{"NO_EQUALS", "=value", `UNTERMINATED="value`, "UNTERMINATED='value"} {
+		if _, err := ParseEnvFile([]byte(invalid)); err == nil {
+			t.Errorf("ParseEnvFile(%q) expected an error", invalid)
+		}
+	}
+}
+
+func TestLoadEnvFiles(t *testing.T) {
+	dir := fs.AbsolutePathFromUpstream(t.TempDir())
+	if err := dir.Join(".env").WriteFile([]byte("API_URL=https://example.com\nDEBUG=false\n"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if err := dir.Join(".env.local").WriteFile([]byte("DEBUG=true\n"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	got, err := LoadEnvFiles(dir, []string{".env", ".env.production", ".env.local"})
+	if err != nil {
+		t.Fatalf("LoadEnvFiles() error = %v", err)
+	}
+	want := map[string]string{"API_URL": "https://example.com", "DEBUG": "true"}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("LoadEnvFiles() got = %v, want %v", got, want)
+	}
+	if pairs := EnvFilePairs(got); !reflect.DeepEqual(pairs, []string{"API_URL=https://example.com", "DEBUG=true"}) {
+		t.Errorf("EnvFilePairs() got = %v", pairs)
+	}
+}
+
+func TestExpandEnvFiles(t *testing.T) {
+	files := []string{".env", ".env.{mode}", ".env.local", ".env.{mode}.local"}
+	if got := ExpandEnvFiles(files, ""); !reflect.DeepEqual(got, []string{".env", ".env.local"}) {
+		t.Errorf("ExpandEnvFiles() without a mode got = %v", got)
+	}
+	want := []string{".env", ".env.production", ".env.local", ".env.production.local"}
+	if got := ExpandEnvFiles(files, "production"); !reflect.DeepEqual(got, want) {
+		t.Errorf("ExpandEnvFiles() got = %v, want %v", got, want)
+	}
+}
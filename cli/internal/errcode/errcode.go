@@ -0,0 +1,165 @@
+// Package errcode gives the errors that turbo reports stable codes, so that tooling
+// can react to specific failures without matching on messages.
+package errcode
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+)
+
+// Code identifies a kind of failure. Codes are never reused or renumbered.
+type Code string
+
+const (
+	// Unknown is the code of errors that haven't been given one
+	Unknown Code = "TURBO_UNKNOWN"
+
+	// ConfigSyntax means turbo.json or a package.json isn't valid JSON
+	ConfigSyntax Code = "TURBO_CONFIG_001"
+	// ConfigMissing means there is no turbo.json, and no "turbo" key in package.json
+	ConfigMissing Code = "TURBO_CONFIG_002"
+	// ConfigInvalid means turbo.json parsed, but holds a value turbo doesn't accept
+	ConfigInvalid Code = "TURBO_CONFIG_003"
+	// ConfigMultipleLockfiles means lockfiles for more than one package manager were found
+	ConfigMultipleLockfiles Code = "TURBO_CONFIG_004"
+	// ConfigNoPackageManager means the package manager couldn't be detected
+	ConfigNoPackageManager Code = "TURBO_CONFIG_005"
+
+	// CacheNoCachesEnabled means every cache was turned off
+	CacheNoCachesEnabled Code = "TURBO_CACHE_001"
+	// CacheRemoteDisabled means the Remote Cache refused the request, see util.CachingStatus
+	CacheRemoteDisabled Code = "TURBO_CACHE_002"
+	// CacheVerificationFailed means a downloaded artifact's signature didn't check out
+	CacheVerificationFailed Code = "TURBO_CACHE_003"
+	// CacheSignatureKeyMissing means signatures are enabled without a key to sign with
+	CacheSignatureKeyMissing Code = "TURBO_CACHE_004"
+
+	// RunLocked means another run holds the run lock
+	RunLocked Code = "TURBO_RUN_001"
+	// RunTaskFailed means a task exited with a non-zero exit code
+	RunTaskFailed Code = "TURBO_RUN_002"
+	// RunBoundaryViolation means an import crosses a workspace boundary that turbo.json enforces
+	RunBoundaryViolation Code = "TURBO_RUN_003"
+
+	// FilterSyntax means a --filter expression is malformed
+	FilterSyntax Code = "TURBO_FILTER_001"
+
+	// DaemonConnection means turbo couldn't connect to its daemon
+	DaemonConnection Code = "TURBO_DAEMON_001"
+)
+
+// Span locates the cause of an error within a file. Line and Column start at 1.
+type Span struct {
+	File   string `json:"file"`
+	Line   int    `json:"line"`
+	Column int    `json:"column"`
+}
+
+// SpanAt returns the span of the byte at offset within the contents of file
+func SpanAt(file string, contents []byte, offset int64) *Span {
+	if offset < 0 || offset > int64(len(contents)) {
+		return nil
+	}
+	before := contents[:offset]
+	line := bytes.Count(before, []byte("\n")) + 1
+	column := len(before) - bytes.LastIndexByte(before, '\n')
+	return &Span{File: file, Line: line, Column: column}
+}
+
+// Coded is implemented by errors that have a code
+type Coded interface {
+	error
+	ErrorCode() Code
+}
+
+// Error attaches a code, and optionally a span, to an error
+type Error struct {
+	Code Code
+	Span *Span
+	Err  error
+}
+
+var _ Coded = &Error{}
+
+func (e *Error) Error() string {
+	return e.Err.Error()
+}
+
+// Unwrap returns the error that was given a code
+func (e *Error) Unwrap() error {
+	return e.Err
+}
+
+// ErrorCode returns the code of the error
+func (e *Error) ErrorCode() Code {
+	return e.Code
+}
+
+// New returns an error with the given code and message, for use as a sentinel error
+func New(code Code, message string) error {
+	return &Error{Code: code, Err: errors.New(message)}
+}
+
+// Wrap gives err a code. It returns nil if err is nil.
+func Wrap(code Code, err error) error {
+	if err == nil {
+		return nil
+	}
+	return &Error{Code: code, Err: err}
+}
+
+// WithSpan gives err a code and the location of its cause. It returns nil if err is nil.
+func WithSpan(code Code, span *Span, err error) error {
+	if err == nil {
+		return nil
+	}
+	return &Error{Code: code, Span: span, Err: err}
+}
+
+// Of returns the code of the outermost coded error in err's chain, or Unknown
+func Of(err error) Code {
+	var coded Coded
+	if errors.As(err, &coded) {
+		return coded.ErrorCode()
+	}
+	return Unknown
+}
+
+// Report is the machine-readable form of an error
+type Report struct {
+	Code    Code   `json:"code"`
+	Message string `json:"message"`
+	Span    *Span  `json:"span,omitempty"`
+}
+
+// NewReport describes err, using the code and span of the outermost errors in its chain
+// that have them
+func NewReport(err error) Report {
+	report := Report{
+		Code:    Of(err),
+		Message: err.Error(),
+	}
+	for cause := err; cause != nil; cause = errors.Unwrap(cause) {
+		if withSpan, ok := cause.(*Error); ok && withSpan.Span != nil {
+			report.Span = withSpan.Span
+			break
+		}
+	}
+	return report
+}
+
+// JSON renders the report as a single line of JSON
+func (r Report) JSON() string {
+	b, err := json.Marshal(r)
+	if err != nil {
+		// A Report only holds strings and numbers
+		panic(err)
+	}
+	return string(b)
+}
+
+// JSON renders the report for err as a single line of JSON
+func JSON(err error) string {
+	return NewReport(err).JSON()
+}
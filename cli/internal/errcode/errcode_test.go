@@ -0,0 +1,25 @@
+package errcode
+
+import (
+	"fmt"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestSpanAt(t *testing.T) {
+	contents := []byte("{\n  \"pipeline\": {\n    oops\n")
+	assert.DeepEqual(t, SpanAt("turbo.json", contents, 0), &Span{File: "turbo.json", Line: 1, Column: 1})
+	assert.DeepEqual(t, SpanAt("turbo.json", contents, 22), &Span{File: "turbo.json", Line: 3, Column: 5})
+	assert.Assert(t, SpanAt("turbo.json", contents, 100) == nil)
+}
+
+func TestReport(t *testing.T) {
+	span := &Span{File: "turbo.json", Line: 3, Column: 5}
+	err := fmt.Errorf("turbo.json: %w", WithSpan(ConfigSyntax, span, fmt.Errorf("invalid character 'o'")))
+	assert.Equal(t, Of(err), ConfigSyntax)
+	assert.Equal(t, JSON(err), `{"code":"TURBO_CONFIG_001","message":"turbo.json: invalid character 'o'","span":{"file":"turbo.json","line":3,"column":5}}`)
+
+	assert.Equal(t, Of(fmt.Errorf("something else")), Unknown)
+	assert.Equal(t, JSON(fmt.Errorf("something else")), `{"code":"TURBO_UNKNOWN","message":"something else"}`)
+}
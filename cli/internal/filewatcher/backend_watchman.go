@@ -0,0 +1,389 @@
+package filewatcher
+
+import (
+	"bufio"
+	"encoding/json"
+	"fmt"
+	"net"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"runtime"
+	"sync"
+	"time"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// The filewatching backends that can be selected
+const (
+	// BackendAuto uses watchman if it's running, and the native backend otherwise
+	BackendAuto = "auto"
+	// BackendWatchman uses a running watchman service
+	BackendWatchman = "watchman"
+	// BackendNative uses fsnotify, or fsevents on macOS
+	BackendNative = "native"
+)
+
+// _watchmanCommandTimeout bounds how long watchman may take to answer a command
+var _watchmanCommandTimeout = 30 * time.Second
+
+// GetBackend returns the filewatching backend of the given kind. BackendAuto falls back to
+// the native backend when watchman isn't installed or isn't running.
+func GetBackend(logger hclog.Logger, kind string) (Backend, error) {
+	switch kind {
+	case BackendNative:
+		return GetPlatformSpecificBackend(logger)
+	case BackendWatchman:
+		return NewWatchmanBackend(logger)
+	case BackendAuto, "":
+		backend, err := NewWatchmanBackend(logger)
+		if err == nil {
+			return backend, nil
+		}
+		logger.Debug(fmt.Sprintf("watchman is unavailable, using the native file watcher: %v", err))
+		return GetPlatformSpecificBackend(logger)
+	default:
+		return nil, fmt.Errorf("unknown file watcher %q, expected %v, %v or %v", kind, BackendAuto, BackendWatchman, BackendNative)
+	}
+}
+
+// watchmanBackend receives file events from a watchman service, through a subscription
+// per root. See https://facebook.github.io/watchman/docs/socket-interface
+type watchmanBackend struct {
+	conn   net.Conn
+	events chan Event
+	errors chan error
+	logger hclog.Logger
+	// responses receives the responses to commands, in the order they were sent.
+	// Subscription updates are handled as they arrive instead.
+	responses chan watchmanResponse
+	// cmdMu makes sure that only one command is waiting for a response at a time
+	cmdMu sync.Mutex
+
+	mu            sync.Mutex
+	subscriptions map[string]*watchmanSubscription
+	nextID        int
+	closed        bool
+}
+
+// watchmanSubscription is a root that watchman reports changes under
+type watchmanSubscription struct {
+	// watch is the root watchman is watching, which may be an ancestor of root
+	watch           string
+	root            turbopath.AbsolutePath
+	excludePatterns []string
+}
+
+// watchmanFile is the information watchman sends about a changed file
+type watchmanFile struct {
+	Name   string `json:"name"`
+	Exists bool   `json:"exists"`
+	New    bool   `json:"new"`
+}
+
+// watchmanResponse is either the response to a command, or a message that watchman sends
+// on its own, such as a subscription update
+type watchmanResponse struct {
+	Error        string `json:"error"`
+	Unilateral   bool   `json:"unilateral"`
+	Log          string `json:"log"`
+	Watch        string `json:"watch"`
+	RelativePath string `json:"relative_path"`
+	Clock        string `json:"clock"`
+	Subscription string `json:"subscription"`
+	// Canceled is set when watchman stops a subscription, for instance because its
+	// root was deleted
+	Canceled bool `json:"canceled"`
+	// IsFreshInstance is set when watchman can't tell what changed since the last update,
+	// for instance because it restarted or had to recrawl
+	IsFreshInstance bool           `json:"is_fresh_instance"`
+	Files           []watchmanFile `json:"files"`
+}
+
+// NewWatchmanBackend connects to the watchman service. It fails if watchman isn't installed
+// or isn't running, rather than starting it.
+func NewWatchmanBackend(logger hclog.Logger) (Backend, error) {
+	sockname, err := findWatchmanSocket()
+	if err != nil {
+		return nil, err
+	}
+	return newWatchmanBackend(logger, sockname)
+}
+
+// findWatchmanSocket returns the path to the socket of the watchman service
+func findWatchmanSocket() (string, error) {
+	if runtime.GOOS == "windows" {
+		return "", errors.New("the watchman backend is not supported on Windows")
+	}
+	if sockname := os.Getenv("WATCHMAN_SOCK"); sockname != "" {
+		return sockname, nil
+	}
+	watchman, err := exec.LookPath("watchman")
+	if err != nil {
+		return "", errors.Wrap(err, "watchman is not installed")
+	}
+	output, err := exec.Command(watchman, "--output-encoding=json", "--no-pretty", "get-sockname").Output()
+	if err != nil {
+		return "", errors.Wrap(err, "failed to find the watchman socket")
+	}
+	var resp struct {
+		Sockname     string `json:"sockname"`
+		UnixDomain   string `json:"unix_domain"`
+		ErrorMessage string `json:"error"`
+	}
+	if err := json.Unmarshal(output, &resp); err != nil {
+		return "", errors.Wrap(err, "failed to parse the output of watchman get-sockname")
+	}
+	if resp.ErrorMessage != "" {
+		return "", errors.New(resp.ErrorMessage)
+	}
+	if resp.UnixDomain != "" {
+		return resp.UnixDomain, nil
+	}
+	return resp.Sockname, nil
+}
+
+func newWatchmanBackend(logger hclog.Logger, sockname string) (*watchmanBackend, error) {
+	conn, err := net.DialTimeout("unix", sockname, _watchmanCommandTimeout)
+	if err != nil {
+		return nil, errors.Wrap(err, "watchman is not running")
+	}
+	w := &watchmanBackend{
+		conn:          conn,
+		events:        make(chan Event),
+		errors:        make(chan error),
+		logger:        logger.Named("watchman"),
+		responses:     make(chan watchmanResponse),
+		subscriptions: make(map[string]*watchmanSubscription),
+	}
+	go w.read()
+	// Check that what's listening is really watchman
+	if _, err := w.command("version"); err != nil {
+		_ = conn.Close()
+		return nil, err
+	}
+	return w, nil
+}
+
+func (w *watchmanBackend) Events() <-chan Event {
+	return w.events
+}
+
+func (w *watchmanBackend) Errors() <-chan error {
+	return w.errors
+}
+
+func (w *watchmanBackend) Start() error {
+	return nil
+}
+
+// AddRoot subscribes to changes under root. Events for files that already exist aren't
+// sent, only those for later changes.
+func (w *watchmanBackend) AddRoot(root turbopath.AbsolutePath, excludePatterns ...string) error {
+	watched, err := w.command("watch-project", root.ToString())
+	if err != nil {
+		return errors.Wrapf(err, "watchman failed to watch %v", root)
+	}
+	clock, err := w.command("clock", watched.Watch)
+	if err != nil {
+		return err
+	}
+	w.mu.Lock()
+	if w.closed {
+		w.mu.Unlock()
+		return ErrFilewatchingClosed
+	}
+	w.nextID++
+	name := fmt.Sprintf("turbo-%v-%v", os.Getpid(), w.nextID)
+	w.subscriptions[name] = &watchmanSubscription{
+		watch:           watched.Watch,
+		root:            root,
+		excludePatterns: excludePatterns,
+	}
+	w.mu.Unlock()
+	query := map[string]interface{}{
+		"fields": []string{"name", "exists", "new"},
+		// Starting from the current clock skips the files that already exist
+		"since": clock.Clock,
+	}
+	if watched.RelativePath != "" {
+		query["relative_root"] = watched.RelativePath
+	}
+	if _, err := w.command("subscribe", watched.Watch, name, query); err != nil {
+		w.mu.Lock()
+		delete(w.subscriptions, name)
+		w.mu.Unlock()
+		return errors.Wrapf(err, "watchman failed to subscribe to %v", root)
+	}
+	w.logger.Debug(fmt.Sprintf("watching root %v, excluding %v", root, excludePatterns))
+	return nil
+}
+
+// Close cancels the subscriptions and disconnects from watchman. Watchman keeps watching
+// the roots for other clients.
+func (w *watchmanBackend) Close() error {
+	w.mu.Lock()
+	if w.closed {
+		w.mu.Unlock()
+		return ErrFilewatchingClosed
+	}
+	subscriptions := make(map[string]string, len(w.subscriptions))
+	for name, subscription := range w.subscriptions {
+		subscriptions[name] = subscription.watch
+	}
+	w.mu.Unlock()
+	for name, watch := range subscriptions {
+		if _, err := w.command("unsubscribe", watch, name); err != nil {
+			w.logger.Debug(fmt.Sprintf("failed to unsubscribe from %v: %v", watch, err))
+		}
+	}
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	if w.closed {
+		return ErrFilewatchingClosed
+	}
+	w.closed = true
+	close(w.events)
+	close(w.errors)
+	return w.conn.Close()
+}
+
+// command sends a command to watchman and waits for its response
+func (w *watchmanBackend) command(args ...interface{}) (watchmanResponse, error) {
+	w.cmdMu.Lock()
+	defer w.cmdMu.Unlock()
+	payload, err := json.Marshal(args)
+	if err != nil {
+		return watchmanResponse{}, err
+	}
+	if _, err := w.conn.Write(append(payload, '\n')); err != nil {
+		return watchmanResponse{}, err
+	}
+	select {
+	case resp, ok := <-w.responses:
+		if !ok {
+			return watchmanResponse{}, errors.New("the connection to watchman closed")
+		}
+		if resp.Error != "" {
+			return resp, errors.New(resp.Error)
+		}
+		return resp, nil
+	case <-time.After(_watchmanCommandTimeout):
+		return watchmanResponse{}, fmt.Errorf("timed out waiting for watchman to respond to %v", args[0])
+	}
+}
+
+// read reads what watchman sends until the connection closes, forwarding responses to
+// commands and turning subscription updates into events
+func (w *watchmanBackend) read() {
+	defer close(w.responses)
+	reader := bufio.NewReader(w.conn)
+	for {
+		line, err := reader.ReadBytes('\n')
+		if err != nil {
+			w.fail(errors.Wrap(err, "lost the connection to watchman"))
+			return
+		}
+		var resp watchmanResponse
+		if err := json.Unmarshal(line, &resp); err != nil {
+			w.fail(errors.Wrap(err, "failed to parse a message from watchman"))
+			return
+		}
+		switch {
+		case resp.Log != "":
+			w.logger.Debug(resp.Log)
+		case resp.Subscription != "":
+			if err := w.onSubscriptionUpdate(&resp); err != nil {
+				// Events may have been missed, so those listening can't trust what they know
+				// about the filesystem anymore
+				w.logger.Warn(err.Error())
+				go func() { _ = w.Close() }()
+				return
+			}
+		default:
+			w.responses <- resp
+		}
+	}
+}
+
+func (w *watchmanBackend) onSubscriptionUpdate(resp *watchmanResponse) error {
+	w.mu.Lock()
+	subscription, ok := w.subscriptions[resp.Subscription]
+	closed := w.closed
+	w.mu.Unlock()
+	if !ok || closed {
+		return nil
+	}
+	if resp.Canceled {
+		return fmt.Errorf("watchman canceled the subscription to %v", subscription.root)
+	}
+	if resp.IsFreshInstance {
+		return fmt.Errorf("watchman can't tell what changed under %v, it may have restarted or recrawled", subscription.root)
+	}
+	for _, file := range resp.Files {
+		path := subscription.root.Join(filepath.FromSlash(file.Name))
+		if excluded, err := isExcluded(path, subscription.excludePatterns); err != nil {
+			w.sendError(err)
+			continue
+		} else if excluded {
+			continue
+		}
+		w.sendEvent(Event{Path: path, EventType: watchmanFileEvent(file)})
+	}
+	return nil
+}
+
+// sendEvent forwards ev, unless the backend has been closed
+func (w *watchmanBackend) sendEvent(ev Event) {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	if !w.closed {
+		w.events <- ev
+	}
+}
+
+// sendError forwards err, unless the backend has been closed
+func (w *watchmanBackend) sendError(err error) {
+	w.mu.Lock()
+	defer w.mu.Unlock()
+	if !w.closed {
+		w.errors <- err
+	}
+}
+
+// fail reports an error that ends the connection to watchman
+func (w *watchmanBackend) fail(err error) {
+	w.mu.Lock()
+	closed := w.closed
+	w.mu.Unlock()
+	if !closed {
+		w.logger.Warn(err.Error())
+		go func() { _ = w.Close() }()
+	}
+}
+
+func isExcluded(path turbopath.AbsolutePath, excludePatterns []string) (bool, error) {
+	for _, pattern := range excludePatterns {
+		matches, err := doublestar.Match(pattern, filepath.ToSlash(path.ToString()))
+		if err != nil {
+			return false, err
+		} else if matches {
+			return true, nil
+		}
+	}
+	return false, nil
+}
+
+func watchmanFileEvent(file watchmanFile) FileEvent {
+	if !file.Exists {
+		return FileDeleted
+	} else if file.New {
+		return FileAdded
+	}
+	return FileModified
+}
+
@@ -0,0 +1,126 @@
+//go:build !windows
+// +build !windows
+
+package filewatcher
+
+import (
+	"bufio"
+	"encoding/json"
+	"net"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+// fakeWatchman answers the commands the watchman backend sends, and pushes subscription
+// updates when asked to
+type fakeWatchman struct {
+	commands chan []interface{}
+	updates  chan map[string]interface{}
+}
+
+func startFakeWatchman(t *testing.T, watch string) (*fakeWatchman, string) {
+	// Unix socket paths are limited in length, so avoid the long path of t.TempDir()
+	dir, err := os.MkdirTemp("", "watchman")
+	assert.NilError(t, err)
+	t.Cleanup(func() { _ = os.RemoveAll(dir) })
+	sockname := filepath.Join(dir, "sock")
+	listener, err := net.Listen("unix", sockname)
+	assert.NilError(t, err)
+	t.Cleanup(func() { _ = listener.Close() })
+
+	fake := &fakeWatchman{
+		commands: make(chan []interface{}, 16),
+		updates:  make(chan map[string]interface{}),
+	}
+	go func() {
+		conn, err := listener.Accept()
+		if err != nil {
+			return
+		}
+		defer func() { _ = conn.Close() }()
+		responses := make(chan map[string]interface{})
+		go func() {
+			scanner := bufio.NewScanner(conn)
+			for scanner.Scan() {
+				var args []interface{}
+				if err := json.Unmarshal(scanner.Bytes(), &args); err != nil {
+					t.Errorf("unmarshal: %v", err)
+					return
+				}
+				fake.commands <- args
+				switch args[0] {
+				case "watch-project":
+					responses <- map[string]interface{}{"watch": watch}
+				case "clock":
+					responses <- map[string]interface{}{"clock": "c:1:1"}
+				default:
+					responses <- map[string]interface{}{}
+				}
+			}
+			close(responses)
+		}()
+		encoder := json.NewEncoder(conn)
+		for {
+			select {
+			case resp, ok := <-responses:
+				if !ok {
+					return
+				}
+				_ = encoder.Encode(resp)
+			case update := <-fake.updates:
+				_ = encoder.Encode(update)
+			}
+		}
+	}()
+	return fake, sockname
+}
+
+func TestWatchmanBackend(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fake, sockname := startFakeWatchman(t, repoRoot.ToString())
+	backend, err := newWatchmanBackend(hclog.Default(), sockname)
+	assert.NilError(t, err)
+	assert.Equal(t, (<-fake.commands)[0], "version")
+
+	excludes := filepath.ToSlash(repoRoot.Join("node_modules").ToString() + "/**")
+	assert.NilError(t, backend.AddRoot(repoRoot, excludes))
+	assert.DeepEqual(t, <-fake.commands, []interface{}{"watch-project", repoRoot.ToString()})
+	assert.DeepEqual(t, <-fake.commands, []interface{}{"clock", repoRoot.ToString()})
+	subscribe := <-fake.commands
+	assert.Equal(t, subscribe[0], "subscribe")
+	name := subscribe[2].(string)
+	assert.Equal(t, subscribe[3].(map[string]interface{})["since"], "c:1:1")
+
+	fake.updates <- map[string]interface{}{
+		"unilateral":   true,
+		"subscription": name,
+		"files": []map[string]interface{}{
+			{"name": "node_modules/foo/index.js", "exists": true, "new": true},
+			{"name": "apps/web/index.js", "exists": true, "new": true},
+			{"name": "apps/web/README.md", "exists": false},
+		},
+	}
+	ev := <-backend.Events()
+	assert.Equal(t, ev, Event{Path: repoRoot.Join("apps", "web", "index.js"), EventType: FileAdded})
+	ev = <-backend.Events()
+	assert.Equal(t, ev, Event{Path: repoRoot.Join("apps", "web", "README.md"), EventType: FileDeleted})
+
+	// If watchman can't tell what changed, events may have been missed, so the backend closes
+	fake.updates <- map[string]interface{}{
+		"unilateral":        true,
+		"subscription":      name,
+		"is_fresh_instance": true,
+	}
+	_, ok := <-backend.Events()
+	assert.Assert(t, !ok, "expected the events channel to close")
+}
+
+func TestGetBackendUnknown(t *testing.T) {
+	_, err := GetBackend(hclog.Default(), "inotify")
+	assert.ErrorContains(t, err, "unknown file watcher")
+}
@@ -58,3 +58,30 @@ func Test_DirContainsPath(t *testing.T) {
 		}
 	}
 }
+
+func TestNonUTF8PathPolicy(t *testing.T) {
+	testcases := []struct {
+		policy      NonUTF8PathPolicy
+		path        string
+		wantAllowed bool
+		wantErr     bool
+	}{
+		{policy: NonUTF8PathsError, path: "café", wantAllowed: true},
+		{policy: "", path: "caf\xe9", wantAllowed: true},
+		{policy: NonUTF8PathsKeep, path: "caf\xe9", wantAllowed: true},
+		{policy: NonUTF8PathsSkip, path: "caf\xe9", wantAllowed: false},
+		{policy: NonUTF8PathsError, path: "caf\xe9", wantAllowed: false, wantErr: true},
+	}
+	for _, tc := range testcases {
+		allowed, err := tc.policy.Allow(tc.path)
+		if (err != nil) != tc.wantErr {
+			t.Errorf("%v.Allow(%q) error = %v, wantErr %v", tc.policy, tc.path, err, tc.wantErr)
+		}
+		if allowed != tc.wantAllowed {
+			t.Errorf("%v.Allow(%q) got %v, want %v", tc.policy, tc.path, allowed, tc.wantAllowed)
+		}
+	}
+	if _, err := ParseNonUTF8PathPolicy("sometimes"); err == nil {
+		t.Error("expected an error parsing an unknown policy")
+	}
+}
@@ -1,13 +1,17 @@
 package fs
 
 import (
+	"bufio"
+	"bytes"
 	"crypto/sha1"
 	"encoding/hex"
 	"fmt"
 	"io"
 	"os"
+	"sort"
 	"strconv"
 
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/xxhash"
 )
 
@@ -19,6 +23,35 @@ func HashObject(i interface{}) (string, error) {
 	return hex.EncodeToString(hash.Sum(nil)), err
 }
 
+// HashFileHashes returns the same hash as HashObject for a map of file paths to file
+// hashes, without formatting the whole map into a string first. Packages can have a
+// great many inputs, so this keeps the memory used for hashing them small.
+func HashFileHashes(fileHashes map[turbopath.AnchoredUnixPath]string) (string, error) {
+	paths := make([]string, 0, len(fileHashes))
+	for path := range fileHashes {
+		paths = append(paths, path.ToString())
+	}
+	sort.Strings(paths)
+
+	hash := xxhash.New()
+	// Match the "map[path:hash path:hash]" formatting of %v
+	writer := bufio.NewWriter(hash)
+	writer.WriteString("map[")
+	for i, path := range paths {
+		if i > 0 {
+			writer.WriteString(" ")
+		}
+		writer.WriteString(path)
+		writer.WriteString(":")
+		writer.WriteString(fileHashes[turbopath.AnchoredUnixPath(path)])
+	}
+	writer.WriteString("]")
+	if err := writer.Flush(); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(hash.Sum(nil)), nil
+}
+
 func HashFile(filePath string) (string, error) {
 	file, err := os.Open(filePath)
 	if err != nil {
@@ -59,3 +92,96 @@ func GitLikeHashFile(filePath string) (string, error) {
 
 	return hex.EncodeToString(hash.Sum(nil)), nil
 }
+
+// _binarySniffLength is how much of a file is checked for NUL bytes to decide whether it
+// is binary, the same amount that git checks
+const _binarySniffLength = 8000
+
+// GitLikeHashFileWithLineEndings is like GitLikeHashFile, but with normalizeLineEndings,
+// text files are hashed as if their CRLF line endings were LF. This makes the hash of a
+// file the same whether it was checked out with Windows or Unix line endings. Files with
+// a NUL byte near the start are considered binary and are hashed as they are.
+//
+// Like GitLikeHashFile, it streams the file rather than reading it into memory. Since the
+// size of the normalized contents is part of the hash, text files are read twice.
+func GitLikeHashFileWithLineEndings(filePath string, normalizeLineEndings bool) (string, error) {
+	if !normalizeLineEndings {
+		return GitLikeHashFile(filePath)
+	}
+	file, err := os.Open(filePath)
+	if err != nil {
+		return "", err
+	}
+	defer file.Close()
+
+	sniff := make([]byte, _binarySniffLength)
+	n, err := io.ReadFull(file, sniff)
+	if err != nil && err != io.EOF && err != io.ErrUnexpectedEOF {
+		return "", err
+	}
+	if bytes.IndexByte(sniff[:n], 0) >= 0 {
+		return GitLikeHashFile(filePath)
+	}
+
+	if _, err := file.Seek(0, io.SeekStart); err != nil {
+		return "", err
+	}
+	counter := &countingWriter{}
+	if err := copyNormalized(counter, file); err != nil {
+		return "", err
+	}
+	if _, err := file.Seek(0, io.SeekStart); err != nil {
+		return "", err
+	}
+	hash := sha1.New()
+	hash.Write([]byte("blob"))
+	hash.Write([]byte(" "))
+	hash.Write([]byte(strconv.FormatInt(counter.n, 10)))
+	hash.Write([]byte{0})
+	if err := copyNormalized(hash, file); err != nil {
+		return "", err
+	}
+	return hex.EncodeToString(hash.Sum(nil)), nil
+}
+
+// countingWriter counts the bytes written to it
+type countingWriter struct {
+	n int64
+}
+
+func (w *countingWriter) Write(p []byte) (int, error) {
+	w.n += int64(len(p))
+	return len(p), nil
+}
+
+// copyNormalized copies src to dst with CRLF line endings converted to LF, a buffer
+// at a time
+func copyNormalized(dst io.Writer, src io.Reader) error {
+	in := make([]byte, 32*1024)
+	out := make([]byte, 0, len(in)+1)
+	pendingCR := false
+	for {
+		n, err := src.Read(in)
+		out = out[:0]
+		for _, b := range in[:n] {
+			if pendingCR && b != '\n' {
+				out = append(out, '\r')
+			}
+			pendingCR = b == '\r'
+			if !pendingCR {
+				out = append(out, b)
+			}
+		}
+		if err == io.EOF && pendingCR {
+			out = append(out, '\r')
+		}
+		if _, writeErr := dst.Write(out); writeErr != nil {
+			return writeErr
+		}
+		if err == io.EOF {
+			return nil
+		} else if err != nil {
+			return err
+		}
+	}
+}
@@ -0,0 +1,50 @@
+package fs
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+func TestHashFileHashes(t *testing.T) {
+	cases := []map[turbopath.AnchoredUnixPath]string{
+		{},
+		{"package.json": "abc"},
+		{"src/b.ts": "def", "package.json": "abc", "src/a.ts": "ghi"},
+	}
+	for _, fileHashes := range cases {
+		expected, err := HashObject(fileHashes)
+		assert.NilError(t, err)
+		actual, err := HashFileHashes(fileHashes)
+		assert.NilError(t, err)
+		assert.Equal(t, actual, expected, fileHashes)
+	}
+}
+
+func TestGitLikeHashFileWithLineEndings(t *testing.T) {
+	dir := t.TempDir()
+	writeFile := func(name string, contents string) string {
+		t.Helper()
+		path := filepath.Join(dir, name)
+		assert.NilError(t, os.WriteFile(path, []byte(contents), 0644))
+		return path
+	}
+	// Line endings that straddle the buffers the file is read in, and a trailing \r
+	body := strings.Repeat("x", 32*1024-1)
+	windows := writeFile("windows.txt", body+"\r\n"+body+"\r\r\nend\r")
+	unix := writeFile("unix.txt", body+"\n"+body+"\r\nend\r")
+
+	expected, err := GitLikeHashFile(unix)
+	assert.NilError(t, err)
+	actual, err := GitLikeHashFileWithLineEndings(windows, true)
+	assert.NilError(t, err)
+	assert.Equal(t, actual, expected)
+
+	unnormalized, err := GitLikeHashFileWithLineEndings(windows, false)
+	assert.NilError(t, err)
+	assert.Assert(t, unnormalized != expected)
+}
@@ -0,0 +1,43 @@
+package fs
+
+import (
+	"fmt"
+	"unicode/utf8"
+)
+
+// NonUTF8PathPolicy is what turbo does with files whose names aren't valid UTF-8.
+// Hashing and cache artifacts handle the raw bytes of such names, but other tools,
+// remote caches and file systems may not, so a repository can choose to leave them out
+// or to refuse them.
+type NonUTF8PathPolicy string
+
+const (
+	// NonUTF8PathsKeep hashes and caches files with non-UTF-8 names like any other file
+	NonUTF8PathsKeep NonUTF8PathPolicy = "keep"
+	// NonUTF8PathsSkip leaves files with non-UTF-8 names out of hashes and cache artifacts
+	NonUTF8PathsSkip NonUTF8PathPolicy = "skip"
+	// NonUTF8PathsError fails when a file with a non-UTF-8 name would be hashed or cached
+	NonUTF8PathsError NonUTF8PathPolicy = "error"
+)
+
+// ParseNonUTF8PathPolicy validates the name of a NonUTF8PathPolicy
+func ParseNonUTF8PathPolicy(value string) (NonUTF8PathPolicy, error) {
+	switch policy := NonUTF8PathPolicy(value); policy {
+	case NonUTF8PathsKeep, NonUTF8PathsSkip, NonUTF8PathsError:
+		return policy, nil
+	default:
+		return "", fmt.Errorf("invalid non-UTF-8 path policy %q, expected %v, %v or %v", value, NonUTF8PathsKeep, NonUTF8PathsSkip, NonUTF8PathsError)
+	}
+}
+
+// Allow reports whether the file at path should be hashed or cached. The zero value
+// behaves like NonUTF8PathsKeep. Under NonUTF8PathsError, a non-UTF-8 path is an error.
+func (p NonUTF8PathPolicy) Allow(path string) (bool, error) {
+	if utf8.ValidString(path) || p == "" || p == NonUTF8PathsKeep {
+		return true, nil
+	}
+	if p == NonUTF8PathsSkip {
+		return false, nil
+	}
+	return false, fmt.Errorf("file name %q is not valid UTF-8. Rename it, or pass --non-utf8-paths=skip to leave such files out of hashes and cache artifacts", path)
+}
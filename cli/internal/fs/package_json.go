@@ -16,6 +16,7 @@ type PackageJSON struct {
 	DevDependencies        map[string]string            `json:"devDependencies,omitempty"`
 	OptionalDependencies   map[string]string            `json:"optionalDependencies,omitempty"`
 	PeerDependencies       map[string]string            `json:"peerDependencies,omitempty"`
+	DependenciesMeta       map[string]DependencyMeta    `json:"dependenciesMeta,omitempty"`
 	PackageManager         string                       `json:"packageManager,omitempty"`
 	Os                     []string                     `json:"os,omitempty"`
 	Workspaces             Workspaces                   `json:"workspaces,omitempty"`
@@ -29,6 +30,15 @@ type PackageJSON struct {
 	LegacyTurboConfig      *TurboJSON `json:"turbo"`
 	Mu                     sync.Mutex
 	ExternalDepsHash       string
+	// RawJSON is the whole package.json, including fields turbo doesn't otherwise use
+	RawJSON map[string]interface{} `json:"-"`
+}
+
+// DependencyMeta is pnpm's metadata about one of a package's dependencies
+type DependencyMeta struct {
+	// Injected workspace dependencies are installed as a copy of the workspace, rather
+	// than as a symlink to it
+	Injected bool `json:"injected,omitempty"`
 }
 
 type Workspaces []string
@@ -59,6 +69,9 @@ func ReadPackageJSON(path turbopath.AbsolutePath) (*PackageJSON, error) {
 	}
 	packageJSON := &PackageJSON{}
 	if err := json.Unmarshal(b, packageJSON); err != nil {
+		return nil, codeJSONError(path.ToString(), b, err)
+	}
+	if err := json.Unmarshal(b, &packageJSON.RawJSON); err != nil {
 		return nil, err
 	}
 	return packageJSON, nil
@@ -101,6 +101,20 @@ func GetTurboDataDir() turbopath.AbsolutePath {
 	return dataHome.Join("turborepo")
 }
 
+// GetGlobalConfigDir returns the directory of the config file with the user's defaults
+// for every repository. It is ~/.config/turbo on every platform, or turbo within
+// $XDG_CONFIG_HOME if that is set.
+func GetGlobalConfigDir() turbopath.AbsolutePath {
+	if configHome := os.Getenv("XDG_CONFIG_HOME"); configHome != "" {
+		return AbsolutePathFromUpstream(configHome).Join("turbo")
+	}
+	home, err := os.UserHomeDir()
+	if err != nil {
+		return AbsolutePathFromUpstream(xdg.ConfigHome).Join("turbo")
+	}
+	return AbsolutePathFromUpstream(home).Join(".config", "turbo")
+}
+
 // GetUserConfigDir returns the platform-specific common location
 // for configuration files that belong to a user.
 func GetUserConfigDir() turbopath.AbsolutePath {
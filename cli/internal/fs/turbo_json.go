@@ -2,11 +2,15 @@ package fs
 
 import (
 	"encoding/json"
+	"errors"
 	"fmt"
 	"io/ioutil"
 	"log"
+	"path/filepath"
 	"strings"
 
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/util"
 	"muzzammil.xyz/jsonc"
@@ -30,14 +34,107 @@ type rawTurboJSON struct {
 	Pipeline Pipeline
 	// Configuration options when interfacing with the remote cache
 	RemoteCacheOptions RemoteCacheOptions `json:"remoteCache,omitempty"`
+	// Repo-relative directories that task outputs may resolve to, even
+	// though they are outside of the task's package
+	AllowedExternalOutputs []string `json:"allowedExternalOutputs,omitempty"`
+	// Additional frameworks to infer environment variable prefixes for
+	Frameworks []FrameworkConfig `json:"frameworks,omitempty"`
+	// Namespace folded into the global hash so that build variants never share cache entries
+	CacheNamespace string `json:"cacheNamespace,omitempty"`
+	// Whether text inputs are hashed with CRLF line endings converted to LF
+	NormalizeLineEndings bool `json:"normalizeLineEndings,omitempty"`
+	// Workspace dependencies that are only consumed for their types. Only meaningful
+	// in the "turbo" key of a workspace's package.json.
+	DependsOnTypesOnly []string `json:"dependsOnTypesOnly,omitempty"`
+	// Globs, relative to the workspace, of the files that can affect its types, for
+	// dependents that only consume its types. Only meaningful in the "turbo" key of a
+	// workspace's package.json.
+	TypesInputs []string `json:"typesInputs,omitempty"`
+	// Rules for which workspaces may import from each other
+	Boundaries *BoundariesConfig `json:"boundaries,omitempty"`
+	// Tags that boundary rules refer to the workspace by. Only meaningful in the "turbo"
+	// key of a workspace's package.json.
+	Tags []string `json:"tags,omitempty"`
+	// Commands and webhooks to notify when a run finishes
+	RunHooks []RunHook `json:"runHooks,omitempty"`
+	// Other turborepos whose workspaces are added to this repo's package graph
+	ExternalRepos []ExternalRepo `json:"externalRepos,omitempty"`
 }
 
 // TurboJSON is the root turborepo configuration
 type TurboJSON struct {
-	GlobalDeps         []string
-	GlobalEnv          []string
-	Pipeline           Pipeline
-	RemoteCacheOptions RemoteCacheOptions
+	GlobalDeps             []string
+	GlobalEnv              []string
+	Pipeline               Pipeline
+	RemoteCacheOptions     RemoteCacheOptions
+	AllowedExternalOutputs []string
+	Frameworks             []FrameworkConfig
+	CacheNamespace         string
+	NormalizeLineEndings   bool
+	DependsOnTypesOnly     []string
+	TypesInputs            []string
+	Boundaries             *BoundariesConfig
+	Tags                   []string
+	RunHooks               []RunHook
+	ExternalRepos          []ExternalRepo
+}
+
+// FrameworkConfig is a struct for deserializing a user-defined framework in .frameworks of configFile
+type FrameworkConfig struct {
+	Slug         string   `json:"slug"`
+	EnvPrefix    string   `json:"envPrefix"`
+	Dependencies []string `json:"dependencies"`
+	// DependencyMatch is either "all" (the default) or "some"
+	DependencyMatch string `json:"dependencyMatch,omitempty"`
+}
+
+// BoundariesConfig is a struct for deserializing .boundaries of configFile
+type BoundariesConfig struct {
+	// Level is "error" (the default) to fail runs that break a boundary, or "warn" to
+	// only report them
+	Level string `json:"level,omitempty"`
+	// Tags maps a tag to the rules for the workspaces that have it
+	Tags map[string]TagRules `json:"tags,omitempty"`
+}
+
+// TagRules restrict which workspaces the workspaces with a tag may import, by the
+// tags of the imported workspaces
+type TagRules struct {
+	// Allow, if set, lists the tags that imported workspaces must have one of
+	Allow []string `json:"allow,omitempty"`
+	// Deny lists tags that imported workspaces mustn't have
+	Deny []string `json:"deny,omitempty"`
+}
+
+// The runs that trigger a RunHook
+const (
+	RunHookAlways  = "always"
+	RunHookSuccess = "success"
+	RunHookFailure = "failure"
+)
+
+// RunHook is a command to run, or a webhook to call, with a summary of a run once it
+// finishes
+type RunHook struct {
+	// Command is run in a shell from the repository root, with the summary on its stdin
+	Command string `json:"command,omitempty"`
+	// Webhook is a URL the summary is POSTed to. Environment variables in it are expanded,
+	// so that it doesn't have to be committed.
+	Webhook string `json:"webhook,omitempty"`
+	// When is which runs trigger the hook, one of the RunHook constants. Defaults to
+	// RunHookAlways.
+	When string `json:"when,omitempty"`
+	// CacheHitRateBelow, if set, only triggers the hook when less than this percentage
+	// of the run's tasks were restored from the cache
+	CacheHitRateBelow *float64 `json:"cacheHitRateBelow,omitempty"`
+}
+
+// ExternalRepo is a turborepo outside of this one, typically a sibling checkout, whose
+// workspaces can be depended on and run as if they were in this repo. It is experimental,
+// to help with merging several repos into one.
+type ExternalRepo struct {
+	// Path is the root of the repo, relative to this repo's root
+	Path string `json:"path"`
 }
 
 // RemoteCacheOptions is a struct for deserializing .remoteCache of configFile
@@ -47,12 +144,31 @@ type RemoteCacheOptions struct {
 }
 
 type pipelineJSON struct {
-	Outputs    *[]string           `json:"outputs"`
-	Cache      *bool               `json:"cache,omitempty"`
-	DependsOn  []string            `json:"dependsOn,omitempty"`
-	Inputs     []string            `json:"inputs,omitempty"`
-	OutputMode util.TaskOutputMode `json:"outputMode,omitempty"`
-	Env        []string            `json:"env,omitempty"`
+	Outputs               *[]string           `json:"outputs"`
+	Cache                 *bool               `json:"cache,omitempty"`
+	DependsOn             []string            `json:"dependsOn,omitempty"`
+	Inputs                []string            `json:"inputs,omitempty"`
+	OutputMode            util.TaskOutputMode `json:"outputMode,omitempty"`
+	Env                   []string            `json:"env,omitempty"`
+	Before                string              `json:"before,omitempty"`
+	After                 string              `json:"after,omitempty"`
+	Interactive           bool                `json:"interactive,omitempty"`
+	EnvFiles              []string            `json:"envFiles,omitempty"`
+	InjectEnvFiles        bool                `json:"injectEnvFiles,omitempty"`
+	HashDependencyOutputs bool                `json:"hashDependencyOutputs,omitempty"`
+	EnvOutput             string              `json:"envOutput,omitempty"`
+	EnvInputsFrom         []string            `json:"envInputsFrom,omitempty"`
+	ExcludeGitAttributes  []string            `json:"excludeGitAttributes,omitempty"`
+	OnFailure             *FailureOptions     `json:"onFailure,omitempty"`
+}
+
+// FailureOptions is what to keep from a task when it fails
+type FailureOptions struct {
+	// Capture are globs, relative to the package, of the files to keep along with the
+	// task's log
+	Capture []string `json:"capture,omitempty"`
+	// Remote uploads the failure artifact to the remote cache as well
+	Remote bool `json:"remote,omitempty"`
 }
 
 // Pipeline is a struct for deserializing .pipeline in configFile
@@ -67,6 +183,29 @@ type TaskDefinition struct {
 	TaskDependencies        []string
 	Inputs                  []string
 	OutputMode              util.TaskOutputMode
+	Before                  string
+	After                   string
+	// Interactive tasks read from turbo's stdin and write straight to its output
+	Interactive bool
+	// EnvFiles are dotenv files, relative to the package, whose variables are part of the
+	// task's hash. Later files override earlier ones.
+	EnvFiles []string
+	// InjectEnvFiles sets the variables from EnvFiles in the task's environment
+	InjectEnvFiles bool
+	// HashDependencyOutputs hashes the task's dependencies by the contents of their
+	// outputs rather than by their task hashes
+	HashDependencyOutputs bool
+	// EnvOutput is a dotenv file, relative to the package, that the task writes for its
+	// dependents to read. It is one of the task's outputs.
+	EnvOutput string
+	// EnvInputsFrom are the IDs of tasks whose EnvOutput variables are set in the task's
+	// environment and are part of its hash
+	EnvInputsFrom []string
+	// ExcludeGitAttributes are gitattributes, such as linguist-generated, that leave the
+	// files they are set on out of the task's default inputs
+	ExcludeGitAttributes []string
+	// OnFailure is what to capture into a failure artifact when the task fails, if anything
+	OnFailure *FailureOptions
 }
 
 // ReadTurboConfig toggles between reading from package.json or the configFile to support early adopters.
@@ -102,7 +241,7 @@ func ReadTurboConfig(rootPath turbopath.AbsolutePath, rootPackageJSON *PackageJS
 	}
 
 	// If there's no turbo.json and no turbo key in package.json, return an error.
-	return nil, fmt.Errorf("Could not find %s. Follow directions at https://turborepo.org/docs/getting-started to create one", configFile)
+	return nil, errcode.Wrap(errcode.ConfigMissing, fmt.Errorf("Could not find %s. Follow directions at https://turborepo.org/docs/getting-started to create one", configFile))
 }
 
 // readTurboJSON reads the configFile in to a struct
@@ -117,15 +256,49 @@ func readTurboJSON(path turbopath.AbsolutePath) (*TurboJSON, error) {
 		return nil, err
 	}
 
-	err = jsonc.Unmarshal(data, &turboJSON)
+	jsonData := jsonc.ToJSON(data)
+	err = json.Unmarshal(jsonData, &turboJSON)
 
 	if err != nil {
-		return nil, err
+		// jsonc.ToJSON strips comments and whitespace, so offsets into the translated JSON
+		// don't line up with the file. Decoding the file as it is finds the same error at its
+		// real offset, unless a comment gets in the way first.
+		var located *TurboJSON
+		if rawErr := json.Unmarshal(data, &located); rawErr != nil && rawErr.Error() == err.Error() {
+			err = rawErr
+		} else {
+			data = nil
+		}
+		return nil, codeJSONError(path.ToString(), data, err)
 	}
 
 	return turboJSON, nil
 }
 
+// codeJSONError gives an error from decoding the JSON in file a code, and a span when the
+// decoder reports where it went wrong. contents may be nil if the decoder's offsets don't
+// correspond to the file.
+func codeJSONError(file string, contents []byte, err error) error {
+	if errcode.Of(err) != errcode.Unknown {
+		return err
+	}
+	var span *errcode.Span
+	code := errcode.ConfigSyntax
+	var syntaxErr *json.SyntaxError
+	var typeErr *json.UnmarshalTypeError
+	if errors.As(err, &syntaxErr) {
+		if contents != nil {
+			span = errcode.SpanAt(file, contents, syntaxErr.Offset-1)
+		}
+	} else if errors.As(err, &typeErr) {
+		code = errcode.ConfigInvalid
+		if contents != nil {
+			span = errcode.SpanAt(file, contents, typeErr.Offset-1)
+		}
+	}
+	return errcode.WithSpan(code, span, err)
+}
+
 // GetTaskDefinition returns a TaskDefinition from a serialized definition in configFile
 func (pc Pipeline) GetTaskDefinition(taskID string) (TaskDefinition, bool) {
 	if entry, ok := pc[taskID]; ok {
@@ -194,7 +367,7 @@ func (c *TaskDefinition) UnmarshalJSON(data []byte) error {
 		if strings.HasPrefix(value, envPipelineDelimiter) {
 			// Hard error to help people specify this correctly during migration.
 			// TODO: Remove this error after we have run summary.
-			return fmt.Errorf("You specified \"%s\" in the \"env\" key. You should not prefix your environment variables with \"$\"", value)
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("You specified \"%s\" in the \"env\" key. You should not prefix your environment variables with \"$\"", value))
 		}
 
 		envVarDependencies.Add(value)
@@ -203,6 +376,32 @@ func (c *TaskDefinition) UnmarshalJSON(data []byte) error {
 	c.EnvVarDependencies = envVarDependencies.UnsafeListOfStrings()
 	c.Inputs = rawPipeline.Inputs
 	c.OutputMode = rawPipeline.OutputMode
+	c.Before = rawPipeline.Before
+	c.After = rawPipeline.After
+	c.Interactive = rawPipeline.Interactive
+	c.EnvFiles = rawPipeline.EnvFiles
+	c.InjectEnvFiles = rawPipeline.InjectEnvFiles
+	c.HashDependencyOutputs = rawPipeline.HashDependencyOutputs
+	c.EnvOutput = rawPipeline.EnvOutput
+	c.EnvInputsFrom = rawPipeline.EnvInputsFrom
+	for _, attribute := range rawPipeline.ExcludeGitAttributes {
+		if attribute == "" || strings.HasPrefix(attribute, "-") || strings.ContainsAny(attribute, " \t=!") {
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("%q in \"excludeGitAttributes\" is not a gitattribute name", attribute))
+		}
+	}
+	c.ExcludeGitAttributes = rawPipeline.ExcludeGitAttributes
+	if rawPipeline.OnFailure != nil {
+		for _, glob := range rawPipeline.OnFailure.Capture {
+			if filepath.IsAbs(glob) || strings.HasPrefix(glob, "../") || !doublestar.ValidatePattern(glob) {
+				return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("%q in \"onFailure.capture\" is not a glob relative to the package", glob))
+			}
+		}
+	}
+	c.OnFailure = rawPipeline.OnFailure
+	if c.EnvOutput != "" {
+		// Copy rather than append, so that defaultOutputs isn't shared
+		c.Outputs = append(append([]string{}, c.Outputs...), c.EnvOutput)
+	}
 	return nil
 }
 
@@ -220,7 +419,7 @@ func (c *TurboJSON) UnmarshalJSON(data []byte) error {
 		if strings.HasPrefix(value, envPipelineDelimiter) {
 			// Hard error to help people specify this correctly during migration.
 			// TODO: Remove this error after we have run summary.
-			return fmt.Errorf("You specified \"%s\" in the \"env\" key. You should not prefix your environment variables with \"%s\"", value, envPipelineDelimiter)
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("You specified \"%s\" in the \"env\" key. You should not prefix your environment variables with \"%s\"", value, envPipelineDelimiter))
 		}
 
 		envVarDependencies.Add(value)
@@ -238,9 +437,43 @@ func (c *TurboJSON) UnmarshalJSON(data []byte) error {
 	c.GlobalEnv = envVarDependencies.UnsafeListOfStrings()
 	c.GlobalDeps = globalFileDependencies.UnsafeListOfStrings()
 
+	if raw.Boundaries != nil && raw.Boundaries.Level != "" && raw.Boundaries.Level != "error" && raw.Boundaries.Level != "warn" {
+		return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("\"boundaries.level\" must be \"error\" or \"warn\", not %q", raw.Boundaries.Level))
+	}
+
+	for i, hook := range raw.RunHooks {
+		if (hook.Command == "") == (hook.Webhook == "") {
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("\"runHooks[%v]\" must have either a \"command\" or a \"webhook\"", i))
+		}
+		if hook.When != "" && hook.When != RunHookAlways && hook.When != RunHookSuccess && hook.When != RunHookFailure {
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("\"runHooks[%v].when\" must be \"always\", \"success\" or \"failure\", not %q", i, hook.When))
+		}
+		if rate := hook.CacheHitRateBelow; rate != nil && (*rate < 0 || *rate > 100) {
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("\"runHooks[%v].cacheHitRateBelow\" must be a percentage between 0 and 100", i))
+		}
+	}
+
+	for i, repo := range raw.ExternalRepos {
+		path := filepath.ToSlash(filepath.Clean(filepath.FromSlash(repo.Path)))
+		// Cleaning leaves a leading ../ only on paths that leave the repo
+		if !strings.HasPrefix(path, "../") {
+			return errcode.Wrap(errcode.ConfigInvalid, fmt.Errorf("\"externalRepos[%v].path\" must be a relative path to a directory outside of this repo, not %q", i, repo.Path))
+		}
+	}
+
 	// copy these over, we don't need any changes here.
 	c.Pipeline = raw.Pipeline
 	c.RemoteCacheOptions = raw.RemoteCacheOptions
+	c.AllowedExternalOutputs = raw.AllowedExternalOutputs
+	c.Frameworks = raw.Frameworks
+	c.CacheNamespace = raw.CacheNamespace
+	c.NormalizeLineEndings = raw.NormalizeLineEndings
+	c.DependsOnTypesOnly = raw.DependsOnTypesOnly
+	c.TypesInputs = raw.TypesInputs
+	c.Boundaries = raw.Boundaries
+	c.Tags = raw.Tags
+	c.RunHooks = raw.RunHooks
+	c.ExternalRepos = raw.ExternalRepos
 
 	return nil
 }
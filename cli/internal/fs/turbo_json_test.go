@@ -1,12 +1,14 @@
 package fs
 
 import (
+	"encoding/json"
 	"os"
 	"sort"
 	"strings"
 	"testing"
 
 	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/util"
 )
@@ -60,6 +62,8 @@ func Test_ReadTurboConfig(t *testing.T) {
 			ShouldCache:             false,
 			Inputs:                  []string{"build/**/*"},
 			OutputMode:              util.FullTaskOutput,
+			Before:                  "node scripts/check-env.js",
+			After:                   "node scripts/notify.js",
 		},
 	}
 
@@ -262,3 +266,102 @@ func sortedArray(arr []string) []string {
 	sort.Strings(arr)
 	return arr
 }
+
+func Test_ReadTurboConfig_InvalidSyntax(t *testing.T) {
+	testDir := getTestDir(t, "invalid-syntax")
+	rootPackageJSON, err := ReadPackageJSON(testDir.Join("package.json"))
+	assert.NoError(t, err)
+
+	_, err = ReadTurboConfig(testDir, rootPackageJSON)
+	report := errcode.NewReport(err)
+	assert.Equal(t, errcode.ConfigSyntax, report.Code)
+	assert.Equal(t, &errcode.Span{File: testDir.Join("turbo.json").ToString(), Line: 7, Column: 3}, report.Span)
+}
+
+func Test_TaskDefinition_EnvOutput(t *testing.T) {
+	var taskDefinition TaskDefinition
+	err := json.Unmarshal([]byte(`{"envOutput": ".env.generated", "envInputsFrom": ["codegen#build"]}`), &taskDefinition)
+	assert.NoError(t, err)
+	assert.Equal(t, ".env.generated", taskDefinition.EnvOutput)
+	assert.Equal(t, []string{"codegen#build"}, taskDefinition.EnvInputsFrom)
+	// The env file is restored from the cache along with the default outputs
+	assert.Equal(t, []string{"dist/**/*", "build/**/*", ".env.generated"}, taskDefinition.Outputs)
+	assert.Equal(t, []string{"dist/**/*", "build/**/*"}, defaultOutputs)
+}
+
+func Test_TurboJSON_Boundaries(t *testing.T) {
+	var turboJSON TurboJSON
+	err := json.Unmarshal([]byte(`{"boundaries": {"level": "warn", "tags": {"app": {"allow": ["shared"]}}}}`), &turboJSON)
+	assert.NoError(t, err)
+	assert.Equal(t, "warn", turboJSON.Boundaries.Level)
+	assert.Equal(t, TagRules{Allow: []string{"shared"}}, turboJSON.Boundaries.Tags["app"])
+
+	err = json.Unmarshal([]byte(`{"boundaries": {"level": "strict"}}`), &turboJSON)
+	assert.Equal(t, errcode.ConfigInvalid, errcode.NewReport(err).Code)
+}
+
+func Test_TaskDefinition_ExcludeGitAttributes(t *testing.T) {
+	var taskDefinition TaskDefinition
+	err := json.Unmarshal([]byte(`{"excludeGitAttributes": ["linguist-generated", "export-ignore"]}`), &taskDefinition)
+	assert.NoError(t, err)
+	assert.Equal(t, []string{"linguist-generated", "export-ignore"}, taskDefinition.ExcludeGitAttributes)
+
+	err = json.Unmarshal([]byte(`{"excludeGitAttributes": ["linguist-generated=true"]}`), &taskDefinition)
+	assert.Equal(t, errcode.ConfigInvalid, errcode.NewReport(err).Code)
+}
+
+func Test_TaskDefinition_OnFailure(t *testing.T) {
+	var taskDefinition TaskDefinition
+	err := json.Unmarshal([]byte(`{"onFailure": {"capture": ["playwright-report/**"], "remote": true}}`), &taskDefinition)
+	assert.NoError(t, err)
+	assert.Equal(t, &FailureOptions{Capture: []string{"playwright-report/**"}, Remote: true}, taskDefinition.OnFailure)
+
+	taskDefinition = TaskDefinition{}
+	err = json.Unmarshal([]byte(`{}`), &taskDefinition)
+	assert.NoError(t, err)
+	assert.Nil(t, taskDefinition.OnFailure)
+
+	err = json.Unmarshal([]byte(`{"onFailure": {"capture": ["../other/**"]}}`), &taskDefinition)
+	assert.Equal(t, errcode.ConfigInvalid, errcode.NewReport(err).Code)
+}
+
+func Test_TurboJSON_RunHooks(t *testing.T) {
+	var turboJSON TurboJSON
+	err := json.Unmarshal([]byte(`{"runHooks": [
+		{"webhook": "$SLACK_WEBHOOK_URL", "when": "failure"},
+		{"command": "node notify.js", "cacheHitRateBelow": 50}
+	]}`), &turboJSON)
+	assert.NoError(t, err)
+	threshold := 50.0
+	assert.Equal(t, []RunHook{
+		{Webhook: "$SLACK_WEBHOOK_URL", When: RunHookFailure},
+		{Command: "node notify.js", CacheHitRateBelow: &threshold},
+	}, turboJSON.RunHooks)
+
+	for _, invalid := range []string{
+		`{"runHooks": [{}]}`,
+		`{"runHooks": [{"command": "notify", "webhook": "https://example.com"}]}`,
+		`{"runHooks": [{"command": "notify", "when": "sometimes"}]}`,
+		`{"runHooks": [{"command": "notify", "cacheHitRateBelow": 150}]}`,
+	} {
+		err = json.Unmarshal([]byte(invalid), &turboJSON)
+		assert.Equal(t, errcode.ConfigInvalid, errcode.NewReport(err).Code, invalid)
+	}
+}
+
+func Test_TurboJSON_ExternalRepos(t *testing.T) {
+	var turboJSON TurboJSON
+	err := json.Unmarshal([]byte(`{"externalRepos": [{"path": "../design-system"}]}`), &turboJSON)
+	assert.NoError(t, err)
+	assert.Equal(t, []ExternalRepo{{Path: "../design-system"}}, turboJSON.ExternalRepos)
+
+	for _, invalid := range []string{
+		`{"externalRepos": [{}]}`,
+		`{"externalRepos": [{"path": "/repos/design-system"}]}`,
+		`{"externalRepos": [{"path": "packages/../../"}]}`,
+		`{"externalRepos": [{"path": "packages/ui"}]}`,
+	} {
+		err = json.Unmarshal([]byte(invalid), &turboJSON)
+		assert.Equal(t, errcode.ConfigInvalid, errcode.NewReport(err).Code, invalid)
+	}
+}
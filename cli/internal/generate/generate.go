@@ -0,0 +1,197 @@
+// Package generate scaffolds new parts of a repository from templates
+package generate
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/AlecAivazis/survey/v2"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/login"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+// GetCmd returns the gen command and its subcommands for use with cobra
+func GetCmd(helper *cmdutil.Helper) *cobra.Command {
+	cmd := &cobra.Command{
+		Use:           "gen",
+		Short:         "Generate new parts of your repository from templates",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+	}
+	addWorkspaceCmd(cmd, helper)
+	return cmd
+}
+
+type workspaceOpts struct {
+	name        string
+	template    string
+	destination string
+	variables   map[string]string
+}
+
+func addWorkspaceCmd(root *cobra.Command, helper *cmdutil.Helper) {
+	opts := &workspaceOpts{}
+	cmd := &cobra.Command{
+		Use:           "workspace",
+		Short:         "Create a new workspace from a template directory",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			if opts.template == "" {
+				err := fmt.Errorf("--template is required")
+				base.LogError("%v", err)
+				return err
+			}
+			var destination turbopath.AbsolutePath
+			if opts.destination != "" {
+				destination = fs.ResolveUnknownPath(base.RepoRoot, opts.destination)
+			}
+			result, err := Workspace(base.RepoRoot, WorkspaceOptions{
+				Name:           opts.name,
+				Template:       fs.ResolveUnknownPath(base.RepoRoot, opts.template),
+				Destination:    destination,
+				Variables:      opts.variables,
+				PackageManager: base.RepoConfig.PackageManager(),
+			})
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			relativeDir, err := base.RepoRoot.PathTo(result.Dir)
+			if err != nil {
+				return err
+			}
+			base.UI.Output(fmt.Sprintf("Created %v in %v", ui.Bold(opts.name), relativeDir))
+			for _, file := range result.Files {
+				base.UI.Output(ui.Dim("  " + file))
+			}
+			if result.WorkspaceGlob != "" {
+				base.UI.Output(fmt.Sprintf("Added %v to your workspaces", result.WorkspaceGlob))
+			}
+			for _, task := range result.Tasks {
+				base.UI.Output(fmt.Sprintf("Added %v to the pipeline in turbo.json", task))
+			}
+			base.UI.Output("Run your package manager's install command to link the new workspace")
+			return nil
+		},
+	}
+	cmd.Flags().StringVar(&opts.name, "name", "", "The package name of the new workspace")
+	cmd.Flags().StringVar(&opts.template, "template", "", "The directory to create the workspace from")
+	cmd.Flags().StringVar(&opts.destination, "destination", "", "Where to create the workspace. Defaults to a directory named after the workspace, next to existing workspaces")
+	cmd.Flags().StringToStringVar(&opts.variables, "var", nil, "Set a variable used by the template, as key=value")
+	root.AddCommand(cmd)
+}
+
+type initOpts struct {
+	yes  bool
+	link bool
+}
+
+// InitCmd returns the init command for use with cobra
+func InitCmd(helper *cmdutil.Helper) *cobra.Command {
+	opts := &initOpts{}
+	cmd := &cobra.Command{
+		Use:           "init",
+		Short:         "Create a turbo.json for your repository",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			interactive := ui.IsTTY && !opts.yes
+			initOptions := InitOptions{PackageManager: base.RepoConfig.PackageManager()}
+			if interactive {
+				initOptions.ChooseTasks = promptTasks
+			}
+			result, err := Init(base.RepoRoot, initOptions)
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			base.UI.Output(fmt.Sprintf("Found %v workspaces using %v", len(result.Workspaces), result.PackageManager))
+			for _, task := range result.Tasks {
+				line := fmt.Sprintf("  %v %v", ui.Bold(task.Name), ui.Dim(fmt.Sprintf("(%v)", strings.Join(task.Workspaces, ", "))))
+				if len(task.Frameworks) > 0 {
+					line += ui.Dim(fmt.Sprintf(", outputs inferred from %v", strings.Join(task.Frameworks, ", ")))
+				}
+				base.UI.Output(line)
+			}
+			base.UI.Output("Created turbo.json")
+
+			shouldLink := opts.link
+			if !shouldLink && interactive {
+				shouldLink, err = promptLink()
+				if err != nil {
+					return err
+				}
+			}
+			if shouldLink {
+				return login.Link(base, true)
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVarP(&opts.yes, "yes", "y", false, "Write every task that was found without asking, and don't offer to set up Remote Caching")
+	cmd.Flags().BoolVar(&opts.link, "link", false, "Link the repository to a Remote Cache after creating turbo.json")
+	return cmd
+}
+
+func promptTasks(tasks []InitTask) ([]InitTask, error) {
+	names := make([]string, len(tasks))
+	for i, task := range tasks {
+		names[i] = task.Name
+	}
+	chosen := []string{}
+	err := survey.AskOne(
+		&survey.MultiSelect{
+			Message: "Which tasks should turbo.json have a pipeline entry for?",
+			Options: names,
+			Default: names,
+		},
+		&chosen,
+		survey.WithIcons(func(icons *survey.IconSet) {
+			// for more information on formatting the icons, see here: https://github.com/mgutz/ansi#style-format
+			icons.Question.Format = "gray+hb"
+		}))
+	if err != nil {
+		return nil, err
+	}
+	chosenTasks := []InitTask{}
+	for _, task := range tasks {
+		for _, name := range chosen {
+			if task.Name == name {
+				chosenTasks = append(chosenTasks, task)
+				break
+			}
+		}
+	}
+	return chosenTasks, nil
+}
+
+func promptLink() (bool, error) {
+	shouldLink := false
+	err := survey.AskOne(
+		&survey.Confirm{
+			Default: false,
+			Message: "Would you like to share your cache with your team through Remote Caching?",
+		},
+		&shouldLink,
+		survey.WithIcons(func(icons *survey.IconSet) {
+			// for more information on formatting the icons, see here: https://github.com/mgutz/ansi#style-format
+			icons.Question.Format = "gray+hb"
+		}))
+	if err != nil {
+		return false, err
+	}
+	return shouldLink, nil
+}
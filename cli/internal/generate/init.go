@@ -0,0 +1,226 @@
+package generate
+
+import (
+	"encoding/json"
+	"fmt"
+	"path/filepath"
+	"sort"
+
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/inference"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _schemaURL is where editors find the schema of turbo.json
+const _schemaURL = "https://turborepo.org/schema.json"
+
+// _defaultBuildOutputs are the build outputs of workspaces that don't use a framework
+// turbo knows about
+var _defaultBuildOutputs = []string{"dist/**"}
+
+// _commonTasks are the scripts that init writes a pipeline entry for, in the order they
+// are offered
+var _commonTasks = []string{"build", "test", "lint", "typecheck", "check-types", "dev", "start"}
+
+// InitOptions describes how to generate turbo.json for a repository
+type InitOptions struct {
+	// PackageManager overrides package manager detection, if set
+	PackageManager string
+	// ChooseTasks picks which of the tasks that were found go in turbo.json. If nil,
+	// all of them do.
+	ChooseTasks func(tasks []InitTask) ([]InitTask, error)
+}
+
+// InitTask is a pipeline entry that init generates
+type InitTask struct {
+	// Name is the task, which is also the name of the script it runs
+	Name string
+	// Workspaces are the workspaces with a script for the task
+	Workspaces []string
+	// Frameworks are the frameworks that the task's outputs were inferred from
+	Frameworks []string
+	// Definition is the pipeline entry for the task
+	Definition InitTaskDefinition
+}
+
+// InitTaskDefinition is the part of a pipeline entry that init fills in
+type InitTaskDefinition struct {
+	DependsOn []string  `json:"dependsOn,omitempty"`
+	Outputs   *[]string `json:"outputs,omitempty"`
+	Cache     *bool     `json:"cache,omitempty"`
+}
+
+// InitResult describes the repository init inspected and the turbo.json it wrote
+type InitResult struct {
+	// PackageManager is the name of the repository's package manager
+	PackageManager string
+	// Workspaces are the names of the repository's workspaces
+	Workspaces []string
+	// Tasks are the pipeline entries written to turbo.json
+	Tasks []InitTask
+}
+
+type initTurboJSON struct {
+	Schema   string                        `json:"$schema"`
+	Pipeline map[string]InitTaskDefinition `json:"pipeline"`
+}
+
+// Init writes a starter turbo.json for the repository at repoRoot. It finds the
+// package manager and the workspaces, and adds a pipeline entry for each of the common
+// scripts that any workspace has. The outputs of builds are inferred from the
+// frameworks the workspaces use.
+func Init(repoRoot turbopath.AbsolutePath, opts InitOptions) (*InitResult, error) {
+	turboJSONPath := repoRoot.Join("turbo.json")
+	if turboJSONPath.FileExists() {
+		return nil, fmt.Errorf("%v already exists", turboJSONPath)
+	}
+	rootPackageJSON, err := turbofs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read package.json: %w", err)
+	}
+	pm, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON, opts.PackageManager)
+	if err != nil {
+		return nil, err
+	}
+	workspaces, err := readWorkspaces(repoRoot, pm)
+	if err != nil {
+		return nil, err
+	}
+	if len(workspaces) == 0 {
+		return nil, fmt.Errorf("no workspaces found, add some to your %v workspace configuration first", pm.Name)
+	}
+
+	tasks := findInitTasks(workspaces)
+	if opts.ChooseTasks != nil && len(tasks) > 0 {
+		tasks, err = opts.ChooseTasks(tasks)
+		if err != nil {
+			return nil, err
+		}
+	}
+	turboJSON := initTurboJSON{
+		Schema:   _schemaURL,
+		Pipeline: make(map[string]InitTaskDefinition, len(tasks)),
+	}
+	for _, task := range tasks {
+		turboJSON.Pipeline[task.Name] = task.Definition
+	}
+	contents, err := json.MarshalIndent(turboJSON, "", "  ")
+	if err != nil {
+		return nil, err
+	}
+	if err := turboJSONPath.WriteFile(append(contents, '\n'), 0644); err != nil {
+		return nil, err
+	}
+
+	result := &InitResult{PackageManager: pm.Name, Tasks: tasks}
+	for _, pkg := range workspaces {
+		result.Workspaces = append(result.Workspaces, pkg.Name)
+	}
+	return result, nil
+}
+
+// readWorkspaces reads the package.json of every workspace, sorted by name
+func readWorkspaces(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager) ([]*turbofs.PackageJSON, error) {
+	paths, err := pm.GetWorkspaces(repoRoot)
+	if err != nil {
+		return nil, fmt.Errorf("workspace configuration error: %w", err)
+	}
+	workspaces := make([]*turbofs.PackageJSON, 0, len(paths))
+	for _, path := range paths {
+		pkg, err := turbofs.ReadPackageJSON(turbofs.UnsafeToAbsolutePath(path))
+		if err != nil {
+			relativePath, _ := filepath.Rel(repoRoot.ToString(), path)
+			return nil, fmt.Errorf("parsing %v: %w", relativePath, err)
+		}
+		// Frameworks are inferred from every dependency, before the graph tells internal
+		// and external ones apart
+		pkg.UnresolvedExternalDeps = make(map[string]string)
+		for _, deps := range []map[string]string{pkg.DevDependencies, pkg.OptionalDependencies, pkg.Dependencies} {
+			for dep, version := range deps {
+				pkg.UnresolvedExternalDeps[dep] = version
+			}
+		}
+		workspaces = append(workspaces, pkg)
+	}
+	sort.Slice(workspaces, func(i, j int) bool { return workspaces[i].Name < workspaces[j].Name })
+	return workspaces, nil
+}
+
+// findInitTasks returns a pipeline entry for each common script that a workspace has
+func findInitTasks(workspaces []*turbofs.PackageJSON) []InitTask {
+	found := map[string]*InitTask{}
+	for _, name := range _commonTasks {
+		found[name] = &InitTask{Name: name}
+	}
+	buildOutputs := make(util.Set)
+	frameworks := make(util.Set)
+	for _, pkg := range workspaces {
+		for script := range pkg.Scripts {
+			if task, ok := found[script]; ok {
+				task.Workspaces = append(task.Workspaces, pkg.Name)
+			}
+		}
+		if _, ok := pkg.Scripts["build"]; !ok {
+			continue
+		}
+		outputs := _defaultBuildOutputs
+		if framework := inference.InferFramework(pkg); framework != nil && len(framework.BuildOutputs) > 0 {
+			outputs = framework.BuildOutputs
+			frameworks.Add(framework.Slug)
+		}
+		for _, output := range outputs {
+			buildOutputs.Add(output)
+		}
+	}
+
+	hasBuild := len(found["build"].Workspaces) > 0
+	var dependsOnBuild []string
+	if hasBuild {
+		dependsOnBuild = []string{"^build"}
+	}
+	noCache := false
+	tasks := []InitTask{}
+	for _, name := range _commonTasks {
+		task := found[name]
+		if len(task.Workspaces) == 0 {
+			continue
+		}
+		switch name {
+		case "build":
+			task.Definition = InitTaskDefinition{DependsOn: []string{"^build"}, Outputs: sortedOutputs(buildOutputs)}
+			task.Frameworks = frameworks.UnsafeListOfStrings()
+			sort.Strings(task.Frameworks)
+		case "test":
+			task.Definition = InitTaskDefinition{DependsOn: dependsOnBuild, Outputs: &[]string{"coverage/**"}}
+		case "typecheck", "check-types":
+			task.Definition = InitTaskDefinition{DependsOn: dependsOnBuild, Outputs: &[]string{}}
+		case "dev":
+			task.Definition = InitTaskDefinition{Cache: &noCache}
+		case "start":
+			var dependsOn []string
+			if hasBuild {
+				dependsOn = []string{"build"}
+			}
+			task.Definition = InitTaskDefinition{DependsOn: dependsOn, Cache: &noCache}
+		default:
+			task.Definition = InitTaskDefinition{Outputs: &[]string{}}
+		}
+		tasks = append(tasks, *task)
+	}
+	return tasks
+}
+
+// sortedOutputs sorts outputs globs, keeping each exclusion after what it excludes from
+func sortedOutputs(outputs util.Set) *[]string {
+	sorted := outputs.UnsafeListOfStrings()
+	sort.Slice(sorted, func(i, j int) bool {
+		a, b := sorted[i], sorted[j]
+		if (a[0] == '!') != (b[0] == '!') {
+			return b[0] == '!'
+		}
+		return a < b
+	})
+	return &sorted
+}
@@ -0,0 +1,77 @@
+package generate
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestInit(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile(t, repoRoot.Join("package.json"), `{"name": "monorepo", "packageManager": "npm@8.19.2", "workspaces": ["apps/*", "packages/*"]}`)
+	writeFile(t, repoRoot.Join("apps", "web", "package.json"), `{"name": "web", "scripts": {"build": "next build", "dev": "next dev", "lint": "next lint"}, "dependencies": {"next": "^13.0.0"}}`)
+	writeFile(t, repoRoot.Join("apps", "docs", "package.json"), `{"name": "docs", "scripts": {"build": "vite build", "deploy": "vercel"}, "devDependencies": {"vite": "^4.0.0"}}`)
+	writeFile(t, repoRoot.Join("packages", "ui", "package.json"), `{"name": "ui", "scripts": {"build": "tsc", "test": "jest", "lint": "eslint ."}}`)
+
+	result, err := Init(repoRoot, InitOptions{})
+	assert.NilError(t, err, "Init")
+	assert.Equal(t, result.PackageManager, "nodejs-npm")
+	assert.DeepEqual(t, result.Workspaces, []string{"docs", "ui", "web"})
+	names := []string{}
+	for _, task := range result.Tasks {
+		names = append(names, task.Name)
+	}
+	assert.DeepEqual(t, names, []string{"build", "test", "lint", "dev"})
+	assert.DeepEqual(t, result.Tasks[0].Frameworks, []string{"nextjs", "vite"})
+	assert.Equal(t, readFile(t, repoRoot.Join("turbo.json")), `{
+  "$schema": "https://turborepo.org/schema.json",
+  "pipeline": {
+    "build": {
+      "dependsOn": [
+        "^build"
+      ],
+      "outputs": [
+        ".next/**",
+        "dist/**",
+        "!.next/cache/**"
+      ]
+    },
+    "dev": {
+      "cache": false
+    },
+    "lint": {
+      "outputs": []
+    },
+    "test": {
+      "dependsOn": [
+        "^build"
+      ],
+      "outputs": [
+        "coverage/**"
+      ]
+    }
+  }
+}
+`)
+
+	_, err = Init(repoRoot, InitOptions{})
+	assert.ErrorContains(t, err, "already exists")
+}
+
+func TestInitChooseTasks(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile(t, repoRoot.Join("package.json"), `{"name": "monorepo", "packageManager": "npm@8.19.2", "workspaces": ["packages/*"]}`)
+	writeFile(t, repoRoot.Join("packages", "ui", "package.json"), `{"name": "ui", "scripts": {"test": "jest", "lint": "eslint ."}}`)
+
+	result, err := Init(repoRoot, InitOptions{
+		ChooseTasks: func(tasks []InitTask) ([]InitTask, error) {
+			return tasks[:1], nil
+		},
+	})
+	assert.NilError(t, err, "Init")
+	assert.Equal(t, len(result.Tasks), 1)
+	assert.Equal(t, result.Tasks[0].Name, "test")
+	// Without a build task, tests don't depend on one
+	assert.Equal(t, readFile(t, repoRoot.Join("turbo.json")), "{\n  \"$schema\": \"https://turborepo.org/schema.json\",\n  \"pipeline\": {\n    \"test\": {\n      \"outputs\": [\n        \"coverage/**\"\n      ]\n    }\n  }\n}\n")
+}
@@ -0,0 +1,43 @@
+package generate
+
+import (
+	"bytes"
+	"fmt"
+	"regexp"
+	"sort"
+	"strings"
+)
+
+// _placeholder matches a handlebars-style variable, e.g. `{{ name }}`
+var _placeholder = regexp.MustCompile(`\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}`)
+
+// render substitutes variables into a template. Unlike handlebars, a variable that
+// has no value is an error rather than an empty string, since a workspace generated
+// with missing values is almost never what was intended.
+func render(template string, variables map[string]string) (string, error) {
+	missing := map[string]bool{}
+	rendered := _placeholder.ReplaceAllStringFunc(template, func(match string) string {
+		name := _placeholder.FindStringSubmatch(match)[1]
+		value, ok := variables[name]
+		if !ok {
+			missing[name] = true
+			return match
+		}
+		return value
+	})
+	if len(missing) > 0 {
+		names := make([]string, 0, len(missing))
+		for name := range missing {
+			names = append(names, name)
+		}
+		sort.Strings(names)
+		return "", fmt.Errorf("no value for %v", strings.Join(names, ", "))
+	}
+	return rendered, nil
+}
+
+// isBinary reports whether contents look like something other than text, in which
+// case they are copied as is rather than rendered
+func isBinary(contents []byte) bool {
+	return bytes.IndexByte(contents, 0) != -1
+}
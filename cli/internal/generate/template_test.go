@@ -0,0 +1,19 @@
+package generate
+
+import (
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func Test_render(t *testing.T) {
+	rendered, err := render(`{"name": "{{name}}", "description": "{{ description }}"}`, map[string]string{
+		"name":        "@acme/ui",
+		"description": "Shared components",
+	})
+	assert.NilError(t, err, "render")
+	assert.Equal(t, rendered, `{"name": "@acme/ui", "description": "Shared components"}`)
+
+	_, err = render("{{name}} {{ owner }} {{license}}", map[string]string{"name": "ui"})
+	assert.ErrorContains(t, err, "no value for license, owner")
+}
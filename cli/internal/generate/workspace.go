@@ -0,0 +1,432 @@
+package generate
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"regexp"
+	"sort"
+	"strings"
+	"unicode"
+
+	turbofs "github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"gopkg.in/yaml.v3"
+)
+
+// WorkspaceOptions describes the workspace to generate
+type WorkspaceOptions struct {
+	// Name is the name of the new workspace's package
+	Name string
+	// Template is the directory to copy the workspace from
+	Template turbopath.AbsolutePath
+	// Destination is where to create the workspace. If empty, it is derived from
+	// the first workspace glob of the form `<dir>/*`.
+	Destination turbopath.AbsolutePath
+	// Variables are substituted into the template, in addition to `name`
+	Variables map[string]string
+	// PackageManager overrides package manager detection, if set
+	PackageManager string
+}
+
+// WorkspaceResult describes what generating a workspace changed
+type WorkspaceResult struct {
+	// Dir is the directory the workspace was created in
+	Dir turbopath.AbsolutePath
+	// Files are the created files, relative to Dir
+	Files []string
+	// WorkspaceGlob is the entry added to the workspace configuration, if
+	// the existing globs didn't already match Dir
+	WorkspaceGlob string
+	// Tasks are the pipeline entries added to turbo.json
+	Tasks []string
+}
+
+// Workspace creates a new workspace in the repository at repoRoot from a template.
+// Files in the template are rendered with the given variables, the new workspace is
+// added to the package manager's workspaces if none of the existing globs match it,
+// and each of its scripts that the pipeline doesn't cover yet gets a pipeline entry.
+func Workspace(repoRoot turbopath.AbsolutePath, opts WorkspaceOptions) (*WorkspaceResult, error) {
+	if opts.Name == "" {
+		return nil, fmt.Errorf("a workspace name is required")
+	}
+	if !opts.Template.DirExists() {
+		return nil, fmt.Errorf("template %v is not a directory", opts.Template)
+	}
+	rootPackageJSON, err := turbofs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read package.json: %w", err)
+	}
+	pm, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON, opts.PackageManager)
+	if err != nil {
+		return nil, err
+	}
+	dest := opts.Destination
+	if dest == "" {
+		dest, err = defaultDestination(repoRoot, pm, opts.Name)
+		if err != nil {
+			return nil, err
+		}
+	}
+	if dest.FileExists() || dest.DirExists() {
+		return nil, fmt.Errorf("%v already exists", dest)
+	}
+	relativeDest, err := repoRoot.PathTo(dest)
+	if err != nil {
+		return nil, err
+	}
+	if strings.HasPrefix(relativeDest, "..") {
+		return nil, fmt.Errorf("%v is outside of the repository", dest)
+	}
+
+	variables := map[string]string{}
+	for k, v := range opts.Variables {
+		variables[k] = v
+	}
+	variables["name"] = opts.Name
+
+	// Don't leave a partially generated workspace behind if any step fails
+	succeeded := false
+	cleanups := []func(){func() { _ = dest.RemoveAll() }}
+	defer func() {
+		if succeeded {
+			return
+		}
+		for i := len(cleanups) - 1; i >= 0; i-- {
+			cleanups[i]()
+		}
+	}()
+
+	files, err := copyTemplate(opts.Template, dest, variables)
+	if err != nil {
+		return nil, err
+	}
+	result := &WorkspaceResult{Dir: dest, Files: files}
+
+	pkgJSON, err := turbofs.ReadPackageJSON(dest.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("the generated workspace has no valid package.json: %w", err)
+	}
+	if pkgJSON.Name != opts.Name {
+		return nil, fmt.Errorf("the generated package.json is named %q rather than %q; use {{name}} in the template", pkgJSON.Name, opts.Name)
+	}
+
+	included, err := isWorkspace(repoRoot, pm, dest)
+	if err != nil {
+		return nil, err
+	}
+	if !included {
+		result.WorkspaceGlob = filepath.ToSlash(relativeDest)
+		configPath := workspaceConfigurationPath(repoRoot, pm)
+		original, err := configPath.ReadFile()
+		if err != nil {
+			return nil, err
+		}
+		info, err := os.Stat(configPath.ToString())
+		if err != nil {
+			return nil, err
+		}
+		if err := addWorkspaceGlob(repoRoot, pm, result.WorkspaceGlob); err != nil {
+			return nil, err
+		}
+		cleanups = append(cleanups, func() { _ = configPath.WriteFile(original, info.Mode()) })
+	}
+
+	result.Tasks, err = addPipelineTasks(repoRoot, rootPackageJSON, pkgJSON)
+	if err != nil {
+		return nil, err
+	}
+	succeeded = true
+	return result, nil
+}
+
+// defaultDestination puts the workspace in the directory of the first glob that
+// matches all of a directory's children, e.g. `packages/*`
+func defaultDestination(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager, name string) (turbopath.AbsolutePath, error) {
+	globs, err := workspaceGlobs(repoRoot, pm)
+	if err != nil {
+		return "", err
+	}
+	// Scoped packages go in a directory named after the unscoped part of the name
+	dirName := name[strings.LastIndex(name, "/")+1:]
+	for _, glob := range globs {
+		if parent := strings.TrimSuffix(glob, "/*"); parent != glob && !strings.ContainsAny(parent, "*?[{!") {
+			return repoRoot.Join(filepath.FromSlash(parent), dirName), nil
+		}
+	}
+	return "", fmt.Errorf("could not choose a directory for %v from the workspace globs, pass --destination", name)
+}
+
+func workspaceGlobs(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager) ([]string, error) {
+	if pm.WorkspaceConfigurationPath != "" {
+		contents, err := repoRoot.Join(pm.WorkspaceConfigurationPath).ReadFile()
+		if err != nil {
+			return nil, err
+		}
+		var workspaces packagemanager.PnpmWorkspaces
+		if err := yaml.Unmarshal(contents, &workspaces); err != nil {
+			return nil, fmt.Errorf("%v: %w", pm.WorkspaceConfigurationPath, err)
+		}
+		return workspaces.Packages, nil
+	}
+	pkg, err := turbofs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return nil, err
+	}
+	return pkg.Workspaces, nil
+}
+
+func isWorkspace(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager, dir turbopath.AbsolutePath) (bool, error) {
+	workspaces, err := pm.GetWorkspaces(repoRoot)
+	if err != nil {
+		return false, err
+	}
+	pkgJSONPath := dir.Join("package.json").ToString()
+	for _, workspace := range workspaces {
+		if filepath.Clean(workspace) == pkgJSONPath {
+			return true, nil
+		}
+	}
+	return false, nil
+}
+
+// copyTemplate copies every file in template to dest, rendering variables into
+// both file names and contents, and returns the created files relative to dest
+func copyTemplate(template turbopath.AbsolutePath, dest turbopath.AbsolutePath, variables map[string]string) ([]string, error) {
+	files := []string{}
+	err := filepath.WalkDir(template.ToString(), func(path string, entry fs.DirEntry, err error) error {
+		if err != nil {
+			return err
+		}
+		if entry.IsDir() {
+			return nil
+		}
+		relativePath, err := filepath.Rel(template.ToString(), path)
+		if err != nil {
+			return err
+		}
+		renderedPath, err := render(relativePath, variables)
+		if err != nil {
+			return fmt.Errorf("%v: %w", relativePath, err)
+		}
+		info, err := entry.Info()
+		if err != nil {
+			return err
+		}
+		contents, err := os.ReadFile(path)
+		if err != nil {
+			return err
+		}
+		if !isBinary(contents) {
+			rendered, err := render(string(contents), variables)
+			if err != nil {
+				return fmt.Errorf("%v: %w", relativePath, err)
+			}
+			contents = []byte(rendered)
+		}
+		target := dest.Join(renderedPath)
+		if err := target.EnsureDir(); err != nil {
+			return err
+		}
+		if err := target.WriteFile(contents, info.Mode().Perm()); err != nil {
+			return err
+		}
+		files = append(files, filepath.ToSlash(renderedPath))
+		return nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	sort.Strings(files)
+	return files, nil
+}
+
+// workspaceConfigurationPath returns the file that lists the package manager's workspace globs
+func workspaceConfigurationPath(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager) turbopath.AbsolutePath {
+	if pm.WorkspaceConfigurationPath != "" {
+		return repoRoot.Join(pm.WorkspaceConfigurationPath)
+	}
+	return repoRoot.Join("package.json")
+}
+
+// addWorkspaceGlob adds an entry to the package manager's workspace configuration,
+// editing the file in place so that its formatting is left alone
+func addWorkspaceGlob(repoRoot turbopath.AbsolutePath, pm *packagemanager.PackageManager, glob string) error {
+	path := workspaceConfigurationPath(repoRoot, pm)
+	if pm.WorkspaceConfigurationPath != "" {
+		return editFile(path, func(contents []byte) ([]byte, error) {
+			return insertYAMLListItem(contents, "packages", glob)
+		})
+	}
+	return editFile(path, func(contents []byte) ([]byte, error) {
+		return insertJSONArrayItem(contents, "workspaces", glob)
+	})
+}
+
+// addPipelineTasks adds a `<workspace>#<script>` pipeline entry to turbo.json for each
+// script of the new workspace that no pipeline entry runs yet
+func addPipelineTasks(repoRoot turbopath.AbsolutePath, rootPackageJSON *turbofs.PackageJSON, pkgJSON *turbofs.PackageJSON) ([]string, error) {
+	turboJSON, err := turbofs.ReadTurboConfig(repoRoot, rootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	tasks := []string{}
+	for script := range pkgJSON.Scripts {
+		taskID := util.GetTaskId(pkgJSON.Name, script)
+		if _, ok := turboJSON.Pipeline.GetTaskDefinition(taskID); !ok {
+			tasks = append(tasks, taskID)
+		}
+	}
+	if len(tasks) == 0 {
+		return tasks, nil
+	}
+	sort.Strings(tasks)
+	err = editFile(repoRoot.Join("turbo.json"), func(contents []byte) ([]byte, error) {
+		for i := len(tasks) - 1; i >= 0; i-- {
+			contents, err = insertJSONObjectEntry(contents, "pipeline", fmt.Sprintf("%q: {}", tasks[i]))
+			if err != nil {
+				return nil, err
+			}
+		}
+		return contents, nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	return tasks, nil
+}
+
+func editFile(path turbopath.AbsolutePath, edit func(contents []byte) ([]byte, error)) error {
+	info, err := os.Stat(path.ToString())
+	if err != nil {
+		return err
+	}
+	contents, err := path.ReadFile()
+	if err != nil {
+		return err
+	}
+	updated, err := edit(contents)
+	if err != nil {
+		return fmt.Errorf("%v: %w", path.Base(), err)
+	}
+	return path.WriteFile(updated, info.Mode())
+}
+
+// insertJSONArrayItem adds a string to the start of the array under key. If the value
+// under key is an object, the array is its `packages` entry, as for yarn's
+// `"workspaces": {"packages": [...]}`.
+func insertJSONArrayItem(contents []byte, key string, item string) ([]byte, error) {
+	start, err := findJSONValue(contents, 0, key)
+	if err != nil {
+		return nil, err
+	}
+	if contents[start] == '{' {
+		start, err = findJSONValue(contents, start, "packages")
+		if err != nil {
+			return nil, err
+		}
+	}
+	if contents[start] != '[' {
+		return nil, fmt.Errorf("expected %q to be an array", key)
+	}
+	return insertIntoContainer(contents, start, fmt.Sprintf("%q", item)), nil
+}
+
+// insertJSONObjectEntry adds a `"key": value` entry to the start of the object under key
+func insertJSONObjectEntry(contents []byte, key string, entry string) ([]byte, error) {
+	start, err := findJSONValue(contents, 0, key)
+	if err != nil {
+		return nil, err
+	}
+	if contents[start] != '{' {
+		return nil, fmt.Errorf("expected %q to be an object", key)
+	}
+	return insertIntoContainer(contents, start, entry), nil
+}
+
+// findJSONValue returns the offset of the first character of the value of key in the
+// object that opens at offset. Only the object's own keys are considered, not those of
+// the objects nested in it.
+func findJSONValue(contents []byte, offset int, key string) (int, error) {
+	decoder := json.NewDecoder(bytes.NewReader(contents[offset:]))
+	if token, err := decoder.Token(); err != nil || token != json.Delim('{') {
+		return 0, fmt.Errorf("expected a JSON object")
+	}
+	for decoder.More() {
+		token, err := decoder.Token()
+		if err != nil {
+			return 0, err
+		}
+		if token == key {
+			// The decoder stops right after the key, so skip the colon and whitespace
+			// that separate it from its value
+			keyEnd := offset + int(decoder.InputOffset())
+			valueStart := bytes.IndexFunc(contents[keyEnd:], func(r rune) bool {
+				return r != ':' && !unicode.IsSpace(r)
+			})
+			if valueStart == -1 {
+				break
+			}
+			return keyEnd + valueStart, nil
+		}
+		var value json.RawMessage
+		if err := decoder.Decode(&value); err != nil {
+			return 0, err
+		}
+	}
+	return 0, fmt.Errorf("no %q key found", key)
+}
+
+// insertIntoContainer inserts item as the first element of the array or object that
+// opens at start, matching the layout of the existing elements
+func insertIntoContainer(contents []byte, start int, item string) []byte {
+	rest := string(contents[start+1:])
+	trimmed := strings.TrimLeft(rest, " \t\r\n")
+	whitespace := rest[:len(rest)-len(trimmed)]
+	var inserted string
+	switch {
+	case strings.HasPrefix(trimmed, "]") || strings.HasPrefix(trimmed, "}"):
+		inserted = item
+	case strings.Contains(whitespace, "\n"):
+		// One element per line: put the new element on its own line, indented the same way
+		inserted = whitespace + item + ","
+	default:
+		inserted = item + ", "
+	}
+	updated := append([]byte{}, contents[:start+1]...)
+	updated = append(updated, inserted...)
+	return append(updated, rest...)
+}
+
+// _yamlKey matches a top level YAML key whose value is a block sequence
+var _yamlKey = regexp.MustCompile(`(?m)^([A-Za-z]+):[ \t]*(#.*)?\r?\n`)
+
+// _yamlItem matches the first item of a block sequence, capturing its indentation
+var _yamlItem = regexp.MustCompile(`^([ \t]*)- `)
+
+// insertYAMLListItem adds a quoted string to the start of the block sequence under key
+func insertYAMLListItem(contents []byte, key string, item string) ([]byte, error) {
+	for _, match := range _yamlKey.FindAllSubmatchIndex(contents, -1) {
+		if string(contents[match[2]:match[3]]) != key {
+			continue
+		}
+		listStart := match[1]
+		indent := "  "
+		if itemMatch := _yamlItem.FindSubmatch(contents[listStart:]); itemMatch != nil {
+			indent = string(itemMatch[1])
+		}
+		newline := "\n"
+		if strings.HasSuffix(string(contents[match[0]:match[1]]), "\r\n") {
+			newline = "\r\n"
+		}
+		updated := append([]byte{}, contents[:listStart]...)
+		updated = append(updated, fmt.Sprintf("%s- %q%s", indent, item, newline)...)
+		return append(updated, contents[listStart:]...), nil
+	}
+	return nil, fmt.Errorf("no %q list found", key)
+}
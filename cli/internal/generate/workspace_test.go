@@ -0,0 +1,122 @@
+package generate
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+func writeFile(t *testing.T, path turbopath.AbsolutePath, contents string) {
+	t.Helper()
+	assert.NilError(t, path.EnsureDir(), "EnsureDir")
+	assert.NilError(t, path.WriteFile([]byte(contents), 0644), "WriteFile")
+}
+
+func readFile(t *testing.T, path turbopath.AbsolutePath) string {
+	t.Helper()
+	contents, err := path.ReadFile()
+	assert.NilError(t, err, "ReadFile")
+	return string(contents)
+}
+
+func Test_insertJSONArrayItem(t *testing.T) {
+	testCases := []struct {
+		contents string
+		want     string
+	}{
+		{
+			contents: "{\n  \"workspaces\": [\n    \"apps/*\"\n  ]\n}",
+			want:     "{\n  \"workspaces\": [\n    \"tools/gen\",\n    \"apps/*\"\n  ]\n}",
+		},
+		{
+			contents: `{"workspaces": ["apps/*"]}`,
+			want:     `{"workspaces": ["tools/gen", "apps/*"]}`,
+		},
+		{
+			contents: `{"workspaces": []}`,
+			want:     `{"workspaces": ["tools/gen"]}`,
+		},
+		{
+			contents: `{"workspaces": {"packages": ["apps/*"], "nohoist": []}}`,
+			want:     `{"workspaces": {"packages": ["tools/gen", "apps/*"], "nohoist": []}}`,
+		},
+		{
+			// Keys of nested objects don't match
+			contents: `{"config": {"workspaces": {}}, "workspaces": {"nohoist": {"packages": []}, "packages": ["apps/*"]}}`,
+			want:     `{"config": {"workspaces": {}}, "workspaces": {"nohoist": {"packages": []}, "packages": ["tools/gen", "apps/*"]}}`,
+		},
+	}
+	for _, tc := range testCases {
+		updated, err := insertJSONArrayItem([]byte(tc.contents), "workspaces", "tools/gen")
+		assert.NilError(t, err, "insertJSONArrayItem")
+		assert.Equal(t, string(updated), tc.want)
+	}
+
+	_, err := insertJSONArrayItem([]byte(`{"name": "monorepo"}`), "workspaces", "tools/gen")
+	assert.ErrorContains(t, err, `no "workspaces" key found`)
+}
+
+func Test_insertYAMLListItem(t *testing.T) {
+	updated, err := insertYAMLListItem([]byte("packages:\n    - 'apps/*'\n"), "packages", "tools/gen")
+	assert.NilError(t, err, "insertYAMLListItem")
+	assert.Equal(t, string(updated), "packages:\n    - \"tools/gen\"\n    - 'apps/*'\n")
+}
+
+func TestWorkspace(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile(t, repoRoot.Join("package.json"), "{\n  \"name\": \"monorepo\",\n  \"packageManager\": \"npm@8.19.2\",\n  \"workspaces\": [\n    \"packages/*\"\n  ]\n}\n")
+	writeFile(t, repoRoot.Join("turbo.json"), "{\n  \"pipeline\": {\n    \"build\": {}\n  }\n}\n")
+	writeFile(t, repoRoot.Join("packages", "web", "package.json"), `{"name": "web"}`)
+	writeFile(t, repoRoot.Join("templates", "library", "package.json"), `{"name": "{{name}}", "scripts": {"build": "tsc", "lint": "eslint ."}}`)
+	writeFile(t, repoRoot.Join("templates", "library", "src", "{{module}}.ts"), "export const owner = \"{{owner}}\";\n")
+
+	result, err := Workspace(repoRoot, WorkspaceOptions{
+		Name:      "@acme/ui",
+		Template:  repoRoot.Join("templates", "library"),
+		Variables: map[string]string{"module": "index", "owner": "design"},
+	})
+	assert.NilError(t, err, "Workspace")
+	assert.Equal(t, result.Dir, repoRoot.Join("packages", "ui"))
+	assert.DeepEqual(t, result.Files, []string{"package.json", "src/index.ts"})
+	assert.Equal(t, result.WorkspaceGlob, "")
+	assert.DeepEqual(t, result.Tasks, []string{"@acme/ui#lint"})
+	assert.Equal(t, readFile(t, repoRoot.Join("packages", "ui", "src", "index.ts")), "export const owner = \"design\";\n")
+	assert.Equal(t, readFile(t, repoRoot.Join("turbo.json")), "{\n  \"pipeline\": {\n    \"@acme/ui#lint\": {},\n    \"build\": {}\n  }\n}\n")
+
+	result, err = Workspace(repoRoot, WorkspaceOptions{
+		Name:        "codegen",
+		Template:    repoRoot.Join("templates", "library"),
+		Destination: repoRoot.Join("tools", "codegen"),
+		Variables:   map[string]string{"module": "index", "owner": "platform"},
+	})
+	assert.NilError(t, err, "Workspace")
+	assert.Equal(t, result.WorkspaceGlob, "tools/codegen")
+	assert.Equal(t, readFile(t, repoRoot.Join("package.json")), "{\n  \"name\": \"monorepo\",\n  \"packageManager\": \"npm@8.19.2\",\n  \"workspaces\": [\n    \"tools/codegen\",\n    \"packages/*\"\n  ]\n}\n")
+
+	_, err = Workspace(repoRoot, WorkspaceOptions{
+		Name:     "docs",
+		Template: repoRoot.Join("templates", "library"),
+	})
+	assert.ErrorContains(t, err, "no value for module")
+	assert.Assert(t, !repoRoot.Join("packages", "docs").DirExists())
+}
+
+func TestWorkspaceCleansUpOnFailure(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	rootPackageJSON := "{\n  \"name\": \"monorepo\",\n  \"packageManager\": \"npm@8.19.2\",\n  \"workspaces\": [\n    \"packages/*\"\n  ]\n}\n"
+	writeFile(t, repoRoot.Join("package.json"), rootPackageJSON)
+	// Adding pipeline entries fails after the workspace has been created and added to the globs
+	writeFile(t, repoRoot.Join("turbo.json"), `{"pipeline": []}`)
+	writeFile(t, repoRoot.Join("templates", "library", "package.json"), `{"name": "{{name}}", "scripts": {"build": "tsc"}}`)
+
+	_, err := Workspace(repoRoot, WorkspaceOptions{
+		Name:        "codegen",
+		Template:    repoRoot.Join("templates", "library"),
+		Destination: repoRoot.Join("tools", "codegen"),
+	})
+	assert.Assert(t, err != nil)
+	assert.Assert(t, !repoRoot.Join("tools", "codegen").DirExists())
+	assert.Equal(t, readFile(t, repoRoot.Join("package.json")), rootPackageJSON)
+}
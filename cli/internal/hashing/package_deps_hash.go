@@ -24,6 +24,18 @@ type PackageDepsOptions struct {
 	PackagePath turbopath.AnchoredSystemPath
 
 	InputPatterns []string
+
+	// NormalizeLineEndings hashes the text files read from the working tree as if their
+	// line endings were LF, instead of leaving conversion to the user's git config
+	NormalizeLineEndings bool
+
+	// ExcludeAttributes are gitattributes that leave the files they are set on out of the
+	// result. They only apply when there are no InputPatterns.
+	ExcludeAttributes []string
+
+	// SkipWorktreeFiles shares the sparse checkout status of the repo between the packages
+	// in it. If omitted, it is read for this package alone.
+	SkipWorktreeFiles *SkipWorktreeFiles
 }
 
 // GetPackageDeps Builds an object containing git hashes for the files under the specified `packagePath` folder.
@@ -41,6 +53,19 @@ func GetPackageDeps(rootPath turbopath.AbsolutePath, p *PackageDepsOptions) (map
 		if err != nil {
 			return nil, fmt.Errorf("could not get git hashes for files in package %s: %w", p.PackagePath, err)
 		}
+		// In a sparse checkout, files outside of the sparse patterns are
+		// present in the index but not on disk, so don't hash them.
+		skipWorktreeFiles := p.SkipWorktreeFiles
+		if skipWorktreeFiles == nil {
+			skipWorktreeFiles = &SkipWorktreeFiles{}
+		}
+		skipped, err := skipWorktreeFiles.forPackage(rootPath, pkgPath)
+		if err != nil {
+			return nil, fmt.Errorf("could not get sparse checkout status for files in package %s: %w", p.PackagePath, err)
+		}
+		for filePath := range skipped {
+			delete(gitLsTreeOutput, filePath)
+		}
 		result = gitLsTreeOutput
 	} else {
 
@@ -79,7 +104,7 @@ func GetPackageDeps(rootPath turbopath.AbsolutePath, p *PackageDepsOptions) (map
 			filesToHash[i] = turbopath.AnchoredSystemPathFromUpstream(relativePathString)
 		}
 
-		hashes, err := gitHashObject(turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToStringDuringMigration()), filesToHash)
+		hashes, err := hashWorkingTreeFiles(turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToStringDuringMigration()), filesToHash, p.NormalizeLineEndings)
 		if err != nil {
 			return nil, errors.Wrap(err, "failed hashing resolved inputs globs")
 		}
@@ -102,7 +127,7 @@ func GetPackageDeps(rootPath turbopath.AbsolutePath, p *PackageDepsOptions) (map
 		}
 	}
 
-	hashes, err := gitHashObject(turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToString()), filesToHash)
+	hashes, err := hashWorkingTreeFiles(turbopath.AbsoluteSystemPathFromUpstream(pkgPath.ToString()), filesToHash, p.NormalizeLineEndings)
 	if err != nil {
 		return nil, err
 	}
@@ -112,13 +137,37 @@ func GetPackageDeps(rootPath turbopath.AbsolutePath, p *PackageDepsOptions) (map
 		result[filePath] = hash
 	}
 
+	if len(p.InputPatterns) == 0 && len(p.ExcludeAttributes) > 0 {
+		filePaths := make([]turbopath.AnchoredUnixPath, 0, len(result))
+		for filePath := range result {
+			filePaths = append(filePaths, filePath)
+		}
+		excluded, err := gitAttributeFiles(pkgPath, filePaths, p.ExcludeAttributes)
+		if err != nil {
+			return nil, fmt.Errorf("could not get gitattributes for files in package %s: %w", p.PackagePath, err)
+		}
+		for filePath := range excluded {
+			delete(result, filePath)
+		}
+	}
+
 	return result, nil
 }
 
-func manuallyHashFiles(rootPath turbopath.AbsoluteSystemPath, files []turbopath.AnchoredSystemPath) (map[turbopath.AnchoredUnixPath]string, error) {
+// hashWorkingTreeFiles hashes files as they are on disk. Without normalizeLineEndings,
+// git hashes them, applying whatever line ending conversion the user's git config sets
+// up. With it, they are hashed with CRLF converted to LF regardless of that config.
+func hashWorkingTreeFiles(anchor turbopath.AbsoluteSystemPath, files []turbopath.AnchoredSystemPath, normalizeLineEndings bool) (map[turbopath.AnchoredUnixPath]string, error) {
+	if normalizeLineEndings {
+		return manuallyHashFiles(anchor, files, true)
+	}
+	return gitHashObject(anchor, files)
+}
+
+func manuallyHashFiles(rootPath turbopath.AbsoluteSystemPath, files []turbopath.AnchoredSystemPath, normalizeLineEndings bool) (map[turbopath.AnchoredUnixPath]string, error) {
 	hashObject := make(map[turbopath.AnchoredUnixPath]string)
 	for _, file := range files {
-		hash, err := fs.GitLikeHashFile(file.ToString())
+		hash, err := fs.GitLikeHashFileWithLineEndings(file.RestoreAnchor(rootPath).ToString(), normalizeLineEndings)
 		if err != nil {
 			return nil, fmt.Errorf("could not hash file %v. \n%w", file.ToString(), err)
 		}
@@ -130,7 +179,7 @@ func manuallyHashFiles(rootPath turbopath.AbsoluteSystemPath, files []turbopath.
 
 // GetHashableDeps hashes the list of given files, then returns a map of normalized path to hash
 // this map is suitable for cross-platform caching.
-func GetHashableDeps(rootPath turbopath.AbsolutePath, files []turbopath.AbsoluteSystemPath) (map[turbopath.AnchoredUnixPath]string, error) {
+func GetHashableDeps(rootPath turbopath.AbsolutePath, files []turbopath.AbsoluteSystemPath, normalizeLineEndings bool) (map[turbopath.AnchoredUnixPath]string, error) {
 	output := make([]turbopath.AnchoredSystemPath, len(files))
 	convertedRootPath := turbopath.AbsoluteSystemPathFromUpstream(rootPath.ToString())
 
@@ -141,9 +190,9 @@ func GetHashableDeps(rootPath turbopath.AbsolutePath, files []turbopath.Absolute
 		}
 		output[index] = anchoredSystemPath
 	}
-	hashObject, err := gitHashObject(convertedRootPath, output)
+	hashObject, err := hashWorkingTreeFiles(convertedRootPath, output, normalizeLineEndings)
 	if err != nil {
-		manuallyHashedObject, err := manuallyHashFiles(convertedRootPath, output)
+		manuallyHashedObject, err := manuallyHashFiles(convertedRootPath, output, normalizeLineEndings)
 		if err != nil {
 			return nil, err
 		}
@@ -197,11 +246,13 @@ func gitHashObject(anchor turbopath.AbsoluteSystemPath, filesToHash []turbopath.
 				// `git hash-object` expects paths to use Unix separators, even on Windows.
 				// `git hash-object` expects paths to be one per line so we must escape newlines.
 				// In order to understand the escapes, the path must be quoted.
-				// In order to quote the path, the quotes in the path must be escaped.
-				// Other than that, we just write everything with full Unicode.
+				// In order to quote the path, the quotes and backslashes in the path must be escaped.
+				// Other than that, we write the bytes of the path as they are, whether or not
+				// they are valid UTF-8, since git unquotes paths byte by byte.
 				stringPath := converted.ToString()
 				toSlashed := filepath.ToSlash(stringPath)
-				escapedNewLines := strings.ReplaceAll(toSlashed, "\n", "\\n")
+				escapedBackslashes := strings.ReplaceAll(toSlashed, "\\", "\\\\")
+				escapedNewLines := strings.ReplaceAll(escapedBackslashes, "\n", "\\n")
 				escapedQuotes := strings.ReplaceAll(escapedNewLines, "\"", "\\\"")
 				prepared := fmt.Sprintf("\"%s\"\n", escapedQuotes)
 				_, err := io.WriteString(stdinPipe, prepared)
@@ -324,6 +375,115 @@ func gitLsTree(rootPath turbopath.AbsolutePath) (map[turbopath.AnchoredUnixPath]
 	return output, nil
 }
 
+// gitSkipWorktreeFiles returns the set of files starting at a particular directory
+// that are marked skip-worktree in the `git` index, which is how a sparse checkout
+// excludes files. It returns an empty set if sparse checkout is not enabled.
+func gitSkipWorktreeFiles(rootPath turbopath.AbsolutePath) (map[turbopath.AnchoredUnixPath]struct{}, error) {
+	output := make(map[turbopath.AnchoredUnixPath]struct{})
+
+	configCmd := exec.Command("git", "config", "--bool", "core.sparseCheckout")
+	configCmd.Dir = rootPath.ToString()
+	// `git config` exits non-zero when the key is unset
+	if sparse, err := configCmd.Output(); err != nil || strings.TrimSpace(string(sparse)) != "true" {
+		return output, nil
+	}
+
+	cmd := exec.Command(
+		"git",      // Using `git` from $PATH,
+		"ls-files", // list the files in the git index,
+		"-t",       // tagged with their status,
+		"-z",       // with each file path relative to the invocation directory and \000-terminated.
+	)
+	cmd.Dir = rootPath.ToString() // Include files only from this directory.
+
+	out, err := cmd.Output()
+	if err != nil {
+		return nil, fmt.Errorf("failed to read `git ls-files`: %w", err)
+	}
+	for _, entry := range strings.Split(string(out), "\000") {
+		// Each entry is a single-letter tag, a space, and the path. "S" marks skip-worktree.
+		if strings.HasPrefix(entry, "S ") {
+			output[turbopath.AnchoredUnixPathFromUpstream(entry[2:])] = struct{}{}
+		}
+	}
+	return output, nil
+}
+
+// SkipWorktreeFiles lists the files of a repo that are marked skip-worktree. They are read
+// with a single `git ls-files` the first time a package in the repo needs them.
+type SkipWorktreeFiles struct {
+	once  sync.Once
+	files map[turbopath.AnchoredUnixPath]struct{}
+	err   error
+}
+
+// forPackage returns the skip-worktree files under pkgPath, relative to it
+func (s *SkipWorktreeFiles) forPackage(rootPath turbopath.AbsolutePath, pkgPath turbopath.AbsolutePath) (map[turbopath.AnchoredUnixPath]struct{}, error) {
+	relativePath, err := rootPath.PathTo(pkgPath)
+	if err != nil {
+		return nil, err
+	}
+	relativePath = filepath.ToSlash(relativePath)
+	if relativePath == ".." || strings.HasPrefix(relativePath, "../") {
+		// The package isn't in this repo, so it has its own sparse checkout status
+		return gitSkipWorktreeFiles(pkgPath)
+	}
+	s.once.Do(func() {
+		s.files, s.err = gitSkipWorktreeFiles(rootPath)
+	})
+	if s.err != nil {
+		return nil, s.err
+	}
+	output := make(map[turbopath.AnchoredUnixPath]struct{})
+	prefix := relativePath + "/"
+	for filePath := range s.files {
+		if relativePath == "." {
+			output[filePath] = struct{}{}
+		} else if strings.HasPrefix(filePath.ToString(), prefix) {
+			output[turbopath.AnchoredUnixPathFromUpstream(strings.TrimPrefix(filePath.ToString(), prefix))] = struct{}{}
+		}
+	}
+	return output, nil
+}
+
+// gitAttributeFiles returns the files, out of filePaths, that have any of the given
+// gitattributes set, either on its own (`linguist-generated`) or to true
+// (`linguist-generated=true`). The paths are relative to rootPath.
+func gitAttributeFiles(rootPath turbopath.AbsolutePath, filePaths []turbopath.AnchoredUnixPath, attributes []string) (map[turbopath.AnchoredUnixPath]struct{}, error) {
+	output := make(map[turbopath.AnchoredUnixPath]struct{})
+	if len(filePaths) == 0 {
+		return output, nil
+	}
+
+	args := append([]string{
+		"check-attr", // Report the gitattributes
+		"-z",         // of \000-terminated paths, with \000-separated output,
+		"--stdin",    // read from stdin, for these attributes.
+	}, attributes...)
+	cmd := exec.Command("git", args...)
+	cmd.Dir = rootPath.ToString()
+
+	var stdin strings.Builder
+	for _, filePath := range filePaths {
+		stdin.WriteString(filePath.ToString())
+		stdin.WriteByte(0)
+	}
+	cmd.Stdin = strings.NewReader(stdin.String())
+
+	out, err := cmd.Output()
+	if err != nil {
+		return nil, fmt.Errorf("failed to read `git check-attr`: %w", err)
+	}
+	// Each result is a path, an attribute and its value, each \000-terminated
+	fields := strings.Split(string(out), "\000")
+	for i := 0; i+2 < len(fields); i += 3 {
+		if value := fields[i+2]; value == "set" || value == "true" {
+			output[turbopath.AnchoredUnixPathFromUpstream(fields[i])] = struct{}{}
+		}
+	}
+	return output, nil
+}
+
 // getTraversePath gets the distance of the current working directory to the repository root.
 // This is used to convert repo-relative paths to cwd-relative paths.
 //
@@ -8,6 +8,7 @@ import (
 	"path/filepath"
 	"reflect"
 	"runtime"
+	"sort"
 	"strings"
 	"testing"
 
@@ -50,25 +51,29 @@ func TestSpecialCharacters(t *testing.T) {
 	fixturePath := getFixture(1)
 	newlinePath := turbopath.AnchoredSystemPath("new\nline")
 	quotePath := turbopath.AnchoredSystemPath("\"quote\"")
+	backslashPath := turbopath.AnchoredSystemPath("back\\slash")
 	newline := newlinePath.RestoreAnchor(fixturePath)
 	quote := quotePath.RestoreAnchor(fixturePath)
+	backslash := backslashPath.RestoreAnchor(fixturePath)
 
 	// Setup
 	one := os.WriteFile(newline.ToString(), []byte{}, 0644)
 	two := os.WriteFile(quote.ToString(), []byte{}, 0644)
+	three := os.WriteFile(backslash.ToString(), []byte{}, 0644)
 
 	// Cleanup
 	defer func() {
 		one := os.Remove(newline.ToString())
 		two := os.Remove(quote.ToString())
+		three := os.Remove(backslash.ToString())
 
-		if one != nil || two != nil {
+		if one != nil || two != nil || three != nil {
 			return
 		}
 	}()
 
 	// Setup error check
-	if one != nil || two != nil {
+	if one != nil || two != nil || three != nil {
 		return
 	}
 
@@ -99,6 +104,16 @@ func TestSpecialCharacters(t *testing.T) {
 				newlinePath.ToUnixPath(): "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
 			},
 		},
+		{
+			name:     "Backslashes",
+			rootPath: fixturePath,
+			filesToHash: []turbopath.AnchoredSystemPath{
+				turbopath.AnchoredSystemPath(backslashPath),
+			},
+			want: map[turbopath.AnchoredUnixPath]string{
+				backslashPath.ToUnixPath(): "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+			},
+		},
 	}
 	for _, tt := range tests {
 		t.Run(tt.name, func(t *testing.T) {
@@ -112,6 +127,29 @@ func TestSpecialCharacters(t *testing.T) {
 			}
 		})
 	}
+
+	// Non-UTF-8 names are set up separately, so that file systems that refuse them only skip
+	// this case
+	nonUTF8Path := turbopath.AnchoredSystemPath("caf\xe9")
+	nonUTF8 := nonUTF8Path.RestoreAnchor(fixturePath)
+	t.Run("Non-UTF-8 names", func(t *testing.T) {
+		if err := os.WriteFile(nonUTF8.ToString(), []byte{}, 0644); err != nil {
+			// Some file systems, such as APFS, refuse names that aren't valid UTF-8
+			t.Skipf("file system doesn't allow non-UTF-8 names: %v", err)
+		}
+		defer func() { _ = os.Remove(nonUTF8.ToString()) }()
+
+		got, err := gitHashObject(fixturePath, []turbopath.AnchoredSystemPath{nonUTF8Path})
+		if err != nil {
+			t.Fatalf("gitHashObject() error = %v", err)
+		}
+		want := map[turbopath.AnchoredUnixPath]string{
+			nonUTF8Path.ToUnixPath(): "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391",
+		}
+		if !reflect.DeepEqual(got, want) {
+			t.Errorf("gitHashObject() = %v, want %v", got, want)
+		}
+	})
 }
 
 func Test_gitHashObject(t *testing.T) {
@@ -376,6 +414,103 @@ func TestGetPackageDeps(t *testing.T) {
 	}
 }
 
+func TestGetPackageDepsExcludeAttributes(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	files := map[string]string{
+		".gitattributes":               "**/generated/** linguist-generated\nvendor.js export-ignore\nkept.js linguist-generated=false\n",
+		"my-pkg/index.js":              "index",
+		"my-pkg/kept.js":               "kept",
+		"my-pkg/vendor.js":             "vendor",
+		"my-pkg/generated/schema.ts":   "schema",
+		"my-pkg/generated/uncommitted": "uncommitted",
+	}
+	for path, contents := range files {
+		filePath := repoRoot.Join(filepath.FromSlash(path))
+		assert.NilError(t, filePath.EnsureDir(), "EnsureDir")
+		assert.NilError(t, filePath.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+	requireGitCmd(t, repoRoot, "init", ".")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.name", "test")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.email", "test@example.com")
+	requireGitCmd(t, repoRoot, "add", ".gitattributes", "my-pkg/index.js", "my-pkg/kept.js", "my-pkg/vendor.js", "my-pkg/generated/schema.ts")
+	requireGitCmd(t, repoRoot, "commit", "-m", "foo")
+
+	got, err := GetPackageDeps(repoRoot, &PackageDepsOptions{
+		PackagePath:       "my-pkg",
+		ExcludeAttributes: []string{"linguist-generated", "export-ignore"},
+	})
+	assert.NilError(t, err)
+	paths := []string{}
+	for path := range got {
+		paths = append(paths, path.ToString())
+	}
+	sort.Strings(paths)
+	assert.DeepEqual(t, paths, []string{"index.js", "kept.js"})
+
+	// Explicit inputs aren't affected
+	got, err = GetPackageDeps(repoRoot, &PackageDepsOptions{
+		PackagePath:       "my-pkg",
+		InputPatterns:     []string{"generated/**"},
+		ExcludeAttributes: []string{"linguist-generated"},
+	})
+	assert.NilError(t, err)
+	assert.Equal(t, len(got), 2)
+}
+
+func TestGetPackageDepsSparseCheckout(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	files := []string{
+		"package.json",
+		"my-pkg/index.js",
+		"my-pkg/skipped.js",
+		"other-pkg/index.js",
+		"other-pkg/skipped.js",
+	}
+	for _, path := range files {
+		filePath := repoRoot.Join(filepath.FromSlash(path))
+		assert.NilError(t, filePath.EnsureDir(), "EnsureDir")
+		assert.NilError(t, filePath.WriteFile([]byte(path), 0644), "WriteFile")
+	}
+	requireGitCmd(t, repoRoot, "init", ".")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.name", "test")
+	requireGitCmd(t, repoRoot, "config", "--local", "user.email", "test@example.com")
+	requireGitCmd(t, repoRoot, "add", ".")
+	requireGitCmd(t, repoRoot, "commit", "-m", "foo")
+	// Leave the skipped files out of the checkout, as sparse checkout patterns would
+	requireGitCmd(t, repoRoot, "config", "--local", "core.sparseCheckout", "true")
+	requireGitCmd(t, repoRoot, "update-index", "--skip-worktree", "my-pkg/skipped.js", "other-pkg/skipped.js")
+	assert.NilError(t, repoRoot.Join("my-pkg", "skipped.js").Remove(), "Remove")
+	assert.NilError(t, repoRoot.Join("other-pkg", "skipped.js").Remove(), "Remove")
+
+	skipWorktreeFiles := &SkipWorktreeFiles{}
+	tests := []struct {
+		packagePath turbopath.AnchoredSystemPath
+		expected    []string
+	}{
+		{packagePath: "my-pkg", expected: []string{"index.js"}},
+		{packagePath: "other-pkg", expected: []string{"index.js"}},
+		{packagePath: "", expected: []string{"my-pkg/index.js", "other-pkg/index.js", "package.json"}},
+	}
+	for _, tt := range tests {
+		got, err := GetPackageDeps(repoRoot, &PackageDepsOptions{
+			PackagePath:       tt.packagePath,
+			SkipWorktreeFiles: skipWorktreeFiles,
+		})
+		assert.NilError(t, err)
+		paths := []string{}
+		for path := range got {
+			paths = append(paths, path.ToString())
+		}
+		sort.Strings(paths)
+		assert.DeepEqual(t, paths, tt.expected)
+	}
+	// The skip-worktree files were read once, for the whole repo
+	assert.DeepEqual(t, skipWorktreeFiles.files, map[turbopath.AnchoredUnixPath]struct{}{
+		"my-pkg/skipped.js":    {},
+		"other-pkg/skipped.js": {},
+	})
+}
+
 func Test_memoizedGetTraversePath(t *testing.T) {
 	fixturePath := getFixture(1)
 
@@ -1,12 +1,19 @@
 package inference
 
-import "github.com/vercel/turborepo/cli/internal/fs"
+import (
+	"fmt"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
 
 // Framework is an identifier for something that we wish to inference against.
 type Framework struct {
 	Slug            string
 	EnvPrefix       string
 	DependencyMatch matcher
+	// BuildOutputs are the files a production build of the framework writes, as
+	// outputs globs relative to the workspace
+	BuildOutputs []string
 }
 
 type matcher struct {
@@ -29,6 +36,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"blitz"},
 		},
+		BuildOutputs: []string{".next/**", "!.next/cache/**"},
 	},
 	{
 		Slug:      "nextjs",
@@ -37,6 +45,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"next"},
 		},
+		BuildOutputs: []string{".next/**", "!.next/cache/**"},
 	},
 	{
 		Slug:      "gatsby",
@@ -45,6 +54,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"gatsby"},
 		},
+		BuildOutputs: []string{"public/**"},
 	},
 	{
 		Slug:      "astro",
@@ -53,6 +63,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"astro"},
 		},
+		BuildOutputs: []string{"dist/**"},
 	},
 	{
 		Slug:      "solidstart",
@@ -61,6 +72,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"solid-js", "solid-start"},
 		},
+		BuildOutputs: []string{"dist/**"},
 	},
 	{
 		Slug:      "vue",
@@ -69,6 +81,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"@vue/cli-service"},
 		},
+		BuildOutputs: []string{"dist/**"},
 	},
 	{
 		Slug:      "sveltekit",
@@ -77,6 +90,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"@sveltejs/kit"},
 		},
+		BuildOutputs: []string{".svelte-kit/**"},
 	},
 	{
 		Slug:      "create-react-app",
@@ -85,6 +99,7 @@ var _frameworks = []Framework{
 			strategy:     some,
 			dependencies: []string{"react-scripts", "react-dev-utils"},
 		},
+		BuildOutputs: []string{"build/**"},
 	},
 	{
 		Slug:      "nuxtjs",
@@ -93,6 +108,7 @@ var _frameworks = []Framework{
 			strategy:     some,
 			dependencies: []string{"nuxt", "nuxt-edge", "nuxt3", "nuxt3-edge"},
 		},
+		BuildOutputs: []string{".nuxt/**", ".output/**"},
 	},
 	{
 		Slug:      "redwoodjs",
@@ -101,6 +117,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"@redwoodjs/core"},
 		},
+		BuildOutputs: []string{"web/dist/**", "api/dist/**"},
 	},
 	{
 		Slug:      "vite",
@@ -109,6 +126,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"vite"},
 		},
+		BuildOutputs: []string{"dist/**"},
 	},
 	{
 		Slug:      "sanity",
@@ -117,6 +135,7 @@ var _frameworks = []Framework{
 			strategy:     all,
 			dependencies: []string{"@sanity/cli"},
 		},
+		BuildOutputs: []string{"dist/**"},
 	},
 }
 
@@ -145,13 +164,69 @@ func (f Framework) match(pkg *fs.PackageJSON) bool {
 	return f.DependencyMatch.match(pkg)
 }
 
-// InferFramework returns a reference to a matched framework
+// Registry is an ordered list of frameworks to infer against. When a package
+// matches more than one framework, the earliest one wins.
+type Registry struct {
+	frameworks []Framework
+}
+
+// DefaultRegistry contains the frameworks that turbo knows about out of the box
+var DefaultRegistry = &Registry{frameworks: _frameworks}
+
+// NewRegistry creates a Registry of the given user-defined frameworks, followed by
+// the built-in frameworks. User-defined frameworks take precedence, so that a repo
+// can override the detection of a built-in framework.
+func NewRegistry(custom []fs.FrameworkConfig) (*Registry, error) {
+	frameworks := make([]Framework, 0, len(custom)+len(_frameworks))
+	for _, config := range custom {
+		framework, err := frameworkFromConfig(config)
+		if err != nil {
+			return nil, err
+		}
+		frameworks = append(frameworks, framework)
+	}
+	frameworks = append(frameworks, _frameworks...)
+	return &Registry{frameworks: frameworks}, nil
+}
+
+func frameworkFromConfig(config fs.FrameworkConfig) (Framework, error) {
+	if config.Slug == "" {
+		return Framework{}, fmt.Errorf("frameworks must have a \"slug\"")
+	}
+	if len(config.Dependencies) == 0 {
+		return Framework{}, fmt.Errorf("framework %v must list at least one dependency", config.Slug)
+	}
+	var strategy matchStrategy
+	switch config.DependencyMatch {
+	case "", "all":
+		strategy = all
+	case "some":
+		strategy = some
+	default:
+		return Framework{}, fmt.Errorf("framework %v: unknown dependencyMatch %q, expected \"all\" or \"some\"", config.Slug, config.DependencyMatch)
+	}
+	return Framework{
+		Slug:      config.Slug,
+		EnvPrefix: config.EnvPrefix,
+		DependencyMatch: matcher{
+			strategy:     strategy,
+			dependencies: config.Dependencies,
+		},
+	}, nil
+}
+
+// InferFramework returns a reference to a matched built-in framework
 func InferFramework(pkg *fs.PackageJSON) *Framework {
+	return DefaultRegistry.InferFramework(pkg)
+}
+
+// InferFramework returns a reference to the first framework in the registry that matches
+func (r *Registry) InferFramework(pkg *fs.PackageJSON) *Framework {
 	if pkg == nil {
 		return nil
 	}
 
-	for _, candidateFramework := range _frameworks {
+	for _, candidateFramework := range r.frameworks {
 		if candidateFramework.match(pkg) {
 			return &candidateFramework
 		}
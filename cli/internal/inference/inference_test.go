@@ -85,3 +85,86 @@ func TestInferFramework(t *testing.T) {
 		})
 	}
 }
+
+func TestRegistryWithCustomFrameworks(t *testing.T) {
+	registry, err := NewRegistry([]fs.FrameworkConfig{
+		{
+			Slug:         "my-framework",
+			EnvPrefix:    "MY_FRAMEWORK_",
+			Dependencies: []string{"my-framework"},
+		},
+		{
+			Slug:            "my-next",
+			EnvPrefix:       "MY_NEXT_",
+			Dependencies:    []string{"next", "my-next-plugin"},
+			DependencyMatch: "all",
+		},
+	})
+	if err != nil {
+		t.Fatalf("NewRegistry: %v", err)
+	}
+
+	tests := []struct {
+		name     string
+		pkg      *fs.PackageJSON
+		wantSlug string
+	}{
+		{
+			name: "Finds a custom framework",
+			pkg: &fs.PackageJSON{UnresolvedExternalDeps: map[string]string{
+				"my-framework": "*",
+			}},
+			wantSlug: "my-framework",
+		},
+		{
+			name: "Custom frameworks take precedence over built-in ones",
+			pkg: &fs.PackageJSON{UnresolvedExternalDeps: map[string]string{
+				"next":           "*",
+				"my-next-plugin": "*",
+			}},
+			wantSlug: "my-next",
+		},
+		{
+			name: "Falls back to built-in frameworks",
+			pkg: &fs.PackageJSON{UnresolvedExternalDeps: map[string]string{
+				"next": "*",
+			}},
+			wantSlug: "nextjs",
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got := registry.InferFramework(tt.pkg)
+			if got == nil || got.Slug != tt.wantSlug {
+				t.Errorf("InferFramework() = %v, want %v", got, tt.wantSlug)
+			}
+		})
+	}
+}
+
+func TestNewRegistryValidation(t *testing.T) {
+	tests := []struct {
+		name   string
+		config fs.FrameworkConfig
+	}{
+		{
+			name:   "Missing slug",
+			config: fs.FrameworkConfig{Dependencies: []string{"foo"}},
+		},
+		{
+			name:   "Missing dependencies",
+			config: fs.FrameworkConfig{Slug: "foo"},
+		},
+		{
+			name:   "Unknown dependency match",
+			config: fs.FrameworkConfig{Slug: "foo", Dependencies: []string{"foo"}, DependencyMatch: "any"},
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if _, err := NewRegistry([]fs.FrameworkConfig{tt.config}); err == nil {
+				t.Error("expected an error")
+			}
+		})
+	}
+}
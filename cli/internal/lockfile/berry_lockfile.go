@@ -71,6 +71,11 @@ func (l *BerryLockfile) Subgraph(_ []turbopath.AnchoredSystemPath, packages []st
 	return &lockfile, nil
 }
 
+// OmitDevDependencies returns the lockfile unchanged, since it doesn't record the dependencies of workspaces
+func (l *BerryLockfile) OmitDevDependencies(_ []turbopath.AnchoredSystemPath) Lockfile {
+	return l
+}
+
 // Encode encode the lockfile representation and write it to the given writer
 func (l *BerryLockfile) Encode(w io.Writer) error {
 	var tmp bytes.Buffer
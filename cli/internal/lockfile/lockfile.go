@@ -15,6 +15,8 @@ type Lockfile interface {
 	AllDependencies(key string) (map[string]string, bool)
 	// Subgraph Given a list of lockfile keys returns a Lockfile based off the original one that only contains the packages given
 	Subgraph(workspacePackages []turbopath.AnchoredSystemPath, packages []string) (Lockfile, error)
+	// OmitDevDependencies returns a copy of the lockfile that doesn't record devDependencies for the given workspaces
+	OmitDevDependencies(workspacePackages []turbopath.AnchoredSystemPath) Lockfile
 	// Encode encode the lockfile representation and write it to the given writer
 	Encode(w io.Writer) error
 	// Patches return a list of patches used in the lockfile
@@ -3,6 +3,7 @@ package lockfile
 import (
 	"fmt"
 	"io"
+	"strings"
 
 	"github.com/pkg/errors"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
@@ -226,6 +227,39 @@ func pruneImporters(importers map[string]ProjectSnapshot, workspacePackages []tu
 	return prunedImporters, nil
 }
 
+// OmitDevDependencies returns a copy of the lockfile whose importers for the given workspaces have
+// no devDependencies, and no specifiers for them, so that it matches their package.json files
+// once devDependencies are removed from them
+func (p *PnpmLockfile) OmitDevDependencies(workspacePackages []turbopath.AnchoredSystemPath) Lockfile {
+	importers := make(map[string]ProjectSnapshot, len(p.Importers))
+	for workspace, importer := range p.Importers {
+		importers[workspace] = importer
+	}
+	for _, workspacePath := range workspacePackages {
+		workspace := workspacePath.ToUnixPath().ToString()
+		importer, ok := importers[workspace]
+		if !ok || len(importer.DevDependencies) == 0 {
+			continue
+		}
+		specifiers := make(map[string]string, len(importer.Specifiers))
+		for name, specifier := range importer.Specifiers {
+			_, isDev := importer.DevDependencies[name]
+			_, isProd := importer.Dependencies[name]
+			_, isOptional := importer.OptionalDependencies[name]
+			if !isDev || isProd || isOptional {
+				specifiers[name] = specifier
+			}
+		}
+		importer.Specifiers = specifiers
+		importer.DevDependencies = nil
+		importers[workspace] = importer
+	}
+
+	lockfile := *p
+	lockfile.Importers = importers
+	return &lockfile
+}
+
 // Encode encode the lockfile representation and write it to the given writer
 func (p *PnpmLockfile) Encode(w io.Writer) error {
 	if err := isSupportedVersion(p.Version); err != nil {
@@ -282,5 +316,10 @@ func (p *PnpmLockfile) resolveSpecifier(name string, specifier string) (string,
 }
 
 func formatPnpmKey(name string, version string) string {
+	// Packages installed from a directory, such as injected workspace dependencies, are
+	// keyed by their resolution alone, e.g. "file:packages/ui"
+	if strings.HasPrefix(version, "file:") {
+		return version
+	}
 	return fmt.Sprintf("/%s/%s", name, version)
 }
@@ -3,10 +3,12 @@ package lockfile
 import (
 	"bytes"
 	"os"
+	"path/filepath"
 	"testing"
 
 	"github.com/pkg/errors"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"gotest.tools/v3/assert"
 )
 
@@ -81,3 +83,48 @@ func Test_SpecifierResolution(t *testing.T) {
 		assert.Equal(t, actualVersion, testCase.version, "%s@%s", testCase.pkg, testCase.version)
 	}
 }
+
+func Test_InjectedWorkspaceResolution(t *testing.T) {
+	contents, err := getFixture(t, "pnpm-injected.yaml")
+	if err != nil {
+		t.Error(err)
+	}
+	lockfile, err := DecodePnpmLockfile(contents)
+	if err != nil {
+		t.Errorf("failure decoding lockfile: %v", err)
+	}
+
+	key, version, found := lockfile.ResolvePackage("ui", "workspace:*")
+	assert.Assert(t, found, "ui@workspace:*")
+	assert.Equal(t, key, "file:packages/ui_react@18.2.0")
+	assert.Equal(t, version, "0.0.0")
+
+	deps, found := lockfile.AllDependencies(key)
+	assert.Assert(t, found, key)
+	assert.DeepEqual(t, deps, map[string]string{"react": "^18.2.0"})
+}
+
+func Test_OmitDevDependencies(t *testing.T) {
+	contents, err := getFixture(t, "pnpm7-workspace.yaml")
+	if err != nil {
+		t.Error(err)
+	}
+	lockfile, err := DecodePnpmLockfile(contents)
+	if err != nil {
+		t.Errorf("failure decoding lockfile: %v", err)
+	}
+
+	omitted := lockfile.OmitDevDependencies([]turbopath.AnchoredSystemPath{turbopath.AnchoredSystemPathFromUpstream(filepath.Join("apps", "docs"))}).(*PnpmLockfile)
+	docs := omitted.Importers["apps/docs"]
+	assert.Assert(t, docs.DevDependencies == nil)
+	assert.DeepEqual(t, docs.Specifiers, map[string]string{
+		"next":       "12.2.5",
+		"react":      "18.2.0",
+		"react-dom":  "18.2.0",
+		"ui":         "workspace:*",
+		"underscore": "^1.13.4",
+	})
+	assert.DeepEqual(t, omitted.Importers["."], lockfile.Importers["."])
+	// The original lockfile is left as it was
+	assert.Equal(t, len(lockfile.Importers["apps/docs"].DevDependencies), 8)
+}
@@ -63,6 +63,11 @@ func (l *YarnLockfile) Subgraph(_ []turbopath.AnchoredSystemPath, packages []str
 	return &YarnLockfile{lockfile, l.hasCRLF}, nil
 }
 
+// OmitDevDependencies returns the lockfile unchanged, since it doesn't record the dependencies of workspaces
+func (l *YarnLockfile) OmitDevDependencies(_ []turbopath.AnchoredSystemPath) Lockfile {
+	return l
+}
+
 // Encode encode the lockfile representation and write it to the given writer
 func (l *YarnLockfile) Encode(w io.Writer) error {
 	writer := w
@@ -55,33 +55,39 @@ func getCmd(helper *cmdutil.Helper) *cobra.Command {
 			if err != nil {
 				return err
 			}
-			link := &link{
-				base:                base,
-				modifyGitIgnore:     !dontModifyGitIgnore,
-				apiClient:           base.APIClient,
-				promptSetup:         promptSetup,
-				promptTeam:          promptTeam,
-				promptEnableCaching: promptEnableCaching,
-				openBrowser:         browser.OpenBrowser,
-			}
-			err = link.run()
-			if err != nil {
-				if errors.Is(err, errUserCanceled) {
-					base.UI.Info("Canceled. Turborepo not set up.")
-				} else if errors.Is(err, errTryAfterEnable) || errors.Is(err, errNeedCachingEnabled) || errors.Is(err, errOverage) {
-					base.UI.Info("Remote Caching not enabled. Please run 'turbo login' again after Remote Caching has been enabled")
-				} else {
-					link.logError(err)
-				}
-				return err
-			}
-			return nil
+			return Link(base, !dontModifyGitIgnore)
 		},
 	}
 	cmd.Flags().BoolVar(&dontModifyGitIgnore, "no-gitignore", false, "Do not create or modify .gitignore (default false)")
 	return cmd
 }
 
+// Link links the repository to a Remote Cache, prompting for the team to use. If
+// modifyGitIgnore is set, .turbo is added to .gitignore.
+func Link(base *cmdutil.CmdBase, modifyGitIgnore bool) error {
+	link := &link{
+		base:                base,
+		modifyGitIgnore:     modifyGitIgnore,
+		apiClient:           base.APIClient,
+		promptSetup:         promptSetup,
+		promptTeam:          promptTeam,
+		promptEnableCaching: promptEnableCaching,
+		openBrowser:         browser.OpenBrowser,
+	}
+	err := link.run()
+	if err != nil {
+		if errors.Is(err, errUserCanceled) {
+			base.UI.Info("Canceled. Turborepo not set up.")
+		} else if errors.Is(err, errTryAfterEnable) || errors.Is(err, errNeedCachingEnabled) || errors.Is(err, errOverage) {
+			base.UI.Info("Remote Caching not enabled. Please run 'turbo login' again after Remote Caching has been enabled")
+		} else {
+			link.logError(err)
+		}
+		return err
+	}
+	return nil
+}
+
 var errUserCanceled = errors.New("canceled")
 
 func (l *link) run() error {
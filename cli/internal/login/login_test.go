@@ -102,7 +102,7 @@ func newTest(t *testing.T, redirectedURL string) *testResult {
 		t.Fatalf("setting up user config: %v", err)
 	}
 	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
-	repoConfig, err := config.ReadRepoConfigFile(config.GetRepoConfigPath(repoRoot), flags)
+	repoConfig, err := config.ReadRepoConfigFile(config.GetRepoConfigPath(repoRoot), fs.AbsolutePathFromUpstream(t.TempDir()).Join("config.json"), flags)
 	if err != nil {
 		t.Fatalf("setting up repo config: %v", err)
 	}
@@ -0,0 +1,38 @@
+package logsink
+
+import (
+	"io"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// fileSink writes the output of each task to its own file in a directory
+type fileSink struct {
+	dir turbopath.AbsolutePath
+}
+
+// _taskFileNameReplacer turns a task ID, such as @acme/ui#build, into a file name
+var _taskFileNameReplacer = strings.NewReplacer("/", "_", "\\", "_", ":", "_")
+
+func newFileSink(repoRoot turbopath.AbsolutePath, dir string) (*fileSink, error) {
+	sink := &fileSink{dir: fs.ResolveUnknownPath(repoRoot, dir)}
+	if err := sink.dir.MkdirAll(); err != nil {
+		return nil, err
+	}
+	return sink, nil
+}
+
+// getTaskLogPath returns the file that the output of taskID is written to
+func (s *fileSink) getTaskLogPath(taskID string) turbopath.AbsolutePath {
+	return s.dir.Join(_taskFileNameReplacer.Replace(taskID) + ".log")
+}
+
+func (s *fileSink) Open(taskID string) (io.WriteCloser, error) {
+	return s.getTaskLogPath(taskID).Create()
+}
+
+func (s *fileSink) Close() error {
+	return nil
+}
@@ -0,0 +1,99 @@
+package logsink
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"io/ioutil"
+	"net/http"
+	"net/url"
+	"time"
+)
+
+// httpSink streams the output of each task as the body of a POST request to an endpoint.
+// The task's ID is sent in the X-Turbo-Task header.
+type httpSink struct {
+	endpoint string
+	client   *http.Client
+}
+
+func newHTTPSink(endpoint string) (*httpSink, error) {
+	if u, err := url.Parse(endpoint); err != nil {
+		return nil, err
+	} else if u.Host == "" {
+		return nil, fmt.Errorf("%v has no host", endpoint)
+	}
+	// No timeout: a request lasts as long as its task does
+	return &httpSink{endpoint: endpoint, client: &http.Client{}}, nil
+}
+
+func (s *httpSink) Open(taskID string) (io.WriteCloser, error) {
+	reader, writer := io.Pipe()
+	ctx, cancel := context.WithCancel(context.Background())
+	req, err := http.NewRequestWithContext(ctx, http.MethodPost, s.endpoint, reader)
+	if err != nil {
+		cancel()
+		return nil, err
+	}
+	req.Header.Set("Content-Type", "text/plain; charset=utf-8")
+	req.Header.Set("X-Turbo-Task", taskID)
+	w := &httpTaskWriter{pipe: writer, cancel: cancel, done: make(chan error, 1)}
+	w.queue = newQueue(w.write, w.abort)
+	go func() {
+		resp, err := s.client.Do(req)
+		if err == nil {
+			_, _ = io.Copy(ioutil.Discard, resp.Body)
+			_ = resp.Body.Close()
+			if resp.StatusCode >= 300 {
+				err = fmt.Errorf("%v responded with %v", s.endpoint, resp.Status)
+			}
+		}
+		// Unblock writes if the request ended before the task's output did
+		_ = reader.CloseWithError(err)
+		w.done <- err
+	}()
+	return w, nil
+}
+
+func (s *httpSink) Close() error {
+	return nil
+}
+
+type httpTaskWriter struct {
+	pipe   *io.PipeWriter
+	cancel context.CancelFunc
+	queue  *queue
+	done   chan error
+}
+
+func (w *httpTaskWriter) write(p []byte) error {
+	_, err := w.pipe.Write(p)
+	return err
+}
+
+// abort ends the request, which unblocks a pending write
+func (w *httpTaskWriter) abort(err error) {
+	_ = w.pipe.CloseWithError(err)
+	w.cancel()
+}
+
+func (w *httpTaskWriter) Write(p []byte) (int, error) {
+	if err := w.queue.push(p); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
+
+// Close sends what is still queued, ends the request body and waits for the response,
+// for at most _sinkWriteTimeout
+func (w *httpTaskWriter) Close() error {
+	defer w.cancel()
+	err := w.queue.close()
+	_ = w.pipe.Close()
+	timer := time.AfterFunc(_sinkWriteTimeout, w.cancel)
+	defer timer.Stop()
+	if respErr := <-w.done; err == nil {
+		err = respErr
+	}
+	return err
+}
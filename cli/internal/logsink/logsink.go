@@ -0,0 +1,138 @@
+// Package logsink streams the output of tasks somewhere other than the terminal: a file
+// per task, an HTTP endpoint, or a websocket. Sinks are configured per run with --log-sink.
+package logsink
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"strings"
+	"sync"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// Sink receives the output of the tasks in a run
+type Sink interface {
+	// Open returns a writer for the output of the given task, which is closed when
+	// the task finishes
+	Open(taskID string) (io.WriteCloser, error)
+	// Close releases what the sink holds once the run is over
+	Close() error
+}
+
+// New returns the sink described by spec:
+//
+//	file:<directory>       a file per task in directory, relative to repoRoot
+//	http(s)://<endpoint>   a streaming POST request per task
+//	ws(s)://<endpoint>     a websocket connection for the whole run
+func New(repoRoot turbopath.AbsolutePath, spec string) (Sink, error) {
+	switch {
+	case strings.HasPrefix(spec, "file:"):
+		return newFileSink(repoRoot, strings.TrimPrefix(spec, "file:"))
+	case strings.HasPrefix(spec, "http://"), strings.HasPrefix(spec, "https://"):
+		return newHTTPSink(spec)
+	case strings.HasPrefix(spec, "ws://"), strings.HasPrefix(spec, "wss://"):
+		return dialWebsocket(spec)
+	default:
+		return nil, fmt.Errorf("invalid log sink %q, expected file:<directory>, an http(s):// URL or a ws(s):// URL", spec)
+	}
+}
+
+// Sinks are the sinks that the output of a run's tasks is copied to
+type Sinks []Sink
+
+// Open opens each of the sinks given by specs. If any of them fails to open, the ones
+// that did are closed again.
+func Open(repoRoot turbopath.AbsolutePath, specs []string) (Sinks, error) {
+	sinks := Sinks{}
+	for _, spec := range specs {
+		sink, err := New(repoRoot, spec)
+		if err != nil {
+			_ = sinks.Close()
+			return nil, fmt.Errorf("failed to open log sink %v: %w", spec, err)
+		}
+		sinks = append(sinks, sink)
+	}
+	return sinks, nil
+}
+
+// Open returns a writer that copies the output of the given task to each sink. It is safe
+// for the task's stdout and stderr to write to it at the same time. A sink that fails, or
+// falls too far behind, stops receiving output, but doesn't fail the write, so that a log
+// viewer going away doesn't fail or stall the task. Its error is returned by Close instead.
+func (s Sinks) Open(taskID string) io.WriteCloser {
+	tw := &taskWriter{}
+	for _, sink := range s {
+		w, err := sink.Open(taskID)
+		if err != nil {
+			tw.errs = append(tw.errs, err)
+			continue
+		}
+		tw.writers = append(tw.writers, w)
+	}
+	return tw
+}
+
+// Close closes each of the sinks
+func (s Sinks) Close() error {
+	var errs []error
+	for _, sink := range s {
+		if err := sink.Close(); err != nil {
+			errs = append(errs, err)
+		}
+	}
+	return joinErrors(errs)
+}
+
+type taskWriter struct {
+	mu      sync.Mutex
+	writers []io.WriteCloser
+	errs    []error
+}
+
+func (tw *taskWriter) Write(p []byte) (int, error) {
+	tw.mu.Lock()
+	defer tw.mu.Unlock()
+	for i, w := range tw.writers {
+		if w == nil {
+			continue
+		}
+		if _, err := w.Write(p); err != nil {
+			tw.errs = append(tw.errs, err)
+			_ = w.Close()
+			tw.writers[i] = nil
+		}
+	}
+	return len(p), nil
+}
+
+func (tw *taskWriter) Close() error {
+	tw.mu.Lock()
+	defer tw.mu.Unlock()
+	for _, w := range tw.writers {
+		if w == nil {
+			continue
+		}
+		if err := w.Close(); err != nil {
+			tw.errs = append(tw.errs, err)
+		}
+	}
+	tw.writers = nil
+	return joinErrors(tw.errs)
+}
+
+// joinErrors combines errs into one error, or returns nil if there are none
+func joinErrors(errs []error) error {
+	switch len(errs) {
+	case 0:
+		return nil
+	case 1:
+		return errs[0]
+	}
+	msgs := make([]string, len(errs))
+	for i, err := range errs {
+		msgs[i] = err.Error()
+	}
+	return errors.New(strings.Join(msgs, ", "))
+}
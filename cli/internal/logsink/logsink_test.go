@@ -0,0 +1,207 @@
+package logsink
+
+import (
+	"errors"
+	"io"
+	"io/ioutil"
+	"net/http"
+	"net/http/httptest"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/gorilla/websocket"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestNew(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	_, err := New(repoRoot, "syslog://localhost")
+	assert.ErrorContains(t, err, "invalid log sink")
+	_, err = New(repoRoot, "http://")
+	assert.ErrorContains(t, err, "has no host")
+}
+
+func TestFileSink(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	sinks, err := Open(repoRoot, []string{"file:logs"})
+	assert.NilError(t, err)
+
+	w := sinks.Open("@acme/ui#build")
+	_, err = w.Write([]byte("compiled\n"))
+	assert.NilError(t, err)
+	assert.NilError(t, w.Close())
+	assert.NilError(t, sinks.Close())
+
+	contents, err := repoRoot.Join("logs", "@acme_ui#build.log").ReadFile()
+	assert.NilError(t, err)
+	assert.Equal(t, string(contents), "compiled\n")
+}
+
+func TestHTTPSink(t *testing.T) {
+	received := make(chan string, 1)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		body, _ := ioutil.ReadAll(r.Body)
+		received <- r.Header.Get("X-Turbo-Task") + ": " + string(body)
+	}))
+	defer server.Close()
+
+	sink, err := newHTTPSink(server.URL)
+	assert.NilError(t, err)
+	w, err := sink.Open("web#build")
+	assert.NilError(t, err)
+	_, err = w.Write([]byte("one\n"))
+	assert.NilError(t, err)
+	_, err = w.Write([]byte("two\n"))
+	assert.NilError(t, err)
+	assert.NilError(t, w.Close())
+	assert.Equal(t, <-received, "web#build: one\ntwo\n")
+}
+
+func TestHTTPSinkRejected(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusForbidden)
+	}))
+	defer server.Close()
+
+	sink, err := newHTTPSink(server.URL)
+	assert.NilError(t, err)
+	w, err := sink.Open("web#build")
+	assert.NilError(t, err)
+	// Whether or not the write races the response, it must not block
+	_, _ = w.Write([]byte("one\n"))
+	assert.ErrorContains(t, w.Close(), "403")
+}
+
+var _testUpgrader = websocket.Upgrader{}
+
+func TestWebsocketSink(t *testing.T) {
+	messages := make(chan websocketMessage, 4)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		conn, err := _testUpgrader.Upgrade(w, r, nil)
+		if err != nil {
+			t.Errorf("upgrade: %v", err)
+			return
+		}
+		defer func() { _ = conn.Close() }()
+		for {
+			var message websocketMessage
+			if err := conn.ReadJSON(&message); err != nil {
+				close(messages)
+				return
+			}
+			messages <- message
+		}
+	}))
+	defer server.Close()
+
+	sink, err := dialWebsocket("ws" + strings.TrimPrefix(server.URL, "http"))
+	assert.NilError(t, err)
+	w, err := sink.Open("web#build")
+	assert.NilError(t, err)
+	_, err = w.Write([]byte(strings.Repeat("x", 200)))
+	assert.NilError(t, err)
+	assert.NilError(t, w.Close())
+	assert.NilError(t, sink.Close())
+
+	received := []websocketMessage{}
+	for message := range messages {
+		received = append(received, message)
+	}
+	assert.DeepEqual(t, received, []websocketMessage{
+		{Task: "web#build", Output: strings.Repeat("x", 200)},
+		{Task: "web#build", Done: true},
+	})
+}
+
+func TestWebsocketSinkRejectsLargeMessages(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		conn, err := _testUpgrader.Upgrade(w, r, nil)
+		if err != nil {
+			t.Errorf("upgrade: %v", err)
+			return
+		}
+		defer func() { _ = conn.Close() }()
+		_ = conn.WriteMessage(websocket.TextMessage, make([]byte, 2*_websocketReadLimit))
+		for {
+			if _, _, err := conn.NextReader(); err != nil {
+				return
+			}
+		}
+	}))
+	defer server.Close()
+
+	sink, err := dialWebsocket("ws" + strings.TrimPrefix(server.URL, "http"))
+	assert.NilError(t, err)
+	<-sink.readDone
+	w, err := sink.Open("web#build")
+	assert.NilError(t, err)
+	_, err = w.Write([]byte("one\n"))
+	assert.ErrorContains(t, err, "read limit exceeded")
+	assert.ErrorContains(t, sink.Close(), "read limit exceeded")
+}
+
+func TestQueueFallsBehind(t *testing.T) {
+	unblock := make(chan struct{})
+	aborted := make(chan error, 1)
+	q := newQueue(func(p []byte) error {
+		<-unblock
+		return nil
+	}, func(err error) {
+		aborted <- err
+		close(unblock)
+	})
+	var err error
+	// One write is taken off the queue and blocks, the rest fill it up
+	for i := 0; i < _sinkQueueSize+2 && err == nil; i++ {
+		err = q.push([]byte("output\n"))
+	}
+	assert.ErrorContains(t, err, "writes behind")
+	assert.ErrorContains(t, <-aborted, "writes behind")
+	assert.ErrorContains(t, q.push([]byte("more\n")), "writes behind")
+	assert.ErrorContains(t, q.close(), "writes behind")
+}
+
+func TestQueueWriteTimeout(t *testing.T) {
+	timeout := _sinkWriteTimeout
+	_sinkWriteTimeout = 10 * time.Millisecond
+	defer func() { _sinkWriteTimeout = timeout }()
+
+	unblock := make(chan struct{})
+	q := newQueue(func(p []byte) error {
+		<-unblock
+		return errors.New("aborted")
+	}, func(err error) {
+		close(unblock)
+	})
+	assert.NilError(t, q.push([]byte("output\n")))
+	assert.ErrorContains(t, q.close(), "didn't accept output")
+}
+
+type failingSink struct{}
+
+func (failingSink) Open(taskID string) (io.WriteCloser, error) {
+	return nil, errors.New("unavailable")
+}
+
+func (failingSink) Close() error {
+	return nil
+}
+
+func TestSinksTolerateFailures(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	fileSink, err := newFileSink(repoRoot, "logs")
+	assert.NilError(t, err)
+	sinks := Sinks{failingSink{}, fileSink}
+
+	w := sinks.Open("web#build")
+	n, err := w.Write([]byte("compiled\n"))
+	assert.NilError(t, err)
+	assert.Equal(t, n, len("compiled\n"))
+	assert.ErrorContains(t, w.Close(), "unavailable")
+
+	contents, err := repoRoot.Join("logs", "web#build.log").ReadFile()
+	assert.NilError(t, err)
+	assert.Equal(t, string(contents), "compiled\n")
+}
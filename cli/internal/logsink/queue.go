@@ -0,0 +1,120 @@
+package logsink
+
+import (
+	"errors"
+	"fmt"
+	"sync"
+	"time"
+)
+
+// _sinkQueueSize is how many writes a sink may fall behind by before it is dropped
+const _sinkQueueSize = 256
+
+// _sinkWriteTimeout bounds how long a sink may take to accept one write before it
+// is dropped
+var _sinkWriteTimeout = 10 * time.Second
+
+var errQueueClosed = errors.New("log sink is closed")
+
+// queue decouples writing a task's output from sending it to a sink, so that a slow or
+// stalled sink can't hold up the task. Writes are sent in order by one goroutine. If the
+// sink falls more than _sinkQueueSize writes behind, or takes longer than
+// _sinkWriteTimeout to accept one, the queue fails: abort is called to unblock the
+// pending write, and every write after it returns the error.
+type queue struct {
+	mu     sync.Mutex
+	items  chan []byte
+	closed bool
+	err    error
+	done   chan struct{}
+
+	write func(p []byte) error
+	abort func(err error)
+}
+
+func newQueue(write func(p []byte) error, abort func(err error)) *queue {
+	q := &queue{
+		items: make(chan []byte, _sinkQueueSize),
+		done:  make(chan struct{}),
+		write: write,
+		abort: abort,
+	}
+	go q.run()
+	return q
+}
+
+func (q *queue) run() {
+	defer close(q.done)
+	for p := range q.items {
+		if q.failed() {
+			// Drain what's left so that close doesn't wait on it
+			continue
+		}
+		timer := time.AfterFunc(_sinkWriteTimeout, func() {
+			q.fail(fmt.Errorf("log sink didn't accept output within %v", _sinkWriteTimeout))
+		})
+		err := q.write(p)
+		timer.Stop()
+		if err != nil {
+			q.fail(err)
+		}
+	}
+}
+
+func (q *queue) failed() bool {
+	q.mu.Lock()
+	defer q.mu.Unlock()
+	return q.err != nil
+}
+
+// fail records err, unless the queue already failed, and aborts the sink
+func (q *queue) fail(err error) {
+	q.mu.Lock()
+	first := q.err == nil
+	if first {
+		q.err = err
+	}
+	q.mu.Unlock()
+	if first {
+		q.abort(err)
+	}
+}
+
+// push queues a copy of p to be written. It returns an error, and fails the queue, if
+// the sink has fallen too far behind.
+func (q *queue) push(p []byte) error {
+	q.mu.Lock()
+	if q.err != nil {
+		defer q.mu.Unlock()
+		return q.err
+	}
+	if q.closed {
+		q.mu.Unlock()
+		return errQueueClosed
+	}
+	select {
+	case q.items <- append([]byte(nil), p...):
+		q.mu.Unlock()
+		return nil
+	default:
+		q.mu.Unlock()
+		err := fmt.Errorf("log sink fell more than %v writes behind", _sinkQueueSize)
+		q.fail(err)
+		return err
+	}
+}
+
+// close waits for what was queued to be written, and returns the error the queue failed
+// with, if any
+func (q *queue) close() error {
+	q.mu.Lock()
+	if !q.closed {
+		q.closed = true
+		close(q.items)
+	}
+	q.mu.Unlock()
+	<-q.done
+	q.mu.Lock()
+	defer q.mu.Unlock()
+	return q.err
+}
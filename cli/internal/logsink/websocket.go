@@ -0,0 +1,116 @@
+package logsink
+
+import (
+	"encoding/json"
+	"fmt"
+	"io"
+	"net/http"
+	"time"
+
+	"github.com/gorilla/websocket"
+)
+
+// _websocketHandshakeTimeout bounds how long connecting to the endpoint may take
+var _websocketHandshakeTimeout = 10 * time.Second
+
+// _websocketReadLimit is the largest message the server may send. It has no reason to
+// send anything but control messages, so a larger one fails the sink.
+const _websocketReadLimit = 64 * 1024
+
+// websocketMessage is sent for each write of a task's output, and once more with Done
+// set when the task finishes
+type websocketMessage struct {
+	Task   string `json:"task"`
+	Output string `json:"output,omitempty"`
+	Done   bool   `json:"done,omitempty"`
+}
+
+// websocketSink sends the output of every task in the run over one websocket connection,
+// as JSON text messages
+type websocketSink struct {
+	conn  *websocket.Conn
+	queue *queue
+	// readDone is closed once nothing more is read from the server
+	readDone chan struct{}
+}
+
+func dialWebsocket(endpoint string) (*websocketSink, error) {
+	dialer := &websocket.Dialer{
+		Proxy:            http.ProxyFromEnvironment,
+		HandshakeTimeout: _websocketHandshakeTimeout,
+	}
+	conn, resp, err := dialer.Dial(endpoint, nil)
+	if err != nil {
+		if resp != nil && resp.StatusCode != http.StatusSwitchingProtocols {
+			return nil, fmt.Errorf("%v responded with %v instead of upgrading to a websocket", endpoint, resp.Status)
+		}
+		return nil, err
+	}
+	conn.SetReadLimit(_websocketReadLimit)
+	sink := &websocketSink{conn: conn, readDone: make(chan struct{})}
+	sink.queue = newQueue(sink.write, sink.abort)
+	go sink.readMessages()
+	return sink, nil
+}
+
+func (s *websocketSink) write(p []byte) error {
+	return s.conn.WriteMessage(websocket.TextMessage, p)
+}
+
+// abort closes the connection, which unblocks a pending write
+func (s *websocketSink) abort(err error) {
+	_ = s.conn.Close()
+}
+
+// readMessages reads until the connection closes, so that the server's pings are
+// answered. Nothing else the server sends is of interest.
+func (s *websocketSink) readMessages() {
+	defer close(s.readDone)
+	for {
+		if _, _, err := s.conn.NextReader(); err != nil {
+			s.queue.fail(fmt.Errorf("websocket connection failed: %w", err))
+			return
+		}
+	}
+}
+
+func (s *websocketSink) Open(taskID string) (io.WriteCloser, error) {
+	return &websocketTaskWriter{sink: s, taskID: taskID}, nil
+}
+
+func (s *websocketSink) send(message *websocketMessage) error {
+	payload, err := json.Marshal(message)
+	if err != nil {
+		return err
+	}
+	return s.queue.push(payload)
+}
+
+// Close sends what is still queued, then a close message, and closes the connection
+func (s *websocketSink) Close() error {
+	err := s.queue.close()
+	if err == nil {
+		closeMessage := websocket.FormatCloseMessage(websocket.CloseNormalClosure, "")
+		_ = s.conn.WriteControl(websocket.CloseMessage, closeMessage, time.Now().Add(_sinkWriteTimeout))
+	}
+	if closeErr := s.conn.Close(); err == nil {
+		err = closeErr
+	}
+	return err
+}
+
+type websocketTaskWriter struct {
+	sink   *websocketSink
+	taskID string
+}
+
+func (w *websocketTaskWriter) Write(p []byte) (int, error) {
+	if err := w.sink.send(&websocketMessage{Task: w.taskID, Output: string(p)}); err != nil {
+		return 0, err
+	}
+	return len(p), nil
+}
+
+func (w *websocketTaskWriter) Close() error {
+	return w.sink.send(&websocketMessage{Task: w.taskID, Done: true})
+}
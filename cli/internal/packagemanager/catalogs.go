@@ -0,0 +1,67 @@
+package packagemanager
+
+import (
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gopkg.in/yaml.v3"
+)
+
+// _catalogProtocol is the prefix of dependency versions that refer to a catalog entry
+const _catalogProtocol = "catalog:"
+
+// _defaultCatalog is the name of the catalog that a bare "catalog:" reference uses
+const _defaultCatalog = "default"
+
+// Catalogs are the dependency version ranges that pnpm-workspace.yaml defines for workspaces
+// to reference as "catalog:<name>", keyed by catalog name and then by package name. The
+// catalog referenced by a bare "catalog:" is named "default".
+type Catalogs map[string]map[string]string
+
+// pnpmCatalogs is the part of pnpm-workspace.yaml that defines catalogs
+type pnpmCatalogs struct {
+	Catalog  map[string]string            `yaml:"catalog,omitempty"`
+	Catalogs map[string]map[string]string `yaml:"catalogs,omitempty"`
+}
+
+// ParsePnpmCatalogs reads the catalogs from the contents of a pnpm-workspace.yaml file
+func ParsePnpmCatalogs(contents []byte) (Catalogs, error) {
+	var parsed pnpmCatalogs
+	if err := yaml.Unmarshal(contents, &parsed); err != nil {
+		return nil, err
+	}
+	catalogs := make(Catalogs, len(parsed.Catalogs)+1)
+	for name, entries := range parsed.Catalogs {
+		catalogs[name] = entries
+	}
+	if len(parsed.Catalog) > 0 {
+		// "catalog" is shorthand for the default catalog, and pnpm doesn't allow both
+		catalogs[_defaultCatalog] = parsed.Catalog
+	}
+	return catalogs, nil
+}
+
+// Resolve returns the version range that a dependency version refers to. Versions that
+// aren't catalog references, and references to entries that don't exist, are returned as-is.
+func (c Catalogs) Resolve(name string, version string) string {
+	if !strings.HasPrefix(version, _catalogProtocol) {
+		return version
+	}
+	catalog := strings.TrimPrefix(version, _catalogProtocol)
+	if catalog == "" {
+		catalog = _defaultCatalog
+	}
+	if resolved, ok := c[catalog][name]; ok {
+		return resolved
+	}
+	return version
+}
+
+// GetCatalogs returns the catalogs of dependency versions defined for the workspace, or nil
+// if the package manager doesn't support catalogs.
+func (pm PackageManager) GetCatalogs(rootpath turbopath.AbsolutePath) (Catalogs, error) {
+	if pm.getCatalogs == nil {
+		return nil, nil
+	}
+	return pm.getCatalogs(rootpath)
+}
@@ -5,13 +5,16 @@
 package packagemanager
 
 import (
-	"errors"
 	"fmt"
 	"os"
+	"os/exec"
+	"path"
 	"path/filepath"
 	"regexp"
 	"strings"
 
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/globby"
 	"github.com/vercel/turborepo/cli/internal/lockfile"
@@ -63,6 +66,10 @@ type PackageManager struct {
 
 	// Read a lockfile for a given package manager
 	readLockfile func(contents []byte) (lockfile.Lockfile, error)
+
+	// Return the catalogs of dependency versions defined for the workspace. Nil if the
+	// Package Manager doesn't support catalogs.
+	getCatalogs func(rootpath turbopath.AbsolutePath) (Catalogs, error)
 }
 
 var packageManagers = []PackageManager{
@@ -89,7 +96,14 @@ func ParsePackageManagerString(packageManager string) (manager string, version s
 }
 
 // GetPackageManager attempts all methods for identifying the package manager in use.
-func GetPackageManager(projectDirectory turbopath.AbsolutePath, pkg *fs.PackageJSON) (packageManager *PackageManager, err error) {
+// A non-empty override, like "yarn" or "pnpm@7.14.0", is used as-is. Otherwise the
+// packageManager field of the root package.json wins, then the lockfile in the project
+// directory, then the package manager binaries that are installed.
+func GetPackageManager(projectDirectory turbopath.AbsolutePath, pkg *fs.PackageJSON, override string) (packageManager *PackageManager, err error) {
+	if override != "" {
+		return readPackageManagerOverride(projectDirectory, override)
+	}
+
 	result, _ := readPackageManager(pkg)
 	if result != nil {
 		return result, nil
@@ -98,6 +112,47 @@ func GetPackageManager(projectDirectory turbopath.AbsolutePath, pkg *fs.PackageJ
 	return detectPackageManager(projectDirectory)
 }
 
+// readPackageManagerOverride finds the package manager named by override. A bare name
+// is resolved using the version of that package manager that is installed.
+func readPackageManagerOverride(projectDirectory turbopath.AbsolutePath, override string) (*PackageManager, error) {
+	if strings.Contains(override, "@") {
+		manager, version, err := ParsePackageManagerString(override)
+		if err != nil {
+			return nil, fmt.Errorf("invalid package manager override %q, expected npm, pnpm, yarn or a name@version string", override)
+		}
+		return matchPackageManager(manager, version)
+	}
+	switch override {
+	case "npm", "pnpm", "yarn":
+		return matchInstalledPackageManager(projectDirectory, override)
+	default:
+		return nil, fmt.Errorf("invalid package manager override %q, expected npm, pnpm, yarn or a name@version string", override)
+	}
+}
+
+// matchPackageManager finds the package manager responsible for the given manager and version
+func matchPackageManager(manager string, version string) (*PackageManager, error) {
+	for _, packageManager := range packageManagers {
+		isResponsible, err := packageManager.Matches(manager, version)
+		if isResponsible && (err == nil) {
+			return &packageManager, nil
+		}
+	}
+	return nil, fmt.Errorf("%v@%v is not a supported package manager", manager, version)
+}
+
+// matchInstalledPackageManager finds the package manager responsible for the installed
+// version of the manager binary
+func matchInstalledPackageManager(projectDirectory turbopath.AbsolutePath, manager string) (*PackageManager, error) {
+	cmd := exec.Command(manager, "--version")
+	cmd.Dir = projectDirectory.ToString()
+	out, err := cmd.Output()
+	if err != nil {
+		return nil, fmt.Errorf("could not detect %v version: %w", manager, err)
+	}
+	return matchPackageManager(manager, strings.TrimSpace(string(out)))
+}
+
 // readPackageManager attempts to read the package manager from the package.json.
 func readPackageManager(pkg *fs.PackageJSON) (packageManager *PackageManager, err error) {
 	if pkg.PackageManager != "" {
@@ -114,47 +169,134 @@ func readPackageManager(pkg *fs.PackageJSON) (packageManager *PackageManager, er
 		}
 	}
 
-	return nil, errors.New(util.Sprintf("We did not find a package manager specified in your root package.json. Please set the \"packageManager\" property in your root package.json (${UNDERLINE}https://nodejs.org/api/packages.html#packagemanager)${RESET} or run `npx @turbo/codemod add-package-manager` in the root of your monorepo."))
+	return nil, errcode.New(errcode.ConfigNoPackageManager, util.Sprintf("We did not find a package manager specified in your root package.json. Please set the \"packageManager\" property in your root package.json (${UNDERLINE}https://nodejs.org/api/packages.html#packagemanager)${RESET} or run `npx @turbo/codemod add-package-manager` in the root of your monorepo."))
 }
 
-// detectPackageManager attempts to detect the package manager by inspecting the project directory state.
-func detectPackageManager(projectDirectory turbopath.AbsolutePath) (packageManager *PackageManager, err error) {
+// MultipleLockfilesError is returned when the project directory has lockfiles for more
+// than one package manager, so the lockfile can't tell which one is in use
+type MultipleLockfilesError struct {
+	// Lockfiles are the names of the lockfiles that were found
+	Lockfiles []string
+}
+
+func (e *MultipleLockfilesError) Error() string {
+	return fmt.Sprintf("Found lockfiles for more than one package manager: %v. Set the \"packageManager\" property in your root package.json, pass --package-manager, or delete the lockfiles that aren't in use.", strings.Join(e.Lockfiles, ", "))
+}
+
+// ErrorCode returns the code of the error
+func (e *MultipleLockfilesError) ErrorCode() errcode.Code {
+	return errcode.ConfigMultipleLockfiles
+}
+
+// FindLockfiles returns the names of the package manager lockfiles in the project directory
+func FindLockfiles(projectDirectory turbopath.AbsolutePath) []string {
+	lockfiles := []string{}
+	seen := make(util.Set)
 	for _, packageManager := range packageManagers {
-		isResponsible, err := packageManager.detect(projectDirectory, &packageManager)
-		if err != nil {
-			return nil, err
+		if seen.Includes(packageManager.Lockfile) {
+			continue
 		}
-		if isResponsible {
-			return &packageManager, nil
+		seen.Add(packageManager.Lockfile)
+		if projectDirectory.Join(packageManager.Lockfile).FileExists() {
+			lockfiles = append(lockfiles, packageManager.Lockfile)
 		}
 	}
+	return lockfiles
+}
+
+// detectPackageManager attempts to detect the package manager by inspecting the project directory state.
+func detectPackageManager(projectDirectory turbopath.AbsolutePath) (packageManager *PackageManager, err error) {
+	lockfiles := FindLockfiles(projectDirectory)
+	if len(lockfiles) > 1 {
+		return nil, &MultipleLockfilesError{Lockfiles: lockfiles}
+	}
 
-	return nil, errors.New(util.Sprintf("We did not detect an in-use package manager for your project. Please set the \"packageManager\" property in your root package.json (${UNDERLINE}https://nodejs.org/api/packages.html#packagemanager)${RESET} or run `npx @turbo/codemod add-package-manager` in the root of your monorepo."))
+	if len(lockfiles) == 1 {
+		for _, packageManager := range packageManagers {
+			isResponsible, err := packageManager.detect(projectDirectory, &packageManager)
+			if err != nil {
+				return nil, err
+			}
+			if isResponsible {
+				return &packageManager, nil
+			}
+		}
+	} else if packageManager := detectInstalledPackageManager(projectDirectory); packageManager != nil {
+		return packageManager, nil
+	}
+
+	return nil, errcode.New(errcode.ConfigNoPackageManager, util.Sprintf("We did not detect an in-use package manager for your project. Please set the \"packageManager\" property in your root package.json (${UNDERLINE}https://nodejs.org/api/packages.html#packagemanager)${RESET} or run `npx @turbo/codemod add-package-manager` in the root of your monorepo."))
+}
+
+// detectInstalledPackageManager picks a package manager for a project without a lockfile
+// from the binaries that are installed. A pnpm-workspace.yaml file means pnpm, otherwise
+// npm is preferred over yarn since it ships with node.
+func detectInstalledPackageManager(projectDirectory turbopath.AbsolutePath) *PackageManager {
+	candidates := []string{"npm", "yarn"}
+	if projectDirectory.Join("pnpm-workspace.yaml").FileExists() {
+		candidates = []string{"pnpm"}
+	}
+	for _, manager := range candidates {
+		if _, err := exec.LookPath(manager); err != nil {
+			continue
+		}
+		if packageManager, err := matchInstalledPackageManager(projectDirectory, manager); err == nil {
+			return packageManager
+		}
+	}
+	return nil
 }
 
 // GetWorkspaces returns the list of package.json files for the current repository.
+// As in npm, workspace globs are applied in order and may use "**". A glob prefixed
+// with "!" removes the workspaces found so far whose directory it matches, so
+// ["packages/**", "!packages/legacy-*"] keeps packages/legacy-a/plugin but drops
+// packages/legacy-a.
 func (pm PackageManager) GetWorkspaces(rootpath turbopath.AbsolutePath) ([]string, error) {
 	globs, err := pm.getWorkspaceGlobs(rootpath)
 	if err != nil {
 		return nil, err
 	}
 
-	justJsons := make([]string, len(globs))
-	for i, space := range globs {
-		justJsons[i] = filepath.Join(space, "package.json")
-	}
-
 	ignores, err := pm.getWorkspaceIgnores(pm, rootpath)
 	if err != nil {
 		return nil, err
 	}
 
-	f, err := globby.GlobFiles(rootpath.ToStringDuringMigration(), justJsons, ignores)
-	if err != nil {
-		return nil, err
+	workspaces := make(util.Set)
+	for _, glob := range globs {
+		if strings.HasPrefix(glob, "!") {
+			negation := path.Clean(filepath.ToSlash(strings.TrimPrefix(glob, "!")))
+			var matchErr error
+			workspaces = workspaces.Filter(func(workspace interface{}) bool {
+				dir, err := rootpath.RelativePathString(filepath.Dir(workspace.(string)))
+				if err != nil {
+					matchErr = err
+					return true
+				}
+				isNegated, err := doublestar.Match(negation, filepath.ToSlash(dir))
+				if err != nil {
+					matchErr = err
+					return true
+				}
+				return !isNegated
+			})
+			if matchErr != nil {
+				return nil, fmt.Errorf("invalid workspace glob %q: %w", glob, matchErr)
+			}
+			continue
+		}
+
+		f, err := globby.GlobFiles(rootpath.ToStringDuringMigration(), []string{filepath.Join(glob, "package.json")}, ignores)
+		if err != nil {
+			return nil, err
+		}
+		for _, workspace := range f {
+			workspaces.Add(workspace)
+		}
 	}
 
-	return f, nil
+	return workspaces.UnsafeListOfStrings(), nil
 }
 
 // GetWorkspaceIgnores returns an array of globs not to search for workspaces.
@@ -1,6 +1,7 @@
 package packagemanager
 
 import (
+	"errors"
 	"os"
 	"path/filepath"
 	"reflect"
@@ -149,7 +150,7 @@ func TestGetPackageManager(t *testing.T) {
 	}
 	for _, tt := range tests {
 		t.Run(tt.name, func(t *testing.T) {
-			gotPackageManager, err := GetPackageManager(tt.projectDirectory, tt.pkg)
+			gotPackageManager, err := GetPackageManager(tt.projectDirectory, tt.pkg, "")
 			if (err != nil) != tt.wantErr {
 				t.Errorf("GetPackageManager() error = %v, wantErr %v", err, tt.wantErr)
 				return
@@ -304,6 +305,41 @@ func Test_GetWorkspaces(t *testing.T) {
 	}
 }
 
+func Test_GetWorkspacesNegations(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	files := map[string]string{
+		"package.json":                                `{"workspaces": ["packages/**", "!packages/legacy-*", "packages/legacy-kept"]}`,
+		"packages/a/package.json":                     "{}",
+		"packages/group/b/package.json":               "{}",
+		"packages/legacy-a/package.json":              "{}",
+		"packages/legacy-a/plugin/package.json":       "{}",
+		"packages/legacy-kept/package.json":           "{}",
+		"packages/a/node_modules/dep/package.json":    "{}",
+		"packages/group/b/node_modules/c/package.json": "{}",
+	}
+	for name, contents := range files {
+		file := repoRoot.Join(filepath.FromSlash(name))
+		assert.NilError(t, file.EnsureDir(), "EnsureDir")
+		assert.NilError(t, file.WriteFile([]byte(contents), 0644), "WriteFile")
+	}
+
+	workspaces, err := nodejsNpm.GetWorkspaces(repoRoot)
+	assert.NilError(t, err, "GetWorkspaces")
+	got := make([]string, len(workspaces))
+	for i, workspace := range workspaces {
+		relative, err := repoRoot.RelativePathString(workspace)
+		assert.NilError(t, err, "Rel")
+		got[i] = filepath.ToSlash(relative)
+	}
+	sort.Strings(got)
+	assert.DeepEqual(t, got, []string{
+		"packages/a/package.json",
+		"packages/group/b/package.json",
+		"packages/legacy-a/plugin/package.json",
+		"packages/legacy-kept/package.json",
+	})
+}
+
 func Test_GetWorkspaceIgnores(t *testing.T) {
 	type test struct {
 		name     string
@@ -403,3 +439,39 @@ func Test_CanPrune(t *testing.T) {
 		})
 	}
 }
+
+func Test_detectPackageManagerMultipleLockfiles(t *testing.T) {
+	projectDirectory := fs.AbsolutePathFromUpstream(t.TempDir())
+	for _, name := range []string{"package.json", "pnpm-lock.yaml", "package-lock.json"} {
+		assert.NilError(t, projectDirectory.Join(name).WriteFile([]byte("{}"), 0644), "WriteFile")
+	}
+
+	_, err := detectPackageManager(projectDirectory)
+	var multipleLockfiles *MultipleLockfilesError
+	assert.Assert(t, errors.As(err, &multipleLockfiles))
+	assert.DeepEqual(t, multipleLockfiles.Lockfiles, []string{"package-lock.json", "pnpm-lock.yaml"})
+
+	packageManager, err := GetPackageManager(projectDirectory, &fs.PackageJSON{}, "npm@8.19.2")
+	assert.NilError(t, err, "GetPackageManager")
+	assert.Equal(t, packageManager.Name, "nodejs-npm")
+
+	_, err = GetPackageManager(projectDirectory, &fs.PackageJSON{}, "bun")
+	assert.ErrorContains(t, err, "invalid package manager override")
+}
+
+func Test_ParsePnpmCatalogs(t *testing.T) {
+	catalogs, err := ParsePnpmCatalogs([]byte(`packages:
+  - "packages/*"
+catalog:
+  react: ^18.2.0
+catalogs:
+  legacy:
+    react: ^17.0.2
+`))
+	assert.NilError(t, err, "ParsePnpmCatalogs")
+	assert.Equal(t, catalogs.Resolve("react", "catalog:"), "^18.2.0")
+	assert.Equal(t, catalogs.Resolve("react", "catalog:default"), "^18.2.0")
+	assert.Equal(t, catalogs.Resolve("react", "catalog:legacy"), "^17.0.2")
+	assert.Equal(t, catalogs.Resolve("react", "catalog:missing"), "catalog:missing")
+	assert.Equal(t, catalogs.Resolve("react", "^16.0.0"), "^16.0.0")
+}
@@ -3,6 +3,7 @@ package packagemanager
 import (
 	"fmt"
 	"io/ioutil"
+	"os"
 
 	"github.com/Masterminds/semver"
 	"github.com/vercel/turborepo/cli/internal/lockfile"
@@ -91,4 +92,18 @@ var nodejsPnpm = PackageManager{
 	readLockfile: func(contents []byte) (lockfile.Lockfile, error) {
 		return lockfile.DecodePnpmLockfile(contents)
 	},
+
+	getCatalogs: func(rootpath turbopath.AbsolutePath) (Catalogs, error) {
+		bytes, err := ioutil.ReadFile(rootpath.Join("pnpm-workspace.yaml").ToStringDuringMigration())
+		if os.IsNotExist(err) {
+			return nil, nil
+		} else if err != nil {
+			return nil, fmt.Errorf("pnpm-workspace.yaml: %w", err)
+		}
+		catalogs, err := ParsePnpmCatalogs(bytes)
+		if err != nil {
+			return nil, fmt.Errorf("pnpm-workspace.yaml: %w", err)
+		}
+		return catalogs, nil
+	},
 }
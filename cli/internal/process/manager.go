@@ -9,6 +9,7 @@ import (
 	"time"
 
 	"github.com/hashicorp/go-hclog"
+	"github.com/vercel/turborepo/cli/internal/errcode"
 )
 
 // ErrClosing is returned when the process manager is in the process of closing,
@@ -26,6 +27,11 @@ func (ce *ChildExit) Error() string {
 	return fmt.Sprintf("command %s exited (%d)", ce.Command, ce.ExitCode)
 }
 
+// ErrorCode returns the code of the error
+func (ce *ChildExit) ErrorCode() errcode.Code {
+	return errcode.RunTaskFailed
+}
+
 // Manager tracks all of the child processes that have been spawned
 type Manager struct {
 	done     bool
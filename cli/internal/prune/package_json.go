@@ -0,0 +1,219 @@
+package prune
+
+import (
+	"bytes"
+	"encoding/json"
+	"fmt"
+
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/lockfile"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _lockedFields can't be stripped from package.json, since the package manager or the pruned
+// lockfile depends on them
+var _lockedFields = []string{
+	"name",
+	"version",
+	"dependencies",
+	"optionalDependencies",
+	"peerDependencies",
+	"dependenciesMeta",
+	"workspaces",
+	"packageManager",
+	// The version overrides of npm, yarn and pnpm, which the lockfile was resolved with
+	"overrides",
+	"resolutions",
+	"pnpm",
+}
+
+// _installScripts run when dependencies are installed, so they are kept whichever tasks the
+// pruned monorepo is for
+var _installScripts = []string{"preinstall", "install", "postinstall", "prepare"}
+
+// packageJSONRewrite describes how the package.json files of the pruned workspaces are changed
+type packageJSONRewrite struct {
+	// stripFields are the top-level fields that are removed
+	stripFields util.Set
+	// keepScripts are the scripts that are kept, or nil to keep all of them
+	keepScripts util.Set
+}
+
+// newPackageJSONRewrite checks the options for rewriting package.json files, returning nil if
+// they are copied as they are
+func newPackageJSONRewrite(stripFields []string, keepScriptsFor []string, pipeline fs.Pipeline) (*packageJSONRewrite, error) {
+	if len(stripFields) == 0 && len(keepScriptsFor) == 0 {
+		return nil, nil
+	}
+	rewrite := &packageJSONRewrite{stripFields: util.SetFromStrings(stripFields)}
+	for _, field := range _lockedFields {
+		if rewrite.stripFields.Includes(field) {
+			return nil, fmt.Errorf("cannot strip %q from package.json, the pruned monorepo wouldn't install", field)
+		}
+	}
+	if len(keepScriptsFor) > 0 {
+		rewrite.keepScripts = scriptsForTasks(pipeline, keepScriptsFor)
+	}
+	return rewrite, nil
+}
+
+// stripsDevDependencies returns true if devDependencies are removed, in which case the pruned
+// lockfile mustn't have them either
+func (r *packageJSONRewrite) stripsDevDependencies() bool {
+	return r != nil && r.stripFields.Includes("devDependencies")
+}
+
+// scriptsForTasks returns the scripts that running tasks needs: the tasks themselves, the
+// tasks they depend on through "dependsOn" in any workspace, their npm pre and post scripts,
+// and the scripts that run on install
+func scriptsForTasks(pipeline fs.Pipeline, tasks []string) util.Set {
+	scripts := util.SetFromStrings(_installScripts)
+	seen := make(util.Set)
+	queue := append([]string{}, tasks...)
+	for len(queue) > 0 {
+		task := queue[0]
+		queue = queue[1:]
+		if seen.Includes(task) {
+			continue
+		}
+		seen.Add(task)
+		scripts.Add(task)
+		scripts.Add("pre" + task)
+		scripts.Add("post" + task)
+		for taskID, definition := range pipeline {
+			if taskName(taskID) != task {
+				continue
+			}
+			queue = append(queue, definition.TopologicalDependencies...)
+			for _, dependency := range definition.TaskDependencies {
+				queue = append(queue, taskName(dependency))
+			}
+		}
+	}
+	return scripts
+}
+
+// taskName returns the task of a pipeline entry, which may be workspace-specific
+func taskName(taskID string) string {
+	if util.IsPackageTask(taskID) {
+		_, task := util.GetPackageTaskFromId(taskID)
+		return task
+	}
+	return taskID
+}
+
+// apply returns the rewritten contents of a package.json file. The fields that are kept stay
+// in the order they were in.
+func (r *packageJSONRewrite) apply(contents []byte) ([]byte, error) {
+	var out bytes.Buffer
+	err := forEachField(contents, &out, func(field string, value json.RawMessage) (json.RawMessage, bool, error) {
+		if r.stripFields.Includes(field) {
+			return nil, false, nil
+		}
+		if field != "scripts" || r.keepScripts == nil {
+			return value, true, nil
+		}
+		var scripts bytes.Buffer
+		if err := forEachField(value, &scripts, func(script string, command json.RawMessage) (json.RawMessage, bool, error) {
+			return command, r.keepScripts.Includes(script), nil
+		}); err != nil {
+			return nil, false, fmt.Errorf("scripts: %w", err)
+		}
+		return scripts.Bytes(), true, nil
+	})
+	if err != nil {
+		return nil, err
+	}
+	var indented bytes.Buffer
+	if err := json.Indent(&indented, out.Bytes(), "", "  "); err != nil {
+		return nil, err
+	}
+	indented.WriteByte('\n')
+	return indented.Bytes(), nil
+}
+
+// forEachField writes the JSON object in contents to out, with each field's value replaced by
+// what fn returns, or dropped if fn doesn't keep it
+func forEachField(contents []byte, out *bytes.Buffer, fn func(field string, value json.RawMessage) (json.RawMessage, bool, error)) error {
+	decoder := json.NewDecoder(bytes.NewReader(contents))
+	if token, err := decoder.Token(); err != nil {
+		return err
+	} else if token != json.Delim('{') {
+		return fmt.Errorf("expected an object, got %v", token)
+	}
+	out.WriteByte('{')
+	first := true
+	for decoder.More() {
+		token, err := decoder.Token()
+		if err != nil {
+			return err
+		}
+		field := token.(string)
+		var value json.RawMessage
+		if err := decoder.Decode(&value); err != nil {
+			return err
+		}
+		value, keep, err := fn(field, value)
+		if err != nil {
+			return err
+		} else if !keep {
+			continue
+		}
+		if !first {
+			out.WriteByte(',')
+		}
+		first = false
+		key, err := json.Marshal(field)
+		if err != nil {
+			return err
+		}
+		out.Write(key)
+		out.WriteByte(':')
+		out.Write(value)
+	}
+	out.WriteByte('}')
+	return nil
+}
+
+// productionLockfileKeys returns the lockfile keys of a workspace's dependencies and their own
+// dependencies, leaving out those that are only reachable from its devDependencies
+func productionLockfileKeys(ctx *context.Context, pkgName string, pkg *fs.PackageJSON) []string {
+	deps := make(map[string]string)
+	for name, version := range pkg.UnresolvedExternalDeps {
+		_, isProd := pkg.Dependencies[name]
+		_, isOptional := pkg.OptionalDependencies[name]
+		if isProd || isOptional {
+			deps[name] = version
+		}
+	}
+	// Only pnpm installs injected dependencies, as copies with lockfile entries of their own
+	if _, ok := ctx.Lockfile.(*lockfile.PnpmLockfile); ok {
+		for name := range ctx.InjectedDependencies[pkgName] {
+			name := name.(string)
+			if version, ok := pkg.Dependencies[name]; ok {
+				deps[name] = version
+			} else if version, ok := pkg.OptionalDependencies[name]; ok {
+				deps[name] = version
+			}
+		}
+	}
+	keys := []string{}
+	seen := make(util.Set)
+	var walk func(deps map[string]string)
+	walk = func(deps map[string]string) {
+		for name, version := range deps {
+			key, _, ok := ctx.Lockfile.ResolvePackage(name, version)
+			if !ok || seen.Includes(key) {
+				continue
+			}
+			seen.Add(key)
+			keys = append(keys, key)
+			if transitiveDeps, ok := ctx.Lockfile.AllDependencies(key); ok {
+				walk(transitiveDeps)
+			}
+		}
+	}
+	walk(deps)
+	return keys
+}
@@ -0,0 +1,63 @@
+package prune
+
+import (
+	"fmt"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func TestPackageJSONRewrite(t *testing.T) {
+	pipeline := fs.Pipeline{
+		"build": fs.TaskDefinition{
+			TopologicalDependencies: []string{"build"},
+			TaskDependencies:        []string{"codegen"},
+		},
+		"web#start": fs.TaskDefinition{
+			TaskDependencies: []string{"web#build"},
+		},
+		"lint": fs.TaskDefinition{},
+	}
+	rewrite, err := newPackageJSONRewrite([]string{"devDependencies", "jest"}, []string{"start"}, pipeline)
+	assert.NilError(t, err)
+	assert.Assert(t, rewrite.stripsDevDependencies())
+
+	contents := []byte(`{
+	"name": "web",
+	"scripts": {"lint": "eslint .", "prebuild": "rm -rf dist", "build": "next build", "codegen": "graphql-codegen", "start": "next start", "postinstall": "patch-package"},
+	"jest": {"preset": "ts-jest"},
+	"dependencies": {"next": "^13.0.0"},
+	"devDependencies": {"eslint": "^8.0.0"},
+	"private": true
+}`)
+	rewritten, err := rewrite.apply(contents)
+	assert.NilError(t, err)
+	assert.Equal(t, string(rewritten), `{
+  "name": "web",
+  "scripts": {
+    "prebuild": "rm -rf dist",
+    "build": "next build",
+    "codegen": "graphql-codegen",
+    "start": "next start",
+    "postinstall": "patch-package"
+  },
+  "dependencies": {
+    "next": "^13.0.0"
+  },
+  "private": true
+}
+`)
+}
+
+func TestPackageJSONRewriteOptions(t *testing.T) {
+	rewrite, err := newPackageJSONRewrite(nil, nil, nil)
+	assert.NilError(t, err)
+	assert.Assert(t, rewrite == nil)
+	assert.Assert(t, !rewrite.stripsDevDependencies())
+
+	for _, field := range []string{"dependencies", "packageManager", "overrides", "resolutions", "pnpm"} {
+		_, err = newPackageJSONRewrite([]string{field}, nil, nil)
+		assert.ErrorContains(t, err, fmt.Sprintf("cannot strip %q", field))
+	}
+}
@@ -20,15 +20,19 @@ import (
 )
 
 type opts struct {
-	scope     string
-	docker    bool
-	outputDir string
+	scope          string
+	docker         bool
+	outputDir      string
+	stripFields    []string
+	keepScriptsFor []string
 }
 
 func addPruneFlags(opts *opts, flags *pflag.FlagSet) {
 	flags.StringVar(&opts.scope, "scope", "", "Specify package to act as entry point for pruned monorepo (required).")
 	flags.BoolVar(&opts.docker, "docker", false, "Output pruned workspace into 'full' and 'json' directories optimized for Docker layer caching.")
 	flags.StringVar(&opts.outputDir, "out-dir", "out", "Set the root directory for files output by this command")
+	flags.StringSliceVar(&opts.stripFields, "strip-field", nil, "Remove a field, such as devDependencies, from the package.json of pruned workspaces. Removing devDependencies also removes them from the pruned lockfile.")
+	flags.StringSliceVar(&opts.keepScriptsFor, "keep-scripts-for", nil, "Remove the scripts that running these tasks doesn't need from the package.json of pruned workspaces.")
 	// No-op the cwd flag while the root level command is not yet cobra
 	_ = flags.String("cwd", "", "")
 	if err := flags.MarkHidden("cwd"); err != nil {
@@ -88,7 +92,7 @@ func (p *prune) prune(opts *opts) error {
 	if err != nil {
 		return fmt.Errorf("failed to read package.json: %w", err)
 	}
-	ctx, err := context.New(context.WithGraph(p.base.RepoRoot, rootPackageJSON, cacheDir))
+	ctx, err := context.New(context.WithGraph(p.base.RepoRoot, rootPackageJSON, cacheDir, p.base.RepoConfig.PackageManager()))
 	if err != nil {
 		return errors.Wrap(err, "could not construct graph")
 	}
@@ -118,6 +122,19 @@ func (p *prune) prune(opts *opts) error {
 		return errors.Errorf("this command is not yet implemented for %s", ctx.PackageManager.Name)
 	}
 
+	var pipeline fs.Pipeline
+	if len(opts.keepScriptsFor) > 0 {
+		turboJSON, err := fs.ReadTurboConfig(p.base.RepoRoot, rootPackageJSON)
+		if err != nil {
+			return err
+		}
+		pipeline = turboJSON.Pipeline
+	}
+	rewrite, err := newPackageJSONRewrite(opts.stripFields, opts.keepScriptsFor, pipeline)
+	if err != nil {
+		return err
+	}
+
 	p.base.UI.Output(fmt.Sprintf("Generating pruned monorepo for %v in %v", ui.Bold(opts.scope), ui.Bold(outDir.ToString())))
 
 	packageJSONPath := outDir.Join("package.json")
@@ -153,17 +170,39 @@ func (p *prune) prune(opts *opts) error {
 		if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].Dir.ToStringDuringMigration(), targetDir.ToStringDuringMigration()); err != nil {
 			return errors.Wrapf(err, "failed to copy %v into %v", internalDep, targetDir)
 		}
+		var packageJSON []byte
+		if rewrite != nil {
+			contents, err := p.base.RepoRoot.Join(ctx.PackageInfos[internalDep].PackageJSONPath.ToStringDuringMigration()).ReadFile()
+			if err != nil {
+				return errors.Wrapf(err, "failed to read package.json of %v", internalDep)
+			}
+			packageJSON, err = rewrite.apply(contents)
+			if err != nil {
+				return errors.Wrapf(err, "failed to rewrite package.json of %v", internalDep)
+			}
+			if err := fullDir.Join(ctx.PackageInfos[internalDep].PackageJSONPath.ToStringDuringMigration()).WriteFile(packageJSON, 0644); err != nil {
+				return errors.Wrapf(err, "failed to write package.json of %v", internalDep)
+			}
+		}
 		if opts.docker {
 			jsonDir := outDir.Join("json", ctx.PackageInfos[internalDep].PackageJSONPath.ToStringDuringMigration())
 			if err := jsonDir.EnsureDir(); err != nil {
 				return errors.Wrapf(err, "failed to create folder %v for %v", jsonDir, internalDep)
 			}
-			if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].PackageJSONPath.ToStringDuringMigration(), jsonDir.ToStringDuringMigration()); err != nil {
+			if packageJSON != nil {
+				if err := jsonDir.WriteFile(packageJSON, 0644); err != nil {
+					return errors.Wrapf(err, "failed to write package.json of %v", internalDep)
+				}
+			} else if err := fs.RecursiveCopy(ctx.PackageInfos[internalDep].PackageJSONPath.ToStringDuringMigration(), jsonDir.ToStringDuringMigration()); err != nil {
 				return errors.Wrapf(err, "failed to copy %v into %v", internalDep, jsonDir)
 			}
 		}
 
-		lockfileKeys = append(lockfileKeys, ctx.PackageInfos[internalDep].TransitiveDeps...)
+		if rewrite.stripsDevDependencies() {
+			lockfileKeys = append(lockfileKeys, productionLockfileKeys(ctx, internalDep.(string), ctx.PackageInfos[internalDep])...)
+		} else {
+			lockfileKeys = append(lockfileKeys, ctx.PackageInfos[internalDep].TransitiveDeps...)
+		}
 
 		p.base.UI.Output(fmt.Sprintf(" - Added %v", ctx.PackageInfos[internalDep].Name))
 	}
@@ -190,10 +229,27 @@ func (p *prune) prune(opts *opts) error {
 		}
 	}
 
+	// .npmrc holds settings that change how the pruned lockfile installs, such as pnpm's
+	// node-linker=hoisted, so it goes wherever the install happens
+	if p.base.RepoRoot.Join(".npmrc").FileExists() {
+		npmrc := &fs.LstatCachedFile{Path: p.base.RepoRoot.Join(".npmrc")}
+		if err := fs.CopyFile(npmrc, outDir.Join(".npmrc").ToStringDuringMigration()); err != nil {
+			return errors.Wrap(err, "failed to copy root .npmrc")
+		}
+		if opts.docker {
+			if err := fs.CopyFile(npmrc, fullDir.Join(".npmrc").ToStringDuringMigration()); err != nil {
+				return errors.Wrap(err, "failed to copy root .npmrc")
+			}
+		}
+	}
+
 	lockfile, err := ctx.Lockfile.Subgraph(workspaces, lockfileKeys)
 	if err != nil {
 		return errors.Wrap(err, "Failed creating pruned lockfile")
 	}
+	if rewrite.stripsDevDependencies() {
+		lockfile = lockfile.OmitDevDependencies(workspaces)
+	}
 
 	if patches := lockfile.Patches(); patches != nil {
 		for _, patch := range patches {
@@ -0,0 +1,41 @@
+package run
+
+import (
+	"os/exec"
+
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/process"
+)
+
+// ExecutionBackend is responsible for running the commands that make up a task.
+// The default backend spawns local child processes, but alternate backends can
+// dispatch tasks elsewhere, e.g. to a remote build farm. Backends must leave the
+// task's outputs on disk in the package directory, so that the cache layer can
+// save them once the task completes.
+type ExecutionBackend interface {
+	// Exec runs the given command on behalf of packageTask, blocking until it
+	// completes. It returns process.ErrClosing if the backend has been closed,
+	// and a *process.ChildExit if the command exited with a non-zero exit code.
+	Exec(packageTask *nodes.PackageTask, hash string, cmd *exec.Cmd) error
+	// Close stops any in-flight commands and prevents new ones from starting.
+	Close()
+}
+
+// localBackend runs tasks as child processes on this machine
+type localBackend struct {
+	processes *process.Manager
+}
+
+var _ ExecutionBackend = (*localBackend)(nil)
+
+func newLocalBackend(processes *process.Manager) *localBackend {
+	return &localBackend{processes: processes}
+}
+
+func (lb *localBackend) Exec(packageTask *nodes.PackageTask, hash string, cmd *exec.Cmd) error {
+	return lb.processes.Exec(cmd)
+}
+
+func (lb *localBackend) Close() {
+	lb.processes.Close()
+}
@@ -0,0 +1,56 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/fatih/color"
+	"github.com/pkg/errors"
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/boundaries"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/errcode"
+	"github.com/vercel/turborepo/cli/internal/ui"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// checkBoundaries reports the imports that cross workspace boundaries in the workspaces
+// that have tasks in the graph, if turbo.json sets up boundaries. Unless they are only
+// warnings, the run fails before any task runs.
+func (r *run) checkBoundaries(g *completeGraph, taskGraph *dag.AcyclicGraph) error {
+	if g.Boundaries == nil {
+		return nil
+	}
+	packages := make(util.Set)
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		pkg, _ := util.GetPackageTaskFromId(taskID)
+		packages.Add(pkg)
+	}
+	names := packages.UnsafeListOfStrings()
+	sort.Strings(names)
+	violations, err := boundaries.Check(r.base.RepoRoot, g.PackageInfos, g.Boundaries, names)
+	if err != nil {
+		return errors.Wrap(err, "failed to check workspace boundaries")
+	}
+	if len(violations) == 0 {
+		return nil
+	}
+	warnOnly := g.Boundaries.Level == "warn"
+	for _, violation := range violations {
+		r.base.Logger.Warn("import crosses a workspace boundary", "package", violation.Package, "file", violation.File, "line", violation.Line, "import", violation.Import, "kind", violation.Kind)
+		if warnOnly {
+			r.base.UI.Error(fmt.Sprintf("%s %s", ui.WARNING_PREFIX, color.YellowString(violation.String())))
+		} else {
+			r.base.UI.Error(fmt.Sprintf("%s %s", ui.ERROR_PREFIX, color.RedString(violation.String())))
+		}
+	}
+	if warnOnly {
+		return nil
+	}
+	return errcode.Wrap(errcode.RunBoundaryViolation, fmt.Errorf("%v imports cross workspace boundaries, see above", len(violations)))
+}
@@ -0,0 +1,70 @@
+package run
+
+import (
+	"fmt"
+	"os"
+	"os/signal"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// getConcurrencyControlPath is the file that a run reads its new concurrency from when
+// it receives _concurrencySignal
+func getConcurrencyControlPath(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
+	return repoRoot.Join(".turbo", "concurrency")
+}
+
+// readConcurrencyControl reads a concurrency, as a number or a percentage of the
+// available CPUs, from the control file
+func readConcurrencyControl(path turbopath.AbsolutePath) (int, error) {
+	contents, err := path.ReadFile()
+	if err != nil {
+		return 0, fmt.Errorf("failed to read %v: %w", path, err)
+	}
+	concurrency, err := util.ParseConcurrency(strings.TrimSpace(string(contents)))
+	if err != nil {
+		return 0, fmt.Errorf("%v: %w", path, err)
+	}
+	return concurrency, nil
+}
+
+// watchConcurrencyControl re-reads the control file each time this process receives
+// _concurrencySignal, and sends each concurrency it reads on the returned channel after
+// passing it to onChange. Unreadable control files are passed to onError and otherwise
+// ignored. The returned function stops watching. On platforms without _concurrencySignal,
+// the channel is nil.
+func watchConcurrencyControl(repoRoot turbopath.AbsolutePath, onChange func(concurrency int), onError func(err error)) (<-chan int, func()) {
+	if _concurrencySignal == nil {
+		return nil, func() {}
+	}
+	signals := make(chan os.Signal, 1)
+	signal.Notify(signals, _concurrencySignal)
+	changes := make(chan int)
+	done := make(chan struct{})
+	go func() {
+		for {
+			select {
+			case <-signals:
+				concurrency, err := readConcurrencyControl(getConcurrencyControlPath(repoRoot))
+				if err != nil {
+					onError(err)
+					continue
+				}
+				onChange(concurrency)
+				select {
+				case changes <- concurrency:
+				case <-done:
+					return
+				}
+			case <-done:
+				return
+			}
+		}
+	}()
+	return changes, func() {
+		signal.Stop(signals)
+		close(done)
+	}
+}
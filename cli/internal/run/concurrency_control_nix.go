@@ -0,0 +1,12 @@
+//go:build !windows
+// +build !windows
+
+package run
+
+import (
+	"os"
+	"syscall"
+)
+
+// _concurrencySignal tells a run to re-read its concurrency from the control file
+var _concurrencySignal os.Signal = syscall.SIGUSR2
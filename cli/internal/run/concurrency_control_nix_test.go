@@ -0,0 +1,61 @@
+//go:build !windows
+// +build !windows
+
+package run
+
+import (
+	"syscall"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestWatchConcurrencyControl(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	controlPath := getConcurrencyControlPath(repoRoot)
+	assert.NoError(t, controlPath.EnsureDir())
+	assert.NoError(t, controlPath.WriteFile([]byte("2"), 0644))
+
+	changes, stop := watchConcurrencyControl(repoRoot, func(int) {}, func(err error) {
+		t.Errorf("unexpected error %v", err)
+	})
+	defer stop()
+	assert.NoError(t, syscall.Kill(syscall.Getpid(), syscall.SIGUSR2))
+	select {
+	case concurrency := <-changes:
+		assert.Equal(t, 2, concurrency)
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for a concurrency change")
+	}
+}
+
+func TestWatchConcurrencyControlQueuesChanges(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	controlPath := getConcurrencyControlPath(repoRoot)
+	assert.NoError(t, controlPath.EnsureDir())
+	assert.NoError(t, controlPath.WriteFile([]byte("3"), 0644))
+
+	changed := make(chan int, 1)
+	changes, stop := watchConcurrencyControl(repoRoot, func(concurrency int) {
+		changed <- concurrency
+	}, func(err error) {
+		t.Errorf("unexpected error %v", err)
+	})
+	defer stop()
+	// Nothing takes the change yet, like while the package graph is being built
+	assert.NoError(t, syscall.Kill(syscall.Getpid(), syscall.SIGUSR2))
+	select {
+	case concurrency := <-changed:
+		assert.Equal(t, 3, concurrency)
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for the control file to be read")
+	}
+	select {
+	case concurrency := <-changes:
+		assert.Equal(t, 3, concurrency)
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for the queued concurrency change")
+	}
+}
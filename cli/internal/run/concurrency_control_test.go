@@ -0,0 +1,26 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestReadConcurrencyControl(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	controlPath := getConcurrencyControlPath(repoRoot)
+
+	_, err := readConcurrencyControl(controlPath)
+	assert.Error(t, err)
+
+	assert.NoError(t, controlPath.EnsureDir())
+	assert.NoError(t, controlPath.WriteFile([]byte("3\n"), 0644))
+	concurrency, err := readConcurrencyControl(controlPath)
+	assert.NoError(t, err)
+	assert.Equal(t, 3, concurrency)
+
+	assert.NoError(t, controlPath.WriteFile([]byte("0"), 0644))
+	_, err = readConcurrencyControl(controlPath)
+	assert.Error(t, err)
+}
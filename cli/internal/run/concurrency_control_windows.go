@@ -0,0 +1,10 @@
+//go:build windows
+// +build windows
+
+package run
+
+import "os"
+
+// _concurrencySignal is nil because Windows has no signal to spare for changing the
+// concurrency of a run
+var _concurrencySignal os.Signal
@@ -0,0 +1,89 @@
+package run
+
+import (
+	"fmt"
+	"strings"
+	"time"
+
+	"github.com/pyr-sh/dag"
+)
+
+// criticalPath returns the chain of tasks that determined how long a run took, in
+// execution order. Starting from the task that finished last, it repeatedly steps to
+// the dependency that finished last, since that is the dependency the task was
+// waiting on before it could start. Tasks that never finished are ignored.
+func criticalPath(state map[string]*BuildTargetState, taskGraph *dag.AcyclicGraph) []*BuildTargetState {
+	var current *BuildTargetState
+	for _, target := range state {
+		if finished(target) && finishesAfter(target, current) {
+			current = target
+		}
+	}
+	path := []*BuildTargetState{}
+	for current != nil {
+		path = append(path, current)
+		current = lastFinishedDependency(state, taskGraph, current.Label)
+	}
+	// We walked backwards from the end of the run, reverse to get execution order
+	for i, j := 0, len(path)-1; i < j; i, j = i+1, j-1 {
+		path[i], path[j] = path[j], path[i]
+	}
+	return path
+}
+
+// lastFinishedDependency returns the dependency of taskID that finished last. Dependencies
+// that never finished, such as tasks without a script, are looked through to their own
+// dependencies.
+func lastFinishedDependency(state map[string]*BuildTargetState, taskGraph *dag.AcyclicGraph, taskID string) *BuildTargetState {
+	var last *BuildTargetState
+	for _, dep := range taskGraph.DownEdges(taskID) {
+		depID := dep.(string)
+		target, ok := state[depID]
+		if !ok || !finished(target) {
+			target = lastFinishedDependency(state, taskGraph, depID)
+		}
+		if target != nil && finishesAfter(target, last) {
+			last = target
+		}
+	}
+	return last
+}
+
+func finished(target *BuildTargetState) bool {
+	return target.Status == TargetBuilt || target.Status == TargetCached || target.Status == TargetBuildFailed
+}
+
+// finishesAfter returns true if target finished later than other. Everything finishes
+// after nil. Ties are broken by label so that the result doesn't depend on map order.
+func finishesAfter(target *BuildTargetState, other *BuildTargetState) bool {
+	if other == nil {
+		return true
+	}
+	targetEnd := target.StartAt.Add(target.Duration)
+	otherEnd := other.StartAt.Add(other.Duration)
+	if targetEnd.Equal(otherEnd) {
+		return target.Label < other.Label
+	}
+	return targetEnd.After(otherEnd)
+}
+
+// formatCriticalPath renders the critical path as the tasks along it, followed by
+// how its time splits between restoring from cache and executing. The result
+// contains color placeholders to be expanded by util.Sprintf.
+func formatCriticalPath(path []*BuildTargetState) (string, string) {
+	var restoring time.Duration
+	var executing time.Duration
+	steps := make([]string, len(path))
+	for i, target := range path {
+		duration := target.Duration.Truncate(time.Millisecond)
+		if target.Status == TargetCached {
+			restoring += target.Duration
+			steps[i] = fmt.Sprintf("%v ${GRAY}(%v, cached)${RESET}", target.Label, duration)
+		} else {
+			executing += target.Duration
+			steps[i] = fmt.Sprintf("%v ${GRAY}(%v)${RESET}", target.Label, duration)
+		}
+	}
+	breakdown := fmt.Sprintf("%v restoring from cache, %v executing", restoring.Truncate(time.Millisecond), executing.Truncate(time.Millisecond))
+	return strings.Join(steps, " > "), breakdown
+}
@@ -0,0 +1,61 @@
+package run
+
+import (
+	"testing"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+)
+
+func TestCriticalPath(t *testing.T) {
+	start := time.Now()
+	target := func(label string, startOffset int, duration int, status RunResultStatus) *BuildTargetState {
+		return &BuildTargetState{
+			Label:    label,
+			StartAt:  start.Add(time.Duration(startOffset) * time.Second),
+			Duration: time.Duration(duration) * time.Second,
+			Status:   status,
+		}
+	}
+	// web#build depends on ui#build and utils#build, which both have no dependencies.
+	// ui#codegen has no script, so it never finishes, but sits between ui#build and
+	// ui#generate. web#test depends on web#build.
+	state := map[string]*BuildTargetState{
+		"ui#generate": target("ui#generate", 0, 2, TargetCached),
+		"ui#codegen":  target("ui#codegen", 2, 0, TargetBuilding),
+		"ui#build":    target("ui#build", 2, 5, TargetBuilt),
+		"utils#build": target("utils#build", 0, 3, TargetBuilt),
+		"web#build":   target("web#build", 7, 10, TargetBuilt),
+		"web#test":    target("web#test", 17, 4, TargetBuilt),
+		"docs#lint":   target("docs#lint", 0, 6, TargetBuilt),
+	}
+	var taskGraph dag.AcyclicGraph
+	for label := range state {
+		taskGraph.Add(label)
+	}
+	connect := func(from string, to string) {
+		taskGraph.Connect(dag.BasicEdge(from, to))
+	}
+	connect("ui#codegen", "ui#generate")
+	connect("ui#build", "ui#codegen")
+	connect("web#build", "ui#build")
+	connect("web#build", "utils#build")
+	connect("web#test", "web#build")
+
+	path := criticalPath(state, &taskGraph)
+	labels := []string{}
+	for _, target := range path {
+		labels = append(labels, target.Label)
+	}
+	assert.Equal(t, []string{"ui#generate", "ui#build", "web#build", "web#test"}, labels)
+
+	steps, breakdown := formatCriticalPath(path)
+	assert.Equal(t, "ui#generate ${GRAY}(2s, cached)${RESET} > ui#build ${GRAY}(5s)${RESET} > web#build ${GRAY}(10s)${RESET} > web#test ${GRAY}(4s)${RESET}", steps)
+	assert.Equal(t, "2s restoring from cache, 19s executing", breakdown)
+}
+
+func TestCriticalPathEmpty(t *testing.T) {
+	var taskGraph dag.AcyclicGraph
+	assert.Empty(t, criticalPath(map[string]*BuildTargetState{}, &taskGraph))
+}
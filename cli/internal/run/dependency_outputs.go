@@ -0,0 +1,30 @@
+package run
+
+import (
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// dependencyOutputTasks returns the tasks in the graph whose outputs need hashing, because
+// a task that depends on them hashes its dependencies by outputs
+func dependencyOutputTasks(taskGraph *dag.AcyclicGraph, pipeline fs.Pipeline) util.Set {
+	tasks := make(util.Set)
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		if taskDefinition, ok := pipeline.GetTaskDefinition(taskID); ok && taskDefinition.HashDependencyOutputs {
+			for _, dep := range taskGraph.DownEdges(taskID) {
+				if depID := dag.VertexName(dep); depID != core.ROOT_NODE_NAME {
+					tasks.Add(depID)
+				}
+			}
+		}
+	}
+	return tasks
+}
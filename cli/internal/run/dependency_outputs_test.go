@@ -0,0 +1,34 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestDependencyOutputTasks(t *testing.T) {
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "ui#build", "web#build", "web#test", "docs#build"} {
+		taskGraph.Add(v)
+	}
+	taskGraph.Connect(dag.BasicEdge("ui#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("web#test", "web#build"))
+	taskGraph.Connect(dag.BasicEdge("docs#build", "ui#build"))
+	pipeline := fs.Pipeline{
+		"build":      {},
+		"test":       {},
+		"web#build":  {HashDependencyOutputs: true},
+		"docs#build": {},
+	}
+	assert.ElementsMatch(t, dependencyOutputTasks(&taskGraph, pipeline).UnsafeListOfStrings(), []string{"ui#build"})
+
+	pipeline["build"] = fs.TaskDefinition{HashDependencyOutputs: true}
+	assert.ElementsMatch(t, dependencyOutputTasks(&taskGraph, pipeline).UnsafeListOfStrings(), []string{"ui#build"})
+
+	pipeline["test"] = fs.TaskDefinition{HashDependencyOutputs: true}
+	assert.ElementsMatch(t, dependencyOutputTasks(&taskGraph, pipeline).UnsafeListOfStrings(), []string{"ui#build", "web#build"})
+}
@@ -0,0 +1,78 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// formatDryRunTree renders the tasks of a dry run grouped by package, with the tasks
+// each one directly depends on nested underneath it. The result contains color
+// placeholders to be expanded by util.Sprintf.
+func formatDryRunTree(packagesInScope []string, tasks []hashedTask) string {
+	byID := make(map[string]hashedTask, len(tasks))
+	byPackage := make(map[string][]hashedTask)
+	packages := util.SetFromStrings(packagesInScope)
+	for _, task := range tasks {
+		byID[task.TaskID] = task
+		byPackage[task.Package] = append(byPackage[task.Package], task)
+		packages.Add(task.Package)
+	}
+	packageNames := packages.UnsafeListOfStrings()
+	sort.Strings(packageNames)
+
+	var b strings.Builder
+	for _, pkg := range packageNames {
+		fmt.Fprintf(&b, "${BOLD}%v${RESET}\n", pkg)
+		pkgTasks := byPackage[pkg]
+		if len(pkgTasks) == 0 {
+			b.WriteString("${GREY}└── (no tasks)${RESET}\n")
+			continue
+		}
+		sort.Slice(pkgTasks, func(i, j int) bool { return pkgTasks[i].Task < pkgTasks[j].Task })
+		for i, task := range pkgTasks {
+			branch, indent := "├── ", "│   "
+			if i == len(pkgTasks)-1 {
+				branch, indent = "└── ", "    "
+			}
+			fmt.Fprintf(&b, "%v%v ${GREY}%v${RESET} %v\n", branch, task.Task, task.Hash, cacheStatus(task))
+			deps := directDependencies(task, byID)
+			for j, dep := range deps {
+				depBranch := "├── "
+				if j == len(deps)-1 {
+					depBranch = "└── "
+				}
+				fmt.Fprintf(&b, "%v${GREY}%v%v${RESET}\n", indent, depBranch, dep)
+			}
+		}
+	}
+	return strings.TrimSuffix(b.String(), "\n")
+}
+
+func cacheStatus(task hashedTask) string {
+	if task.CachedLocally {
+		return "${GREEN}cached${RESET}"
+	}
+	return "${YELLOW}not cached${RESET}"
+}
+
+// directDependencies reduces the dependencies of a task, which include transitive
+// dependencies, to the ones that aren't also dependencies of another dependency
+func directDependencies(task hashedTask, byID map[string]hashedTask) []string {
+	transitive := make(util.Set)
+	for _, dep := range task.Dependencies {
+		for _, depOfDep := range byID[dep].Dependencies {
+			transitive.Add(depOfDep)
+		}
+	}
+	direct := []string{}
+	for _, dep := range task.Dependencies {
+		if !transitive.Includes(dep) {
+			direct = append(direct, dep)
+		}
+	}
+	sort.Strings(direct)
+	return direct
+}
@@ -0,0 +1,29 @@
+package run
+
+import (
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestFormatDryRunTree(t *testing.T) {
+	tasks := []hashedTask{
+		{TaskID: "web#test", Task: "test", Package: "web", Hash: "3333", Dependencies: []string{"web#build", "ui#build"}},
+		{TaskID: "web#build", Task: "build", Package: "web", Hash: "2222", Dependencies: []string{"ui#build"}},
+		{TaskID: "ui#build", Task: "build", Package: "ui", Hash: "1111", CachedLocally: true, Dependencies: []string{}},
+	}
+	actual := formatDryRunTree([]string{"web", "ui", "docs"}, tasks)
+	expected := strings.Join([]string{
+		"${BOLD}docs${RESET}",
+		"${GREY}└── (no tasks)${RESET}",
+		"${BOLD}ui${RESET}",
+		"└── build ${GREY}1111${RESET} ${GREEN}cached${RESET}",
+		"${BOLD}web${RESET}",
+		"├── build ${GREY}2222${RESET} ${YELLOW}not cached${RESET}",
+		"│   ${GREY}└── ui#build${RESET}",
+		"└── test ${GREY}3333${RESET} ${YELLOW}not cached${RESET}",
+		"    ${GREY}└── web#build${RESET}",
+	}, "\n")
+	assert.Equal(t, expected, actual)
+}
@@ -0,0 +1,82 @@
+package run
+
+import (
+	"encoding/json"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// taskDurations records how long each task took the last time it executed, as opposed
+// to being restored from the cache. Durations are keyed by task ID rather than by hash:
+// a task that is about to execute usually has a hash that hasn't executed before.
+type taskDurations struct {
+	// Tasks maps task IDs to durations in milliseconds
+	Tasks map[string]int64 `json:"tasks"`
+}
+
+func getTaskDurationsPath(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
+	return repoRoot.Join(".turbo", "durations.json")
+}
+
+// readTaskDurations loads the durations recorded by previous runs. A missing or unreadable
+// file is treated as having no history, since durations only affect scheduling order.
+func readTaskDurations(repoRoot turbopath.AbsolutePath) *taskDurations {
+	durations := &taskDurations{Tasks: make(map[string]int64)}
+	contents, err := getTaskDurationsPath(repoRoot).ReadFile()
+	if err != nil {
+		return durations
+	}
+	if err := json.Unmarshal(contents, durations); err != nil || durations.Tasks == nil {
+		return &taskDurations{Tasks: make(map[string]int64)}
+	}
+	return durations
+}
+
+// record updates the durations of the tasks that executed in this run
+func (td *taskDurations) record(state map[string]*BuildTargetState) {
+	for label, target := range state {
+		if target.Status == TargetBuilt {
+			td.Tasks[label] = target.Duration.Milliseconds()
+		}
+	}
+}
+
+func (td *taskDurations) save(repoRoot turbopath.AbsolutePath) error {
+	path := getTaskDurationsPath(repoRoot)
+	if err := path.EnsureDir(); err != nil {
+		return err
+	}
+	contents, err := json.MarshalIndent(td, "", "  ")
+	if err != nil {
+		return err
+	}
+	return path.WriteFile(contents, 0644)
+}
+
+// priorities returns the scheduling priority of every task in the graph: the expected
+// duration of the longest chain of tasks that starts with it. Running the task at the
+// head of the longest remaining chain first shortens the run as a whole. Tasks without
+// a recorded duration are expected to be instant.
+func (td *taskDurations) priorities(taskGraph *dag.AcyclicGraph) map[string]int64 {
+	priorities := make(map[string]int64)
+	var visit func(taskID string) int64
+	visit = func(taskID string) int64 {
+		if priority, ok := priorities[taskID]; ok {
+			return priority
+		}
+		var longestDependent int64
+		for _, dependent := range taskGraph.UpEdges(taskID) {
+			if chain := visit(dependent.(string)); chain > longestDependent {
+				longestDependent = chain
+			}
+		}
+		priority := td.Tasks[taskID] + longestDependent
+		priorities[taskID] = priority
+		return priority
+	}
+	for _, v := range taskGraph.Vertices() {
+		visit(dag.VertexName(v))
+	}
+	return priorities
+}
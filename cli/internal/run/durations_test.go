@@ -0,0 +1,50 @@
+package run
+
+import (
+	"testing"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestTaskDurationsPriorities(t *testing.T) {
+	// web#build and docs#build both depend on ui#build. web#test depends on web#build.
+	var taskGraph dag.AcyclicGraph
+	for _, taskID := range []string{"ui#build", "web#build", "docs#build", "web#test"} {
+		taskGraph.Add(taskID)
+	}
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("docs#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("web#test", "web#build"))
+
+	durations := &taskDurations{Tasks: map[string]int64{
+		"ui#build":   100,
+		"web#build":  300,
+		"docs#build": 500,
+		"web#test":   400,
+	}}
+	priorities := durations.priorities(&taskGraph)
+	assert.Equal(t, map[string]int64{
+		"ui#build":   800,
+		"web#build":  700,
+		"docs#build": 500,
+		"web#test":   400,
+	}, priorities)
+}
+
+func TestTaskDurationsRoundTrip(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	durations := readTaskDurations(repoRoot)
+	assert.Empty(t, durations.Tasks)
+
+	durations.record(map[string]*BuildTargetState{
+		"web#build": {Label: "web#build", Status: TargetBuilt, Duration: 2 * time.Second},
+		"ui#build":  {Label: "ui#build", Status: TargetCached, Duration: 10 * time.Millisecond},
+	})
+	assert.NoError(t, durations.save(repoRoot))
+
+	durations = readTaskDurations(repoRoot)
+	assert.Equal(t, map[string]int64{"web#build": 2000}, durations.Tasks)
+}
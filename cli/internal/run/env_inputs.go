@@ -0,0 +1,42 @@
+package run
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// checkEnvInputs returns an error if a task in the graph takes env inputs from a task that
+// it doesn't depend on, or that doesn't declare an env output. Otherwise, it returns the
+// tasks whose env outputs need to be read for their dependents.
+func checkEnvInputs(taskGraph *dag.AcyclicGraph, pipeline fs.Pipeline) (util.Set, error) {
+	tasks := make(util.Set)
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		taskDefinition, ok := pipeline.GetTaskDefinition(taskID)
+		if !ok || len(taskDefinition.EnvInputsFrom) == 0 {
+			continue
+		}
+		deps, err := taskGraph.Ancestors(taskID)
+		if err != nil {
+			return nil, err
+		}
+		for _, envInputTask := range taskDefinition.EnvInputsFrom {
+			if !deps.Include(envInputTask) {
+				return nil, fmt.Errorf("%v takes env inputs from %v, but doesn't depend on it. Add it to \"dependsOn\"", taskID, envInputTask)
+			}
+			if producer, ok := pipeline.GetTaskDefinition(envInputTask); !ok || producer.EnvOutput == "" {
+				return nil, fmt.Errorf("%v takes env inputs from %v, which doesn't declare an \"envOutput\"", taskID, envInputTask)
+			}
+			tasks.Add(envInputTask)
+		}
+	}
+	return tasks, nil
+}
@@ -0,0 +1,38 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestCheckEnvInputs(t *testing.T) {
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "codegen#build", "ui#build", "web#build", "docs#build"} {
+		taskGraph.Add(v)
+	}
+	taskGraph.Connect(dag.BasicEdge("codegen#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("ui#build", "codegen#build"))
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("docs#build", core.ROOT_NODE_NAME))
+	pipeline := fs.Pipeline{
+		"build":         {},
+		"codegen#build": {EnvOutput: ".env.generated"},
+		"web#build":     {EnvInputsFrom: []string{"codegen#build"}},
+	}
+	tasks, err := checkEnvInputs(&taskGraph, pipeline)
+	assert.NoError(t, err)
+	assert.ElementsMatch(t, tasks.UnsafeListOfStrings(), []string{"codegen#build"})
+
+	pipeline["docs#build"] = fs.TaskDefinition{EnvInputsFrom: []string{"codegen#build"}}
+	_, err = checkEnvInputs(&taskGraph, pipeline)
+	assert.EqualError(t, err, "docs#build takes env inputs from codegen#build, but doesn't depend on it. Add it to \"dependsOn\"")
+
+	delete(pipeline, "docs#build")
+	pipeline["web#build"] = fs.TaskDefinition{EnvInputsFrom: []string{"ui#build"}}
+	_, err = checkEnvInputs(&taskGraph, pipeline)
+	assert.EqualError(t, err, "web#build takes env inputs from ui#build, which doesn't declare an \"envOutput\"")
+}
@@ -0,0 +1,134 @@
+package run
+
+import (
+	"archive/tar"
+	"bytes"
+	"compress/gzip"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+	"sort"
+
+	"github.com/vercel/turborepo/cli/internal/globby"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// artifactUploader is the part of the API client that uploads artifacts to the remote cache
+type artifactUploader interface {
+	PutArtifact(hash string, artifactBody []byte, duration int, tag string) error
+}
+
+// failureArtifacts saves the logs and debug outputs of the tasks that fail and ask for it
+// with "onFailure", so that failures can be looked into after the machine that ran them
+// is gone
+type failureArtifacts struct {
+	repoRoot turbopath.AbsolutePath
+	// dir is where failure artifacts are written
+	dir turbopath.AbsolutePath
+	// uploader uploads the failure artifacts of tasks with "onFailure.remote" to the remote
+	// cache. It is nil when the run can't write to the remote cache.
+	uploader artifactUploader
+}
+
+// failureArtifactKey is what the failure artifact of a task with the given hash is called,
+// locally and in the remote cache. It can't be mistaken for the task's cached outputs.
+func failureArtifactKey(hash string) string {
+	return hash + "-failure"
+}
+
+// capture writes a gzipped tarball of the task's log file, if there is one, and of the
+// files in its package matching its "onFailure.capture" globs, to the failure artifacts
+// directory. It returns the repo-relative path of the tarball, and whether it was also
+// uploaded to the remote cache.
+func (f *failureArtifacts) capture(packageTask *nodes.PackageTask, hash string, logFile turbopath.AbsolutePath, duration int) (string, bool, error) {
+	options := packageTask.TaskDefinition.OnFailure
+	pkgDir := f.repoRoot.Join(packageTask.Pkg.Dir.ToStringDuringMigration())
+	files := []string{}
+	if logFile != "" && logFile.FileExists() {
+		files = append(files, logFile.ToString())
+	}
+	if len(options.Capture) > 0 {
+		captured, err := globby.GlobFiles(pkgDir.ToStringDuringMigration(), options.Capture, nil)
+		if err != nil {
+			return "", false, err
+		}
+		files = append(files, captured...)
+	}
+	// The log file can match the capture globs too
+	files = util.SetFromStrings(files).UnsafeListOfStrings()
+	sort.Strings(files)
+
+	var artifact bytes.Buffer
+	if err := f.writeTarball(&artifact, files); err != nil {
+		return "", false, err
+	}
+	path := f.dir.Join(failureArtifactKey(hash) + ".tar.gz")
+	if err := path.EnsureDir(); err != nil {
+		return "", false, err
+	}
+	if err := path.WriteFile(artifact.Bytes(), 0644); err != nil {
+		return "", false, err
+	}
+	relativePath, err := f.repoRoot.RelativePathString(path.ToString())
+	if err != nil {
+		relativePath = path.ToString()
+	}
+
+	uploaded := false
+	if options.Remote && f.uploader != nil {
+		if err := f.uploader.PutArtifact(failureArtifactKey(hash), artifact.Bytes(), duration, ""); err != nil {
+			return relativePath, false, fmt.Errorf("uploading the failure artifact: %w", err)
+		}
+		uploaded = true
+	}
+	return relativePath, uploaded, nil
+}
+
+// writeTarball writes the given files to w as a gzipped tarball, with their paths relative
+// to the repo root
+func (f *failureArtifacts) writeTarball(w io.Writer, files []string) error {
+	gzw := gzip.NewWriter(w)
+	tw := tar.NewWriter(gzw)
+	for _, file := range files {
+		if err := f.addFile(tw, file); err != nil {
+			return err
+		}
+	}
+	if err := tw.Close(); err != nil {
+		return err
+	}
+	return gzw.Close()
+}
+
+func (f *failureArtifacts) addFile(tw *tar.Writer, file string) error {
+	relativePath, err := f.repoRoot.RelativePathString(file)
+	if err != nil {
+		return err
+	}
+	info, err := os.Lstat(file)
+	if err != nil {
+		return err
+	}
+	if !info.Mode().IsRegular() {
+		// Only the contents of files help with debugging a failure
+		return nil
+	}
+	header, err := tar.FileInfoHeader(info, "")
+	if err != nil {
+		return err
+	}
+	header.Name = filepath.ToSlash(relativePath)
+	if err := tw.WriteHeader(header); err != nil {
+		return err
+	}
+	contents, err := os.Open(file)
+	if err != nil {
+		return err
+	}
+	defer func() { _ = contents.Close() }()
+	_, err = io.Copy(tw, contents)
+	return err
+}
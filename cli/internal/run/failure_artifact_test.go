@@ -0,0 +1,91 @@
+package run
+
+import (
+	"archive/tar"
+	"compress/gzip"
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+type fakeUploader struct {
+	uploads map[string][]byte
+}
+
+func (u *fakeUploader) PutArtifact(hash string, artifactBody []byte, duration int, tag string) error {
+	u.uploads[hash] = artifactBody
+	return nil
+}
+
+func tarballFiles(t *testing.T, path string) map[string]string {
+	f, err := os.Open(path)
+	assert.NoError(t, err)
+	defer func() { _ = f.Close() }()
+	gzr, err := gzip.NewReader(f)
+	assert.NoError(t, err)
+	tr := tar.NewReader(gzr)
+	files := map[string]string{}
+	for {
+		header, err := tr.Next()
+		if err == io.EOF {
+			return files
+		}
+		assert.NoError(t, err)
+		contents, err := io.ReadAll(tr)
+		assert.NoError(t, err)
+		files[header.Name] = string(contents)
+	}
+}
+
+func TestFailureArtifactsCapture(t *testing.T) {
+	repoRoot := turbopath.AbsolutePath(t.TempDir())
+	writeFile := func(path string, contents string) {
+		path = filepath.Join(repoRoot.ToString(), filepath.FromSlash(path))
+		assert.NoError(t, os.MkdirAll(filepath.Dir(path), 0755))
+		assert.NoError(t, os.WriteFile(path, []byte(contents), 0644))
+	}
+	writeFile("apps/web/.turbo/turbo-test.log", "1 test failed")
+	writeFile("apps/web/playwright-report/index.html", "<html></html>")
+	writeFile("apps/web/playwright-report/trace.zip", "trace")
+	writeFile("apps/web/src/index.ts", "export {}")
+
+	uploader := &fakeUploader{uploads: map[string][]byte{}}
+	artifacts := &failureArtifacts{
+		repoRoot: repoRoot,
+		dir:      repoRoot.Join("node_modules", ".cache", "turbo", "failures"),
+		uploader: uploader,
+	}
+	packageTask := &nodes.PackageTask{
+		TaskID: "web#test",
+		Pkg:    &fs.PackageJSON{Dir: turbopath.AnchoredSystemPath(filepath.Join("apps", "web"))},
+		TaskDefinition: &fs.TaskDefinition{
+			OnFailure: &fs.FailureOptions{Capture: []string{"playwright-report/**"}},
+		},
+	}
+	logFile := repoRoot.Join("apps", "web", ".turbo", "turbo-test.log")
+
+	path, uploaded, err := artifacts.capture(packageTask, "abc123", logFile, 1500)
+	assert.NoError(t, err)
+	assert.False(t, uploaded)
+	assert.Equal(t, filepath.Join("node_modules", ".cache", "turbo", "failures", "abc123-failure.tar.gz"), path)
+	assert.Equal(t, map[string]string{
+		"apps/web/.turbo/turbo-test.log":        "1 test failed",
+		"apps/web/playwright-report/index.html": "<html></html>",
+		"apps/web/playwright-report/trace.zip":  "trace",
+	}, tarballFiles(t, repoRoot.Join(path).ToString()))
+
+	// Only tasks that ask for it upload their failure artifacts, and without a log file
+	// only the captured files are kept
+	packageTask.TaskDefinition.OnFailure.Remote = true
+	_, uploaded, err = artifacts.capture(packageTask, "def456", "", 1500)
+	assert.NoError(t, err)
+	assert.True(t, uploaded)
+	assert.Contains(t, uploader.uploads, "def456-failure")
+	assert.Equal(t, 2, len(tarballFiles(t, artifacts.dir.Join("def456-failure.tar.gz").ToString())))
+}
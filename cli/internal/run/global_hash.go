@@ -18,12 +18,17 @@ import (
 
 const _globalCacheKey = "Real G's move in silence like lasagna"
 
+// _cacheNamespaceEnvName overrides the cacheNamespace configured in turbo.json
+const _cacheNamespaceEnvName = "TURBO_CACHE_NAMESPACE"
+
 // Variables that we always include
 var _defaultEnvVars = []string{
 	"VERCEL_ANALYTICS_ID",
 }
 
-func calculateGlobalHash(rootpath turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON, pipeline fs.Pipeline, envVarDependencies []string, globalFileDependencies []string, packageManager *packagemanager.PackageManager, logger hclog.Logger, env []string) (string, error) {
+// calculateGlobalHash returns the hash of everything that affects every task, along with
+// the global file dependencies that nonUTF8Paths left out of it
+func calculateGlobalHash(rootpath turbopath.AbsolutePath, rootPackageJSON *fs.PackageJSON, pipeline fs.Pipeline, envVarDependencies []string, globalFileDependencies []string, packageManager *packagemanager.PackageManager, cacheNamespace string, logger hclog.Logger, env []string, nonUTF8Paths fs.NonUTF8PathPolicy, normalizeLineEndings bool) (string, []string, error) {
 	// Calculate env var dependencies
 	globalHashableEnvNames := []string{}
 	globalHashableEnvPairs := []string{}
@@ -40,18 +45,30 @@ func calculateGlobalHash(rootpath turbopath.AbsolutePath, rootPackageJSON *fs.Pa
 
 	// Calculate global file dependencies
 	globalDeps := make(util.Set)
+	skipped := []string{}
 	if len(globalFileDependencies) > 0 {
 		ignores, err := packageManager.GetWorkspaceIgnores(rootpath)
 		if err != nil {
-			return "", err
+			return "", nil, err
 		}
 
 		f, err := globby.GlobFiles(rootpath.ToStringDuringMigration(), globalFileDependencies, ignores)
 		if err != nil {
-			return "", err
+			return "", nil, err
 		}
 
 		for _, val := range f {
+			relativePath, err := rootpath.RelativePathString(val)
+			if err != nil {
+				return "", nil, err
+			}
+			allowed, err := nonUTF8Paths.Allow(relativePath)
+			if err != nil {
+				return "", nil, err
+			} else if !allowed {
+				skipped = append(skipped, relativePath)
+				continue
+			}
 			globalDeps.Add(val)
 		}
 	}
@@ -79,28 +96,44 @@ func calculateGlobalHash(rootpath turbopath.AbsolutePath, rootPackageJSON *fs.Pa
 		globalDepsPaths[i] = turbopath.AbsoluteSystemPathFromUpstream(path)
 	}
 
-	globalFileHashMap, err := hashing.GetHashableDeps(rootpath, globalDepsPaths)
+	globalFileHashMap, err := hashing.GetHashableDeps(rootpath, globalDepsPaths, normalizeLineEndings)
 	if err != nil {
-		return "", fmt.Errorf("error hashing files: %w", err)
+		return "", nil, fmt.Errorf("error hashing files: %w", err)
 	}
 	globalHashable := struct {
 		globalFileHashMap    map[turbopath.AnchoredUnixPath]string
 		rootExternalDepsHash string
 		hashedSortedEnvPairs []string
 		globalCacheKey       string
+		cacheNamespace       string
 		pipeline             fs.Pipeline
 	}{
 		globalFileHashMap:    globalFileHashMap,
 		rootExternalDepsHash: rootPackageJSON.ExternalDepsHash,
 		hashedSortedEnvPairs: globalHashableEnvPairs,
 		globalCacheKey:       _globalCacheKey,
+		cacheNamespace:       getCacheNamespace(cacheNamespace, env),
 		pipeline:             pipeline,
 	}
 	globalHash, err := fs.HashObject(globalHashable)
 	if err != nil {
-		return "", fmt.Errorf("error hashing global dependencies %w", err)
+		return "", nil, fmt.Errorf("error hashing global dependencies %w", err)
+	}
+	sort.Strings(skipped)
+	return globalHash, skipped, nil
+}
+
+// getCacheNamespace returns the namespace that separates the cache entries of build variants
+// whose inputs are otherwise identical. TURBO_CACHE_NAMESPACE takes precedence over the
+// cacheNamespace configured in turbo.json.
+func getCacheNamespace(configured string, env []string) string {
+	for _, e := range env {
+		kv := strings.SplitN(e, "=", 2)
+		if kv[0] == _cacheNamespaceEnvName && len(kv) == 2 {
+			return kv[1]
+		}
 	}
-	return globalHash, nil
+	return configured
 }
 
 // getHashableTurboEnvVarsFromOs returns a list of environment variables names and
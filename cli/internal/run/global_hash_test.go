@@ -24,3 +24,24 @@ func Test_getHashableTurboEnvVarsFromOs(t *testing.T) {
 		t.Errorf("getHashableTurboEnvVarsFromOs() env pairs got = %v, want %v", gotPairs, wantPairs)
 	}
 }
+
+func Test_getCacheNamespace(t *testing.T) {
+	tests := []struct {
+		name       string
+		configured string
+		env        []string
+		want       string
+	}{
+		{"unset", "", []string{"SOME_ENV_VAR=excluded"}, ""},
+		{"configured", "asan", []string{"SOME_ENV_VAR=excluded"}, "asan"},
+		{"env overrides configured", "asan", []string{"TURBO_CACHE_NAMESPACE=node-18"}, "node-18"},
+		{"empty env overrides configured", "asan", []string{"TURBO_CACHE_NAMESPACE="}, ""},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			if got := getCacheNamespace(tt.configured, tt.env); got != tt.want {
+				t.Errorf("getCacheNamespace() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
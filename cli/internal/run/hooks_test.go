@@ -0,0 +1,79 @@
+package run
+
+import (
+	"errors"
+	"os/exec"
+	"strings"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+)
+
+// recordingBackend records the commands it is asked to run, and fails the one whose
+// last argument is fail
+type recordingBackend struct {
+	ran  []*exec.Cmd
+	fail string
+}
+
+func (b *recordingBackend) Exec(packageTask *nodes.PackageTask, hash string, cmd *exec.Cmd) error {
+	b.ran = append(b.ran, cmd)
+	if cmd.Args[len(cmd.Args)-1] == b.fail {
+		return errors.New("exit status 1")
+	}
+	return nil
+}
+
+func (b *recordingBackend) Close() {}
+
+func (b *recordingBackend) lastArgs() []string {
+	args := []string{}
+	for _, cmd := range b.ran {
+		args = append(args, cmd.Args[len(cmd.Args)-1])
+	}
+	return args
+}
+
+func TestExecWithHooks(t *testing.T) {
+	packageTask := &nodes.PackageTask{
+		TaskID:         "web#build",
+		TaskDefinition: &fs.TaskDefinition{Before: "codegen", After: "report"},
+	}
+	taskCmd := func() *exec.Cmd {
+		cmd := exec.Command("npm", "run", "build")
+		cmd.Dir = "apps/web"
+		cmd.Env = []string{"TURBO_HASH=abc123"}
+		cmd.Stdin = strings.NewReader("")
+		return cmd
+	}
+
+	backend := &recordingBackend{}
+	e := &execContext{backend: backend}
+	assert.NoError(t, e.execWithHooks(packageTask, "abc123", taskCmd()))
+	assert.Equal(t, []string{"codegen", "build", "report"}, backend.lastArgs())
+	// Hooks run like the task's command
+	for _, hook := range []*exec.Cmd{backend.ran[0], backend.ran[2]} {
+		assert.Equal(t, "apps/web", hook.Dir)
+		assert.Equal(t, []string{"TURBO_HASH=abc123"}, hook.Env)
+		assert.Equal(t, backend.ran[1].Stdin, hook.Stdin)
+	}
+
+	// A failing before hook keeps the task from running
+	backend = &recordingBackend{fail: "codegen"}
+	e = &execContext{backend: backend}
+	assert.EqualError(t, e.execWithHooks(packageTask, "abc123", taskCmd()), "\"before\" hook failed: exit status 1")
+	assert.Equal(t, []string{"codegen"}, backend.lastArgs())
+
+	// The after hook only runs once the task succeeds
+	backend = &recordingBackend{fail: "build"}
+	e = &execContext{backend: backend}
+	assert.EqualError(t, e.execWithHooks(packageTask, "abc123", taskCmd()), "exit status 1")
+	assert.Equal(t, []string{"codegen", "build"}, backend.lastArgs())
+
+	backend = &recordingBackend{fail: "report"}
+	e = &execContext{backend: backend}
+	assert.EqualError(t, e.execWithHooks(packageTask, "abc123", taskCmd()), "\"after\" hook failed: exit status 1")
+	assert.Equal(t, []string{"codegen", "build", "report"}, backend.lastArgs())
+}
@@ -0,0 +1,30 @@
+package run
+
+import (
+	"fmt"
+	"io"
+	"strings"
+	"text/tabwriter"
+
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// printInputSizes writes a table of how large the inputs of each package are, for
+// finding the packages whose inputs make hashing slow
+func printInputSizes(out io.Writer, sizes []taskhash.InputSize) {
+	w := tabwriter.NewWriter(out, 0, 0, 1, ' ', 0)
+	fmt.Fprintln(w, "Package\tInputs\tFiles\tSize\tLargest file\t")
+	for _, size := range sizes {
+		inputs := "(all files)"
+		if len(size.Inputs) > 0 {
+			inputs = strings.Join(size.Inputs, ", ")
+		}
+		largest := ""
+		if size.Largest != "" {
+			largest = fmt.Sprintf("%v (%v)", size.Largest, util.FormatBytes(size.LargestBytes))
+		}
+		fmt.Fprintf(w, "%v\t%v\t%v\t%v\t%v\t\n", size.Package, inputs, size.Files, util.FormatBytes(size.Bytes), largest)
+	}
+	w.Flush()
+}
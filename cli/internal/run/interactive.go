@@ -0,0 +1,31 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// checkInteractiveTasks returns an error if more than one task in the graph is
+// interactive, since only one of them can own the terminal's input
+func checkInteractiveTasks(taskGraph *dag.AcyclicGraph, pipeline fs.Pipeline) error {
+	interactive := []string{}
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) {
+			continue
+		}
+		if taskDefinition, ok := pipeline.GetTaskDefinition(taskID); ok && taskDefinition.Interactive {
+			interactive = append(interactive, taskID)
+		}
+	}
+	if len(interactive) > 1 {
+		sort.Strings(interactive)
+		return fmt.Errorf("only one interactive task can run at a time, but this run includes %v. Use --filter to select one of them", strings.Join(interactive, ", "))
+	}
+	return nil
+}
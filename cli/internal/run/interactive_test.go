@@ -0,0 +1,26 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestCheckInteractiveTasks(t *testing.T) {
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "ui#build", "web#dev", "docs#dev"} {
+		taskGraph.Add(v)
+	}
+	pipeline := fs.Pipeline{
+		"build":   {},
+		"dev":     {},
+		"web#dev": {Interactive: true},
+	}
+	assert.NoError(t, checkInteractiveTasks(&taskGraph, pipeline))
+
+	pipeline["dev"] = fs.TaskDefinition{Interactive: true}
+	assert.EqualError(t, checkInteractiveTasks(&taskGraph, pipeline), "only one interactive task can run at a time, but this run includes docs#dev, web#dev. Use --filter to select one of them")
+}
@@ -0,0 +1,54 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/spf13/pflag"
+)
+
+// labelsValue implements a repeatable flag that collects key=value pairs
+// (--label team=checkout --label pipeline=nightly)
+type labelsValue struct {
+	labels *map[string]string
+}
+
+var _ pflag.Value = &labelsValue{}
+
+func (l *labelsValue) String() string {
+	if l.labels == nil {
+		return ""
+	}
+	return formatLabels(*l.labels)
+}
+
+func (l *labelsValue) Set(value string) error {
+	kv := strings.SplitN(value, "=", 2)
+	if len(kv) != 2 || kv[0] == "" {
+		return fmt.Errorf("invalid label %q, expected key=value", value)
+	}
+	if *l.labels == nil {
+		*l.labels = make(map[string]string)
+	}
+	(*l.labels)[kv[0]] = kv[1]
+	return nil
+}
+
+func (l *labelsValue) Type() string {
+	return "key=value"
+}
+
+// formatLabels renders labels as a comma-separated list of key=value pairs, sorted by key
+func formatLabels(labels map[string]string) string {
+	keys := make([]string, 0, len(labels))
+	for key := range labels {
+		keys = append(keys, key)
+	}
+	sort.Strings(keys)
+	pairs := make([]string, len(keys))
+	for i, key := range keys {
+		pairs[i] = fmt.Sprintf("%v=%v", key, labels[key])
+	}
+	return strings.Join(pairs, ", ")
+}
@@ -0,0 +1,27 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestLabelsValue(t *testing.T) {
+	var labels map[string]string
+	value := &labelsValue{labels: &labels}
+	assert.Equal(t, "", value.String())
+
+	assert.NoError(t, value.Set("team=checkout"))
+	assert.NoError(t, value.Set("pipeline=nightly"))
+	assert.NoError(t, value.Set("url=https://example.com/?a=b"))
+	assert.NoError(t, value.Set("team=payments"))
+	assert.Equal(t, map[string]string{
+		"team":     "payments",
+		"pipeline": "nightly",
+		"url":      "https://example.com/?a=b",
+	}, labels)
+	assert.Equal(t, "pipeline=nightly, team=payments, url=https://example.com/?a=b", value.String())
+
+	assert.Error(t, value.Set("team"))
+	assert.Error(t, value.Set("=checkout"))
+}
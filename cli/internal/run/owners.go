@@ -0,0 +1,69 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/codeowners"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// packageOwners maps each of the packages to the owners CODEOWNERS gives its directory.
+// It returns nil if the repository doesn't have a CODEOWNERS file, so that reports can
+// leave owners out entirely rather than show every package as unowned.
+func packageOwners(owners *codeowners.Owners, packageInfos map[interface{}]*fs.PackageJSON, packages []string) map[string][]string {
+	if owners == nil {
+		return nil
+	}
+	byPackage := make(map[string][]string, len(packages))
+	for _, pkg := range packages {
+		info, ok := packageInfos[pkg]
+		if !ok {
+			continue
+		}
+		packageOwners := owners.Of(info.Dir.ToString())
+		if packageOwners == nil {
+			packageOwners = []string{}
+		}
+		byPackage[pkg] = packageOwners
+	}
+	return byPackage
+}
+
+// formatOwners groups the packages in scope by owner for the run output, so that a run
+// filtered to changed packages, such as --filter=[origin/main], names the teams it affects.
+// Packages without owners are listed last. It returns "" if there are no owners to report.
+func formatOwners(owners map[string][]string, packages []string) string {
+	if owners == nil {
+		return ""
+	}
+	byOwner := make(map[string][]string)
+	var unowned []string
+	for _, pkg := range packages {
+		packageOwners, ok := owners[pkg]
+		if !ok {
+			continue
+		}
+		if len(packageOwners) == 0 {
+			unowned = append(unowned, pkg)
+			continue
+		}
+		for _, owner := range packageOwners {
+			byOwner[owner] = append(byOwner[owner], pkg)
+		}
+	}
+	names := make([]string, 0, len(byOwner))
+	for owner := range byOwner {
+		names = append(names, owner)
+	}
+	sort.Strings(names)
+	groups := make([]string, 0, len(names)+1)
+	for _, owner := range names {
+		groups = append(groups, fmt.Sprintf("%v (%v)", owner, strings.Join(byOwner[owner], ", ")))
+	}
+	if len(unowned) > 0 {
+		groups = append(groups, fmt.Sprintf("no owners (%v)", strings.Join(unowned, ", ")))
+	}
+	return strings.Join(groups, ", ")
+}
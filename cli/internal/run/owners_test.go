@@ -0,0 +1,46 @@
+package run
+
+import (
+	"path/filepath"
+	"testing"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/codeowners"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+func TestChangedPackageOwners(t *testing.T) {
+	codeOwners, err := codeowners.Parse([]byte(`apps/          @acme/apps
+/apps/web/     @acme/web @alice
+packages/ui    @acme/design @acme/apps
+packages/tooling
+`))
+	assert.NoError(t, err)
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web":     {Dir: turbopath.AnchoredSystemPath(filepath.Join("apps", "web"))},
+		"docs":    {Dir: turbopath.AnchoredSystemPath(filepath.Join("apps", "docs"))},
+		"ui":      {Dir: turbopath.AnchoredSystemPath(filepath.Join("packages", "ui"))},
+		"tooling": {Dir: turbopath.AnchoredSystemPath(filepath.Join("packages", "tooling"))},
+	}
+	// The packages a --filter=[ref] run found changed, in the sorted order the run reports them
+	changed := []string{"docs", "tooling", "ui", "web"}
+
+	owners := packageOwners(codeOwners, packageInfos, changed)
+	assert.Equal(t, map[string][]string{
+		"docs":    {"@acme/apps"},
+		"tooling": {},
+		"ui":      {"@acme/design", "@acme/apps"},
+		"web":     {"@acme/web", "@alice"},
+	}, owners)
+	assert.Equal(t, "@acme/apps (docs, ui), @acme/design (ui), @acme/web (web), @alice (web), no owners (tooling)", formatOwners(owners, changed))
+}
+
+func TestChangedPackageOwnersWithoutCodeowners(t *testing.T) {
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web": {Dir: turbopath.AnchoredSystemPath(filepath.Join("apps", "web"))},
+	}
+	owners := packageOwners(nil, packageInfos, []string{"web"})
+	assert.Nil(t, owners)
+	assert.Equal(t, "", formatOwners(owners, []string{"web"}))
+}
@@ -0,0 +1,84 @@
+package run
+
+import (
+	gocontext "context"
+	"fmt"
+	"path/filepath"
+	"strings"
+
+	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// buildPackageGraph resolves the package graph of the repository, along with the workspaces
+// of its external repos. When connected to the daemon, the graph that an earlier run resolved
+// from the same files is restored instead. With verifyPackageGraph, the graph is resolved
+// anyway and compared with the restored one.
+func (r *run) buildPackageGraph(ctx gocontext.Context, rootPackageJSON *fs.PackageJSON, externalRepos []fs.ExternalRepo) (*context.Context, error) {
+	repoRoot := r.base.RepoRoot
+	externalRepoRoots := make([]turbopath.AbsolutePath, len(externalRepos))
+	for i, externalRepo := range externalRepos {
+		externalRepoRoots[i] = repoRoot.Join(filepath.FromSlash(externalRepo.Path))
+	}
+	resolve := func() (*context.Context, error) {
+		return context.New(context.WithExternalRepos(externalRepoRoots), context.WithGraph(repoRoot, rootPackageJSON, r.opts.cacheOpts.ResolveCacheDir(repoRoot), r.base.RepoConfig.PackageManager()))
+	}
+	daemonClient := r.opts.runOpts.daemonClient
+	// The daemon only knows about the files of this repo, so it can't tell when the graph
+	// of an external repo changes
+	if daemonClient == nil || len(externalRepoRoots) > 0 {
+		return resolve()
+	}
+	packageManager, err := packagemanager.GetPackageManager(repoRoot, rootPackageJSON, r.base.RepoConfig.PackageManager())
+	if err != nil {
+		return nil, err
+	}
+	key, err := context.GraphKey(repoRoot, packageManager)
+	if err != nil {
+		r.base.Logger.Debug("not using the daemon's package graph", "error", err)
+		return resolve()
+	}
+
+	var restored *context.Context
+	if data, err := daemonClient.GetPackageGraph(ctx, key); err != nil {
+		r.base.Logger.Debug("failed to get the package graph from the daemon", "error", err)
+	} else if data != nil {
+		snapshot, err := context.DecodeSnapshot(data)
+		if err == nil {
+			restored, err = context.New(context.WithSnapshot(repoRoot, rootPackageJSON, packageManager, snapshot))
+		}
+		if err != nil {
+			restored = nil
+			r.base.Logger.Debug("failed to restore the package graph from the daemon", "error", err)
+		}
+	}
+	if restored != nil && !r.opts.runOpts.verifyPackageGraph {
+		r.base.Logger.Debug("restored the package graph from the daemon", "key", key)
+		return restored, nil
+	}
+
+	resolved, err := resolve()
+	if err != nil {
+		return nil, err
+	}
+	snapshot := resolved.Snapshot()
+	if restored != nil {
+		if diffs := restored.Snapshot().Diff(snapshot); len(diffs) > 0 {
+			r.logWarning("", fmt.Errorf("the daemon's package graph doesn't match the resolved one, using the resolved one: %v", strings.Join(diffs, ", ")))
+		} else {
+			r.base.Logger.Debug("verified the package graph from the daemon", "key", key)
+			return resolved, nil
+		}
+	}
+	data, err := snapshot.Encode()
+	if err != nil {
+		return nil, errors.Wrap(err, "failed to encode the package graph")
+	}
+	if err := daemonClient.StorePackageGraph(ctx, key, data); err != nil {
+		r.base.Logger.Debug("failed to store the package graph in the daemon", "error", err)
+	}
+	return resolved, nil
+}
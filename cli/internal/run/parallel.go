@@ -0,0 +1,162 @@
+package run
+
+import (
+	gocontext "context"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// ignoredDependency is a dependency of a task, from "dependsOn", that --parallel doesn't
+// wait for
+type ignoredDependency struct {
+	Task       string `json:"task"`
+	Dependency string `json:"dependency"`
+}
+
+func (d ignoredDependency) String() string {
+	return fmt.Sprintf("%v -> %v", d.Task, d.Dependency)
+}
+
+// withoutPackageDependencies returns a copy of the package graph in which packages only
+// depend on the root, which is what --parallel runs tasks with
+func withoutPackageDependencies(graph *dag.AcyclicGraph, rootNode string) *dag.AcyclicGraph {
+	parallel := &dag.AcyclicGraph{}
+	for _, v := range graph.Vertices() {
+		parallel.Add(v)
+	}
+	for _, edge := range graph.Edges() {
+		if edge.Target() == rootNode {
+			parallel.Connect(edge)
+		}
+	}
+	return parallel
+}
+
+// ignoredDependencies returns the dependencies between tasks in taskGraph that
+// parallelTaskGraph, built from the same pipeline for --parallel, doesn't have
+func ignoredDependencies(taskGraph *dag.AcyclicGraph, parallelTaskGraph *dag.AcyclicGraph) []ignoredDependency {
+	ignored := []ignoredDependency{}
+	for _, edge := range taskGraph.Edges() {
+		task, dependency := dag.VertexName(edge.Source()), dag.VertexName(edge.Target())
+		if dependency == core.ROOT_NODE_NAME {
+			continue
+		}
+		if parallelTaskGraph.HasVertex(task) && parallelTaskGraph.DownEdges(task).Include(dependency) {
+			continue
+		}
+		ignored = append(ignored, ignoredDependency{Task: task, Dependency: dependency})
+	}
+	sort.Slice(ignored, func(i, j int) bool {
+		if ignored[i].Task != ignored[j].Task {
+			return ignored[i].Task < ignored[j].Task
+		}
+		return ignored[i].Dependency < ignored[j].Dependency
+	})
+	return ignored
+}
+
+// warnIgnoredDependencies warns about each dependency that --parallel doesn't wait for
+// in a task that runs
+func (r *run) warnIgnoredDependencies(ignored []ignoredDependency) {
+	for _, d := range ignored {
+		r.base.Logger.Warn("--parallel ignores a dependency", "task", d.Task, "dependency", d.Dependency)
+		r.logWarning("--parallel", fmt.Errorf("%v doesn't wait for %v", d.Task, d.Dependency))
+	}
+}
+
+// formatIgnoredDependencies describes the dependencies --parallel ignored, for the run
+// summary
+func formatIgnoredDependencies(ignored []ignoredDependency, safe bool) string {
+	noun := "dependencies"
+	if len(ignored) == 1 {
+		noun = "dependency"
+	}
+	deps := make([]string, len(ignored))
+	for i, d := range ignored {
+		deps[i] = d.String()
+	}
+	if safe {
+		return fmt.Sprintf("%v %v restored from cache ahead of the run (--parallel=safe): %v", len(ignored), noun, strings.Join(deps, ", "))
+	}
+	return fmt.Sprintf("%v %v (--parallel): %v", len(ignored), noun, strings.Join(deps, ", "))
+}
+
+// parallelizeCachedDependencies is what --parallel=safe does instead of ignoring dependencies
+// outright. It restores the outputs of the dependencies that --parallel would ignore from the
+// cache ahead of the run, and removes them from the task graph, so that the tasks that
+// depend on them can start without waiting for anything they depend on in turn. The
+// dependencies that aren't in the cache are kept. It returns the dependencies it removed,
+// and the dependencies of each task from before, which tasks must still be hashed with.
+func parallelizeCachedDependencies(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, taskHashes *taskhash.Tracker, ec *execContext, ignored []ignoredDependency) ([]ignoredDependency, map[string]dag.Set) {
+	taskDeps := make(map[string]dag.Set)
+	for _, v := range engine.TaskGraph.Vertices() {
+		deps := make(dag.Set)
+		for _, dep := range engine.TaskGraph.DownEdges(v) {
+			deps.Add(dep)
+		}
+		taskDeps[dag.VertexName(v)] = deps
+	}
+	dependencies := make(util.Set)
+	for _, d := range ignored {
+		dependencies.Add(d.Dependency)
+	}
+
+	// The tasks in the graph are hashed in order, like they are for --prefetch, since a
+	// dependency's hash needs the hashes of what it depends on
+	restored := make(util.Set)
+	mu := sync.Mutex{}
+	errs := engine.Execute(g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
+		if packageTask.TaskDefinition.HashDependencyOutputs {
+			return fmt.Errorf("%v hashes its dependencies by outputs", packageTask.TaskID)
+		}
+		if len(packageTask.TaskDefinition.EnvInputsFrom) > 0 {
+			return fmt.Errorf("%v takes env inputs from other tasks", packageTask.TaskID)
+		}
+		deps := taskDeps[packageTask.TaskID]
+		if !dependencies.Includes(packageTask.TaskID) {
+			_, err := taskHashes.CalculateTaskHash(packageTask, deps, rs.ArgsForTask(packageTask.Task))
+			return err
+		}
+		// A dependency that misses the cache is left for the run to execute, which picks
+		// up where restoring it left off
+		hit, err := ec.restore(ctx, packageTask, deps)
+		if err != nil {
+			return err
+		}
+		if hit {
+			mu.Lock()
+			restored.Add(packageTask.TaskID)
+			mu.Unlock()
+		}
+		return nil
+	}), core.ExecOpts{
+		Concurrency: rs.Opts.runOpts.restoreConcurrency,
+	})
+	for _, err := range errs {
+		ec.logger.Debug("dependencies not restored ahead of the run", "error", err)
+	}
+
+	parallelized := []ignoredDependency{}
+	remove := make(util.Set)
+	for _, d := range ignored {
+		if restored.Includes(d.Dependency) {
+			remove.Add(d)
+			parallelized = append(parallelized, d)
+		}
+	}
+	for _, edge := range engine.TaskGraph.Edges() {
+		d := ignoredDependency{Task: dag.VertexName(edge.Source()), Dependency: dag.VertexName(edge.Target())}
+		if remove.Includes(d) {
+			engine.TaskGraph.RemoveEdge(edge)
+		}
+	}
+	return parallelized, taskDeps
+}
@@ -0,0 +1,66 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func TestParallelValue(t *testing.T) {
+	opts := &runOpts{}
+	value := &parallelValue{opts: opts}
+	assert.Equal(t, "", value.String())
+	assert.NoError(t, value.Set(_parallelNoValue))
+	assert.True(t, opts.parallel)
+	assert.False(t, opts.parallelSafe)
+	assert.Equal(t, "true", value.String())
+	assert.NoError(t, value.Set("safe"))
+	assert.True(t, opts.parallel)
+	assert.True(t, opts.parallelSafe)
+	assert.Equal(t, "safe", value.String())
+	assert.NoError(t, value.Set("false"))
+	assert.False(t, opts.parallel)
+	assert.Error(t, value.Set("unsafe"))
+}
+
+func TestIgnoredDependencies(t *testing.T) {
+	// web depends on ui, which depends on nothing but the root
+	topoGraph := &dag.AcyclicGraph{}
+	for _, pkg := range []string{core.ROOT_NODE_NAME, "web", "ui"} {
+		topoGraph.Add(pkg)
+	}
+	topoGraph.Connect(dag.BasicEdge("web", "ui"))
+	topoGraph.Connect(dag.BasicEdge("ui", core.ROOT_NODE_NAME))
+
+	pipeline := fs.Pipeline{
+		"build": {
+			TopologicalDependencies: []string{"build"},
+			TaskDependencies:        []string{"codegen"},
+		},
+		"codegen": {},
+		"web#lint": {
+			TaskDependencies: []string{"ui#build"},
+		},
+	}
+	filteredPkgs := util.SetFromStrings([]string{"web"})
+	rs := &runSpec{
+		FilteredPkgs: filteredPkgs,
+		Targets:      []string{"build", "lint"},
+		Opts:         &Opts{},
+	}
+	engine, err := buildTaskGraph(topoGraph, pipeline, rs)
+	assert.NoError(t, err)
+	parallelEngine, err := buildTaskGraph(withoutPackageDependencies(topoGraph, core.ROOT_NODE_NAME), pipeline, rs)
+	assert.NoError(t, err)
+	// The package graph that --parallel starts from is a copy
+	assert.True(t, topoGraph.DownEdges("web").Include("ui"))
+
+	// Dependencies within a package, and on specific tasks of other packages, are kept
+	ignored := ignoredDependencies(engine.TaskGraph, parallelEngine.TaskGraph)
+	assert.Equal(t, []ignoredDependency{{Task: "web#build", Dependency: "ui#build"}}, ignored)
+	assert.Equal(t, "1 dependency (--parallel): web#build -> ui#build", formatIgnoredDependencies(ignored, false))
+}
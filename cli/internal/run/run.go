@@ -4,11 +4,13 @@ import (
 	gocontext "context"
 	"encoding/json"
 	"fmt"
+	"io"
 	"log"
 	"os"
 	"os/exec"
 	"path/filepath"
 	"regexp"
+	"runtime"
 	"sort"
 	"strings"
 	"sync"
@@ -20,19 +22,26 @@ import (
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/analytics"
 	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/chrometracing"
+	"github.com/vercel/turborepo/cli/internal/client"
+	"github.com/vercel/turborepo/cli/internal/codeowners"
 	"github.com/vercel/turborepo/cli/internal/cmdutil"
 	"github.com/vercel/turborepo/cli/internal/colorcache"
 	"github.com/vercel/turborepo/cli/internal/context"
 	"github.com/vercel/turborepo/cli/internal/core"
 	"github.com/vercel/turborepo/cli/internal/daemon"
 	"github.com/vercel/turborepo/cli/internal/daemonclient"
+	"github.com/vercel/turborepo/cli/internal/env"
 	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/graphvisualizer"
+	"github.com/vercel/turborepo/cli/internal/inference"
+	"github.com/vercel/turborepo/cli/internal/logsink"
 	"github.com/vercel/turborepo/cli/internal/logstreamer"
 	"github.com/vercel/turborepo/cli/internal/nodes"
 	"github.com/vercel/turborepo/cli/internal/packagemanager"
 	"github.com/vercel/turborepo/cli/internal/process"
 	"github.com/vercel/turborepo/cli/internal/runcache"
+	"github.com/vercel/turborepo/cli/internal/runevents"
 	"github.com/vercel/turborepo/cli/internal/scm"
 	"github.com/vercel/turborepo/cli/internal/scope"
 	"github.com/vercel/turborepo/cli/internal/signals"
@@ -56,6 +65,17 @@ type completeGraph struct {
 	PackageInfos     map[interface{}]*fs.PackageJSON
 	GlobalHash       string
 	RootNode         string
+	Frameworks       *inference.Registry
+	// TypesOnlyDependencies maps a workspace to the workspace dependencies it only consumes types from
+	TypesOnlyDependencies map[string]util.Set
+	// InjectedDependencies maps a workspace to the workspace dependencies installed into it as copies
+	InjectedDependencies map[string]util.Set
+	// Boundaries are the rules for imports between workspaces, if turbo.json checks them
+	Boundaries *fs.BoundariesConfig
+	// NormalizeLineEndings hashes text inputs as if their line endings were LF
+	NormalizeLineEndings bool
+	// RunHooks are notified when the run finishes
+	RunHooks []fs.RunHook
 }
 
 // runSpec contains the run-specific configuration elements that come from a particular
@@ -120,9 +140,40 @@ func GetCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Comma
 	}
 	flags = cmd.Flags()
 	opts = optsFromFlags(flags)
+	if err := cmd.RegisterFlagCompletionFunc("filter", func(cmd *cobra.Command, args []string, toComplete string) ([]string, cobra.ShellCompDirective) {
+		return completeWorkspaceNames(helper, cmd.Flags(), opts, toComplete)
+	}); err != nil {
+		// Fail fast if we have misconfigured our flags
+		panic(err)
+	}
 	return cmd
 }
 
+// completeWorkspaceNames lists the workspaces in the package graph as completions for --filter.
+// Directory filters, and repos where we can't build the graph, fall back to the shell's default
+// file completion.
+func completeWorkspaceNames(helper *cmdutil.Helper, flags *pflag.FlagSet, opts *Opts, toComplete string) ([]string, cobra.ShellCompDirective) {
+	if strings.HasPrefix(toComplete, ".") {
+		return nil, cobra.ShellCompDirectiveDefault
+	}
+	base, err := helper.GetCmdBase(flags)
+	if err != nil {
+		return nil, cobra.ShellCompDirectiveDefault
+	}
+	rootPackageJSON, err := fs.ReadPackageJSON(base.RepoRoot.Join("package.json"))
+	if err != nil {
+		return nil, cobra.ShellCompDirectiveDefault
+	}
+	pkgDepGraph, err := context.New(context.WithGraph(base.RepoRoot, rootPackageJSON, opts.cacheOpts.ResolveCacheDir(base.RepoRoot), base.RepoConfig.PackageManager()))
+	if err != nil {
+		return nil, cobra.ShellCompDirectiveDefault
+	}
+	names := make([]string, len(pkgDepGraph.PackageNames))
+	copy(names, pkgDepGraph.PackageNames)
+	sort.Strings(names)
+	return names, cobra.ShellCompDirectiveNoFileComp
+}
+
 func parseTasksAndPassthroughArgs(remainingArgs []string, flags *pflag.FlagSet) ([]string, []string) {
 	if argSplit := flags.ArgsLenAtDash(); argSplit != -1 {
 		return remainingArgs[:argSplit], remainingArgs[argSplit:]
@@ -155,6 +206,29 @@ func configureRun(base *cmdutil.CmdBase, opts *Opts, signalWatcher *signals.Watc
 		opts.cacheOpts.SkipFilesystem = true
 	}
 
+	if opts.runOpts.runLock == "" {
+		if mode := os.Getenv("TURBO_RUN_LOCK"); mode == _runLockWait || mode == _runLockFail {
+			opts.runOpts.runLock = mode
+		}
+	}
+
+	// Flags and environment variables take precedence over the repo and global config files
+	if opts.cacheOpts.OverrideDir == "" {
+		opts.cacheOpts.OverrideDir = base.RepoConfig.CacheDir()
+	}
+	if opts.cacheOpts.LocalStore == "" {
+		opts.cacheOpts.LocalStore = base.RepoConfig.CacheStore()
+	}
+	if opts.runcacheOpts.TaskOutputModeOverride == nil {
+		if mode := base.RepoConfig.OutputLogs(); mode != "" {
+			if outputMode, err := util.FromTaskOutputModeString(mode); err == nil {
+				opts.runcacheOpts.TaskOutputModeOverride = &outputMode
+			} else {
+				base.UI.Warn(fmt.Sprintf("ignoring outputLogs %q from config: %v", mode, err))
+			}
+		}
+	}
+
 	processes := process.NewManager(base.Logger.Named("processes"))
 	signalWatcher.AddOnClose(processes.Close)
 	return &run{
@@ -168,10 +242,27 @@ type run struct {
 	base      *cmdutil.CmdBase
 	opts      *Opts
 	processes *process.Manager
+	// concurrencyChanges delivers the concurrencies read from the control file once
+	// tasks start executing
+	concurrencyChanges <-chan int
 }
 
 func (r *run) run(ctx gocontext.Context, targets []string) error {
 	startAt := time.Now()
+	// Watch for _concurrencySignal before doing anything else, so that receiving it while
+	// the package graph is built or hashes are calculated doesn't fall back to the signal's
+	// default action, killing turbo. A change is applied once tasks start executing.
+	concurrencyChanges, stopWatching := watchConcurrencyControl(r.base.RepoRoot, func(concurrency int) {
+		if r.opts.runOpts.parallel {
+			r.logWarning("", fmt.Errorf("ignoring the change of concurrency to %v, --parallel runs don't limit concurrency", concurrency))
+			return
+		}
+		r.base.UI.Output(fmt.Sprintf("• Concurrency changed to %v", concurrency))
+	}, func(err error) {
+		r.logWarning("", errors.Wrap(err, "failed to change concurrency"))
+	})
+	defer stopWatching()
+	r.concurrencyChanges = concurrencyChanges
 	packageJSONPath := r.base.RepoRoot.Join("package.json")
 	rootPackageJSON, err := fs.ReadPackageJSON(packageJSONPath)
 	if err != nil {
@@ -183,10 +274,7 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 	}
 	// TODO: these values come from a config file, hopefully viper can help us merge these
 	r.opts.cacheOpts.RemoteCacheOpts = turboJSON.RemoteCacheOptions
-	pkgDepGraph, err := context.New(context.WithGraph(r.base.RepoRoot, rootPackageJSON, r.opts.cacheOpts.ResolveCacheDir(r.base.RepoRoot)))
-	if err != nil {
-		return err
-	}
+	r.opts.runcacheOpts.AllowedExternalOutputs = turboJSON.AllowedExternalOutputs
 	if ui.IsCI && !r.opts.runOpts.noDaemon {
 		r.base.Logger.Info("skipping turbod since we appear to be in a non-interactive context")
 	} else if !r.opts.runOpts.noDaemon {
@@ -198,8 +286,14 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 			r.base.Logger.Debug("running in daemon mode")
 			daemonClient := daemonclient.New(turbodClient)
 			r.opts.runcacheOpts.OutputWatcher = daemonClient
+			r.opts.runOpts.daemonClient = daemonClient
 		}
 	}
+	pkgDepGraph, err := r.buildPackageGraph(ctx, rootPackageJSON, turboJSON.ExternalRepos)
+	if err != nil {
+		return err
+	}
+	r.opts.runcacheOpts.ExternalRepoRoots = pkgDepGraph.ExternalRepoRoots
 
 	if err := util.ValidateGraph(&pkgDepGraph.TopologicalGraph); err != nil {
 		return errors.Wrap(err, "Invalid package dependency graph")
@@ -209,6 +303,10 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 	if err := validateTasks(pipeline, targets); err != nil {
 		return err
 	}
+	frameworks, err := inference.NewRegistry(turboJSON.Frameworks)
+	if err != nil {
+		return errors.Wrap(err, "invalid frameworks configuration")
+	}
 
 	scmInstance, err := scm.FromInRepo(r.base.RepoRoot.ToStringDuringMigration())
 	if err != nil {
@@ -218,7 +316,13 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 			return errors.Wrap(err, "failed to create SCM")
 		}
 	}
-	filteredPkgs, isAllPackages, err := scope.ResolvePackages(&r.opts.scopeOpts, r.base.RepoRoot.ToStringDuringMigration(), scmInstance, pkgDepGraph, r.base.UI, r.base.Logger)
+	// The repo root may have been given with --cwd, in which case the directory turbo was run
+	// from is still where relative directory filters should be resolved from
+	invocationDir, err := fs.GetCwd()
+	if err != nil {
+		invocationDir = r.base.RepoRoot
+	}
+	filteredPkgs, isAllPackages, err := scope.ResolvePackages(&r.opts.scopeOpts, r.base.RepoRoot.ToStringDuringMigration(), invocationDir.ToStringDuringMigration(), scmInstance, pkgDepGraph, r.base.UI, r.base.Logger)
 	if err != nil {
 		return errors.Wrap(err, "failed to resolve packages to run")
 	}
@@ -233,21 +337,33 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 			}
 		}
 	}
-	globalHash, err := calculateGlobalHash(
+	globalHash, skippedGlobalDeps, err := calculateGlobalHash(
 		r.base.RepoRoot,
 		rootPackageJSON,
 		pipeline,
 		turboJSON.GlobalEnv,
 		turboJSON.GlobalDeps,
 		pkgDepGraph.PackageManager,
+		turboJSON.CacheNamespace,
 		r.base.Logger,
 		os.Environ(),
+		r.opts.runcacheOpts.NonUTF8Paths,
+		turboJSON.NormalizeLineEndings,
 	)
 	if err != nil {
 		return fmt.Errorf("failed to calculate global hash: %v", err)
 	}
+	r.warnSkippedPaths(skippedGlobalDeps)
 	r.base.Logger.Debug("global hash", "value", globalHash)
 	r.base.Logger.Debug("local cache folder", "path", r.opts.cacheOpts.OverrideDir)
+	// Env file names are resolved after the global hash, so that switching modes only
+	// affects the tasks whose env files depend on the mode
+	for taskName, taskDefinition := range pipeline {
+		if len(taskDefinition.EnvFiles) > 0 {
+			taskDefinition.EnvFiles = env.ExpandEnvFiles(taskDefinition.EnvFiles, r.opts.runOpts.envMode)
+			pipeline[taskName] = taskDefinition
+		}
+	}
 
 	// TODO: consolidate some of these arguments
 	g := &completeGraph{
@@ -256,6 +372,13 @@ func (r *run) run(ctx gocontext.Context, targets []string) error {
 		PackageInfos:     pkgDepGraph.PackageInfos,
 		GlobalHash:       globalHash,
 		RootNode:         pkgDepGraph.RootNode,
+		Frameworks:       frameworks,
+		// Edges to these dependencies still order tasks, but only their types affect hashes
+		TypesOnlyDependencies: pkgDepGraph.TypesOnlyDependencies,
+		InjectedDependencies:  pkgDepGraph.InjectedDependencies,
+		Boundaries:            turboJSON.Boundaries,
+		NormalizeLineEndings:  turboJSON.NormalizeLineEndings,
+		RunHooks:              turboJSON.RunHooks,
 	}
 	rs := &runSpec{
 		Targets:      targets,
@@ -276,27 +399,112 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 	if err != nil {
 		return errors.Wrap(err, "error preparing engine")
 	}
-	tracker := taskhash.NewTracker(g.RootNode, g.GlobalHash, g.Pipeline, g.PackageInfos)
-	err = tracker.CalculateFileHashes(engine.TaskGraph.Vertices(), rs.Opts.runOpts.concurrency, r.base.RepoRoot)
+	// Dependents are found before --parallel removes the edges to them, since they can
+	// still read the outputs of the skipped tasks
+	var skipped *skippedTasks
+	if len(rs.Opts.runOpts.skip) > 0 {
+		skipped, err = newSkippedTasks(engine.TaskGraph, rs.Opts.runOpts.skip)
+		if err != nil {
+			return err
+		}
+	}
+	tracker := taskhash.NewTracker(&taskhash.TrackerOptions{
+		RootNode:              g.RootNode,
+		GlobalHash:            g.GlobalHash,
+		Pipeline:              g.Pipeline,
+		PackageInfos:          g.PackageInfos,
+		Frameworks:            g.Frameworks,
+		TypesOnlyDependencies: g.TypesOnlyDependencies,
+		InjectedDependencies:  g.InjectedDependencies,
+		NonUTF8Paths:          rs.Opts.runcacheOpts.NonUTF8Paths,
+		NormalizeLineEndings:  g.NormalizeLineEndings,
+	})
+	hashConcurrency := rs.Opts.runOpts.hashConcurrency
+	if hashConcurrency == 0 {
+		hashConcurrency = rs.Opts.runOpts.concurrency
+	}
+	if rs.Opts.runOpts.inputsReport {
+		tracker.RecordInputSizes()
+	}
+	err = tracker.CalculateFileHashes(engine.TaskGraph.Vertices(), hashConcurrency, r.base.RepoRoot)
 	if err != nil {
 		return errors.Wrap(err, "error hashing package files")
 	}
+	r.warnSkippedPaths(tracker.SkippedPaths())
+	if rs.Opts.runOpts.inputsReport {
+		r.base.UI.Output("")
+		r.base.UI.Info(util.Sprintf("${CYAN}${BOLD}Package Inputs${RESET}"))
+		printInputSizes(os.Stdout, tracker.InputSizes())
+		r.base.UI.Output("")
+	}
 
-	// If we are running in parallel, then we remove all the edges in the graph
-	// except for the root. Rebuild the task graph for backwards compatibility.
+	// If we are running in parallel, then we remove all the edges in the package
+	// graph except for the root. Rebuild the task graph for backwards compatibility.
 	// We still use dependencies specified by the pipeline configuration.
+	var ignored []ignoredDependency
 	if rs.Opts.runOpts.parallel {
-		for _, edge := range g.TopologicalGraph.Edges() {
-			if edge.Target() != g.RootNode {
-				g.TopologicalGraph.RemoveEdge(edge)
+		parallelEngine, err := buildTaskGraph(withoutPackageDependencies(&g.TopologicalGraph, g.RootNode), g.Pipeline, rs)
+		if err != nil {
+			return errors.Wrap(err, "error preparing engine")
+		}
+		ignored = ignoredDependencies(engine.TaskGraph, parallelEngine.TaskGraph)
+		// With --parallel=safe, which dependencies can be ignored depends on the cache,
+		// so the run starts from the whole task graph
+		if !rs.Opts.runOpts.parallelSafe {
+			engine = parallelEngine
+			// Only the dependencies of tasks that run are worth warning about
+			running := []ignoredDependency{}
+			for _, d := range ignored {
+				if engine.TaskGraph.HasVertex(d.Task) {
+					running = append(running, d)
+				}
 			}
+			ignored = running
+			r.warnIgnoredDependencies(ignored)
+		}
+	}
+
+	if rs.Opts.runOpts.fromTaskGraph != "" {
+		imported, err := readTaskGraph(fs.ResolveUnknownPath(r.base.RepoRoot, rs.Opts.runOpts.fromTaskGraph))
+		if err != nil {
+			return errors.Wrap(err, "failed to read task graph")
 		}
-		engine, err = buildTaskGraph(&g.TopologicalGraph, g.Pipeline, rs)
+		current, err := resolveTaskGraph(ctx, engine, g, tracker, rs)
 		if err != nil {
-			return errors.Wrap(err, "error preparing engine")
+			return err
+		}
+		taskIDs, err := checkImportedTaskGraph(imported, current)
+		if err != nil {
+			return err
+		}
+		if err := pruneTaskGraph(engine.TaskGraph, taskIDs); err != nil {
+			return errors.Wrap(err, "failed to restrict task graph")
 		}
 	}
 
+	if rs.Opts.runOpts.shardCount > 0 {
+		taskIDs := shardTasks(engine.TaskGraph, rs.Opts.runOpts.shardIndex, rs.Opts.runOpts.shardCount)
+		if err := pruneTaskGraph(engine.TaskGraph, taskIDs); err != nil {
+			return errors.Wrap(err, "failed to restrict task graph to shard")
+		}
+	}
+
+	if rs.Opts.runOpts.exportTaskGraph != "" {
+		exported, err := resolveTaskGraph(ctx, engine, g, tracker, rs)
+		if err != nil {
+			return err
+		}
+		if err := writeTaskGraph(fs.ResolveUnknownPath(r.base.RepoRoot, rs.Opts.runOpts.exportTaskGraph), exported); err != nil {
+			return errors.Wrap(err, "failed to write task graph")
+		}
+		r.base.UI.Output(fmt.Sprintf("Wrote %v tasks to %v", len(exported.Tasks), rs.Opts.runOpts.exportTaskGraph))
+		return nil
+	}
+
+	if rs.Opts.runOpts.warmCache {
+		return r.warmCache(ctx, g, rs, engine, tracker)
+	}
+
 	if rs.Opts.runOpts.graphFile != "" || rs.Opts.runOpts.graphDot {
 		visualizer := graphvisualizer.New(r.base.RepoRoot, r.base.UI, engine.TaskGraph)
 
@@ -315,12 +523,19 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		}
 		packagesInScope := rs.FilteredPkgs.UnsafeListOfStrings()
 		sort.Strings(packagesInScope)
+		codeOwners, err := codeowners.Read(r.base.RepoRoot)
+		if err != nil {
+			return errors.Wrap(err, "failed to read CODEOWNERS")
+		}
+		owners := packageOwners(codeOwners, g.PackageInfos, packagesInScope)
 		if rs.Opts.runOpts.dryRunJSON {
 			dryRun := &struct {
-				Packages []string     `json:"packages"`
-				Tasks    []hashedTask `json:"tasks"`
+				Packages []string            `json:"packages"`
+				Owners   map[string][]string `json:"owners,omitempty"`
+				Tasks    []hashedTask        `json:"tasks"`
 			}{
 				Packages: packagesInScope,
+				Owners:   owners,
 				Tasks:    tasksRun,
 			}
 			bytes, err := json.MarshalIndent(dryRun, "", "  ")
@@ -328,13 +543,23 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 				return errors.Wrap(err, "failed to render JSON")
 			}
 			r.base.UI.Output(string(bytes))
+		} else if rs.Opts.runOpts.dryRunTree {
+			r.base.UI.Output("")
+			r.base.UI.Output(util.Sprintf("%s", formatDryRunTree(packagesInScope, tasksRun)))
 		} else {
 			r.base.UI.Output("")
 			r.base.UI.Info(util.Sprintf("${CYAN}${BOLD}Packages in Scope${RESET}"))
 			p := tabwriter.NewWriter(os.Stdout, 0, 0, 1, ' ', 0)
-			fmt.Fprintln(p, "Name\tPath\t")
-			for _, pkg := range packagesInScope {
-				fmt.Fprintf(p, "%s\t%s\t\n", pkg, g.PackageInfos[pkg].Dir)
+			if owners != nil {
+				fmt.Fprintln(p, "Name\tPath\tOwners\t")
+				for _, pkg := range packagesInScope {
+					fmt.Fprintf(p, "%s\t%s\t%s\t\n", pkg, g.PackageInfos[pkg].Dir, strings.Join(owners[pkg], " "))
+				}
+			} else {
+				fmt.Fprintln(p, "Name\tPath\t")
+				for _, pkg := range packagesInScope {
+					fmt.Fprintf(p, "%s\t%s\t\n", pkg, g.PackageInfos[pkg].Dir)
+				}
 			}
 			p.Flush()
 
@@ -347,21 +572,36 @@ func (r *run) runOperation(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Task\t=\t%s\t${RESET}", task.Task))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Package\t=\t%s\t${RESET}", task.Package))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Hash\t=\t%s\t${RESET}", task.Hash))
+				fmt.Fprintln(w, util.Sprintf("  ${GREY}Cached (Local)\t=\t%v\t${RESET}", task.CachedLocally))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Directory\t=\t%s\t${RESET}", task.Dir))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Command\t=\t%s\t${RESET}", task.Command))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Outputs\t=\t%s\t${RESET}", strings.Join(task.Outputs, ", ")))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Log File\t=\t%s\t${RESET}", task.LogFile))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Dependencies\t=\t%s\t${RESET}", strings.Join(task.Dependencies, ", ")))
 				fmt.Fprintln(w, util.Sprintf("  ${GREY}Dependendents\t=\t%s\t${RESET}", strings.Join(task.Dependents, ", ")))
+				fmt.Fprintln(w, util.Sprintf("  ${GREY}Framework\t=\t%s\t${RESET}", task.Framework))
 				w.Flush()
 			}
 		}
 	} else {
 		packagesInScope := rs.FilteredPkgs.UnsafeListOfStrings()
 		sort.Strings(packagesInScope)
+		if err := checkInteractiveTasks(engine.TaskGraph, g.Pipeline); err != nil {
+			return err
+		}
+		if err := r.checkBoundaries(g, engine.TaskGraph); err != nil {
+			return err
+		}
 		r.base.UI.Output(fmt.Sprintf(ui.Dim("• Packages in scope: %v"), strings.Join(packagesInScope, ", ")))
+		codeOwners, err := codeowners.Read(r.base.RepoRoot)
+		if err != nil {
+			return errors.Wrap(err, "failed to read CODEOWNERS")
+		}
+		if owners := formatOwners(packageOwners(codeOwners, g.PackageInfos, packagesInScope), packagesInScope); owners != "" {
+			r.base.UI.Output(fmt.Sprintf(ui.Dim("• Owners: %v"), owners))
+		}
 		r.base.UI.Output(fmt.Sprintf("%s %s %s", ui.Dim("• Running"), ui.Dim(ui.Bold(strings.Join(rs.Targets, ", "))), ui.Dim(fmt.Sprintf("in %v packages", rs.FilteredPkgs.Len()))))
-		return r.executeTasks(ctx, g, rs, engine, packageManager, tracker, startAt)
+		return r.executeTasks(ctx, g, rs, engine, packageManager, tracker, ignored, skipped, startAt)
 	}
 	return nil
 }
@@ -421,8 +661,17 @@ type runOpts struct {
 	dotGraph string
 	// Force execution to be serially one-at-a-time
 	concurrency int
+	// The number of tasks whose outputs can be restored from the cache at once
+	restoreConcurrency int
+	// The number of packages whose inputs are hashed at once. 0 uses concurrency.
+	hashConcurrency int
+	// Whether to print how large the inputs of each package are
+	inputsReport bool
 	// Whether to execute in parallel (defaults to false)
 	parallel bool
+	// Whether to only ignore the dependencies of tasks that are restored from the
+	// cache when executing in parallel
+	parallelSafe bool
 	// Whether to emit a perf profile
 	profile string
 	// If true, continue task executions even if a task fails.
@@ -433,26 +682,115 @@ type runOpts struct {
 	// Dry run flags
 	dryRun     bool
 	dryRunJSON bool
+	dryRunTree bool
 	// Graph flags
 	graphDot  bool
 	graphFile string
 	noDaemon  bool
+	// Whether to download remote artifacts for tasks ahead of their execution
+	prefetch bool
+	// Whether to run the tasks expected to take longest first
+	scheduleByDuration bool
+	// Arbitrary key/value pairs recorded in the run summary and analytics events
+	labels map[string]string
+	// Key/value pairs attached to the artifacts this run uploads to the remote cache
+	artifactTags map[string]string
+	// File to write the resolved task graph to, instead of running it
+	exportTaskGraph string
+	// File with a previously exported task graph to restrict execution to
+	fromTaskGraph string
+	// Which of shardCount shards of the run to execute, starting at 1. A shardCount
+	// of 0 runs everything.
+	shardIndex int
+	shardCount int
+	// Tasks to leave out of the run, as <package>#<task>. The tasks that depend on them
+	// still run, but their outputs aren't cached.
+	skip []string
+	// The daemon to report run statistics to, if we are connected to one
+	daemonClient *daemonclient.DaemonClient
+	// Whether to resolve the package graph even when the daemon has it, and compare the two
+	verifyPackageGraph bool
+	// Substituted for {mode} in the names of tasks' env files
+	envMode string
+	// Whether to download the remote artifacts of the tasks into the local cache instead
+	// of running them
+	warmCache bool
+	// What to do when another run in the repository holds the run lock: wait for it or
+	// fail. Runs don't take the lock when this is empty.
+	runLock string
+	// Where to stream the output of tasks to, in addition to the terminal
+	logSinks []string
+	// The file to write the run's events to, one JSON object per line
+	eventLog string
+	// The file to write an HTML report of the run to
+	htmlReport string
 }
 
 var (
 	_profileHelp = `File to write turbo's performance profile output into.
-You can load the file up in chrome://tracing to see
+You can load the file up in chrome://tracing or Perfetto to see
 which parts of your build were slow.`
 	_continueHelp = `Continue execution even if a task exits with an error
 or non-zero exit code. The default behavior is to bail`
 	_dryRunHelp = `List the packages in scope and the tasks that would be run,
 but don't actually run them. Passing --dry=json or
---dry-run=json will render the output in JSON format.`
+--dry-run=json will render the output in JSON format, and
+--dry=tree will render tasks grouped by package.`
 	_graphHelp = `Generate a graph of the task execution and output to a file when a filename is specified (.svg, .png, .jpg, .pdf, .json, .html).
 Outputs dot graph to stdout when if no filename is provided`
-	_concurrencyHelp = `Limit the concurrency of task execution. Use 1 for serial (i.e. one-at-a-time) execution.`
-	_parallelHelp    = `Execute all tasks in parallel.`
-	_onlyHelp        = `Run only the specified tasks, not their dependencies.`
+	_concurrencyHelp        = `Limit the concurrency of task execution. Use 1 for serial (i.e. one-at-a-time) execution.`
+	_restoreConcurrencyHelp = `Limit how many tasks can restore their outputs from the
+cache at once. Restoring doesn't count towards --concurrency.`
+	_hashConcurrencyHelp = `Limit how many packages can have their input files hashed
+at once. Defaults to --concurrency.`
+	_inputsReportHelp = `Print how many input files each package has and how large
+they are, largest first, after hashing them.`
+	_parallelHelp = `Execute all tasks in parallel, without waiting for the tasks
+they depend on in other packages. With --parallel=safe,
+only skip waiting for dependencies that are restored from
+the cache.`
+	_onlyHelp     = `Run only the specified tasks, not their dependencies.`
+	_prefetchHelp = `Start downloading remote cache artifacts for tasks as soon
+as their hashes are known, rather than when they are run.`
+	_scheduleByDurationHelp = `When more tasks are ready to run than there are free slots,
+run the ones expected to take longest first, based on how
+long they took in previous runs.`
+	_labelHelp = `Attach a key=value label to this run. Labels are shown in
+the run summary and sent along with cache analytics events.
+Can be passed multiple times.`
+	_artifactTagHelp = `Attach a key=value tag to the artifacts this run uploads
+to the remote cache, such as the commit or branch, so
+they can be found later with "turbo artifacts".
+Can be passed multiple times.`
+	_exportTaskGraphHelp = `Write the resolved task graph, including task hashes,
+commands and dependencies, to the given JSON file
+instead of running it.`
+	_fromTaskGraphHelp = `Only run the tasks listed in a task graph previously
+written by --export-task-graph, along with their
+dependencies. Fails if any task's hash has changed.`
+	_shardHelp = `Split the tasks that nothing else depends on into <count>
+shards, and only run the ones in shard <index>, along with
+their dependencies. For example, --shard=1/4.`
+	_envModeHelp = `Load the env files of tasks for the given mode, by
+replacing {mode} in their names. Env files that refer
+to {mode} are skipped when no mode is given.`
+	_runLockHelp = `Don't run tasks while another turbo run in this repository
+is running them. With "wait", wait for the other run to
+finish. With "fail", exit with an error instead.`
+	_logSinkHelp = `Also stream the output of the tasks that run to a file per
+task (file:<directory>), an HTTP endpoint that receives a
+POST request per task (http(s)://...), or a websocket
+(ws(s)://...). Can be passed multiple times.`
+	_eventLogHelp = `Write what happens during the run to the given file as it
+happens, one JSON event per line: tasks being scheduled,
+hashed, restored from cache or executed, and finishing.`
+	_skipHelp = `Don't run the given task, as <package>#<task>, for when it
+is broken and has to be bypassed. The tasks that depend on
+it still run, but their outputs aren't saved to the cache.
+Can be passed multiple times.`
+	_htmlReportHelp = `Write a report of the run to the given HTML file once it is
+done: a table of its tasks, a timeline, cache statistics
+and the hash of each task. It opens in any browser.`
 )
 
 func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string) {
@@ -464,11 +802,47 @@ func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string)
 			Value: &opts.concurrency,
 		},
 	})
-	flags.BoolVar(&opts.parallel, "parallel", false, _parallelHelp)
+	flags.AddFlag(&pflag.Flag{
+		Name:     "restore-concurrency",
+		Usage:    _restoreConcurrencyHelp,
+		DefValue: "32",
+		Value: &util.ConcurrencyValue{
+			Value: &opts.restoreConcurrency,
+		},
+	})
+	flags.AddFlag(&pflag.Flag{
+		Name:  "hash-concurrency",
+		Usage: _hashConcurrencyHelp,
+		Value: &util.ConcurrencyValue{
+			Value: &opts.hashConcurrency,
+		},
+	})
+	flags.AddFlag(&pflag.Flag{
+		Name:        "parallel",
+		Usage:       _parallelHelp,
+		DefValue:    "",
+		NoOptDefVal: _parallelNoValue,
+		Value:       &parallelValue{opts: opts},
+	})
 	flags.StringVar(&opts.profile, "profile", "", _profileHelp)
 	flags.BoolVar(&opts.continueOnError, "continue", false, _continueHelp)
 	flags.BoolVar(&opts.only, "only", false, _onlyHelp)
+	flags.BoolVar(&opts.prefetch, "prefetch", false, _prefetchHelp)
+	flags.BoolVar(&opts.inputsReport, "inputs-report", false, _inputsReportHelp)
+	flags.BoolVar(&opts.scheduleByDuration, "schedule-by-duration", false, _scheduleByDurationHelp)
+	flags.Var(&labelsValue{labels: &opts.labels}, "label", _labelHelp)
+	flags.Var(&labelsValue{labels: &opts.artifactTags}, "artifact-tag", _artifactTagHelp)
+	flags.StringVar(&opts.exportTaskGraph, "export-task-graph", "", _exportTaskGraphHelp)
+	flags.StringVar(&opts.fromTaskGraph, "from-task-graph", "", _fromTaskGraphHelp)
+	flags.Var(&shardValue{opts: opts}, "shard", _shardHelp)
+	flags.StringVar(&opts.envMode, "env-mode", "", _envModeHelp)
+	flags.Var(&runLockValue{opts: opts}, "run-lock", _runLockHelp)
+	flags.StringArrayVar(&opts.logSinks, "log-sink", nil, _logSinkHelp)
+	flags.StringVar(&opts.eventLog, "event-log", "", _eventLogHelp)
+	flags.StringVar(&opts.htmlReport, "html-report", "", _htmlReportHelp)
+	flags.StringArrayVar(&opts.skip, "skip", nil, _skipHelp)
 	flags.BoolVar(&opts.noDaemon, "no-daemon", false, "Run without using turbo's daemon process")
+	flags.BoolVar(&opts.verifyPackageGraph, "verify-package-graph", false, "Resolve the package graph even when the daemon has it from an earlier run, and warn if the two differ")
 	// This is a no-op flag, we don't need it anymore
 	flags.Bool("experimental-use-daemon", false, "Use the experimental turbo daemon")
 	// Daemon-related flags hidden for now, we can unhide when daemon is ready.
@@ -478,6 +852,9 @@ func addRunOpts(opts *runOpts, flags *pflag.FlagSet, aliases map[string]string)
 	if err := flags.MarkHidden("no-daemon"); err != nil {
 		panic(err)
 	}
+	if err := flags.MarkHidden("verify-package-graph"); err != nil {
+		panic(err)
+	}
 	if err := flags.MarkHidden("only"); err != nil {
 		// fail fast if we've messed up our flag configuration
 		panic(err)
@@ -541,12 +918,65 @@ func (d *graphValue) Type() string {
 	return ""
 }
 
+// parallel custom flag
+const (
+	_parallelText      = "true"
+	_parallelSafeText  = "safe"
+	_parallelSafeValue = "safe"
+	_parallelNoValue   = "true|safe"
+	_parallelTrueValue = "true"
+)
+
+// parallelValue implements a flag that can be treated as a boolean (--parallel)
+// or a string (--parallel=safe).
+type parallelValue struct {
+	opts *runOpts
+}
+
+var _ pflag.Value = &parallelValue{}
+
+func (p *parallelValue) String() string {
+	if p.opts.parallelSafe {
+		return _parallelSafeText
+	} else if p.opts.parallel {
+		return _parallelText
+	}
+	return ""
+}
+
+func (p *parallelValue) Set(value string) error {
+	switch value {
+	case _parallelNoValue, _parallelTrueValue:
+		// this case matches the NoOptDefValue, which is used when the flag
+		// is passed, but does not have a value (i.e. boolean flag)
+		p.opts.parallel = true
+		p.opts.parallelSafe = false
+	case _parallelSafeValue:
+		p.opts.parallel = true
+		p.opts.parallelSafe = true
+	case "false":
+		p.opts.parallel = false
+		p.opts.parallelSafe = false
+	default:
+		return fmt.Errorf("invalid parallel mode: %v", value)
+	}
+	return nil
+}
+
+// Type implements Value.Type, and is empty so that the usage
+// only shows the values of the flag.
+func (p *parallelValue) Type() string {
+	return ""
+}
+
 // dry run custom flag
 const (
 	_dryRunText      = "dry run"
 	_dryRunJSONText  = "json"
 	_dryRunJSONValue = "json"
-	_dryRunNoValue   = "text|json"
+	_dryRunTreeText  = "tree"
+	_dryRunTreeValue = "tree"
+	_dryRunNoValue   = "text|json|tree"
 	_dryRunTextValue = "text"
 )
 
@@ -561,6 +991,8 @@ var _ pflag.Value = &dryRunValue{}
 func (d *dryRunValue) String() string {
 	if d.opts.dryRunJSON {
 		return _dryRunJSONText
+	} else if d.opts.dryRunTree {
+		return _dryRunTreeText
 	} else if d.opts.dryRun {
 		return _dryRunText
 	}
@@ -571,6 +1003,9 @@ func (d *dryRunValue) Set(value string) error {
 	if value == _dryRunJSONValue {
 		d.opts.dryRun = true
 		d.opts.dryRunJSON = true
+	} else if value == _dryRunTreeValue {
+		d.opts.dryRun = true
+		d.opts.dryRunTree = true
 	} else if value == _dryRunNoValue {
 		// this case matches the NoOptDefValue, which is used when the flag
 		// is passed, but does not have a value (i.e. boolean flag)
@@ -593,7 +1028,8 @@ func (d *dryRunValue) Type() string {
 func getDefaultOptions() *Opts {
 	return &Opts{
 		runOpts: runOpts{
-			concurrency: 10,
+			concurrency:        10,
+			restoreConcurrency: 32,
 		},
 	}
 }
@@ -609,16 +1045,69 @@ func (r *run) logWarning(prefix string, err error) {
 	r.base.UI.Error(fmt.Sprintf("%s%s%s", ui.WARNING_PREFIX, prefix, color.YellowString(" %v", err)))
 }
 
-func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, packageManager *packagemanager.PackageManager, hashes *taskhash.Tracker, startAt time.Time) error {
+// warnSkippedPaths warns about the files that were left out of hashes because their
+// names aren't valid UTF-8
+func (r *run) warnSkippedPaths(paths []string) {
+	for _, path := range paths {
+		r.logWarning("", fmt.Errorf("not hashing %q, its name isn't valid UTF-8", path))
+	}
+}
+
+// checkRemoteCacheAccess validates the remote cache token once, before any artifacts are
+// requested, and turns off the parts of the remote cache that the run can't use
+func (r *run) checkRemoteCacheAccess(rs *runSpec) client.CacheAccess {
+	access := r.base.APIClient.CheckCacheAccess()
+	access.Read = access.Read && !rs.Opts.runcacheOpts.SkipReads
+	access.Write = access.Write && !rs.Opts.runcacheOpts.SkipWrites
+	access.WriteUnknown = access.WriteUnknown && !rs.Opts.runcacheOpts.SkipWrites
+	mayWrite := access.Write || access.WriteUnknown
+	if !access.Read && !mayWrite {
+		rs.Opts.cacheOpts.SkipRemote = true
+	}
+	if !mayWrite {
+		rs.Opts.cacheOpts.SkipRemoteWrites = true
+	}
+	if access.Reason != "" {
+		r.logWarning(fmt.Sprintf("Remote cache access is %v", access), errors.New(access.Reason))
+	} else {
+		r.base.UI.Output(fmt.Sprintf(ui.Dim("• Remote cache access: %v"), access))
+	}
+	return access
+}
+
+func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, packageManager *packagemanager.PackageManager, hashes *taskhash.Tracker, ignored []ignoredDependency, skipped *skippedTasks, startAt time.Time) error {
+	envOutputsRead, err := checkEnvInputs(engine.TaskGraph, g.Pipeline)
+	if err != nil {
+		return err
+	}
+	if rs.Opts.runOpts.runLock != "" {
+		lock, err := acquireRunLock(ctx, r.base.RepoRoot, rs.Opts.runOpts.runLock, func(holder *runLockHolder) {
+			r.base.UI.Output(ui.Dim(fmt.Sprintf("• Waiting for another turbo run to finish (%v)", holder)))
+		})
+		if err != nil {
+			return err
+		}
+		defer func() {
+			if err := lock.Unlock(); err != nil {
+				r.base.Logger.Warn(fmt.Sprintf("failed to release the run lock: %v", err))
+			}
+		}()
+	}
 	apiClient := r.base.APIClient
 	var analyticsSink analytics.Sink
+	var remoteCacheAccess string
 	if apiClient.IsLinked() {
 		analyticsSink = apiClient
+		remoteCacheAccess = r.checkRemoteCacheAccess(rs).String()
+		apiClient.SetArtifactTags(rs.Opts.runOpts.artifactTags)
 	} else {
 		r.opts.cacheOpts.SkipRemote = true
 		analyticsSink = analytics.NullSink
 	}
-	analyticsClient := analytics.NewClient(ctx, analyticsSink, r.base.Logger.Named("analytics"))
+	if !r.base.RepoConfig.Telemetry() {
+		analyticsSink = analytics.NullSink
+	}
+	analyticsClient := analytics.NewLabeledClient(ctx, analyticsSink, r.base.Logger.Named("analytics"), rs.Opts.runOpts.labels)
 	defer analyticsClient.CloseWithTimeout(50 * time.Millisecond)
 	// Theoretically this is overkill, but bias towards not spamming the console
 	once := &sync.Once{}
@@ -641,8 +1130,30 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		_ = spinner.WaitFor(ctx, turboCache.Shutdown, r.base.UI, "...writing to cache...", 1500*time.Millisecond)
 	}()
 	colorCache := colorcache.New()
-	runState := NewRunState(startAt, rs.Opts.runOpts.profile)
+	runState := NewRunState(startAt, rs.Opts.runOpts.profile, rs.Opts.runOpts.labels)
+	runState.remoteCacheAccess = remoteCacheAccess
+	runState.skipped = skipped.list()
+	if rs.Opts.runOpts.eventLog != "" {
+		eventLog, err := os.Create(rs.Opts.runOpts.eventLog)
+		if err != nil {
+			return errors.Wrap(err, "failed to create the event log")
+		}
+		unsubscribe := runState.Events.Subscribe(runevents.JSONLines(eventLog))
+		defer func() {
+			unsubscribe()
+			_ = eventLog.Close()
+		}()
+	}
 	runCache := runcache.New(turboCache, r.base.RepoRoot, rs.Opts.runcacheOpts, colorCache)
+	logSinks, err := logsink.Open(r.base.RepoRoot, rs.Opts.runOpts.logSinks)
+	if err != nil {
+		return err
+	}
+	defer func() {
+		if err := logSinks.Close(); err != nil {
+			r.logWarning("Failed to close log sinks", err)
+		}
+	}()
 	ec := &execContext{
 		colorCache:     colorCache,
 		runState:       runState,
@@ -651,19 +1162,80 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		runCache:       runCache,
 		logger:         r.base.Logger,
 		packageManager: packageManager,
-		processes:      r.processes,
+		backend:        newLocalBackend(r.processes),
 		taskHashes:     hashes,
 		repoRoot:       r.base.RepoRoot,
+		outputsHashed:  dependencyOutputTasks(engine.TaskGraph, g.Pipeline),
+		envOutputsRead: envOutputsRead,
+		logSinks:       logSinks,
+		skipped:        skipped,
+		failureArtifacts: &failureArtifacts{
+			repoRoot: r.base.RepoRoot,
+			dir:      rs.Opts.cacheOpts.ResolveCacheDir(r.base.RepoRoot).Join("failures"),
+		},
+	}
+	if !rs.Opts.cacheOpts.SkipRemote && !rs.Opts.cacheOpts.SkipRemoteWrites {
+		ec.failureArtifacts.uploader = apiClient
 	}
 
-	// run the thing
-	errs := engine.Execute(g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
-		deps := engine.TaskGraph.DownEdges(packageTask.TaskID)
+	if rs.Opts.runOpts.prefetch && !rs.Opts.runcacheOpts.SkipReads {
+		go r.prefetchArtifacts(ctx, g, rs, engine, hashes, turboCache)
+	}
+
+	taskDeps := func(taskID string) dag.Set {
+		return engine.TaskGraph.DownEdges(taskID)
+	}
+	if rs.Opts.runOpts.parallelSafe && !rs.Opts.runcacheOpts.SkipReads {
+		var depsBefore map[string]dag.Set
+		ignored, depsBefore = parallelizeCachedDependencies(ctx, g, rs, engine, hashes, ec, ignored)
+		// Tasks are hashed with the dependencies they'd have without --parallel, so they
+		// share their cache entries with runs without it
+		taskDeps = func(taskID string) dag.Set {
+			return depsBefore[taskID]
+		}
+	} else if rs.Opts.runOpts.parallelSafe {
+		ignored = nil
+	}
+	runState.ignoredDependencies = ignored
+	runState.parallelSafe = rs.Opts.runOpts.parallelSafe
+
+	visitor := g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
+		deps := taskDeps(packageTask.TaskID)
 		return ec.exec(ctx, packageTask, deps)
-	}), core.ExecOpts{
+	})
+	execOpts := core.ExecOpts{
 		Parallel:    rs.Opts.runOpts.parallel,
 		Concurrency: rs.Opts.runOpts.concurrency,
-	})
+		// Cache hits are restored in a pool of their own, since extracting artifacts is
+		// bound by IO rather than by the CPU that running tasks need
+		Restore: func(taskID string) (bool, error) {
+			restored := true
+			err := g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
+				var err error
+				deps := taskDeps(packageTask.TaskID)
+				restored, err = ec.restore(ctx, packageTask, deps)
+				return err
+			})(taskID)
+			return restored, err
+		},
+		RestoreConcurrency: rs.Opts.runOpts.restoreConcurrency,
+	}
+	// A --parallel run doesn't use the concurrency, but still takes the changes so that
+	// the watcher isn't left waiting to deliver one
+	execOpts.ConcurrencyChanges = r.concurrencyChanges
+	var durations *taskDurations
+	if rs.Opts.runOpts.scheduleByDuration {
+		durations = readTaskDurations(r.base.RepoRoot)
+		priorities := durations.priorities(engine.TaskGraph)
+		execOpts.Priority = func(taskID string) int64 {
+			return priorities[taskID]
+		}
+	}
+
+	// run the thing
+	runState.Events.Publish(runevents.Event{Type: runevents.RunStarted})
+	errs := engine.Execute(visitor, execOpts)
+	runState.Events.Publish(runevents.Event{Type: runevents.RunFinished, Duration: time.Since(startAt)})
 
 	// Track if we saw any child with a non-zero exit code
 	exitCode := 0
@@ -680,9 +1252,33 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 		r.base.UI.Error(err.Error())
 	}
 
-	if err := runState.Close(r.base.UI, rs.Opts.runOpts.profile); err != nil {
+	if err := runState.Close(r.base.UI, rs.Opts.runOpts.profile, engine.TaskGraph); err != nil {
 		return errors.Wrap(err, "error with profiler")
 	}
+	if rs.Opts.runOpts.htmlReport != "" {
+		if err := writeHTMLReport(rs.Opts.runOpts.htmlReport, runState.summary(engine.TaskGraph)); err != nil {
+			r.logWarning("Failed to write the HTML report", err)
+		} else {
+			r.base.UI.Output(ui.Dim(fmt.Sprintf("• Wrote a report of the run to %v", rs.Opts.runOpts.htmlReport)))
+		}
+	}
+	if durations != nil {
+		durations.record(runState.state)
+		if err := durations.save(r.base.RepoRoot); err != nil {
+			r.logWarning("Failed to save task durations", err)
+		}
+	}
+	if rs.Opts.runOpts.daemonClient != nil {
+		if err := rs.Opts.runOpts.daemonClient.RecordRunStats(ctx, runState.taskStats()); err != nil {
+			r.base.Logger.Debug("failed to record run stats with turbod", "error", err)
+		}
+	}
+	if len(g.RunHooks) > 0 {
+		payload := newRunHookPayload(runState.summary(engine.TaskGraph), exitCode == 0)
+		for _, err := range notifyRunHooks(g.RunHooks, r.base.RepoRoot, payload, os.Stdout) {
+			r.logWarning("Run hook failed", err)
+		}
+	}
 	if exitCode != 0 {
 		return &process.ChildExit{
 			ExitCode: exitCode,
@@ -692,16 +1288,64 @@ func (r *run) executeTasks(ctx gocontext.Context, g *completeGraph, rs *runSpec,
 }
 
 type hashedTask struct {
-	TaskID       string   `json:"taskId"`
-	Task         string   `json:"task"`
-	Package      string   `json:"package"`
-	Hash         string   `json:"hash"`
-	Command      string   `json:"command"`
-	Outputs      []string `json:"outputs"`
-	LogFile      string   `json:"logFile"`
-	Dir          string   `json:"directory"`
-	Dependencies []string `json:"dependencies"`
-	Dependents   []string `json:"dependents"`
+	TaskID        string   `json:"taskId"`
+	Task          string   `json:"task"`
+	Package       string   `json:"package"`
+	Hash          string   `json:"hash"`
+	CachedLocally bool     `json:"cachedLocally"`
+	Command       string   `json:"command"`
+	Outputs       []string `json:"outputs"`
+	LogFile       string   `json:"logFile"`
+	Dir           string   `json:"directory"`
+	Dependencies  []string `json:"dependencies"`
+	Dependents    []string `json:"dependents"`
+	Framework     string   `json:"framework"`
+}
+
+// prefetchArtifacts walks the task graph ahead of execution to compute task hashes, then
+// asks the cache to start retrieving the corresponding artifacts. Task hashes only depend
+// on the hashes of their dependencies, not on their outputs, so the walk never waits for a
+// task to finish.
+func (r *run) prefetchArtifacts(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, taskHashes *taskhash.Tracker, turboCache cache.Cache) {
+	hashes, errs := cacheableTaskHashes(ctx, g, rs, engine, taskHashes)
+	for _, err := range errs {
+		r.base.Logger.Debug("prefetch stopped", "error", err)
+	}
+	cache.Prefetch(turboCache, hashes)
+}
+
+// cacheableTaskHashes computes the hashes of the tasks in the graph whose results are cached,
+// without running anything. Tasks that hash their dependencies by outputs or take env inputs
+// from other tasks, and the tasks that depend on them, can't be hashed ahead of time, and are
+// left out.
+func cacheableTaskHashes(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, taskHashes *taskhash.Tracker) ([]string, []error) {
+	hashes := []string{}
+	mu := sync.Mutex{}
+	errs := engine.Execute(g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
+		// The hash of a task that hashes its dependencies by outputs isn't known until they
+		// have run. Stopping here also keeps this task's dependents from being hashed early.
+		if packageTask.TaskDefinition.HashDependencyOutputs {
+			return fmt.Errorf("%v hashes its dependencies by outputs", packageTask.TaskID)
+		}
+		if len(packageTask.TaskDefinition.EnvInputsFrom) > 0 {
+			return fmt.Errorf("%v takes env inputs from other tasks", packageTask.TaskID)
+		}
+		passThroughArgs := rs.ArgsForTask(packageTask.Task)
+		deps := engine.TaskGraph.DownEdges(packageTask.TaskID)
+		hash, err := taskHashes.CalculateTaskHash(packageTask, deps, passThroughArgs)
+		if err != nil {
+			return err
+		}
+		if _, ok := packageTask.Command(); ok && packageTask.TaskDefinition.ShouldCache {
+			mu.Lock()
+			hashes = append(hashes, hash)
+			mu.Unlock()
+		}
+		return nil
+	}), core.ExecOpts{
+		Parallel: true,
+	})
+	return hashes, errs
 }
 
 func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, taskHashes *taskhash.Tracker, rs *runSpec) ([]hashedTask, error) {
@@ -745,17 +1389,23 @@ func (r *run) executeDryRun(ctx gocontext.Context, engine *core.Scheduler, g *co
 		}
 		sort.Strings(stringDescendents)
 
+		framework := ""
+		if inferred := g.Frameworks.InferFramework(packageTask.Pkg); inferred != nil {
+			framework = inferred.Slug
+		}
 		taskIDs = append(taskIDs, hashedTask{
-			TaskID:       packageTask.TaskID,
-			Task:         packageTask.Task,
-			Package:      packageTask.PackageName,
-			Hash:         hash,
-			Command:      command,
-			Dir:          packageTask.Pkg.Dir.ToString(),
-			Outputs:      packageTask.TaskDefinition.Outputs,
-			LogFile:      packageTask.RepoRelativeLogFile(),
-			Dependencies: stringAncestors,
-			Dependents:   stringDescendents,
+			TaskID:        packageTask.TaskID,
+			Task:          packageTask.Task,
+			Package:       packageTask.PackageName,
+			Hash:          hash,
+			CachedLocally: !rs.Opts.cacheOpts.SkipFilesystem && cache.ExistsLocally(rs.Opts.cacheOpts, r.base.RepoRoot, hash),
+			Command:       command,
+			Dir:           packageTask.Pkg.Dir.ToString(),
+			Outputs:       packageTask.TaskDefinition.Outputs,
+			LogFile:       packageTask.RepoRelativeLogFile(),
+			Dependencies:  stringAncestors,
+			Dependents:    stringDescendents,
+			Framework:     framework,
 		})
 		return nil
 	}), core.ExecOpts{
@@ -794,9 +1444,25 @@ type execContext struct {
 	runCache       *runcache.RunCache
 	logger         hclog.Logger
 	packageManager *packagemanager.PackageManager
-	processes      *process.Manager
+	backend        ExecutionBackend
 	taskHashes     *taskhash.Tracker
 	repoRoot       turbopath.AbsolutePath
+	// outputsHashed is the set of tasks whose outputs hash is recorded for their dependents
+	outputsHashed util.Set
+	// envOutputsRead is the set of tasks whose env output is read for their dependents
+	envOutputsRead util.Set
+	// logSinks receive the output of the tasks that execute
+	logSinks logsink.Sinks
+	// pending holds the *taskRun of each task that restore found no cached outputs for,
+	// until exec runs it
+	pending sync.Map
+	// restored holds the tasks that restore is done with
+	restored sync.Map
+	// failureArtifacts saves the logs and debug outputs of tasks that fail
+	failureArtifacts *failureArtifacts
+	// skipped are the tasks that --skip leaves out, and the tasks whose outputs aren't
+	// cached because they depend on them
+	skipped *skippedTasks
 }
 
 func (e *execContext) logError(log hclog.Logger, prefix string, err error) {
@@ -809,14 +1475,47 @@ func (e *execContext) logError(log hclog.Logger, prefix string, err error) {
 	e.ui.Error(fmt.Sprintf("%s%s%s", ui.ERROR_PREFIX, prefix, color.RedString(" %v", err)))
 }
 
-func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask, deps dag.Set) error {
+// taskRun is what executing a task needs that is worked out before its outputs are
+// restored from the cache
+type taskRun struct {
+	cmdTime          time.Time
+	targetLogger     hclog.Logger
+	tracer           func(outcome RunResultStatus, err error)
+	traceEvent       *chrometracing.PendingEvent
+	prettyTaskPrefix string
+	targetUi         *cli.PrefixedUi
+	passThroughArgs  []string
+	hash             string
+	taskCache        runcache.TaskCache
+}
+
+// restore hashes a task and restores its outputs from the cache, returning true if the
+// task doesn't need to execute. If it does, exec picks up where restore left off.
+func (e *execContext) restore(ctx gocontext.Context, packageTask *nodes.PackageTask, deps dag.Set) (bool, error) {
+	// With --parallel=safe, some tasks have been through restore before the run
+	if _, ok := e.restored.Load(packageTask.TaskID); ok {
+		return true, nil
+	} else if _, ok := e.pending.Load(packageTask.TaskID); ok {
+		return false, nil
+	}
+	run := e.prepare(packageTask, deps)
+	if run == nil || e.restoreOutputs(ctx, packageTask, run) {
+		e.restored.Store(packageTask.TaskID, true)
+		return true, nil
+	}
+	e.pending.Store(packageTask.TaskID, run)
+	return false, nil
+}
+
+// prepare hashes a task, returning nil if the task's workspace has no script for it
+func (e *execContext) prepare(packageTask *nodes.PackageTask, deps dag.Set) *taskRun {
 	cmdTime := time.Now()
 
 	targetLogger := e.logger.Named(packageTask.OutputPrefix())
 	targetLogger.Debug("start")
 
 	// Setup tracer
-	tracer := e.runState.Run(packageTask.TaskID)
+	tracer, traceEvent := e.runState.Run(packageTask.TaskID)
 
 	// Create a logger
 	colorPrefixer := e.colorCache.PrefixColor(packageTask.PackageName)
@@ -830,7 +1529,16 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 	}
 
 	passThroughArgs := e.rs.ArgsForTask(packageTask.Task)
+	hashStart := time.Now()
+	hashPhase := traceEvent.Phase("hash")
 	hash, err := e.taskHashes.CalculateTaskHash(packageTask, deps, passThroughArgs)
+	hashPhase.Done()
+	e.runState.Events.Publish(runevents.Event{
+		Type:     runevents.TaskHashed,
+		TaskID:   packageTask.TaskID,
+		Duration: time.Since(hashStart),
+		Hash:     hash,
+	})
 	e.logger.Debug("task hash", "value", hash)
 	if err != nil {
 		e.ui.Error(fmt.Sprintf("Hashing error: %v", err))
@@ -846,15 +1554,75 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 		targetLogger.Debug("done", "status", "skipped", "duration", time.Since(cmdTime))
 		return nil
 	}
-	// Cache ---------------------------------------------
-	taskCache := e.runCache.TaskCache(packageTask, hash)
-	hit, err := taskCache.RestoreOutputs(ctx, targetUi, targetLogger)
+	if e.skipped.isSkipped(packageTask.TaskID) {
+		targetUi.Output(ui.Dim(fmt.Sprintf("skipped with --skip %v", hash)))
+		tracer(TargetSkipped, nil)
+		return nil
+	}
+	return &taskRun{
+		cmdTime:          cmdTime,
+		targetLogger:     targetLogger,
+		tracer:           tracer,
+		traceEvent:       traceEvent,
+		prettyTaskPrefix: prettyTaskPrefix,
+		targetUi:         targetUi,
+		passThroughArgs:  passThroughArgs,
+		hash:             hash,
+		taskCache:        e.runCache.TaskCache(packageTask, hash),
+	}
+}
+
+// restoreOutputs restores the outputs of a task from the cache, returning true on a hit
+func (e *execContext) restoreOutputs(ctx gocontext.Context, packageTask *nodes.PackageTask, run *taskRun) bool {
+	cacheStart := time.Now()
+	cachePhase := run.traceEvent.Phase("cache check")
+	hit, timeSaved, err := run.taskCache.RestoreOutputs(ctx, run.targetUi, run.targetLogger)
+	if hit {
+		// Checking the cache and restoring from it happen together, so on a hit
+		// the whole phase is attributed to restoring
+		cachePhase.SetName("restore")
+	}
+	cachePhase.Done()
+	event := runevents.Event{
+		Type:     runevents.CacheMiss,
+		TaskID:   packageTask.TaskID,
+		Duration: time.Since(cacheStart),
+		Hash:     run.hash,
+	}
 	if err != nil {
-		targetUi.Error(fmt.Sprintf("error fetching from cache: %s", err))
+		run.targetUi.Error(fmt.Sprintf("error fetching from cache: %s", err))
 	} else if hit {
-		tracer(TargetCached, nil)
-		return nil
+		e.recordOutputsHash(packageTask, run.taskCache, run.targetLogger)
+		e.recordEnvOutput(packageTask, run.targetLogger)
+		event.Type = runevents.CacheHit
+		event.TimeSaved = timeSaved
+		e.runState.Events.Publish(event)
+		run.tracer(TargetCached, nil)
+		return true
+	}
+	e.runState.Events.Publish(event)
+	return false
+}
+
+func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask, deps dag.Set) error {
+	var run *taskRun
+	if pending, ok := e.pending.LoadAndDelete(packageTask.TaskID); ok {
+		run = pending.(*taskRun)
+	} else {
+		run = e.prepare(packageTask, deps)
+		if run == nil || e.restoreOutputs(ctx, packageTask, run) {
+			return nil
+		}
 	}
+	cmdTime := run.cmdTime
+	targetLogger := run.targetLogger
+	tracer, traceEvent := run.tracer, run.traceEvent
+	prettyTaskPrefix := run.prettyTaskPrefix
+	targetUi := run.targetUi
+	passThroughArgs := run.passThroughArgs
+	hash := run.hash
+	taskCache := run.taskCache
+
 	// Setup command execution
 	argsactual := append([]string{"run"}, packageTask.Task)
 	if len(passThroughArgs) > 0 {
@@ -868,8 +1636,7 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 	// takes a RelativeSystemPath. Resolve during migration from turbopath.AbsolutePath to
 	// AbsoluteSystemPath
 	cmd.Dir = e.repoRoot.Join(packageTask.Pkg.Dir.ToStringDuringMigration()).ToString()
-	envs := fmt.Sprintf("TURBO_HASH=%v", hash)
-	cmd.Env = append(os.Environ(), envs)
+	cmd.Env = taskEnv(os.Environ(), hash, packageTask.TaskDefinition, e.taskHashes.EnvFileVars(packageTask.TaskID), e.taskHashes.EnvInputVars(packageTask))
 
 	// Setup stdout/stderr
 	// If we are not caching anything, then we don't need to write logs to disk
@@ -887,12 +1654,40 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 	logStreamerOut := logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
 	// Setup a streamer that we'll pipe cmd.Stderr to.
 	logStreamerErr := logstreamer.NewLogstreamer(logger, prettyTaskPrefix, false)
-	cmd.Stderr = logStreamerErr
-	cmd.Stdout = logStreamerOut
+	var sinkWriter io.WriteCloser
+	if len(e.logSinks) > 0 {
+		sinkWriter = e.logSinks.Open(packageTask.TaskID)
+	}
+	if packageTask.TaskDefinition.Interactive {
+		// Interactive tasks own the terminal: they read from our stdin, and their output
+		// skips the line-based prefixing, since prompts usually don't end in a newline and
+		// would be held back. The task can put the terminal in raw mode itself, as it
+		// would when run directly.
+		cmd.Stdin = os.Stdin
+		var output io.Writer = writer
+		if sinkWriter != nil {
+			output = io.MultiWriter(writer, sinkWriter)
+		}
+		// Using the same writer for both keeps exec from writing to it concurrently
+		cmd.Stdout = output
+		cmd.Stderr = output
+	} else if sinkWriter != nil {
+		cmd.Stderr = io.MultiWriter(logStreamerErr, sinkWriter)
+		cmd.Stdout = io.MultiWriter(logStreamerOut, sinkWriter)
+	} else {
+		cmd.Stderr = logStreamerErr
+		cmd.Stdout = logStreamerOut
+	}
 	// Flush/Reset any error we recorded
 	logStreamerErr.FlushRecord()
 	logStreamerOut.FlushRecord()
 	closeOutputs := func() error {
+		if sinkWriter != nil {
+			// Log sinks that fail don't fail the task
+			if err := sinkWriter.Close(); err != nil {
+				targetUi.Warn(fmt.Sprintf("failed to stream output to a log sink: %v", err))
+			}
+		}
 		var closeErrors []error
 		if err := logStreamerOut.Close(); err != nil {
 			closeErrors = append(closeErrors, errors.Wrap(err, "log stdout"))
@@ -913,8 +1708,12 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 		return nil
 	}
 
-	// Run the command
-	if err := e.processes.Exec(cmd); err != nil {
+	// Run the command, along with any lifecycle hooks
+	e.runState.Events.Publish(runevents.Event{Type: runevents.TaskExecuting, TaskID: packageTask.TaskID, Hash: hash})
+	executePhase := traceEvent.Phase("execute")
+	err = e.execWithHooks(packageTask, hash, cmd)
+	executePhase.Done()
+	if err != nil {
 		// close off our outputs. We errored, so we mostly don't care if we fail to close
 		_ = closeOutputs()
 		// if we already know we're in the process of exiting,
@@ -922,11 +1721,14 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 		if errors.Is(err, process.ErrClosing) {
 			return nil
 		}
+		if packageTask.TaskDefinition.OnFailure != nil {
+			e.captureFailure(packageTask, run)
+		}
 		tracer(TargetBuildFailed, err)
 		targetLogger.Error("Error: command finished with error: %w", err)
 		if !e.rs.Opts.runOpts.continueOnError {
 			targetUi.Error(fmt.Sprintf("ERROR: command finished with error: %s", err))
-			e.processes.Close()
+			e.backend.Close()
 		} else {
 			targetUi.Warn("command finished with error, but continuing...")
 		}
@@ -935,13 +1737,19 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 
 	duration := time.Since(cmdTime)
 	// Close off our outputs and cache them
+	savePhase := traceEvent.Phase("save")
 	if err := closeOutputs(); err != nil {
 		e.logError(targetLogger, "", err)
+	} else if e.skipped.isUncacheable(packageTask.TaskID) {
+		targetUi.Output(ui.Dim("not caching outputs, since a task this depends on was skipped with --skip"))
 	} else {
 		if err = taskCache.SaveOutputs(ctx, targetLogger, targetUi, int(duration.Milliseconds())); err != nil {
 			e.logError(targetLogger, "", fmt.Errorf("error caching output: %w", err))
 		}
 	}
+	e.recordOutputsHash(packageTask, taskCache, targetLogger)
+	e.recordEnvOutput(packageTask, targetLogger)
+	savePhase.Done()
 
 	// Clean up tracing
 	tracer(TargetBuilt, nil)
@@ -949,6 +1757,128 @@ func (e *execContext) exec(ctx gocontext.Context, packageTask *nodes.PackageTask
 	return nil
 }
 
+// taskEnv returns the environment a task runs with: environ, TURBO_HASH, the variables
+// from its env files if it injects them, and the variables from its env inputs. The
+// tracker already left variables that are set in environ out of envFileVars, and env
+// inputs don't override those either, nor those from the task's own env files.
+func taskEnv(environ []string, hash string, taskDefinition *fs.TaskDefinition, envFileVars map[string]string, envInputVars map[string]string) []string {
+	environment := append(append([]string{}, environ...), fmt.Sprintf("TURBO_HASH=%v", hash))
+	isSet := make(util.Set)
+	for _, pair := range environ {
+		isSet.Add(strings.SplitN(pair, "=", 2)[0])
+	}
+	if taskDefinition.InjectEnvFiles {
+		environment = append(environment, env.EnvFilePairs(envFileVars)...)
+		for k := range envFileVars {
+			isSet.Add(k)
+		}
+	}
+	inputVars := make(map[string]string)
+	for k, v := range envInputVars {
+		if !isSet.Includes(k) {
+			inputVars[k] = v
+		}
+	}
+	return append(environment, env.EnvFilePairs(inputVars)...)
+}
+
+// captureFailure saves the log and debug outputs of a task that failed into a failure
+// artifact. Failing to doesn't change how the task failed.
+func (e *execContext) captureFailure(packageTask *nodes.PackageTask, run *taskRun) {
+	var logFile turbopath.AbsolutePath
+	if run.taskCache.WritesLogFile() {
+		logFile = run.taskCache.LogFileName
+	}
+	duration := int(time.Since(run.cmdTime).Milliseconds())
+	path, uploaded, err := e.failureArtifacts.capture(packageTask, run.hash, logFile, duration)
+	if path == "" {
+		run.targetUi.Warn(fmt.Sprintf("failed to capture a failure artifact: %v", err))
+		return
+	}
+	e.runState.Events.Publish(runevents.Event{Type: runevents.FailureCaptured, TaskID: packageTask.TaskID, Hash: run.hash, Artifact: path})
+	if err != nil {
+		run.targetUi.Warn(fmt.Sprintf("saved a failure artifact to %v, but %v", path, err))
+	} else if uploaded {
+		run.targetUi.Output(ui.Dim(fmt.Sprintf("saved a failure artifact to %v, and to the remote cache as %v", path, failureArtifactKey(run.hash))))
+	} else {
+		run.targetUi.Output(ui.Dim(fmt.Sprintf("saved a failure artifact to %v", path)))
+	}
+}
+
+// recordOutputsHash hashes the outputs of a task that has finished or been restored from
+// cache, if a task that depends on it hashes its dependencies by outputs. If hashing fails,
+// the dependent tasks fall back to using this task's hash.
+func (e *execContext) recordOutputsHash(packageTask *nodes.PackageTask, taskCache runcache.TaskCache, logger hclog.Logger) {
+	if !e.outputsHashed.Includes(packageTask.TaskID) {
+		return
+	}
+	outputsHash, err := taskCache.OutputsHash()
+	if err != nil {
+		logger.Warn(fmt.Sprintf("failed to hash outputs of %v: %v", packageTask.TaskID, err))
+		return
+	}
+	e.taskHashes.SetOutputsHash(packageTask.TaskID, outputsHash)
+}
+
+// recordEnvOutput reads the env output of a task that has finished or been restored from
+// cache, if a task that runs later takes env inputs from it.
+func (e *execContext) recordEnvOutput(packageTask *nodes.PackageTask, logger hclog.Logger) {
+	if !e.envOutputsRead.Includes(packageTask.TaskID) {
+		return
+	}
+	pkgDir := e.repoRoot.Join(packageTask.Pkg.Dir.ToStringDuringMigration())
+	envOutputPath := pkgDir.Join(packageTask.TaskDefinition.EnvOutput)
+	if !envOutputPath.FileExists() {
+		logger.Warn(fmt.Sprintf("%v declares an env output of %v, but didn't write it", packageTask.TaskID, packageTask.TaskDefinition.EnvOutput))
+	}
+	vars, err := env.LoadEnvFiles(pkgDir, []string{packageTask.TaskDefinition.EnvOutput})
+	if err != nil {
+		logger.Warn(fmt.Sprintf("failed to read the env output of %v: %v", packageTask.TaskID, err))
+		return
+	}
+	e.taskHashes.SetEnvOutputVars(packageTask.TaskID, vars)
+}
+
+// execWithHooks runs the task's "before" hook, then the task's command, then the task's "after"
+// hook, stopping at the first one that fails.
+func (e *execContext) execWithHooks(packageTask *nodes.PackageTask, hash string, cmd *exec.Cmd) error {
+	taskDefinition := packageTask.TaskDefinition
+	if taskDefinition.Before != "" {
+		if err := e.backend.Exec(packageTask, hash, hookCommand(taskDefinition.Before, cmd)); err != nil {
+			return errors.Wrap(err, "\"before\" hook failed")
+		}
+	}
+	if err := e.backend.Exec(packageTask, hash, cmd); err != nil {
+		return err
+	}
+	if taskDefinition.After != "" {
+		if err := e.backend.Exec(packageTask, hash, hookCommand(taskDefinition.After, cmd)); err != nil {
+			return errors.Wrap(err, "\"after\" hook failed")
+		}
+	}
+	return nil
+}
+
+// shellCommand creates a command that runs the given command line in the platform's shell
+func shellCommand(command string) *exec.Cmd {
+	if runtime.GOOS == "windows" {
+		return exec.Command("cmd", "/C", command)
+	}
+	return exec.Command("sh", "-c", command)
+}
+
+// hookCommand creates a command that runs the given hook in a shell, with the same directory,
+// environment, input and output as the task's command.
+func hookCommand(hook string, taskCmd *exec.Cmd) *exec.Cmd {
+	cmd := shellCommand(hook)
+	cmd.Dir = taskCmd.Dir
+	cmd.Env = taskCmd.Env
+	cmd.Stdin = taskCmd.Stdin
+	cmd.Stdout = taskCmd.Stdout
+	cmd.Stderr = taskCmd.Stderr
+	return cmd
+}
+
 func (g *completeGraph) getPackageTaskVisitor(ctx gocontext.Context, visitor func(ctx gocontext.Context, packageTask *nodes.PackageTask) error) func(taskID string) error {
 	return func(taskID string) error {
 
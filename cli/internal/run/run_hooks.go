@@ -0,0 +1,149 @@
+package run
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"io/ioutil"
+	"net/http"
+	"net/url"
+	"os"
+	"strings"
+	"time"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/runevents"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// _webhookTimeout is how long a run hook's webhook has to respond
+const _webhookTimeout = 10 * time.Second
+
+// runHookPayload is the part of the run summary that run hooks are sent
+type runHookPayload struct {
+	// Status is fs.RunHookSuccess or fs.RunHookFailure
+	Status string `json:"status"`
+	// Text is a one-line summary of the run, which chat services such as Slack show as
+	// the message
+	Text      string        `json:"text"`
+	StartedAt time.Time     `json:"startedAt"`
+	Duration  time.Duration `json:"duration"`
+	Attempted int           `json:"attempted"`
+	Cached    int           `json:"cached"`
+	Failed    int           `json:"failed"`
+	// CacheHitRate is the percentage of the attempted tasks that were restored from the cache
+	CacheHitRate float64           `json:"cacheHitRate"`
+	TimeSaved    time.Duration     `json:"timeSaved"`
+	Labels       map[string]string `json:"labels,omitempty"`
+	FailedTasks  []failedTask      `json:"failedTasks"`
+}
+
+// failedTask is a task that failed, in a runHookPayload
+type failedTask struct {
+	TaskID          string `json:"taskId"`
+	Error           string `json:"error,omitempty"`
+	FailureArtifact string `json:"failureArtifact,omitempty"`
+}
+
+func newRunHookPayload(summary *runSummary, success bool) *runHookPayload {
+	payload := &runHookPayload{
+		Status:       fs.RunHookSuccess,
+		StartedAt:    summary.StartedAt,
+		Duration:     summary.Duration,
+		Attempted:    summary.Attempted,
+		Cached:       summary.Cached,
+		Failed:       summary.Failed,
+		CacheHitRate: 100,
+		TimeSaved:    summary.TimeSaved,
+		Labels:       summary.Labels,
+		FailedTasks:  []failedTask{},
+	}
+	if !success {
+		payload.Status = fs.RunHookFailure
+	}
+	if summary.Attempted > 0 {
+		payload.CacheHitRate = float64(summary.Cached) * 100 / float64(summary.Attempted)
+	}
+	failed := []string{}
+	for _, task := range summary.Tasks {
+		if task.Status == runevents.StatusFailed {
+			payload.FailedTasks = append(payload.FailedTasks, failedTask{
+				TaskID:          task.TaskID,
+				Error:           task.Error,
+				FailureArtifact: task.FailureArtifact,
+			})
+			failed = append(failed, task.TaskID)
+		}
+	}
+	payload.Text = fmt.Sprintf("turbo run %v: %v tasks, %v cached (%.0f%%), took %v", payload.Status, summary.Attempted, summary.Cached, payload.CacheHitRate, summary.Duration.Truncate(time.Millisecond))
+	if len(failed) > 0 {
+		payload.Text += fmt.Sprintf(", %v failed: %v", len(failed), strings.Join(failed, ", "))
+	}
+	return payload
+}
+
+// triggers returns true if the run the payload describes should trigger the hook
+func (p *runHookPayload) triggers(hook fs.RunHook) bool {
+	if hook.When != "" && hook.When != fs.RunHookAlways && hook.When != p.Status {
+		return false
+	}
+	return hook.CacheHitRateBelow == nil || p.CacheHitRate < *hook.CacheHitRateBelow
+}
+
+// notifyRunHooks runs the commands and calls the webhooks of the hooks the run triggers,
+// one after another. Hooks that fail don't stop the others.
+func notifyRunHooks(hooks []fs.RunHook, repoRoot turbopath.AbsolutePath, payload *runHookPayload, output io.Writer) []error {
+	body, err := json.Marshal(payload)
+	if err != nil {
+		return []error{err}
+	}
+	client := &http.Client{Timeout: _webhookTimeout}
+	errs := []error{}
+	for i, hook := range hooks {
+		if !payload.triggers(hook) {
+			continue
+		}
+		var err error
+		if hook.Command != "" {
+			err = runCommandHook(hook.Command, repoRoot, payload.Status, body, output)
+		} else if endpoint := os.ExpandEnv(hook.Webhook); endpoint != "" {
+			err = postWebhook(client, endpoint, body)
+		}
+		// Hooks are referred to by their index, since webhook URLs are usually secrets
+		if err != nil {
+			errs = append(errs, fmt.Errorf("runHooks[%v]: %w", i, err))
+		}
+	}
+	return errs
+}
+
+// runCommandHook runs the command in a shell from the repository root, with the payload on its
+// stdin and the run's status in TURBO_RUN_STATUS
+func runCommandHook(command string, repoRoot turbopath.AbsolutePath, status string, body []byte, output io.Writer) error {
+	cmd := shellCommand(command)
+	cmd.Dir = repoRoot.ToString()
+	cmd.Env = append(os.Environ(), fmt.Sprintf("TURBO_RUN_STATUS=%v", status))
+	cmd.Stdin = bytes.NewReader(body)
+	cmd.Stdout = output
+	cmd.Stderr = output
+	return cmd.Run()
+}
+
+func postWebhook(client *http.Client, endpoint string, body []byte) error {
+	resp, err := client.Post(endpoint, "application/json", bytes.NewReader(body))
+	var urlErr *url.Error
+	if errors.As(err, &urlErr) {
+		// Leave out the URL
+		return urlErr.Err
+	} else if err != nil {
+		return err
+	}
+	_, _ = io.Copy(ioutil.Discard, resp.Body)
+	_ = resp.Body.Close()
+	if resp.StatusCode >= 300 {
+		return fmt.Errorf("responded with %v", resp.Status)
+	}
+	return nil
+}
@@ -0,0 +1,107 @@
+package run
+
+import (
+	"encoding/json"
+	"io/ioutil"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"runtime"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/runevents"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+func testSummary() *runSummary {
+	return &runSummary{
+		Duration:  1500 * time.Millisecond,
+		Attempted: 4,
+		Cached:    1,
+		Failed:    1,
+		Tasks: []taskSummary{
+			{TaskID: "web#build", Status: runevents.StatusBuilt},
+			{TaskID: "web#test", Status: runevents.StatusFailed, Error: "exit status 1", FailureArtifact: "failures/abc-failure.tar.gz"},
+		},
+	}
+}
+
+func TestNewRunHookPayload(t *testing.T) {
+	payload := newRunHookPayload(testSummary(), false)
+	assert.Equal(t, fs.RunHookFailure, payload.Status)
+	assert.Equal(t, 25.0, payload.CacheHitRate)
+	assert.Equal(t, []failedTask{{TaskID: "web#test", Error: "exit status 1", FailureArtifact: "failures/abc-failure.tar.gz"}}, payload.FailedTasks)
+	assert.Equal(t, "turbo run failure: 4 tasks, 1 cached (25%), took 1.5s, 1 failed: web#test", payload.Text)
+
+	payload = newRunHookPayload(&runSummary{}, true)
+	assert.Equal(t, fs.RunHookSuccess, payload.Status)
+	assert.Equal(t, 100.0, payload.CacheHitRate)
+}
+
+func TestRunHookPayloadTriggers(t *testing.T) {
+	failure := newRunHookPayload(testSummary(), false)
+	success := newRunHookPayload(testSummary(), true)
+	low, high := 20.0, 50.0
+
+	assert.True(t, failure.triggers(fs.RunHook{}))
+	assert.True(t, failure.triggers(fs.RunHook{When: fs.RunHookAlways}))
+	assert.True(t, failure.triggers(fs.RunHook{When: fs.RunHookFailure}))
+	assert.False(t, success.triggers(fs.RunHook{When: fs.RunHookFailure}))
+	assert.True(t, success.triggers(fs.RunHook{When: fs.RunHookSuccess}))
+	// 25% of the tasks were restored from the cache
+	assert.True(t, success.triggers(fs.RunHook{CacheHitRateBelow: &high}))
+	assert.False(t, success.triggers(fs.RunHook{CacheHitRateBelow: &low}))
+	assert.False(t, failure.triggers(fs.RunHook{When: fs.RunHookSuccess, CacheHitRateBelow: &high}))
+}
+
+func TestNotifyRunHooksWebhook(t *testing.T) {
+	var received []runHookPayload
+	ts := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		var payload runHookPayload
+		assert.NoError(t, json.NewDecoder(r.Body).Decode(&payload))
+		assert.Equal(t, "application/json", r.Header.Get("Content-Type"))
+		received = append(received, payload)
+	}))
+	defer ts.Close()
+	failing := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.WriteHeader(http.StatusInternalServerError)
+	}))
+	defer failing.Close()
+	t.Setenv("TEST_RUN_HOOK_WEBHOOK", ts.URL)
+
+	hooks := []fs.RunHook{
+		{Webhook: "$TEST_RUN_HOOK_WEBHOOK", When: fs.RunHookFailure},
+		{Webhook: "$TEST_RUN_HOOK_WEBHOOK", When: fs.RunHookSuccess},
+		// Webhooks whose URL is an unset variable are skipped
+		{Webhook: "$TEST_RUN_HOOK_UNSET"},
+		{Webhook: failing.URL},
+	}
+	errs := notifyRunHooks(hooks, turbopath.AbsolutePath(t.TempDir()), newRunHookPayload(testSummary(), false), ioutil.Discard)
+	assert.Equal(t, 1, len(errs))
+	assert.EqualError(t, errs[0], "runHooks[3]: responded with 500 Internal Server Error")
+	assert.Equal(t, 1, len(received))
+	assert.Equal(t, "web#test", received[0].FailedTasks[0].TaskID)
+}
+
+func TestNotifyRunHooksCommand(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("uses a POSIX shell")
+	}
+	repoRoot := t.TempDir()
+	hooks := []fs.RunHook{{Command: `cat > payload.json && echo "$TURBO_RUN_STATUS" > status`}}
+	errs := notifyRunHooks(hooks, turbopath.AbsolutePath(repoRoot), newRunHookPayload(testSummary(), true), ioutil.Discard)
+	assert.Empty(t, errs)
+
+	status, err := os.ReadFile(filepath.Join(repoRoot, "status"))
+	assert.NoError(t, err)
+	assert.Equal(t, "success\n", string(status))
+	contents, err := os.ReadFile(filepath.Join(repoRoot, "payload.json"))
+	assert.NoError(t, err)
+	var payload runHookPayload
+	assert.NoError(t, json.Unmarshal(contents, &payload))
+	assert.Equal(t, 4, payload.Attempted)
+}
@@ -0,0 +1,130 @@
+package run
+
+import (
+	gocontext "context"
+	"errors"
+	"fmt"
+	"os"
+	"time"
+
+	"github.com/nightlyone/lockfile"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/errcode"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+const (
+	_runLockWait = "wait"
+	_runLockFail = "fail"
+)
+
+// _runLockPollInterval is how often a run waiting on the run lock checks whether it has
+// been released
+var _runLockPollInterval = 500 * time.Millisecond
+
+// runLockValue implements a flag that selects what a run does when another run holds
+// the run lock (--run-lock=wait or --run-lock=fail)
+type runLockValue struct {
+	opts *runOpts
+}
+
+var _ pflag.Value = &runLockValue{}
+
+func (v *runLockValue) String() string {
+	return v.opts.runLock
+}
+
+func (v *runLockValue) Set(value string) error {
+	if value != _runLockWait && value != _runLockFail {
+		return fmt.Errorf("invalid run lock mode %q, expected %v or %v", value, _runLockWait, _runLockFail)
+	}
+	v.opts.runLock = value
+	return nil
+}
+
+func (v *runLockValue) Type() string {
+	return "wait|fail"
+}
+
+func getRunLockPath(repoRoot turbopath.AbsolutePath) turbopath.AbsolutePath {
+	return repoRoot.Join(".turbo", "run.lock")
+}
+
+// runLockHolder describes the run that holds the run lock
+type runLockHolder struct {
+	pid       int
+	startedAt time.Time
+}
+
+func (h *runLockHolder) String() string {
+	if h.startedAt.IsZero() {
+		return fmt.Sprintf("process %v", h.pid)
+	}
+	return fmt.Sprintf("process %v, started at %v", h.pid, h.startedAt.Format(time.RFC3339))
+}
+
+// errRunLocked is returned when another run holds the run lock and this run was asked
+// not to wait for it
+type errRunLocked struct {
+	holder *runLockHolder
+}
+
+func (e *errRunLocked) Error() string {
+	return fmt.Sprintf("another turbo run is in progress in this repository (%v). Wait for it to finish, or pass --run-lock=wait", e.holder)
+}
+
+func (e *errRunLocked) ErrorCode() errcode.Code {
+	return errcode.RunLocked
+}
+
+// acquireRunLock takes the repository's run lock, so that two runs can't restore or write
+// the same outputs at the same time. With _runLockWait it waits for the current holder to
+// finish, calling onWait once with the holder first. With _runLockFail it returns an
+// errRunLocked instead. The lock is released when the returned lockfile is unlocked, or
+// when this process exits.
+func acquireRunLock(ctx gocontext.Context, repoRoot turbopath.AbsolutePath, mode string, onWait func(holder *runLockHolder)) (lockfile.Lockfile, error) {
+	lockPath := getRunLockPath(repoRoot)
+	if err := lockPath.EnsureDir(); err != nil {
+		return "", err
+	}
+	lock, err := lockfile.New(lockPath.ToString())
+	if err != nil {
+		return "", fmt.Errorf("failed to create the run lock at %v: %w", lockPath, err)
+	}
+	waiting := false
+	for {
+		err := lock.TryLock()
+		if err == nil {
+			return lock, nil
+		}
+		if !errors.Is(err, lockfile.ErrBusy) {
+			return "", fmt.Errorf("failed to take the run lock at %v: %w", lockPath, err)
+		}
+		holder := readRunLockHolder(lock)
+		if mode != _runLockWait {
+			return "", &errRunLocked{holder: holder}
+		}
+		if !waiting {
+			waiting = true
+			onWait(holder)
+		}
+		select {
+		case <-ctx.Done():
+			return "", ctx.Err()
+		case <-time.After(_runLockPollInterval):
+		}
+	}
+}
+
+// readRunLockHolder reports the run that holds lock. The lockfile only records the
+// holder's PID, so the time it was written stands in for when the run started.
+func readRunLockHolder(lock lockfile.Lockfile) *runLockHolder {
+	holder := &runLockHolder{}
+	if owner, err := lock.GetOwner(); err == nil {
+		holder.pid = owner.Pid
+	}
+	if info, err := os.Stat(string(lock)); err == nil {
+		holder.startedAt = info.ModTime()
+	}
+	return holder
+}
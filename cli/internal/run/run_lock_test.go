@@ -0,0 +1,49 @@
+package run
+
+import (
+	gocontext "context"
+	"fmt"
+	"os"
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestAcquireRunLock(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+
+	lock, err := acquireRunLock(gocontext.Background(), repoRoot, _runLockFail, nil)
+	assert.NoError(t, err)
+	assert.NoError(t, lock.Unlock())
+	assert.False(t, getRunLockPath(repoRoot).FileExists())
+
+	// Pretend our parent process, which is alive for the duration of the test, holds the lock
+	lockPath := getRunLockPath(repoRoot)
+	assert.NoError(t, lockPath.WriteFile([]byte(fmt.Sprintf("%d\n", os.Getppid())), 0644))
+
+	_, err = acquireRunLock(gocontext.Background(), repoRoot, _runLockFail, nil)
+	var lockedErr *errRunLocked
+	assert.ErrorAs(t, err, &lockedErr)
+	assert.Equal(t, os.Getppid(), lockedErr.holder.pid)
+	assert.False(t, lockedErr.holder.startedAt.IsZero())
+
+	ctx, cancel := gocontext.WithTimeout(gocontext.Background(), 50*time.Millisecond)
+	defer cancel()
+	waitedOn := []int{}
+	_, err = acquireRunLock(ctx, repoRoot, _runLockWait, func(holder *runLockHolder) {
+		waitedOn = append(waitedOn, holder.pid)
+	})
+	assert.ErrorIs(t, err, gocontext.DeadlineExceeded)
+	assert.Equal(t, []int{os.Getppid()}, waitedOn)
+}
+
+func TestRunLockValue(t *testing.T) {
+	opts := &runOpts{}
+	value := &runLockValue{opts: opts}
+	assert.NoError(t, value.Set("wait"))
+	assert.Equal(t, _runLockWait, opts.runLock)
+	assert.Error(t, value.Set("sometimes"))
+	assert.Equal(t, _runLockWait, opts.runLock)
+}
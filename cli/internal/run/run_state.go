@@ -2,11 +2,16 @@ package run
 
 import (
 	"fmt"
+	"sort"
+	"strings"
 	"sync"
 	"time"
 
+	"github.com/pyr-sh/dag"
 	"github.com/vercel/turborepo/cli/internal/chrometracing"
+	"github.com/vercel/turborepo/cli/internal/daemonclient"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/runevents"
 	"github.com/vercel/turborepo/cli/internal/ui"
 	"github.com/vercel/turborepo/cli/internal/util"
 
@@ -26,6 +31,9 @@ type RunResult struct {
 	Status RunResultStatus
 	// Error, only populated for failure statuses
 	Err error
+	// FailureArtifact is where the logs and debug outputs of the target were saved when
+	// it failed, if they were
+	FailureArtifact string
 }
 
 // RunResultStatus represents the status of a target when we log a build result.
@@ -38,14 +46,53 @@ const (
 	TargetBuilt
 	TargetCached
 	TargetBuildFailed
+	// TargetSkipped is a target that --skip left out of the run
+	TargetSkipped
 )
 
+// eventStatus returns the status that runevents reports for a finished target
+func (s RunResultStatus) eventStatus() string {
+	switch s {
+	case TargetBuilt:
+		return runevents.StatusBuilt
+	case TargetCached:
+		return runevents.StatusCached
+	case TargetBuildFailed:
+		return runevents.StatusFailed
+	case TargetSkipped:
+		return runevents.StatusSkipped
+	default:
+		return runevents.StatusStopped
+	}
+}
+
+// statusFromEvent is the inverse of eventStatus
+func statusFromEvent(status string) RunResultStatus {
+	switch status {
+	case runevents.StatusBuilt:
+		return TargetBuilt
+	case runevents.StatusCached:
+		return TargetCached
+	case runevents.StatusFailed:
+		return TargetBuildFailed
+	case runevents.StatusSkipped:
+		return TargetSkipped
+	default:
+		return TargetBuildStopped
+	}
+}
+
 type BuildTargetState struct {
 	StartAt time.Time
 
 	Duration time.Duration
+	// TimeSaved is how long the target took when it was cached, if it was restored from
+	// the cache
+	TimeSaved time.Duration
 	// Target which has just changed
 	Label string
+	// Hash is the target's hash, once it has been calculated
+	Hash string
 	// Its current status
 	Status RunResultStatus
 	// Error, only populated for failure statuses
@@ -60,17 +107,32 @@ type RunState struct {
 	// Is the output streaming?
 	Cached    int
 	Attempted int
+	// TimeSaved is the sum of the time saved by the targets that were restored from the cache
+	TimeSaved time.Duration
 
 	startedAt time.Time
+	labels    map[string]string
+	// remoteCacheAccess is what the run could do with the remote cache, if it is linked
+	remoteCacheAccess string
+	// ignoredDependencies are the dependencies that --parallel didn't wait for
+	ignoredDependencies []ignoredDependency
+	// parallelSafe is whether --parallel only ignored dependencies that were restored
+	// from the cache
+	parallelSafe bool
+	// skipped are the tasks that --skip left out of the run
+	skipped []string
+	// Events receives what happens to each task. The run state keeps track of the tasks
+	// through it too.
+	Events *runevents.Bus
 }
 
 // NewRunState creates a RunState instance for tracking events during the
 // course of a run.
-func NewRunState(startedAt time.Time, tracingProfile string) *RunState {
+func NewRunState(startedAt time.Time, tracingProfile string, labels map[string]string) *RunState {
 	if tracingProfile != "" {
 		chrometracing.EnableTracing()
 	}
-	return &RunState{
+	r := &RunState{
 		Success:   0,
 		Failure:   0,
 		Cached:    0,
@@ -78,30 +140,91 @@ func NewRunState(startedAt time.Time, tracingProfile string) *RunState {
 		state:     make(map[string]*BuildTargetState),
 
 		startedAt: startedAt,
+		labels:    labels,
+		Events:    runevents.NewBus(),
 	}
+	r.Events.Subscribe(r.onEvent)
+	return r
 }
 
-func (r *RunState) Run(label string) func(outcome RunResultStatus, err error) {
+// Run records the start of the given task. It returns a function to record the task's
+// outcome, and the task's trace event, which phases of the task can be nested under.
+func (r *RunState) Run(label string) (func(outcome RunResultStatus, err error), *chrometracing.PendingEvent) {
 	start := time.Now()
-	r.add(&RunResult{
-		Time:   start,
-		Label:  label,
-		Status: TargetBuilding,
-	}, label, true)
+	r.Events.Publish(runevents.Event{Type: runevents.TaskScheduled, Time: start, TaskID: label})
 	tracer := chrometracing.Event(label)
 	return func(outcome RunResultStatus, err error) {
 		defer tracer.Done()
 		now := time.Now()
-		result := &RunResult{
+		event := runevents.Event{
+			Type:     runevents.TaskFinished,
 			Time:     now,
+			TaskID:   label,
 			Duration: now.Sub(start),
-			Label:    label,
-			Status:   outcome,
+			Status:   outcome.eventStatus(),
+			Err:      err,
 		}
 		if err != nil {
-			result.Err = fmt.Errorf("running %v failed: %w", label, err)
+			event.Error = err.Error()
+		}
+		r.Events.Publish(event)
+	}, tracer
+}
+
+// onEvent keeps track of the state of each task as it changes
+func (r *RunState) onEvent(event runevents.Event) {
+	switch event.Type {
+	case runevents.TaskScheduled:
+		r.add(&RunResult{
+			Time:   event.Time,
+			Label:  event.TaskID,
+			Status: TargetBuilding,
+		}, event.TaskID, true)
+	case runevents.TaskHashed:
+		r.recordHash(event.TaskID, event.Hash)
+	case runevents.CacheHit:
+		r.recordTimeSaved(event.TaskID, event.TimeSaved)
+	case runevents.FailureCaptured:
+		r.recordFailureArtifact(event.TaskID, event.Artifact)
+	case runevents.TaskFinished:
+		result := &RunResult{
+			Time:     event.Time,
+			Duration: event.Duration,
+			Label:    event.TaskID,
+			Status:   statusFromEvent(event.Status),
+		}
+		if event.Err != nil {
+			result.Err = fmt.Errorf("running %v failed: %w", event.TaskID, event.Err)
 		}
-		r.add(result, label, false)
+		r.add(result, event.TaskID, false)
+	}
+}
+
+// recordHash records the hash of the given task
+func (r *RunState) recordHash(label string, hash string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.Hash = hash
+	}
+}
+
+// recordTimeSaved records how much time restoring the given task from the cache saved
+func (r *RunState) recordTimeSaved(label string, timeSaved time.Duration) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.TimeSaved = timeSaved
+	}
+	r.TimeSaved += timeSaved
+}
+
+// recordFailureArtifact records where the failure artifact of the given task was written
+func (r *RunState) recordFailureArtifact(label string, artifact string) {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	if s, ok := r.state[label]; ok {
+		s.FailureArtifact = artifact
 	}
 }
 
@@ -135,8 +258,8 @@ func (r *RunState) add(result *RunResult, previous string, active bool) {
 }
 
 // Close finishes a trace of a turbo run. The tracing file will be written if applicable,
-// and run stats are written to the terminal
-func (r *RunState) Close(terminal cli.Ui, filename string) error {
+// and run stats, including the critical path through taskGraph, are written to the terminal
+func (r *RunState) Close(terminal cli.Ui, filename string, taskGraph *dag.AcyclicGraph) error {
 	if err := writeChrometracing(filename, terminal); err != nil {
 		terminal.Error(fmt.Sprintf("Error writing tracing data: %v", err))
 	}
@@ -149,10 +272,70 @@ func (r *RunState) Close(terminal cli.Ui, filename string) error {
 	terminal.Output(util.Sprintf("${BOLD} Tasks:${BOLD_GREEN}    %v successful${RESET}${GRAY}, %v total${RESET}", r.Cached+r.Success, r.Attempted))
 	terminal.Output(util.Sprintf("${BOLD}Cached:    %v cached${RESET}${GRAY}, %v total${RESET}", r.Cached, r.Attempted))
 	terminal.Output(util.Sprintf("${BOLD}  Time:    %v${RESET} %v${RESET}", time.Since(r.startedAt).Truncate(time.Millisecond), maybeFullTurbo))
+	if r.TimeSaved > 0 {
+		terminal.Output(util.Sprintf("${BOLD} Saved:    %v${RESET}${GRAY} estimated, across %v cache hits${RESET}", r.TimeSaved.Truncate(time.Millisecond), r.Cached))
+	}
+	// A single task is trivially its own critical path
+	if path := criticalPath(r.state, taskGraph); len(path) > 1 {
+		steps, breakdown := formatCriticalPath(path)
+		terminal.Output(util.Sprintf("${BOLD}  Path:    ${RESET}%v", steps))
+		terminal.Output(util.Sprintf("${GRAY}           %v${RESET}", breakdown))
+	}
+	if r.remoteCacheAccess != "" {
+		terminal.Output(util.Sprintf("${BOLD}Remote:    ${RESET}%v", r.remoteCacheAccess))
+	}
+	if len(r.ignoredDependencies) > 0 {
+		terminal.Output(util.Sprintf("${BOLD}Ignored:   ${RESET}%v", formatIgnoredDependencies(r.ignoredDependencies, r.parallelSafe)))
+	}
+	if len(r.labels) > 0 {
+		terminal.Output(util.Sprintf("${BOLD}Labels:    ${RESET}%v", formatLabels(r.labels)))
+	}
+	if len(r.skipped) > 0 {
+		terminal.Output(util.Sprintf("${BOLD}Skipped:   ${RESET}%v${GRAY}, the outputs of the tasks depending on them weren't cached${RESET}", strings.Join(r.skipped, ", ")))
+	}
+	for i, artifact := range r.failureArtifacts() {
+		heading := "Failures:"
+		if i > 0 {
+			heading = ""
+		}
+		terminal.Output(util.Sprintf("${BOLD}%-11v${RESET}%v", heading, artifact))
+	}
 	terminal.Output("")
 	return nil
 }
 
+// failureArtifacts lists the failure artifacts of the run, by task
+func (r *RunState) failureArtifacts() []string {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	artifacts := []string{}
+	for label, target := range r.state {
+		if target.FailureArtifact != "" {
+			artifacts = append(artifacts, fmt.Sprintf("%v: %v", label, target.FailureArtifact))
+		}
+	}
+	sort.Strings(artifacts)
+	return artifacts
+}
+
+// taskStats summarizes how each task that finished in this run did, for the daemon's metrics
+func (r *RunState) taskStats() []daemonclient.TaskStats {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	stats := []daemonclient.TaskStats{}
+	for label, target := range r.state {
+		if target.Status != TargetBuilt && target.Status != TargetCached && target.Status != TargetBuildFailed {
+			continue
+		}
+		stats = append(stats, daemonclient.TaskStats{
+			TaskID:   label,
+			Status:   target.Status.eventStatus(),
+			Duration: target.Duration,
+		})
+	}
+	return stats
+}
+
 func writeChrometracing(filename string, terminal cli.Ui) error {
 	outputPath := chrometracing.Path()
 	if outputPath == "" {
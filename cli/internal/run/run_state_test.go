@@ -0,0 +1,23 @@
+package run
+
+import (
+	"testing"
+	"time"
+
+	"github.com/stretchr/testify/assert"
+)
+
+func TestRecordTimeSaved(t *testing.T) {
+	runState := NewRunState(time.Now(), "", nil)
+	for _, label := range []string{"web#build", "docs#build"} {
+		done, _ := runState.Run(label)
+		runState.recordTimeSaved(label, 1500*time.Millisecond)
+		done(TargetCached, nil)
+	}
+	done, _ := runState.Run("ui#build")
+	done(TargetBuilt, nil)
+
+	assert.Equal(t, 3*time.Second, runState.TimeSaved)
+	assert.Equal(t, 1500*time.Millisecond, runState.state["web#build"].TimeSaved)
+	assert.Equal(t, time.Duration(0), runState.state["ui#build"].TimeSaved)
+}
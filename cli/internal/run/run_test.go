@@ -33,7 +33,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -48,7 +49,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--scope=foo", "--scope=blah"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -67,7 +69,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--concurrency=12"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 12,
+					concurrency:        12,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -82,7 +85,24 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--concurrency=100%"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: cpus,
+					concurrency:        cpus,
+					restoreConcurrency: 32,
+				},
+				cacheOpts: cache.Opts{
+					Workers: 10,
+				},
+				runcacheOpts: runcache.Opts{},
+				scopeOpts:    scope.Opts{},
+			},
+			[]string{"foo"},
+		},
+		{
+			"restore concurrency",
+			[]string{"foo", "--restore-concurrency=4"},
+			&Opts{
+				runOpts: runOpts{
+					concurrency:        10,
+					restoreConcurrency: 4,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -97,9 +117,10 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--graph=g.png"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
-					graphFile:   "g.png",
-					graphDot:    false,
+					concurrency:        10,
+					restoreConcurrency: 32,
+					graphFile:          "g.png",
+					graphDot:           false,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -114,9 +135,10 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--graph"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
-					graphFile:   "",
-					graphDot:    true,
+					concurrency:        10,
+					restoreConcurrency: 32,
+					graphFile:          "",
+					graphDot:           true,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -131,10 +153,11 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--graph=g.png", "--", "--boop", "zoop"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency:     10,
-					graphFile:       "g.png",
-					graphDot:        false,
-					passThroughArgs: []string{"--boop", "zoop"},
+					concurrency:        10,
+					restoreConcurrency: 32,
+					graphFile:          "g.png",
+					graphDot:           false,
+					passThroughArgs:    []string{"--boop", "zoop"},
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -149,7 +172,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--force"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -166,7 +190,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--remote-only"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers:        10,
@@ -182,7 +207,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--no-cache"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -199,10 +225,11 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--graph=g.png", "--"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency:     10,
-					graphFile:       "g.png",
-					graphDot:        false,
-					passThroughArgs: []string{},
+					concurrency:        10,
+					restoreConcurrency: 32,
+					graphFile:          "g.png",
+					graphDot:           false,
+					passThroughArgs:    []string{},
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -217,7 +244,8 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--filter=bar", "--filter=...[main]"},
 			&Opts{
 				runOpts: runOpts{
-					concurrency: 10,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -234,8 +262,9 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--continue"},
 			&Opts{
 				runOpts: runOpts{
-					continueOnError: true,
-					concurrency:     10,
+					continueOnError:    true,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					Workers: 10,
@@ -250,8 +279,9 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--continue", "--cache-dir=bar"},
 			&Opts{
 				runOpts: runOpts{
-					continueOnError: true,
-					concurrency:     10,
+					continueOnError:    true,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					OverrideDir: "bar",
@@ -267,8 +297,9 @@ func TestParseConfig(t *testing.T) {
 			[]string{"foo", "--continue", "--cache-dir=" + defaultCwd.Join("bar").ToString()},
 			&Opts{
 				runOpts: runOpts{
-					continueOnError: true,
-					concurrency:     10,
+					continueOnError:    true,
+					concurrency:        10,
+					restoreConcurrency: 32,
 				},
 				cacheOpts: cache.Opts{
 					OverrideDir: defaultCwd.Join("bar").ToString(),
@@ -362,3 +393,29 @@ func Test_taskSelfRef(t *testing.T) {
 		t.Fatalf("expected to failed to build task graph: %v", err)
 	}
 }
+
+func Test_taskEnv(t *testing.T) {
+	environ := []string{"PATH=/usr/bin", "API_URL=https://env.example.com"}
+	// The tracker has already left API_URL out of the env file vars
+	envFileVars := map[string]string{"DEBUG": "true", "SENTRY_DSN": "from-env-file"}
+	envInputVars := map[string]string{"API_URL": "https://input.example.com", "SENTRY_DSN": "from-env-input", "SCHEMA_VERSION": "2"}
+
+	got := taskEnv(environ, "abc123", &fs.TaskDefinition{}, envFileVars, envInputVars)
+	assert.Equal(t, []string{
+		"PATH=/usr/bin",
+		"API_URL=https://env.example.com",
+		"TURBO_HASH=abc123",
+		"SCHEMA_VERSION=2",
+		"SENTRY_DSN=from-env-input",
+	}, got)
+
+	got = taskEnv(environ, "abc123", &fs.TaskDefinition{InjectEnvFiles: true}, envFileVars, envInputVars)
+	assert.Equal(t, []string{
+		"PATH=/usr/bin",
+		"API_URL=https://env.example.com",
+		"TURBO_HASH=abc123",
+		"DEBUG=true",
+		"SENTRY_DSN=from-env-file",
+		"SCHEMA_VERSION=2",
+	}, got)
+}
@@ -0,0 +1,71 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+	"strconv"
+	"strings"
+
+	"github.com/pyr-sh/dag"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/xxhash"
+)
+
+// shardValue implements a flag that selects one of several shards of a run (--shard=1/4)
+type shardValue struct {
+	opts *runOpts
+}
+
+var _ pflag.Value = &shardValue{}
+
+func (s *shardValue) String() string {
+	if s.opts.shardCount == 0 {
+		return ""
+	}
+	return fmt.Sprintf("%v/%v", s.opts.shardIndex, s.opts.shardCount)
+}
+
+func (s *shardValue) Set(value string) error {
+	parts := strings.SplitN(value, "/", 2)
+	if len(parts) != 2 {
+		return fmt.Errorf("invalid shard %q, expected <index>/<count>", value)
+	}
+	index, indexErr := strconv.Atoi(parts[0])
+	count, countErr := strconv.Atoi(parts[1])
+	if indexErr != nil || countErr != nil || count < 1 || index < 1 || index > count {
+		return fmt.Errorf("invalid shard %q, expected <index>/<count> with 1 <= index <= count", value)
+	}
+	s.opts.shardIndex = index
+	s.opts.shardCount = count
+	return nil
+}
+
+func (s *shardValue) Type() string {
+	return "index/count"
+}
+
+// shardTasks returns the tasks that no other task depends on and that belong to the
+// given 1-based shard. Tasks are assigned to shards by a hash of their task ID, so every
+// machine computes the same partition without coordinating, and a task stays in the same
+// shard from one commit to the next.
+func shardTasks(taskGraph *dag.AcyclicGraph, index int, count int) []string {
+	taskIDs := []string{}
+	for _, v := range taskGraph.Vertices() {
+		taskID := dag.VertexName(v)
+		if strings.Contains(taskID, core.ROOT_NODE_NAME) || len(taskGraph.UpEdges(v)) > 0 {
+			continue
+		}
+		if shardOf(taskID, count) == index {
+			taskIDs = append(taskIDs, taskID)
+		}
+	}
+	sort.Strings(taskIDs)
+	return taskIDs
+}
+
+func shardOf(taskID string, count int) int {
+	digest := xxhash.New()
+	_, _ = digest.Write([]byte(taskID))
+	return int(digest.Sum64()%uint64(count)) + 1
+}
@@ -0,0 +1,50 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func TestShardValue(t *testing.T) {
+	opts := &runOpts{}
+	value := &shardValue{opts: opts}
+	assert.Equal(t, "", value.String())
+	assert.NoError(t, value.Set("2/4"))
+	assert.Equal(t, 2, opts.shardIndex)
+	assert.Equal(t, 4, opts.shardCount)
+	assert.Equal(t, "2/4", value.String())
+
+	for _, invalid := range []string{"2", "0/4", "5/4", "1/0", "a/b"} {
+		assert.Error(t, value.Set(invalid), invalid)
+	}
+}
+
+func TestShardTasks(t *testing.T) {
+	// Every package's test task depends on its build task, which depends on ROOT
+	var taskGraph dag.AcyclicGraph
+	taskGraph.Add(core.ROOT_NODE_NAME)
+	leaves := []string{}
+	for _, pkg := range []string{"a", "b", "c", "d", "e", "f", "g", "h"} {
+		build, test := util.GetTaskId(pkg, "build"), util.GetTaskId(pkg, "test")
+		taskGraph.Add(build)
+		taskGraph.Add(test)
+		taskGraph.Connect(dag.BasicEdge(build, core.ROOT_NODE_NAME))
+		taskGraph.Connect(dag.BasicEdge(test, build))
+		leaves = append(leaves, test)
+	}
+
+	// Every leaf task ends up in exactly one shard, and intermediate tasks in none
+	sharded := []string{}
+	for index := 1; index <= 3; index++ {
+		tasks := shardTasks(&taskGraph, index, 3)
+		assert.Equal(t, tasks, shardTasks(&taskGraph, index, 3), "shards should be deterministic")
+		sharded = append(sharded, tasks...)
+	}
+	assert.ElementsMatch(t, leaves, sharded)
+
+	assert.Equal(t, leaves, shardTasks(&taskGraph, 1, 1))
+}
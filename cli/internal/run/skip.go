@@ -0,0 +1,62 @@
+package run
+
+import (
+	"fmt"
+	"sort"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// skippedTasks are the tasks that --skip leaves out of a run, and the tasks that depend on
+// them. Those run against whatever outputs the skipped tasks left behind, if any, so what
+// they produce mustn't be cached under hashes that other runs trust.
+type skippedTasks struct {
+	skipped util.Set
+	// uncacheable are the tasks that depend on the skipped ones, directly or not
+	uncacheable util.Set
+}
+
+// newSkippedTasks finds the tasks that depend on the tasks to skip. Every task to skip
+// must be a package task, as <package>#<task>, in the task graph.
+func newSkippedTasks(taskGraph *dag.AcyclicGraph, skip []string) (*skippedTasks, error) {
+	s := &skippedTasks{skipped: make(util.Set), uncacheable: make(util.Set)}
+	for _, taskID := range skip {
+		if !util.IsPackageTask(taskID) {
+			return nil, fmt.Errorf("--skip=%v must be a task in a package, as <package>#<task>", taskID)
+		}
+		if !taskGraph.HasVertex(taskID) {
+			return nil, fmt.Errorf("--skip=%v isn't one of the tasks of this run", taskID)
+		}
+		s.skipped.Add(taskID)
+		dependents, err := taskGraph.Descendents(taskID)
+		if err != nil {
+			return nil, err
+		}
+		for dependent := range dependents {
+			s.uncacheable.Add(dag.VertexName(dependent))
+		}
+	}
+	return s, nil
+}
+
+// isSkipped returns true if --skip leaves the task out of the run
+func (s *skippedTasks) isSkipped(taskID string) bool {
+	return s != nil && s.skipped.Includes(taskID)
+}
+
+// isUncacheable returns true if the task depends on a skipped task, so its outputs mustn't
+// be saved to the cache
+func (s *skippedTasks) isUncacheable(taskID string) bool {
+	return s != nil && s.uncacheable.Includes(taskID)
+}
+
+// list returns the skipped tasks in sorted order
+func (s *skippedTasks) list() []string {
+	if s == nil {
+		return nil
+	}
+	list := s.skipped.UnsafeListOfStrings()
+	sort.Strings(list)
+	return list
+}
@@ -0,0 +1,44 @@
+package run
+
+import (
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+)
+
+func TestNewSkippedTasks(t *testing.T) {
+	// web#test depends on web#build, which depends on ui#build. docs#build depends on ui#build.
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "ui#build", "web#build", "docs#build", "web#test", "api#build"} {
+		taskGraph.Add(v)
+	}
+	taskGraph.Connect(dag.BasicEdge("ui#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("api#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("docs#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("web#test", "web#build"))
+
+	skipped, err := newSkippedTasks(&taskGraph, []string{"ui#build"})
+	assert.NoError(t, err)
+	assert.True(t, skipped.isSkipped("ui#build"))
+	assert.False(t, skipped.isSkipped("web#build"))
+	for _, taskID := range []string{"web#build", "docs#build", "web#test"} {
+		assert.True(t, skipped.isUncacheable(taskID), taskID)
+	}
+	assert.False(t, skipped.isUncacheable("api#build"))
+	assert.False(t, skipped.isUncacheable("ui#build"))
+	assert.Equal(t, []string{"ui#build"}, skipped.list())
+
+	_, err = newSkippedTasks(&taskGraph, []string{"build"})
+	assert.EqualError(t, err, "--skip=build must be a task in a package, as <package>#<task>")
+	_, err = newSkippedTasks(&taskGraph, []string{"admin#build"})
+	assert.EqualError(t, err, "--skip=admin#build isn't one of the tasks of this run")
+
+	// Without --skip, nothing is skipped
+	var none *skippedTasks
+	assert.False(t, none.isSkipped("ui#build"))
+	assert.False(t, none.isUncacheable("web#build"))
+	assert.Empty(t, none.list())
+}
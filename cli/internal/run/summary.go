@@ -0,0 +1,125 @@
+package run
+
+import (
+	_ "embed"
+	"fmt"
+	"html/template"
+	"os"
+	"sort"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// runSummary describes what a run did, task by task
+type runSummary struct {
+	StartedAt time.Time     `json:"startedAt"`
+	Duration  time.Duration `json:"duration"`
+	Attempted int           `json:"attempted"`
+	Cached    int           `json:"cached"`
+	Failed    int           `json:"failed"`
+	// TimeSaved is the time the cache hits took when they were cached
+	TimeSaved time.Duration     `json:"timeSaved"`
+	Labels    map[string]string `json:"labels,omitempty"`
+	// CriticalPath are the tasks that determined how long the run took, in order
+	CriticalPath        []string            `json:"criticalPath"`
+	IgnoredDependencies []ignoredDependency `json:"ignoredDependencies,omitempty"`
+	Tasks               []taskSummary       `json:"tasks"`
+}
+
+// taskSummary describes how a task of a run went. Durations are in nanoseconds in JSON.
+type taskSummary struct {
+	TaskID    string        `json:"taskId"`
+	Package   string        `json:"package"`
+	Task      string        `json:"task"`
+	Hash      string        `json:"hash"`
+	Status    string        `json:"status"`
+	StartedAt time.Time     `json:"startedAt"`
+	Duration  time.Duration `json:"duration"`
+	TimeSaved time.Duration `json:"timeSaved"`
+	Error     string        `json:"error,omitempty"`
+	// FailureArtifact is where the logs and debug outputs of the task were saved, if it failed
+	FailureArtifact string `json:"failureArtifact,omitempty"`
+	// Dependencies are the tasks this one waited for
+	Dependencies []string `json:"dependencies"`
+}
+
+// summary describes the run so far, with the tasks in the order they started
+func (r *RunState) summary(taskGraph *dag.AcyclicGraph) *runSummary {
+	r.mu.Lock()
+	defer r.mu.Unlock()
+	summary := &runSummary{
+		StartedAt:           r.startedAt,
+		Duration:            time.Since(r.startedAt),
+		Attempted:           r.Attempted,
+		Cached:              r.Cached,
+		Failed:              r.Failure,
+		TimeSaved:           r.TimeSaved,
+		Labels:              r.labels,
+		CriticalPath:        []string{},
+		IgnoredDependencies: r.ignoredDependencies,
+		Tasks:               []taskSummary{},
+	}
+	if taskGraph != nil {
+		for _, target := range criticalPath(r.state, taskGraph) {
+			summary.CriticalPath = append(summary.CriticalPath, target.Label)
+		}
+	}
+	for label, target := range r.state {
+		pkg, taskName := util.GetPackageTaskFromId(label)
+		task := taskSummary{
+			TaskID:          label,
+			Package:         pkg,
+			Task:            taskName,
+			Hash:            target.Hash,
+			Status:          target.Status.eventStatus(),
+			StartedAt:       target.StartAt,
+			Duration:        target.Duration,
+			TimeSaved:       target.TimeSaved,
+			FailureArtifact: target.FailureArtifact,
+			Dependencies:    []string{},
+		}
+		if target.Err != nil {
+			task.Error = target.Err.Error()
+		}
+		if taskGraph != nil && taskGraph.HasVertex(label) {
+			for _, dep := range taskGraph.DownEdges(label) {
+				if name := dag.VertexName(dep); name != core.ROOT_NODE_NAME {
+					task.Dependencies = append(task.Dependencies, name)
+				}
+			}
+			sort.Strings(task.Dependencies)
+		}
+		summary.Tasks = append(summary.Tasks, task)
+	}
+	sort.Slice(summary.Tasks, func(i, j int) bool {
+		a, b := summary.Tasks[i], summary.Tasks[j]
+		if !a.StartedAt.Equal(b.StartedAt) {
+			return a.StartedAt.Before(b.StartedAt)
+		}
+		return a.TaskID < b.TaskID
+	})
+	return summary
+}
+
+//go:embed summary_report.html
+var _summaryReportHTML string
+
+// _summaryReport renders a run summary as a page that needs nothing but a browser to
+// view: the styles, the scripts and the summary itself are all inlined
+var _summaryReport = template.Must(template.New("report").Parse(_summaryReportHTML))
+
+// writeHTMLReport renders the run summary into a standalone HTML file at path
+func writeHTMLReport(path string, summary *runSummary) error {
+	f, err := os.Create(path)
+	if err != nil {
+		return err
+	}
+	if err := _summaryReport.Execute(f, summary); err != nil {
+		_ = f.Close()
+		return fmt.Errorf("rendering the report: %w", err)
+	}
+	return f.Close()
+}
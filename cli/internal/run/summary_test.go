@@ -0,0 +1,59 @@
+package run
+
+import (
+	"errors"
+	"os"
+	"path/filepath"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/runevents"
+)
+
+func TestSummary(t *testing.T) {
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "ui#build", "web#build"} {
+		taskGraph.Add(v)
+	}
+	taskGraph.Connect(dag.BasicEdge("ui#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+
+	runState := NewRunState(time.Now(), "", map[string]string{"ci": "true"})
+	done, _ := runState.Run("ui#build")
+	runState.Events.Publish(runevents.Event{Type: runevents.TaskHashed, TaskID: "ui#build", Hash: "abc"})
+	runState.Events.Publish(runevents.Event{Type: runevents.CacheHit, TaskID: "ui#build", TimeSaved: time.Second})
+	done(TargetCached, nil)
+	done, _ = runState.Run("web#build")
+	runState.Events.Publish(runevents.Event{Type: runevents.TaskHashed, TaskID: "web#build", Hash: "def"})
+	done(TargetBuildFailed, errors.New("exit status 1"))
+
+	summary := runState.summary(&taskGraph)
+	assert.Equal(t, 2, summary.Attempted)
+	assert.Equal(t, 1, summary.Cached)
+	assert.Equal(t, 1, summary.Failed)
+	assert.Equal(t, time.Second, summary.TimeSaved)
+	assert.Len(t, summary.Tasks, 2)
+
+	ui, web := summary.Tasks[0], summary.Tasks[1]
+	assert.Equal(t, "ui#build", ui.TaskID)
+	assert.Equal(t, "ui", ui.Package)
+	assert.Equal(t, "build", ui.Task)
+	assert.Equal(t, "abc", ui.Hash)
+	assert.Equal(t, runevents.StatusCached, ui.Status)
+	assert.Equal(t, []string{}, ui.Dependencies)
+	assert.Equal(t, runevents.StatusFailed, web.Status)
+	assert.Equal(t, "running web#build failed: exit status 1", web.Error)
+	assert.Equal(t, []string{"ui#build"}, web.Dependencies)
+
+	path := filepath.Join(t.TempDir(), "report.html")
+	assert.NoError(t, writeHTMLReport(path, summary))
+	contents, err := os.ReadFile(path)
+	assert.NoError(t, err)
+	// The summary is inlined as JSON, so the report works without a server
+	assert.True(t, strings.Contains(string(contents), `"taskId":"web#build"`))
+	assert.True(t, strings.Contains(string(contents), `"hash":"abc"`))
+}
@@ -0,0 +1,160 @@
+package run
+
+import (
+	gocontext "context"
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+
+	"github.com/pkg/errors"
+	"github.com/pyr-sh/dag"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+)
+
+// _taskGraphVersion is bumped whenever a change to the export format would
+// make older exports be misread
+const _taskGraphVersion = 1
+
+// exportedTaskGraph is the fully resolved task graph of a run, in a form that external
+// schedulers can split up and hand back to turbo with --from-task-graph
+type exportedTaskGraph struct {
+	Version    int            `json:"version"`
+	GlobalHash string         `json:"globalHash"`
+	Tasks      []exportedTask `json:"tasks"`
+}
+
+type exportedTask struct {
+	TaskID    string   `json:"taskId"`
+	Task      string   `json:"task"`
+	Package   string   `json:"package"`
+	Hash      string   `json:"hash"`
+	Command   string   `json:"command"`
+	Directory string   `json:"directory"`
+	Outputs   []string `json:"outputs"`
+	Env       []string `json:"env"`
+	// Dependencies are the tasks this task directly depends on
+	Dependencies []string `json:"dependencies"`
+}
+
+// resolveTaskGraph walks the task graph to compute the hash of every task, without
+// running any of them
+func resolveTaskGraph(ctx gocontext.Context, engine *core.Scheduler, g *completeGraph, taskHashes *taskhash.Tracker, rs *runSpec) (*exportedTaskGraph, error) {
+	tasks := []exportedTask{}
+	mu := sync.Mutex{}
+	errs := engine.Execute(g.getPackageTaskVisitor(ctx, func(ctx gocontext.Context, packageTask *nodes.PackageTask) error {
+		deps := engine.TaskGraph.DownEdges(packageTask.TaskID)
+		hash, err := taskHashes.CalculateTaskHash(packageTask, deps, rs.ArgsForTask(packageTask.Task))
+		if err != nil {
+			return err
+		}
+		command, _ := packageTask.Command()
+		directDeps := []string{}
+		for _, dep := range deps {
+			// Don't leak out internal ROOT_NODE_NAME nodes, which are just placeholders
+			if !strings.Contains(dep.(string), core.ROOT_NODE_NAME) {
+				directDeps = append(directDeps, dep.(string))
+			}
+		}
+		sort.Strings(directDeps)
+		env := append([]string{}, packageTask.TaskDefinition.EnvVarDependencies...)
+		sort.Strings(env)
+		mu.Lock()
+		defer mu.Unlock()
+		tasks = append(tasks, exportedTask{
+			TaskID:       packageTask.TaskID,
+			Task:         packageTask.Task,
+			Package:      packageTask.PackageName,
+			Hash:         hash,
+			Command:      command,
+			Directory:    packageTask.Pkg.Dir.ToString(),
+			Outputs:      packageTask.TaskDefinition.Outputs,
+			Env:          env,
+			Dependencies: directDeps,
+		})
+		return nil
+	}), core.ExecOpts{
+		Concurrency: rs.Opts.runOpts.concurrency,
+	})
+	if len(errs) > 0 {
+		return nil, errors.Wrap(errs[0], "failed to resolve task graph")
+	}
+	sort.Slice(tasks, func(i, j int) bool { return tasks[i].TaskID < tasks[j].TaskID })
+	return &exportedTaskGraph{
+		Version:    _taskGraphVersion,
+		GlobalHash: g.GlobalHash,
+		Tasks:      tasks,
+	}, nil
+}
+
+func writeTaskGraph(path turbopath.AbsolutePath, taskGraph *exportedTaskGraph) error {
+	contents, err := json.MarshalIndent(taskGraph, "", "  ")
+	if err != nil {
+		return err
+	}
+	return path.WriteFile(contents, 0644)
+}
+
+func readTaskGraph(path turbopath.AbsolutePath) (*exportedTaskGraph, error) {
+	contents, err := path.ReadFile()
+	if err != nil {
+		return nil, err
+	}
+	taskGraph := &exportedTaskGraph{}
+	if err := json.Unmarshal(contents, taskGraph); err != nil {
+		return nil, fmt.Errorf("%v: %w", path, err)
+	}
+	if taskGraph.Version != _taskGraphVersion {
+		return nil, fmt.Errorf("%v: unsupported task graph version %v, expected %v", path, taskGraph.Version, _taskGraphVersion)
+	}
+	return taskGraph, nil
+}
+
+// checkImportedTaskGraph verifies that every task in an imported graph resolves to the
+// same hash in this checkout, and returns their task IDs. A mismatch means the graph
+// was exported from a different commit or environment.
+func checkImportedTaskGraph(imported *exportedTaskGraph, current *exportedTaskGraph) ([]string, error) {
+	currentHashes := make(map[string]string, len(current.Tasks))
+	for _, task := range current.Tasks {
+		currentHashes[task.TaskID] = task.Hash
+	}
+	taskIDs := make([]string, len(imported.Tasks))
+	for i, task := range imported.Tasks {
+		hash, ok := currentHashes[task.TaskID]
+		if !ok {
+			return nil, fmt.Errorf("task %v is not part of this run", task.TaskID)
+		}
+		if hash != task.Hash {
+			return nil, fmt.Errorf("task %v has hash %v, but the task graph expects %v. Was it exported from a different commit?", task.TaskID, hash, task.Hash)
+		}
+		taskIDs[i] = task.TaskID
+	}
+	return taskIDs, nil
+}
+
+// pruneTaskGraph removes every task from the graph that isn't one of the given tasks or
+// one of their dependencies. Dependencies are kept so that their outputs are in place,
+// usually by restoring them from the cache.
+func pruneTaskGraph(taskGraph *dag.AcyclicGraph, taskIDs []string) error {
+	keep := make(dag.Set)
+	for _, taskID := range taskIDs {
+		keep.Add(taskID)
+		deps, err := taskGraph.Ancestors(taskID)
+		if err != nil {
+			return err
+		}
+		for _, dep := range deps {
+			keep.Add(dep)
+		}
+	}
+	for _, v := range taskGraph.Vertices() {
+		if !keep.Include(v) {
+			taskGraph.Remove(v)
+		}
+	}
+	return nil
+}
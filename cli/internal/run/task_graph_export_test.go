@@ -0,0 +1,63 @@
+package run
+
+import (
+	"sort"
+	"testing"
+
+	"github.com/pyr-sh/dag"
+	"github.com/stretchr/testify/assert"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+func TestPruneTaskGraph(t *testing.T) {
+	// web#test depends on web#build, which depends on ui#build. docs#build depends on ui#build.
+	var taskGraph dag.AcyclicGraph
+	for _, v := range []string{core.ROOT_NODE_NAME, "ui#build", "web#build", "docs#build", "web#test"} {
+		taskGraph.Add(v)
+	}
+	taskGraph.Connect(dag.BasicEdge("ui#build", core.ROOT_NODE_NAME))
+	taskGraph.Connect(dag.BasicEdge("web#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("docs#build", "ui#build"))
+	taskGraph.Connect(dag.BasicEdge("web#test", "web#build"))
+
+	assert.NoError(t, pruneTaskGraph(&taskGraph, []string{"web#test"}))
+	remaining := []string{}
+	for _, v := range taskGraph.Vertices() {
+		remaining = append(remaining, dag.VertexName(v))
+	}
+	sort.Strings(remaining)
+	assert.Equal(t, []string{core.ROOT_NODE_NAME, "ui#build", "web#build", "web#test"}, remaining)
+}
+
+func TestCheckImportedTaskGraph(t *testing.T) {
+	current := &exportedTaskGraph{Version: _taskGraphVersion, Tasks: []exportedTask{
+		{TaskID: "ui#build", Hash: "1111"},
+		{TaskID: "web#build", Hash: "2222"},
+	}}
+
+	taskIDs, err := checkImportedTaskGraph(&exportedTaskGraph{Tasks: []exportedTask{{TaskID: "web#build", Hash: "2222"}}}, current)
+	assert.NoError(t, err)
+	assert.Equal(t, []string{"web#build"}, taskIDs)
+
+	_, err = checkImportedTaskGraph(&exportedTaskGraph{Tasks: []exportedTask{{TaskID: "web#build", Hash: "9999"}}}, current)
+	assert.ErrorContains(t, err, "web#build has hash 2222")
+
+	_, err = checkImportedTaskGraph(&exportedTaskGraph{Tasks: []exportedTask{{TaskID: "docs#build", Hash: "3333"}}}, current)
+	assert.ErrorContains(t, err, "docs#build is not part of this run")
+}
+
+func TestTaskGraphRoundTrip(t *testing.T) {
+	path := fs.AbsolutePathFromUpstream(t.TempDir()).Join("graph.json")
+	exported := &exportedTaskGraph{Version: _taskGraphVersion, GlobalHash: "abcd", Tasks: []exportedTask{
+		{TaskID: "web#build", Task: "build", Package: "web", Hash: "2222", Dependencies: []string{"ui#build"}},
+	}}
+	assert.NoError(t, writeTaskGraph(path, exported))
+	imported, err := readTaskGraph(path)
+	assert.NoError(t, err)
+	assert.Equal(t, exported, imported)
+
+	assert.NoError(t, path.WriteFile([]byte(`{"version": 2, "tasks": []}`), 0644))
+	_, err = readTaskGraph(path)
+	assert.ErrorContains(t, err, "unsupported task graph version 2")
+}
@@ -0,0 +1,84 @@
+package run
+
+import (
+	gocontext "context"
+	"fmt"
+
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/spf13/pflag"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/core"
+	"github.com/vercel/turborepo/cli/internal/signals"
+	"github.com/vercel/turborepo/cli/internal/taskhash"
+)
+
+var _prefetchCmdLong = `
+Download the remote cache artifacts of tasks into the local cache, without
+running the tasks or restoring their outputs.
+
+Tasks are selected the same way as for 'turbo run', so a later run with
+the same tasks and filters gets local cache hits, even without a network
+connection.
+`
+
+// GetPrefetchCmd returns the prefetch command
+func GetPrefetchCmd(helper *cmdutil.Helper, signalWatcher *signals.Watcher) *cobra.Command {
+	var opts *Opts
+	var flags *pflag.FlagSet
+	cmd := &cobra.Command{
+		Use:                   "prefetch <task> [...<task>] [<flags>] -- <args passed to tasks>",
+		Short:                 "Download the remote cache artifacts of tasks into the local cache",
+		Long:                  _prefetchCmdLong,
+		SilenceUsage:          true,
+		SilenceErrors:         true,
+		DisableFlagsInUseLine: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			tasks, passThroughArgs := parseTasksAndPassthroughArgs(args, flags)
+			if len(tasks) == 0 {
+				return errors.New("at least one task must be specified")
+			}
+			opts.runOpts.passThroughArgs = passThroughArgs
+			opts.runOpts.warmCache = true
+			run := configureRun(base, opts, signalWatcher)
+			if err := run.run(cmd.Context(), tasks); err != nil {
+				base.LogError("prefetch failed: %v", err)
+				return err
+			}
+			return nil
+		},
+	}
+	flags = cmd.Flags()
+	opts = optsFromFlags(flags)
+	return cmd
+}
+
+// warmCache downloads the remote artifacts of the tasks in the graph into the local cache,
+// without running anything or restoring outputs into the repository
+func (r *run) warmCache(ctx gocontext.Context, g *completeGraph, rs *runSpec, engine *core.Scheduler, taskHashes *taskhash.Tracker) error {
+	apiClient := r.base.APIClient
+	if !apiClient.IsLinked() {
+		return errors.New("there is no Remote Cache to download artifacts from. Run \"turbo link\" first")
+	}
+	if rs.Opts.cacheOpts.SkipFilesystem {
+		return errors.New("there is no local cache to download artifacts into when using --remote-only")
+	}
+	if access := r.checkRemoteCacheAccess(rs); !access.Read {
+		return errors.New("artifacts can't be downloaded from the Remote Cache")
+	}
+	hashes, errs := cacheableTaskHashes(ctx, g, rs, engine, taskHashes)
+	for _, err := range errs {
+		r.logWarning("Skipped", err)
+	}
+	result, err := cache.Warm(rs.Opts.cacheOpts, r.base.RepoRoot, apiClient, hashes)
+	if err != nil {
+		return err
+	}
+	r.base.UI.Output(fmt.Sprintf("Downloaded %v artifacts. %v were already cached locally, and %v aren't in the Remote Cache", len(result.Downloaded), len(result.Local), len(result.Missing)))
+	return nil
+}
@@ -8,6 +8,7 @@ import (
 	"os"
 	"path/filepath"
 	"strings"
+	"time"
 
 	"github.com/fatih/color"
 	"github.com/hashicorp/go-hclog"
@@ -15,6 +16,7 @@ import (
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/cache"
 	"github.com/vercel/turborepo/cli/internal/colorcache"
+	"github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/globby"
 	"github.com/vercel/turborepo/cli/internal/nodes"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
@@ -32,12 +34,19 @@ type Opts struct {
 	TaskOutputModeOverride *util.TaskOutputMode
 	LogReplayer            LogReplayer
 	OutputWatcher          OutputWatcher
+	AllowedExternalOutputs []string
+	RestoreOnly            []string
+	NonUTF8Paths           fs.NonUTF8PathPolicy
+	// ExternalRepoRoots are the roots of the external repos whose workspaces are in the
+	// package graph. The artifacts of their tasks are relative to the repo they are in.
+	ExternalRepoRoots []turbopath.AbsolutePath
 }
 
 // AddFlags adds the flags relevant to the runcache package to the given FlagSet
 func AddFlags(opts *Opts, flags *pflag.FlagSet) {
 	flags.BoolVar(&opts.SkipReads, "force", false, "Ignore the existing cache (to force execution).")
 	flags.BoolVar(&opts.SkipWrites, "no-cache", false, "Avoid saving task results to the cache. Useful for development/watch tasks.")
+	flags.StringArrayVar(&opts.RestoreOnly, "restore-only", nil, "Only restore the cached outputs matching this glob, relative to each package. Can be specified multiple times.")
 
 	defaultTaskOutputMode, err := util.ToTaskOutputModeString(util.FullTaskOutput)
 	if err != nil {
@@ -54,6 +63,15 @@ output.`,
 		DefValue: defaultTaskOutputMode,
 		Value:    &taskOutputModeValue{opts: opts},
 	})
+	flags.AddFlag(&pflag.Flag{
+		Name: "non-utf8-paths",
+		Usage: `What to do with files whose names aren't valid UTF-8.
+Use "keep" to hash and cache them like any other file.
+Use "skip" to leave them out of hashes and cache
+artifacts, with a warning. Use "error" to fail.`,
+		DefValue: string(fs.NonUTF8PathsKeep),
+		Value:    &nonUTF8PathsValue{opts: opts},
+	})
 	_ = flags.Bool("stream", true, "Unused")
 	if err := flags.MarkDeprecated("stream", "[WARNING] The --stream flag is unnecessary and has been deprecated. It will be removed in future versions of turbo."); err != nil {
 		// fail fast if we've misconfigured our flags
@@ -102,6 +120,29 @@ func (l *taskOutputModeValue) Type() string {
 
 var _ pflag.Value = &taskOutputModeValue{}
 
+type nonUTF8PathsValue struct {
+	opts *Opts
+}
+
+var _ pflag.Value = &nonUTF8PathsValue{}
+
+func (v *nonUTF8PathsValue) String() string {
+	return string(v.opts.NonUTF8Paths)
+}
+
+func (v *nonUTF8PathsValue) Set(value string) error {
+	policy, err := fs.ParseNonUTF8PathPolicy(value)
+	if err != nil {
+		return err
+	}
+	v.opts.NonUTF8Paths = policy
+	return nil
+}
+
+func (v *nonUTF8PathsValue) Type() string {
+	return "keep|skip|error"
+}
+
 // RunCache represents the interface to the cache for a single `turbo run`
 type RunCache struct {
 	taskOutputModeOverride *util.TaskOutputMode
@@ -112,6 +153,11 @@ type RunCache struct {
 	logReplayer            LogReplayer
 	outputWatcher          OutputWatcher
 	colorCache             *colorcache.ColorCache
+	allowedExternalOutputs []string
+	restoreOnly            []string
+	nonUTF8Paths           fs.NonUTF8PathPolicy
+	// externalRepoDirs are the repo-relative roots of the external repos
+	externalRepoDirs []string
 }
 
 // New returns a new instance of RunCache, wrapping the given cache
@@ -125,6 +171,9 @@ func New(cache cache.Cache, repoRoot turbopath.AbsolutePath, opts Opts, colorCac
 		logReplayer:            opts.LogReplayer,
 		outputWatcher:          opts.OutputWatcher,
 		colorCache:             colorCache,
+		allowedExternalOutputs: opts.AllowedExternalOutputs,
+		restoreOnly:            opts.RestoreOnly,
+		nonUTF8Paths:           opts.NonUTF8Paths,
 	}
 	if rc.logReplayer == nil {
 		rc.logReplayer = defaultLogReplayer
@@ -132,6 +181,13 @@ func New(cache cache.Cache, repoRoot turbopath.AbsolutePath, opts Opts, colorCac
 	if rc.outputWatcher == nil {
 		rc.outputWatcher = &NoOpOutputWatcher{}
 	}
+	for _, externalRoot := range opts.ExternalRepoRoots {
+		// A root on another volume can't be made repo-relative. The outputs of its tasks
+		// are then outside of the repo root, and refused.
+		if dir, err := repoRoot.PathTo(externalRoot); err == nil {
+			rc.externalRepoDirs = append(rc.externalRepoDirs, dir)
+		}
+	}
 	return rc
 }
 
@@ -145,18 +201,35 @@ type TaskCache struct {
 	taskOutputMode    util.TaskOutputMode
 	cachingDisabled   bool
 	LogFileName       turbopath.AbsolutePath
+	// rootDir is the repo-relative directory that the paths in the task's artifact are
+	// relative to: the repo root, or the root of the external repo the task's workspace
+	// is in, so that none of them start with ".."
+	rootDir string
+	// artifactGlobs are repoRelativeGlobs, relative to rootDir instead
+	artifactGlobs []string
+	// outputWatcher is the run's output watcher, unless the task is in an external repo,
+	// which the daemon doesn't watch
+	outputWatcher OutputWatcher
+	// outputsErr is set if any of the task's output globs can match files outside
+	// of where the task is allowed to write. We refuse to touch outputs in that case.
+	outputsErr error
 }
 
 // RestoreOutputs attempts to restore output for the corresponding task from the cache. Returns true
-// if successful.
-func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi, logger hclog.Logger) (bool, error) {
+// if successful, along with the time that restoring the outputs saved: how long the task took
+// when its outputs were cached, or 0 if the cache didn't record it.
+func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi, logger hclog.Logger) (bool, time.Duration, error) {
 	if tc.cachingDisabled || tc.rc.readsDisabled {
 		if tc.taskOutputMode != util.NoTaskOutput {
 			terminal.Output(fmt.Sprintf("cache bypass, force executing %s", ui.Dim(tc.hash)))
 		}
-		return false, nil
+		return false, 0, nil
+	}
+	if tc.outputsErr != nil {
+		return false, 0, fmt.Errorf("refusing to restore outputs: %w", tc.outputsErr)
 	}
-	changedOutputGlobs, err := tc.rc.outputWatcher.GetChangedOutputs(ctx, tc.hash, tc.repoRelativeGlobs)
+	var savedMs int
+	changedOutputGlobs, err := tc.outputWatcher.GetChangedOutputs(ctx, tc.hash, tc.repoRelativeGlobs)
 	if err != nil {
 		logger.Warn(fmt.Sprintf("Failed to check if we can skip restoring outputs for %v: %v. Proceeding to check cache", tc.pt.TaskID, err))
 		terminal.Warn(ui.Dim(fmt.Sprintf("Failed to check if we can skip restoring outputs for %v: %v. Proceeding to check cache", tc.pt.TaskID, err)))
@@ -164,32 +237,48 @@ func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi
 	}
 	hasChangedOutputs := len(changedOutputGlobs) > 0
 	if hasChangedOutputs {
-		// Note that we currently don't use the output globs when restoring, but we could in the
-		// future to avoid doing unnecessary file I/O
-		hit, _, _, err := tc.rc.cache.Fetch(tc.rc.repoRoot.ToString(), tc.hash, changedOutputGlobs)
+		// Note that we currently only restore a subset of the artifact when asked to with
+		// --restore-only, but we could restore just the changed output globs in the future
+		// to avoid doing unnecessary file I/O
+		restoreGlobs, err := tc.restoreGlobs()
 		if err != nil {
-			return false, err
+			return false, 0, err
+		}
+		hit, _, duration, err := tc.rc.cache.Fetch(tc.root().ToString(), tc.hash, restoreGlobs)
+		if err != nil {
+			return false, 0, err
 		} else if !hit {
 			if tc.taskOutputMode != util.NoTaskOutput {
 				terminal.Output(fmt.Sprintf("cache miss, executing %s", ui.Dim(tc.hash)))
 			}
-			return false, nil
+			return false, 0, nil
 		}
-		if err := tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs); err != nil {
+		savedMs = duration
+		// After a partial restore, the outputs on disk don't match the artifact, so we
+		// can't skip restoring them next time.
+		if len(restoreGlobs) > 0 {
+			logger.Debug(fmt.Sprintf("Restored outputs matching %v, not marking outputs as cached for %v", restoreGlobs, tc.pt.TaskID))
+		} else if err := tc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs); err != nil {
 			// Don't fail the whole operation just because we failed to watch the outputs
 			logger.Warn(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err))
 			terminal.Warn(ui.Dim(fmt.Sprintf("Failed to mark outputs as cached for %v: %v", tc.pt.TaskID, err)))
 		}
 	} else {
 		logger.Debug(fmt.Sprintf("Skipping cache check for %v, outputs have not changed since previous run.", tc.pt.TaskID))
+		savedMs, _ = cache.Duration(tc.rc.cache, tc.hash)
 	}
+	saved := time.Duration(savedMs) * time.Millisecond
 
 	switch tc.taskOutputMode {
 	// When only showing new task output, cached output should only show the computed hash
 	case util.NewTaskOutput:
 		fallthrough
 	case util.HashTaskOutput:
-		terminal.Output(fmt.Sprintf("cache hit, suppressing output %s", ui.Dim(tc.hash)))
+		if saved > 0 {
+			terminal.Output(fmt.Sprintf("cache hit, suppressing output %s %s", ui.Dim(tc.hash), ui.Dim(fmt.Sprintf("(saved %v)", saved))))
+		} else {
+			terminal.Output(fmt.Sprintf("cache hit, suppressing output %s", ui.Dim(tc.hash)))
+		}
 	case util.FullTaskOutput:
 		logger.Debug("log file", "path", tc.LogFileName)
 		if tc.LogFileName.FileExists() {
@@ -200,7 +289,39 @@ func (tc TaskCache) RestoreOutputs(ctx context.Context, terminal *cli.PrefixedUi
 	default:
 		// NoLogs, do not output anything
 	}
-	return true, nil
+	return true, saved, nil
+}
+
+// restoreGlobs returns the globs, relative to the root of the artifact, to pass to the cache
+// when restoring outputs, or nil if the whole artifact should be restored. The task's log
+// file is always restored so that it can be replayed.
+func (tc TaskCache) restoreGlobs() ([]string, error) {
+	if len(tc.rc.restoreOnly) == 0 {
+		return nil, nil
+	}
+	pkgDir := tc.pt.Pkg.Dir.ToStringDuringMigration()
+	logFile, err := artifactPath(tc.rootDir, tc.pt.RepoRelativeLogFile())
+	if err != nil {
+		return nil, err
+	}
+	restoreGlobs := []string{logFile}
+	for _, glob := range tc.rc.restoreOnly {
+		repoRelativeGlob, err := resolveOutputGlob(pkgDir, glob, tc.rc.allowedExternalOutputs)
+		if err != nil {
+			return nil, fmt.Errorf("invalid --restore-only glob: %w", err)
+		}
+		artifactGlob, err := artifactPath(tc.rootDir, repoRelativeGlob)
+		if err != nil {
+			return nil, fmt.Errorf("invalid --restore-only glob: %w", err)
+		}
+		restoreGlobs = append(restoreGlobs, artifactGlob)
+	}
+	return restoreGlobs, nil
+}
+
+// root returns the directory that the paths in the task's artifact are relative to
+func (tc TaskCache) root() turbopath.AbsolutePath {
+	return tc.rc.repoRoot.Join(tc.rootDir)
 }
 
 // nopWriteCloser is modeled after io.NopCloser, which is for Readers
@@ -223,10 +344,15 @@ func (fwc *fileWriterCloser) Close() error {
 	return fwc.file.Close()
 }
 
+// WritesLogFile returns true if OutputWriter writes the task's output to LogFileName
+func (tc TaskCache) WritesLogFile() bool {
+	return !tc.cachingDisabled && !tc.rc.writesDisabled
+}
+
 // OutputWriter creates a sink suitable for handling the output of the command associated
 // with this task.
 func (tc TaskCache) OutputWriter() (io.WriteCloser, error) {
-	if tc.cachingDisabled || tc.rc.writesDisabled {
+	if !tc.WritesLogFile() {
 		return nopWriteCloser{os.Stdout}, nil
 	}
 	// Setup log file
@@ -266,30 +392,41 @@ func (tc TaskCache) SaveOutputs(ctx context.Context, logger hclog.Logger, termin
 	if tc.cachingDisabled || tc.rc.writesDisabled {
 		return nil
 	}
+	if tc.outputsErr != nil {
+		return fmt.Errorf("refusing to save outputs: %w", tc.outputsErr)
+	}
 
 	logger.Debug("caching output", "outputs", tc.repoRelativeGlobs)
 
-	filesToBeCached, err := globby.GlobFiles(tc.rc.repoRoot.ToStringDuringMigration(), tc.repoRelativeGlobs, _emptyIgnore)
+	root := tc.root()
+	filesToBeCached, err := globby.GlobFiles(root.ToStringDuringMigration(), tc.artifactGlobs, _emptyIgnore)
 	if err != nil {
 		return err
 	}
 
-	relativePaths := make([]string, len(filesToBeCached))
+	relativePaths := make([]string, 0, len(filesToBeCached))
 
-	for index, value := range filesToBeCached {
-		relativePath, err := tc.rc.repoRoot.RelativePathString(value)
+	for _, value := range filesToBeCached {
+		relativePath, err := root.RelativePathString(value)
 		if err != nil {
 			logger.Error("error", err)
 			terminal.Error(fmt.Sprintf("%s%s", ui.ERROR_PREFIX, color.RedString(" %v", fmt.Errorf("File path cannot be made relative: %w", err))))
 			continue
 		}
-		relativePaths[index] = relativePath
+		if allowed, err := tc.rc.nonUTF8Paths.Allow(relativePath); err != nil {
+			return fmt.Errorf("refusing to save outputs: %w", err)
+		} else if !allowed {
+			logger.Warn(fmt.Sprintf("Not caching %q for %v, its name isn't valid UTF-8", relativePath, tc.pt.TaskID))
+			terminal.Warn(ui.Dim(fmt.Sprintf("Not caching %q for %v, its name isn't valid UTF-8", relativePath, tc.pt.TaskID)))
+			continue
+		}
+		relativePaths = append(relativePaths, relativePath)
 	}
 
-	if err = tc.rc.cache.Put(tc.pt.Pkg.Dir.ToStringDuringMigration(), tc.hash, duration, relativePaths); err != nil {
+	if err = tc.rc.cache.Put(root.ToString(), tc.hash, duration, relativePaths); err != nil {
 		return err
 	}
-	err = tc.rc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs)
+	err = tc.outputWatcher.NotifyOutputsWritten(ctx, tc.hash, tc.repoRelativeGlobs)
 	if err != nil {
 		// Don't fail the cache write because we also failed to record it, we will just do
 		// extra I/O in the future restoring files that haven't changed from cache
@@ -299,14 +436,72 @@ func (tc TaskCache) SaveOutputs(ctx context.Context, logger hclog.Logger, termin
 	return nil
 }
 
+// OutputsHash returns a hash of the contents of the task's outputs, not including its
+// log file. Unlike the task hash, it only changes when the files the task produced do.
+func (tc TaskCache) OutputsHash() (string, error) {
+	if tc.outputsErr != nil {
+		return "", tc.outputsErr
+	}
+	// Hash the same files, relative to the same root, as the artifact has
+	logFile := tc.pt.RepoRelativeLogFile()
+	outputGlobs := []string{}
+	for index, glob := range tc.repoRelativeGlobs {
+		if glob != logFile {
+			outputGlobs = append(outputGlobs, tc.artifactGlobs[index])
+		}
+	}
+	root := tc.root()
+	fileHashes := make(map[string]string)
+	if len(outputGlobs) > 0 {
+		files, err := globby.GlobFiles(root.ToStringDuringMigration(), outputGlobs, _emptyIgnore)
+		if err != nil {
+			return "", err
+		}
+		for _, file := range files {
+			relativePath, err := root.RelativePathString(file)
+			if err != nil {
+				return "", err
+			}
+			// Leave out the same files that SaveOutputs does
+			if allowed, err := tc.rc.nonUTF8Paths.Allow(relativePath); err != nil {
+				return "", err
+			} else if !allowed {
+				continue
+			}
+			hash, err := fs.GitLikeHashFile(file)
+			if err != nil {
+				return "", fmt.Errorf("failed to hash output %v: %w", relativePath, err)
+			}
+			fileHashes[filepath.ToSlash(relativePath)] = hash
+		}
+	}
+	return fs.HashObject(fileHashes)
+}
+
 // TaskCache returns a TaskCache instance, providing an interface to the underlying cache specific
 // to this run and the given PackageTask
 func (rc *RunCache) TaskCache(pt *nodes.PackageTask, hash string) TaskCache {
 	logFileName := rc.repoRoot.Join(pt.RepoRelativeLogFile())
 	hashableOutputs := pt.HashableOutputs()
 	repoRelativeGlobs := make([]string, len(hashableOutputs))
+	artifactGlobs := make([]string, len(hashableOutputs))
+	rootDir := rc.artifactRootDir(pt.Pkg.Dir.ToStringDuringMigration())
+	var outputsErr error
 	for index, output := range hashableOutputs {
-		repoRelativeGlobs[index] = filepath.Join(pt.Pkg.Dir.ToStringDuringMigration(), output)
+		repoRelativeGlob, err := resolveOutputGlob(pt.Pkg.Dir.ToStringDuringMigration(), output, rc.allowedExternalOutputs)
+		if err != nil && outputsErr == nil {
+			outputsErr = err
+		}
+		repoRelativeGlobs[index] = repoRelativeGlob
+		artifactGlob, err := artifactPath(rootDir, repoRelativeGlob)
+		if err != nil && outputsErr == nil {
+			outputsErr = err
+		}
+		artifactGlobs[index] = artifactGlob
+	}
+	var outputWatcher OutputWatcher = rc.outputWatcher
+	if rootDir != "." {
+		outputWatcher = &NoOpOutputWatcher{}
 	}
 
 	taskOutputMode := pt.TaskDefinition.OutputMode
@@ -317,12 +512,67 @@ func (rc *RunCache) TaskCache(pt *nodes.PackageTask, hash string) TaskCache {
 	return TaskCache{
 		rc:                rc,
 		repoRelativeGlobs: repoRelativeGlobs,
+		rootDir:           rootDir,
+		artifactGlobs:     artifactGlobs,
+		outputWatcher:     outputWatcher,
 		hash:              hash,
 		pt:                pt,
 		taskOutputMode:    taskOutputMode,
 		cachingDisabled:   !pt.TaskDefinition.ShouldCache,
 		LogFileName:       logFileName,
+		outputsErr:        outputsErr,
+	}
+}
+
+// resolveOutputGlob joins a package-relative output glob onto the package directory. It returns
+// an error if the glob is absolute, or if it can match files outside of the package directory
+// that aren't in one of the repo-relative allowedDirs.
+func resolveOutputGlob(pkgDir string, glob string, allowedDirs []string) (string, error) {
+	repoRelativeGlob := filepath.Join(pkgDir, glob)
+	if filepath.IsAbs(glob) || strings.HasPrefix(filepath.ToSlash(glob), "/") {
+		return repoRelativeGlob, fmt.Errorf("output %v is an absolute path, outputs must be relative to the package directory", glob)
+	}
+	if isWithinDir(pkgDir, repoRelativeGlob) {
+		return repoRelativeGlob, nil
+	}
+	if !isWithinDir(".", repoRelativeGlob) {
+		return repoRelativeGlob, fmt.Errorf("output %v resolves to %v, which is outside of the repository", glob, repoRelativeGlob)
+	}
+	for _, allowedDir := range allowedDirs {
+		if isWithinDir(filepath.FromSlash(allowedDir), repoRelativeGlob) {
+			return repoRelativeGlob, nil
+		}
+	}
+	return repoRelativeGlob, fmt.Errorf("output %v resolves to %v, which is outside of package directory %v. Add it to \"allowedExternalOutputs\" in turbo.json if this is intended", glob, repoRelativeGlob, pkgDir)
+}
+
+// artifactRootDir returns the repo-relative directory that the artifacts of the tasks of the
+// workspace at pkgDir are relative to
+func (rc *RunCache) artifactRootDir(pkgDir string) string {
+	for _, dir := range rc.externalRepoDirs {
+		if isWithinDir(dir, pkgDir) {
+			return dir
+		}
+	}
+	return "."
+}
+
+// artifactPath returns the repo-relative path p relative to rootDir instead. It returns an
+// error if p is outside of rootDir, since the cache can't store it.
+func artifactPath(rootDir string, p string) (string, error) {
+	if !isWithinDir(rootDir, p) {
+		return p, fmt.Errorf("%v is outside of %v, the repository its workspace is in", p, rootDir)
+	}
+	return filepath.Rel(rootDir, p)
+}
+
+// isWithinDir lexically checks if the relative path p is dir or one of its descendants
+func isWithinDir(dir string, p string) bool {
+	rel, err := filepath.Rel(filepath.Clean(dir), filepath.Clean(p))
+	if err != nil {
+		return false
 	}
+	return rel != ".." && !strings.HasPrefix(rel, ".."+string(filepath.Separator))
 }
 
 // defaultLogReplayer will try to replay logs back to the given Ui instance
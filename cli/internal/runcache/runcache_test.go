@@ -0,0 +1,224 @@
+package runcache
+
+import (
+	"context"
+	"path/filepath"
+	"testing"
+
+	"github.com/hashicorp/go-hclog"
+	"github.com/mitchellh/cli"
+	"github.com/vercel/turborepo/cli/internal/analytics"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+func Test_resolveOutputGlob(t *testing.T) {
+	testCases := []struct {
+		name        string
+		pkgDir      string
+		glob        string
+		allowedDirs []string
+		want        string
+		wantErr     bool
+	}{
+		{
+			name:   "package output",
+			pkgDir: filepath.Join("packages", "ui"),
+			glob:   "dist/**",
+			want:   filepath.Join("packages", "ui", "dist", "**"),
+		},
+		{
+			name:   "root package output",
+			pkgDir: "",
+			glob:   "dist/**",
+			want:   filepath.Join("dist", "**"),
+		},
+		{
+			name:   "parent directory that stays in the package",
+			pkgDir: filepath.Join("packages", "ui"),
+			glob:   "dist/../build/**",
+			want:   filepath.Join("packages", "ui", "build", "**"),
+		},
+		{
+			name:    "sibling package",
+			pkgDir:  filepath.Join("packages", "ui"),
+			glob:    "../docs/dist/**",
+			want:    filepath.Join("packages", "docs", "dist", "**"),
+			wantErr: true,
+		},
+		{
+			name:        "allowed sibling package",
+			pkgDir:      filepath.Join("packages", "ui"),
+			glob:        "../docs/dist/**",
+			allowedDirs: []string{"packages/docs/dist"},
+			want:        filepath.Join("packages", "docs", "dist", "**"),
+		},
+		{
+			name:        "allowlist doesn't match a prefix of a directory name",
+			pkgDir:      filepath.Join("packages", "ui"),
+			glob:        "../docs-legacy/**",
+			allowedDirs: []string{"packages/docs"},
+			want:        filepath.Join("packages", "docs-legacy", "**"),
+			wantErr:     true,
+		},
+		{
+			name:        "outside of the repository",
+			pkgDir:      filepath.Join("packages", "ui"),
+			glob:        "../../../etc/**",
+			allowedDirs: []string{"."},
+			want:        filepath.Join("..", "etc", "**"),
+			wantErr:     true,
+		},
+		{
+			name:    "absolute glob",
+			pkgDir:  filepath.Join("packages", "ui"),
+			glob:    "/tmp/**",
+			want:    filepath.Join("packages", "ui", "tmp", "**"),
+			wantErr: true,
+		},
+	}
+	for _, tc := range testCases {
+		t.Run(tc.name, func(t *testing.T) {
+			got, err := resolveOutputGlob(tc.pkgDir, tc.glob, tc.allowedDirs)
+			if (err != nil) != tc.wantErr {
+				t.Fatalf("resolveOutputGlob() error = %v, wantErr %v", err, tc.wantErr)
+			}
+			if got != tc.want {
+				t.Errorf("resolveOutputGlob() got %v, want %v", got, tc.want)
+			}
+		})
+	}
+}
+
+func TestOutputsHash(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile := func(path string, contents string) {
+		file := repoRoot.Join(path)
+		if err := file.EnsureDir(); err != nil {
+			t.Fatalf("EnsureDir: %v", err)
+		}
+		if err := file.WriteFile([]byte(contents), 0644); err != nil {
+			t.Fatalf("WriteFile: %v", err)
+		}
+	}
+	rc := &RunCache{repoRoot: repoRoot}
+	pt := &nodes.PackageTask{
+		TaskID:         "ui#build",
+		Task:           "build",
+		PackageName:    "ui",
+		Pkg:            &fs.PackageJSON{Dir: turbopath.AnchoredSystemPath(filepath.Join("packages", "ui"))},
+		TaskDefinition: &fs.TaskDefinition{Outputs: []string{"dist/**"}},
+	}
+	tc := rc.TaskCache(pt, "some-hash")
+	outputsHash := func() string {
+		hash, err := tc.OutputsHash()
+		if err != nil {
+			t.Fatalf("OutputsHash: %v", err)
+		}
+		return hash
+	}
+
+	writeFile("packages/ui/dist/index.js", "export {}")
+	writeFile("packages/ui/.turbo/turbo-build.log", "first run")
+	first := outputsHash()
+
+	writeFile("packages/ui/.turbo/turbo-build.log", "second run")
+	if second := outputsHash(); second != first {
+		t.Errorf("OutputsHash changed with the log file, got %v want %v", second, first)
+	}
+
+	writeFile("packages/ui/dist/index.js", "export const a = 1")
+	if third := outputsHash(); third == first {
+		t.Errorf("OutputsHash didn't change with the outputs, got %v", third)
+	}
+}
+
+type dummyRecorder struct{}
+
+func (dr *dummyRecorder) LogEvent(payload analytics.EventPayload) {}
+
+func TestExternalWorkspaceRoundTrip(t *testing.T) {
+	root := fs.AbsolutePathFromUpstream(t.TempDir())
+	repoRoot := root.Join("repo")
+	externalRoot := root.Join("design-system")
+	cacheDir := root.Join("cache")
+	outputFile := externalRoot.Join("packages", "ui", "dist", "index.js")
+	if err := outputFile.EnsureDir(); err != nil {
+		t.Fatalf("EnsureDir: %v", err)
+	}
+	if err := outputFile.WriteFile([]byte("export {}"), 0644); err != nil {
+		t.Fatalf("WriteFile: %v", err)
+	}
+	if err := repoRoot.MkdirAll(); err != nil {
+		t.Fatalf("MkdirAll: %v", err)
+	}
+
+	turboCache, err := cache.New(cache.Opts{OverrideDir: cacheDir.ToString(), SkipRemote: true}, repoRoot, nil, &dummyRecorder{}, nil)
+	if err != nil {
+		t.Fatalf("cache.New: %v", err)
+	}
+	rc := New(turboCache, repoRoot, Opts{
+		ExternalRepoRoots: []turbopath.AbsolutePath{externalRoot},
+		LogReplayer:       func(logger hclog.Logger, output cli.Ui, logFile turbopath.AbsolutePath) {},
+	}, nil)
+	pt := &nodes.PackageTask{
+		TaskID:      "ui#build",
+		Task:        "build",
+		PackageName: "ui",
+		Pkg:         &fs.PackageJSON{Dir: turbopath.AnchoredSystemPath(filepath.Join("..", "design-system", "packages", "ui"))},
+		TaskDefinition: &fs.TaskDefinition{
+			Outputs:     []string{"dist/**"},
+			ShouldCache: true,
+			OutputMode:  util.NoTaskOutput,
+		},
+	}
+	tc := rc.TaskCache(pt, "some-hash")
+
+	// The outputs hash covers the files of the artifact, relative to the external repo
+	outputsHash, err := tc.OutputsHash()
+	if err != nil {
+		t.Fatalf("OutputsHash: %v", err)
+	}
+	fileHash, err := fs.GitLikeHashFile(outputFile.ToString())
+	if err != nil {
+		t.Fatalf("GitLikeHashFile: %v", err)
+	}
+	wantOutputsHash, err := fs.HashObject(map[string]string{"packages/ui/dist/index.js": fileHash})
+	if err != nil {
+		t.Fatalf("HashObject: %v", err)
+	}
+	if outputsHash != wantOutputsHash {
+		t.Errorf("OutputsHash got %v, want %v", outputsHash, wantOutputsHash)
+	}
+
+	ctx := context.Background()
+	logger := hclog.NewNullLogger()
+	if err := tc.SaveOutputs(ctx, logger, cli.NewMockUi(), 100); err != nil {
+		t.Fatalf("SaveOutputs: %v", err)
+	}
+
+	// The artifact is relative to the root of the external repo, inside of the cache dir
+	if !cacheDir.Join("some-hash", "packages", "ui", "dist", "index.js").FileExists() {
+		t.Errorf("expected the output to be cached relative to %v", externalRoot)
+	}
+	if cacheDir.Join("design-system").DirExists() {
+		t.Errorf("expected nothing to be written outside of the artifact")
+	}
+
+	if err := outputFile.Remove(); err != nil {
+		t.Fatalf("Remove: %v", err)
+	}
+	hit, _, err := tc.RestoreOutputs(ctx, &cli.PrefixedUi{Ui: cli.NewMockUi()}, logger)
+	if err != nil {
+		t.Fatalf("RestoreOutputs: %v", err)
+	}
+	if !hit {
+		t.Fatalf("expected a cache hit")
+	}
+	if !outputFile.FileExists() {
+		t.Errorf("expected %v to be restored", outputFile)
+	}
+}
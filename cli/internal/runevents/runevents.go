@@ -0,0 +1,136 @@
+// Package runevents delivers what happens during a run, such as tasks starting, hitting
+// the cache and finishing, to subscribers as it happens. The run's own summary is built
+// from these events, and --event-log writes them to a file for other tools.
+package runevents
+
+import (
+	"encoding/json"
+	"io"
+	"sync"
+	"time"
+)
+
+// Type is the kind of an event
+type Type string
+
+// The events of a run, in the order they happen for each task
+const (
+	// RunStarted is sent once the tasks of a run are about to be scheduled
+	RunStarted Type = "run-started"
+	// TaskScheduled is sent when a task has been picked up to run, once the tasks it
+	// depends on have finished
+	TaskScheduled Type = "task-scheduled"
+	// TaskHashed is sent when a task's hash has been calculated
+	TaskHashed Type = "task-hashed"
+	// CacheHit is sent when a task's outputs have been restored from the cache
+	CacheHit Type = "cache-hit"
+	// CacheMiss is sent when the cache has no outputs for a task, so it executes
+	CacheMiss Type = "cache-miss"
+	// TaskExecuting is sent when a task's command starts
+	TaskExecuting Type = "task-executing"
+	// FailureCaptured is sent when the logs and debug outputs of a task that failed have
+	// been saved into a failure artifact
+	FailureCaptured Type = "failure-captured"
+	// TaskFinished is sent when a task is done, whether it executed, was restored from
+	// the cache or failed
+	TaskFinished Type = "task-finished"
+	// RunFinished is sent once every task of a run is done
+	RunFinished Type = "run-finished"
+)
+
+// The statuses of a finished task
+const (
+	StatusBuilt   = "built"
+	StatusCached  = "cached"
+	StatusFailed  = "failed"
+	StatusStopped = "stopped"
+	StatusSkipped = "skipped"
+)
+
+// Event is something that happened during a run
+type Event struct {
+	Type Type      `json:"type"`
+	Time time.Time `json:"time"`
+	// TaskID is the task the event is about, unless it's about the whole run
+	TaskID string `json:"taskId,omitempty"`
+	// Duration is how long the step the event ends took: hashing for TaskHashed,
+	// checking the cache for CacheHit and CacheMiss, the whole task for TaskFinished and
+	// the whole run for RunFinished. It is in nanoseconds in JSON.
+	Duration time.Duration `json:"duration,omitempty"`
+	// Hash is the task's hash, from TaskHashed onwards
+	Hash string `json:"hash,omitempty"`
+	// TimeSaved is how long the task took when its outputs were cached, for CacheHit
+	TimeSaved time.Duration `json:"timeSaved,omitempty"`
+	// Status is how the task ended for TaskFinished, one of the Status constants
+	Status string `json:"status,omitempty"`
+	// Error is why the task failed, for TaskFinished
+	Error string `json:"error,omitempty"`
+	// Artifact is where the failure artifact was written, for FailureCaptured
+	Artifact string `json:"artifact,omitempty"`
+	// Err is the error that Error describes, for subscribers in the same process
+	Err error `json:"-"`
+}
+
+// Handler receives the events it subscribed to. It is called from the goroutine of the
+// task the event is about, so it must be safe to call concurrently, and it shouldn't
+// block, since the task waits for it.
+type Handler func(event Event)
+
+// Bus delivers the events of a run to its subscribers. The zero value has no subscribers.
+type Bus struct {
+	mu       sync.RWMutex
+	handlers map[int]Handler
+	nextID   int
+}
+
+// NewBus returns a bus without subscribers
+func NewBus() *Bus {
+	return &Bus{}
+}
+
+// Subscribe calls handler with every event published from now on, until the returned
+// function is called
+func (b *Bus) Subscribe(handler Handler) (unsubscribe func()) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+	if b.handlers == nil {
+		b.handlers = make(map[int]Handler)
+	}
+	id := b.nextID
+	b.nextID++
+	b.handlers[id] = handler
+	return func() {
+		b.mu.Lock()
+		defer b.mu.Unlock()
+		delete(b.handlers, id)
+	}
+}
+
+// Publish sends an event to every subscriber, setting its time if it isn't set. Publishing
+// to a nil bus does nothing.
+func (b *Bus) Publish(event Event) {
+	if b == nil {
+		return
+	}
+	if event.Time.IsZero() {
+		event.Time = time.Now()
+	}
+	b.mu.RLock()
+	defer b.mu.RUnlock()
+	for _, handler := range b.handlers {
+		handler(event)
+	}
+}
+
+// JSONLines returns a handler that writes each event to w as a line of JSON. Events are
+// written one at a time, so w doesn't need to be safe for concurrent use.
+func JSONLines(w io.Writer) Handler {
+	var mu sync.Mutex
+	encoder := json.NewEncoder(w)
+	return func(event Event) {
+		mu.Lock()
+		defer mu.Unlock()
+		// Failing to write the event log doesn't fail the run
+		_ = encoder.Encode(event)
+	}
+}
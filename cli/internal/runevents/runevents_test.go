@@ -0,0 +1,54 @@
+package runevents
+
+import (
+	"bytes"
+	"encoding/json"
+	"errors"
+	"strings"
+	"testing"
+	"time"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestBus(t *testing.T) {
+	bus := NewBus()
+	var first, second []Type
+	unsubscribeFirst := bus.Subscribe(func(event Event) { first = append(first, event.Type) })
+	bus.Subscribe(func(event Event) {
+		assert.Assert(t, !event.Time.IsZero())
+		second = append(second, event.Type)
+	})
+
+	bus.Publish(Event{Type: RunStarted})
+	unsubscribeFirst()
+	bus.Publish(Event{Type: RunFinished})
+
+	assert.DeepEqual(t, first, []Type{RunStarted})
+	assert.DeepEqual(t, second, []Type{RunStarted, RunFinished})
+
+	var nilBus *Bus
+	nilBus.Publish(Event{Type: RunStarted})
+}
+
+func TestJSONLines(t *testing.T) {
+	var out bytes.Buffer
+	bus := NewBus()
+	bus.Subscribe(JSONLines(&out))
+	when := time.Date(2022, 11, 1, 12, 0, 0, 0, time.UTC)
+	bus.Publish(Event{Type: TaskHashed, Time: when, TaskID: "web#build", Duration: time.Millisecond, Hash: "abc"})
+	bus.Publish(Event{Type: TaskFinished, Time: when, TaskID: "web#build", Status: StatusFailed, Error: "exit status 1", Err: errors.New("exit status 1")})
+
+	lines := strings.Split(strings.TrimSpace(out.String()), "\n")
+	assert.Equal(t, len(lines), 2)
+	assert.Equal(t, lines[0], `{"type":"task-hashed","time":"2022-11-01T12:00:00Z","taskId":"web#build","duration":1000000,"hash":"abc"}`)
+	var finished map[string]interface{}
+	assert.NilError(t, json.Unmarshal([]byte(lines[1]), &finished))
+	assert.DeepEqual(t, finished, map[string]interface{}{
+		"type":   "task-finished",
+		"time":   "2022-11-01T12:00:00Z",
+		"taskId": "web#build",
+		"status": "failed",
+		"error":  "exit status 1",
+	})
+}
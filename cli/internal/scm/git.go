@@ -10,14 +10,67 @@ import (
 	"fmt"
 	"os/exec"
 	"path/filepath"
+	"sort"
 	"strings"
 
 	"github.com/pkg/errors"
+	"github.com/vercel/turborepo/cli/internal/fs"
 )
 
+// _submoduleMode is the mode git reports for a submodule pointer in a tree
+const _submoduleMode = "160000"
+
+// _nullSha is the object name git reports for a side of a diff that doesn't exist,
+// or that refers to the working tree
+const _nullSha = "0000000000000000000000000000000000000000"
+
 // git implements operations on a git repository.
 type git struct {
 	repoRoot string
+	// recurseSubmodules is whether ChangedFiles lists the files that changed inside
+	// submodules, rather than only the paths of the submodules whose commits changed
+	recurseSubmodules bool
+}
+
+func (g *git) setRecurseSubmodules(recurse bool) {
+	g.recurseSubmodules = recurse
+}
+
+// command returns a git command that runs from the repo root, so that refs are those of
+// this repo whichever directory turbo was started from
+func (g *git) command(args ...string) *exec.Cmd {
+	cmd := exec.Command("git", args...)
+	cmd.Dir = g.repoRoot
+	return cmd
+}
+
+func (g *git) listFiles(dir string) ([]string, error) {
+	// Paths are reported relative to the directory git runs in
+	listCmd := g.command("ls-files", "-z", "--cached", "--others", "--exclude-standard", "--", ".")
+	listCmd.Dir = dir
+	out, err := listCmd.Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "listing the files in %v", dir)
+	}
+	deletedCmd := g.command("ls-files", "-z", "--deleted", "--", ".")
+	deletedCmd.Dir = dir
+	deletedOut, err := deletedCmd.Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "listing the deleted files in %v", dir)
+	}
+	deleted := make(map[string]bool)
+	for _, file := range strings.Split(string(deletedOut), "\x00") {
+		deleted[file] = true
+	}
+	files := []string{}
+	for _, file := range strings.Split(string(out), "\x00") {
+		if file == "" || deleted[file] {
+			continue
+		}
+		files = append(files, filepath.FromSlash(file))
+	}
+	sort.Strings(files)
+	return files, nil
 }
 
 // ChangedFiles returns a list of modified files since the given commit, optionally including untracked files.
@@ -28,32 +81,42 @@ func (g *git) ChangedFiles(fromCommit string, toCommit string, includeUntracked
 	relSuffix := []string{"--", relativeTo}
 	command := []string{"diff", "--name-only", toCommit}
 
-	out, err := exec.Command("git", append(command, relSuffix...)...).CombinedOutput()
+	out, err := g.command(append(command, relSuffix...)...).CombinedOutput()
 	if err != nil {
 		return nil, errors.Wrapf(err, "finding changes relative to %v", relativeTo)
 	}
 	files := strings.Split(string(out), "\n")
+	submoduleFiles, err := g.submoduleChangedFiles([]string{toCommit}, relativeTo)
+	if err != nil {
+		return nil, err
+	}
+	files = append(files, submoduleFiles...)
 
 	if fromCommit != "" {
 		// Grab the diff from the merge-base to HEAD using ... syntax.  This ensures we have just
 		// the changes that have occurred on the current branch.
 		command = []string{"diff", "--name-only", fromCommit + "..." + toCommit}
-		out, err = exec.Command("git", append(command, relSuffix...)...).CombinedOutput()
+		out, err = g.command(append(command, relSuffix...)...).CombinedOutput()
 		if err != nil {
 			// Check if we can provide a better error message for non-existent commits.
 			// If we error on the check or can't find it, fall back to whatever error git
 			// reported.
-			if exists, err := commitExists(fromCommit); err == nil && !exists {
+			if exists, err := g.commitExists(fromCommit); err == nil && !exists {
 				return nil, fmt.Errorf("commit %v does not exist", fromCommit)
 			}
 			return nil, errors.Wrapf(err, "git comparing with %v", fromCommit)
 		}
 		committedChanges := strings.Split(string(out), "\n")
 		files = append(files, committedChanges...)
+		submoduleFiles, err := g.submoduleChangedFiles([]string{fromCommit + "..." + toCommit}, relativeTo)
+		if err != nil {
+			return nil, err
+		}
+		files = append(files, submoduleFiles...)
 	}
 	if includeUntracked {
 		command = []string{"ls-files", "--other", "--exclude-standard"}
-		out, err = exec.Command("git", append(command, relSuffix...)...).CombinedOutput()
+		out, err = g.command(append(command, relSuffix...)...).CombinedOutput()
 		if err != nil {
 			return nil, errors.Wrap(err, "finding untracked files")
 		}
@@ -75,8 +138,104 @@ func (g *git) ChangedFiles(fromCommit string, toCommit string, includeUntracked
 	return normalized, nil
 }
 
-func commitExists(commit string) (bool, error) {
-	err := exec.Command("git", "cat-file", "-t", commit).Run()
+// submoduleChangedFiles finds the submodules whose pointers changed in the given diff and
+// lists the files that changed within them, relative to the worktree. The submodule
+// pointer itself is already reported by the diff, so a submodule that isn't checked out
+// locally still marks its owning workspace as changed; we just can't look inside it.
+func (g *git) submoduleChangedFiles(diffArgs []string, relativeTo string) ([]string, error) {
+	if !g.recurseSubmodules || !fs.FileExists(filepath.Join(g.repoRoot, ".gitmodules")) {
+		return nil, nil
+	}
+	command := append([]string{"diff", "--raw", "--no-abbrev"}, diffArgs...)
+	command = append(command, "--", relativeTo)
+	out, err := g.command(command...).CombinedOutput()
+	if err != nil {
+		return nil, errors.Wrap(err, "finding changed submodules")
+	}
+	files := []string{}
+	for _, line := range strings.Split(string(out), "\n") {
+		change, ok := parseSubmoduleChange(line)
+		if !ok {
+			continue
+		}
+		submoduleDir := filepath.Join(g.repoRoot, change.path)
+		if !fs.PathExists(filepath.Join(submoduleDir, ".git")) {
+			continue
+		}
+		var innerCommand []string
+		if change.from == _nullSha {
+			// The submodule was added: everything in it is new
+			innerCommand = []string{"ls-files"}
+		} else if change.to == _nullSha {
+			// Compare against the submodule's working tree
+			innerCommand = []string{"diff", "--name-only", change.from}
+		} else {
+			innerCommand = []string{"diff", "--name-only", change.from, change.to}
+		}
+		innerOut, err := exec.Command("git", append([]string{"-C", submoduleDir}, innerCommand...)...).CombinedOutput()
+		if err != nil {
+			// The commits may not have been fetched in the submodule. The pointer change
+			// is still reported, so fall back to treating the submodule as a single file.
+			continue
+		}
+		for _, f := range strings.Split(string(innerOut), "\n") {
+			f = strings.TrimSpace(f)
+			if f != "" {
+				files = append(files, filepath.ToSlash(filepath.Join(change.path, f)))
+			}
+		}
+	}
+	return files, nil
+}
+
+type submoduleChange struct {
+	path string
+	from string
+	to   string
+}
+
+// parseSubmoduleChange parses a line of `git diff --raw` output, of the form
+// ":<old mode> <new mode> <old sha> <new sha> <status>\t<path>", returning
+// the change if it involves a submodule.
+func parseSubmoduleChange(line string) (submoduleChange, bool) {
+	line = strings.TrimPrefix(line, ":")
+	parts := strings.SplitN(line, "\t", 2)
+	if len(parts) != 2 {
+		return submoduleChange{}, false
+	}
+	fields := strings.Fields(parts[0])
+	if len(fields) < 4 || (fields[0] != _submoduleMode && fields[1] != _submoduleMode) {
+		return submoduleChange{}, false
+	}
+	return submoduleChange{
+		path: strings.TrimSpace(parts[1]),
+		from: fields[2],
+		to:   fields[3],
+	}, true
+}
+
+// PreviousContent returns the contents of the file at the absolute filePath as of the merge base
+// of the two commits.
+func (g *git) PreviousContent(fromCommit string, toCommit string, filePath string) ([]byte, error) {
+	mergeBase := g.command("merge-base", fromCommit, toCommit)
+	out, err := mergeBase.Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "finding the merge base of %v and %v", fromCommit, toCommit)
+	}
+	relativePath, err := filepath.Rel(g.repoRoot, filePath)
+	if err != nil {
+		return nil, errors.Wrapf(err, "unable to determine relative path for %s and %s", g.repoRoot, filePath)
+	}
+	show := g.command("show", strings.TrimSpace(string(out))+":"+filepath.ToSlash(relativePath))
+	contents, err := show.Output()
+	if err != nil {
+		return nil, errors.Wrapf(err, "reading %v at %v", relativePath, fromCommit)
+	}
+	return contents, nil
+}
+
+func (g *git) commitExists(commit string) (bool, error) {
+	err := g.command("cat-file", "-t", commit).Run()
 	if err != nil {
 		exitErr := &exec.ExitError{}
 		if errors.As(err, &exitErr) && exitErr.ExitCode() == 128 {
@@ -0,0 +1,129 @@
+package scm
+
+import (
+	"os"
+	"os/exec"
+	"path/filepath"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func Test_parseSubmoduleChange(t *testing.T) {
+	from := "1111111111111111111111111111111111111111"
+	to := "2222222222222222222222222222222222222222"
+	tests := []struct {
+		name   string
+		line   string
+		want   submoduleChange
+		wantOk bool
+	}{
+		{
+			name:   "moved submodule pointer",
+			line:   ":160000 160000 " + from + " " + to + " M\tpackages/ui/vendor",
+			want:   submoduleChange{path: "packages/ui/vendor", from: from, to: to},
+			wantOk: true,
+		},
+		{
+			name:   "added submodule",
+			line:   ":000000 160000 " + _nullSha + " " + to + " A\tvendor/lib",
+			want:   submoduleChange{path: "vendor/lib", from: _nullSha, to: to},
+			wantOk: true,
+		},
+		{
+			name:   "submodule compared with the working tree",
+			line:   ":160000 160000 " + from + " " + _nullSha + " M\tvendor/lib",
+			want:   submoduleChange{path: "vendor/lib", from: from, to: _nullSha},
+			wantOk: true,
+		},
+		{
+			name: "regular file",
+			line: ":100644 100644 " + from + " " + to + " M\tpackages/ui/index.ts",
+		},
+		{
+			name: "empty line",
+			line: "",
+		},
+		{
+			name: "missing path",
+			line: ":160000 160000 " + from + " " + to + " M",
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			got, ok := parseSubmoduleChange(tt.line)
+			assert.Equal(t, ok, tt.wantOk)
+			assert.Equal(t, got, tt.want)
+		})
+	}
+}
+
+func requireGitCmd(t *testing.T, dir string, args ...string) {
+	t.Helper()
+	cmd := exec.Command("git", append([]string{"-c", "protocol.file.allow=always", "-c", "user.name=test", "-c", "user.email=test@example.com"}, args...)...)
+	cmd.Dir = dir
+	out, err := cmd.CombinedOutput()
+	if err != nil {
+		t.Fatalf("git %v failed: %v %v", args, err, string(out))
+	}
+}
+
+func TestChangedFilesInSubmodules(t *testing.T) {
+	// Directory structure:
+	// <dir>/
+	//   lib/ <- the submodule's repo
+	//   repo/
+	//     packages/ui/vendor/ <- lib, as a submodule
+	dir := t.TempDir()
+	lib := filepath.Join(dir, "lib")
+	assert.NilError(t, os.MkdirAll(lib, 0755))
+	assert.NilError(t, os.WriteFile(filepath.Join(lib, "index.js"), []byte("v1"), 0644))
+	requireGitCmd(t, lib, "init", ".")
+	requireGitCmd(t, lib, "add", ".")
+	requireGitCmd(t, lib, "commit", "-m", "v1")
+
+	repoRoot := filepath.Join(dir, "repo")
+	assert.NilError(t, os.MkdirAll(filepath.Join(repoRoot, "packages", "ui"), 0755))
+	assert.NilError(t, os.WriteFile(filepath.Join(repoRoot, "packages", "ui", "package.json"), []byte("{}"), 0644))
+	requireGitCmd(t, repoRoot, "init", ".")
+	requireGitCmd(t, repoRoot, "submodule", "add", lib, "packages/ui/vendor")
+	requireGitCmd(t, repoRoot, "add", ".")
+	requireGitCmd(t, repoRoot, "commit", "-m", "initial")
+
+	// Move the submodule to a new commit, without committing the new pointer
+	vendor := filepath.Join(repoRoot, "packages", "ui", "vendor")
+	assert.NilError(t, os.WriteFile(filepath.Join(vendor, "index.js"), []byte("v2"), 0644))
+	requireGitCmd(t, vendor, "commit", "-am", "v2")
+
+	g := &git{repoRoot: repoRoot}
+	files, err := g.ChangedFiles("", "HEAD", false, repoRoot)
+	assert.NilError(t, err)
+	assert.DeepEqual(t, files, []string{filepath.Join("packages", "ui", "vendor")})
+
+	RecurseSubmodules(g, true)
+	files, err = g.ChangedFiles("", "HEAD", false, repoRoot)
+	assert.NilError(t, err)
+	assert.DeepEqual(t, files, []string{filepath.Join("packages", "ui", "vendor"), filepath.Join("packages", "ui", "vendor", "index.js")})
+}
+
+func TestListFiles(t *testing.T) {
+	repoRoot := t.TempDir()
+	ui := filepath.Join(repoRoot, "packages", "ui")
+	assert.NilError(t, os.MkdirAll(filepath.Join(ui, "src"), 0755))
+	assert.NilError(t, os.WriteFile(filepath.Join(ui, ".gitignore"), []byte("ignored.ts\n"), 0644))
+	for _, file := range []string{"committed.ts", "deleted.ts", filepath.Join("src", "untracked.ts"), "ignored.ts"} {
+		assert.NilError(t, os.WriteFile(filepath.Join(ui, file), []byte{}, 0644))
+	}
+	assert.NilError(t, os.WriteFile(filepath.Join(repoRoot, "root.ts"), []byte{}, 0644))
+	requireGitCmd(t, repoRoot, "init", ".")
+	requireGitCmd(t, repoRoot, "add", "root.ts", "packages/ui/.gitignore", "packages/ui/committed.ts", "packages/ui/deleted.ts")
+	requireGitCmd(t, repoRoot, "commit", "-m", "initial")
+	assert.NilError(t, os.Remove(filepath.Join(ui, "deleted.ts")))
+
+	files, err := ListFiles(&git{repoRoot: repoRoot}, ui)
+	assert.NilError(t, err)
+	assert.DeepEqual(t, files, []string{".gitignore", "committed.ts", filepath.Join("src", "untracked.ts")})
+
+	_, err = ListFiles(&stub{}, ui)
+	assert.ErrorIs(t, err, ErrCannotListFiles)
+}
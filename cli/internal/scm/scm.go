@@ -7,7 +7,9 @@
 package scm
 
 import (
+	"os/exec"
 	"path/filepath"
+	"strings"
 
 	"github.com/pkg/errors"
 
@@ -20,6 +22,41 @@ var ErrFallback = errors.New("cannot find a .git folder. Falling back to manual
 type SCM interface {
 	// ChangedFiles returns a list of modified files since the given commit, optionally including untracked files.*/
 	ChangedFiles(fromCommit string, toCommit string, includeUntracked bool, relativeTo string) ([]string, error)
+	// PreviousContent returns the contents of the file at the absolute filePath as of the merge base
+	// of the two commits, which is what ChangedFiles compares against.
+	PreviousContent(fromCommit string, toCommit string, filePath string) ([]byte, error)
+}
+
+// submoduleRecurser is implemented by SCMs that can look inside submodules
+type submoduleRecurser interface {
+	setRecurseSubmodules(recurse bool)
+}
+
+// RecurseSubmodules sets whether ChangedFiles lists the files that changed inside checked-out
+// submodules, rather than only the paths of the submodules whose commits changed. SCMs
+// without submodules ignore it.
+func RecurseSubmodules(scm SCM, recurse bool) {
+	if r, ok := scm.(submoduleRecurser); ok {
+		r.setRecurseSubmodules(recurse)
+	}
+}
+
+// fileLister is implemented by SCMs that know which files are ignored
+type fileLister interface {
+	listFiles(dir string) ([]string, error)
+}
+
+// ErrCannotListFiles is returned by ListFiles for SCMs that don't know which files are
+// ignored, in which case the caller has to look at every file itself
+var ErrCannotListFiles = errors.New("cannot tell which files are ignored without a git repository")
+
+// ListFiles returns the files in the absolute directory dir that aren't ignored, relative
+// to dir and sorted: the tracked files that haven't been deleted, and the untracked ones.
+func ListFiles(scm SCM, dir string) ([]string, error) {
+	if l, ok := scm.(fileLister); ok {
+		return l.listFiles(dir)
+	}
+	return nil, ErrCannotListFiles
 }
 
 // newGitSCM returns a new SCM instance for this repo root.
@@ -45,9 +82,30 @@ func newFallback(repoRoot string) (SCM, error) {
 // repository. It does not need to be a git repository, and if
 // it is not, the given path is assumed to be the root.
 func FromInRepo(repoRoot string) (SCM, error) {
+	// Prefer asking git, which understands worktrees, .git files that point elsewhere,
+	// and GIT_DIR / GIT_WORK_TREE overrides.
+	if worktreeRoot, ok := gitWorktreeRoot(repoRoot); ok {
+		return &git{repoRoot: worktreeRoot}, nil
+	}
 	dotGitDir, err := fs.FindupFrom(".git", repoRoot)
 	if err != nil {
 		return nil, err
 	}
 	return newFallback(filepath.Dir(dotGitDir))
 }
+
+// gitWorktreeRoot returns the root of the git working tree containing dir,
+// if there is one and git is available to tell us about it.
+func gitWorktreeRoot(dir string) (string, bool) {
+	cmd := exec.Command("git", "rev-parse", "--show-toplevel")
+	cmd.Dir = dir
+	out, err := cmd.Output()
+	if err != nil {
+		return "", false
+	}
+	root := strings.TrimSpace(string(out))
+	if root == "" {
+		return "", false
+	}
+	return filepath.FromSlash(root), true
+}
@@ -26,6 +26,9 @@ type Resolver struct {
 	PackageInfos map[interface{}]*fs.PackageJSON
 	// SCM                  scm.SCM
 	Cwd string
+	// InvocationDir is the directory turbo was invoked from. Relative directory filters
+	// are resolved against it when it is inside Cwd, and against Cwd otherwise.
+	InvocationDir string
 	// HasGlobalChange      bool
 	PackagesChangedInRange PackagesChangedInRange
 }
@@ -34,8 +37,9 @@ type Resolver struct {
 // the selected packages
 func (r *Resolver) GetPackagesFromPatterns(patterns []string) (util.Set, error) {
 	selectors := []*TargetSelector{}
+	prefix := r.selectorPrefix()
 	for _, pattern := range patterns {
-		selector, err := ParseTargetSelector(pattern, r.Cwd)
+		selector, err := ParseTargetSelector(pattern, prefix)
 		if err != nil {
 			return nil, err
 		}
@@ -48,6 +52,19 @@ func (r *Resolver) GetPackagesFromPatterns(patterns []string) (util.Set, error)
 	return selected.pkgs, nil
 }
 
+// selectorPrefix returns the directory that relative directory filters are resolved against
+func (r *Resolver) selectorPrefix() string {
+	if r.InvocationDir == "" {
+		return r.Cwd
+	}
+	relativePath, err := filepath.Rel(r.Cwd, r.InvocationDir)
+	if err != nil || relativePath == ".." || strings.HasPrefix(relativePath, ".."+string(filepath.Separator)) {
+		// turbo was pointed at this repository from somewhere outside of it
+		return r.Cwd
+	}
+	return r.InvocationDir
+}
+
 func (r *Resolver) GetFilteredPackages(selectors []*TargetSelector) (*SelectedPackages, error) {
 	prodPackageSelectors := []*TargetSelector{}
 	allPackageSelectors := []*TargetSelector{}
@@ -256,6 +273,24 @@ func (r *Resolver) filterNodesWithSelector(selector *TargetSelector) (util.Set,
 			entryPackages = matched
 		}
 	}
+	if selector.manifestPredicate != nil {
+		// find packages whose package.json matches
+		selectorWasUsed = true
+		for name, pkg := range r.PackageInfos {
+			if name != util.RootPkgName && selector.manifestPredicate.matches(pkg) {
+				entryPackages.Add(name)
+			}
+		}
+	}
+	if selector.hasTask != "" {
+		// find packages with a script for the task, since that's what makes it run there
+		selectorWasUsed = true
+		for name, pkg := range r.PackageInfos {
+			if _, ok := pkg.Scripts[selector.hasTask]; ok && name != util.RootPkgName {
+				entryPackages.Add(name)
+			}
+		}
+	}
 	// TODO(gsoltis): we can do this earlier
 	// Check if the selector specified anything
 	if !selectorWasUsed {
@@ -273,15 +308,13 @@ func (r *Resolver) filterSubtreesWithSelector(selector *TargetSelector) (util.Se
 		return nil, err
 	}
 
-	parentDir := ""
-	if selector.parentDir != "" {
-		parentDir = filepath.Join(r.Cwd, selector.parentDir)
-	}
+	// parentDir has already been resolved to an absolute path when parsing the selector
+	parentDir := selector.parentDir
 	entryPackages := make(util.Set)
 	for name, pkg := range r.PackageInfos {
 		if parentDir == "" {
 			entryPackages.Add(name)
-		} else if matches, err := doublestar.PathMatch(parentDir, pkg.Dir.ToStringDuringMigration()); err != nil {
+		} else if matches, err := doublestar.PathMatch(parentDir, filepath.Join(r.Cwd, pkg.Dir.ToStringDuringMigration())); err != nil {
 			return nil, fmt.Errorf("failed to resolve directory relationship %v contains %v: %v", selector.parentDir, pkg.Dir, err)
 		} else if matches {
 			entryPackages.Add(name)
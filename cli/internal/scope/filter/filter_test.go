@@ -278,6 +278,75 @@ func Test_filter(t *testing.T) {
 	})
 }
 
+func Test_filterRelativeToInvocationDir(t *testing.T) {
+	root, err := os.Getwd()
+	if err != nil {
+		t.Fatalf("failed to get working directory: %v", err)
+	}
+	packageJSONs := make(map[interface{}]*fs.PackageJSON)
+	graph := &dag.AcyclicGraph{}
+	for _, dir := range []string{"apps/web", "apps/docs", "packages/ui", "packages/utils"} {
+		name := filepath.Base(dir)
+		graph.Add(name)
+		packageJSONs[name] = &fs.PackageJSON{
+			Name: name,
+			Dir:  turbopath.AnchoredSystemPath(filepath.FromSlash(dir)),
+		}
+	}
+	graph.Connect(dag.BasicEdge("web", "ui"))
+
+	testCases := []struct {
+		name          string
+		invocationDir string
+		patterns      []string
+		expected      []string
+	}{
+		{
+			"glob relative to a nested directory",
+			filepath.Join(root, "apps"),
+			[]string{"./*"},
+			[]string{"web", "docs"},
+		},
+		{
+			"current workspace",
+			filepath.Join(root, "apps", "web"),
+			[]string{"."},
+			[]string{"web"},
+		},
+		{
+			"sibling directory with dependents",
+			filepath.Join(root, "apps", "web"),
+			[]string{"...{../../packages/*}"},
+			[]string{"ui", "utils", "web"},
+		},
+		{
+			"repo root from a nested directory",
+			filepath.Join(root, "packages"),
+			[]string{".."},
+			[]string{util.RootPkgName},
+		},
+		{
+			"invoked from outside the repository",
+			filepath.Dir(root),
+			[]string{"./packages/*"},
+			[]string{"ui", "utils"},
+		},
+	}
+	for _, tc := range testCases {
+		r := &Resolver{
+			Graph:         graph,
+			PackageInfos:  packageJSONs,
+			Cwd:           root,
+			InvocationDir: tc.invocationDir,
+		}
+		pkgs, err := r.GetPackagesFromPatterns(tc.patterns)
+		if err != nil {
+			t.Fatalf("%v: failed to filter packages: %v", tc.name, err)
+		}
+		setMatches(t, tc.name, pkgs, tc.expected)
+	}
+}
+
 func Test_matchScopedPackage(t *testing.T) {
 	root, err := os.Getwd()
 	if err != nil {
@@ -306,6 +375,115 @@ func Test_matchScopedPackage(t *testing.T) {
 	setMatches(t, "match scoped package", pkgs.pkgs, []string{"@foo/bar"})
 }
 
+func Test_matchManifestPredicates(t *testing.T) {
+	root, err := os.Getwd()
+	if err != nil {
+		t.Fatalf("failed to get working directory: %v", err)
+	}
+	packageJSONs := make(map[interface{}]*fs.PackageJSON)
+	graph := &dag.AcyclicGraph{}
+	addPackage := func(name string, rawJSON map[string]interface{}) {
+		graph.Add(name)
+		rawJSON["name"] = name
+		packageJSONs[name] = &fs.PackageJSON{
+			Name:    name,
+			Dir:     turbopath.AnchoredSystemPath(filepath.Join("packages", name)),
+			RawJSON: rawJSON,
+		}
+	}
+	addPackage(util.RootPkgName, map[string]interface{}{"private": true})
+	addPackage("web", map[string]interface{}{
+		"private":  true,
+		"keywords": []interface{}{"frontend", "next"},
+	})
+	addPackage("ui", map[string]interface{}{
+		"keywords":      []interface{}{"frontend"},
+		"publishConfig": map[string]interface{}{"access": "public"},
+		"dependencies":  map[string]interface{}{"react": "^18.0.0"},
+	})
+	addPackage("utils", map[string]interface{}{
+		"private":     false,
+		"description": "Shared frontend and backend helpers",
+	})
+	graph.Connect(dag.BasicEdge("web", "ui"))
+	r := &Resolver{
+		Graph:        graph,
+		PackageInfos: packageJSONs,
+		Cwd:          root,
+	}
+
+	testCases := []struct {
+		selector string
+		expected []string
+	}{
+		{"private=true", []string{"web"}},
+		{"private=false", []string{"ui", "utils"}},
+		{"private!=true", []string{"ui", "utils"}},
+		{"{private:false}", []string{"ui", "utils"}},
+		{"{publishConfig.access:public}...", []string{"ui"}},
+		{"keywords~=frontend", []string{"web", "ui"}},
+		{"description~=backend", []string{"utils"}},
+		{"dependencies~=react", []string{"ui"}},
+		{"publishConfig.access=public", []string{"ui"}},
+		{"publishConfig.access=public...", []string{"ui"}},
+		{"...publishConfig.access=public", []string{"ui", "web"}},
+		{"keywords~=backend", []string{}},
+	}
+	for _, tc := range testCases {
+		pkgs, err := r.GetPackagesFromPatterns([]string{tc.selector})
+		if err != nil {
+			t.Fatalf("%v: failed to filter packages: %v", tc.selector, err)
+		}
+		setMatches(t, tc.selector, pkgs, tc.expected)
+	}
+}
+
+func Test_matchHasTask(t *testing.T) {
+	root, err := os.Getwd()
+	if err != nil {
+		t.Fatalf("failed to get working directory: %v", err)
+	}
+	packageJSONs := make(map[interface{}]*fs.PackageJSON)
+	graph := &dag.AcyclicGraph{}
+	addPackage := func(name string, scripts map[string]string) {
+		graph.Add(name)
+		packageJSONs[name] = &fs.PackageJSON{
+			Name:    name,
+			Dir:     turbopath.AnchoredSystemPath(filepath.Join("packages", name)),
+			Scripts: scripts,
+		}
+	}
+	addPackage(util.RootPkgName, map[string]string{"storybook": "storybook dev"})
+	addPackage("web", map[string]string{"build": "next build"})
+	addPackage("ui", map[string]string{"build": "tsc", "storybook": "storybook dev"})
+	addPackage("utils", nil)
+	graph.Connect(dag.BasicEdge("web", "ui"))
+	graph.Connect(dag.BasicEdge("ui", "utils"))
+	r := &Resolver{
+		Graph:        graph,
+		PackageInfos: packageJSONs,
+		Cwd:          root,
+	}
+
+	testCases := []struct {
+		selector string
+		expected []string
+	}{
+		{"has:task(storybook)", []string{"ui"}},
+		{"has:task(build)", []string{"web", "ui"}},
+		{"has:task(storybook)...", []string{"ui", "utils"}},
+		{"...^has:task(storybook)", []string{"web"}},
+		{"has:task(lint)", []string{}},
+	}
+	for _, tc := range testCases {
+		pkgs, err := r.GetPackagesFromPatterns([]string{tc.selector})
+		if err != nil {
+			t.Fatalf("%v: failed to filter packages: %v", tc.selector, err)
+		}
+		setMatches(t, tc.selector, pkgs, tc.expected)
+	}
+}
+
 func Test_matchExactPackages(t *testing.T) {
 	root, err := os.Getwd()
 	if err != nil {
@@ -442,6 +620,18 @@ func Test_SCM(t *testing.T) {
 			},
 			[]string{"package-1", "package-2", util.RootPkgName},
 		},
+		{
+			"packages in directory whose dependencies changed",
+			[]*TargetSelector{
+				{
+					fromRef:           "HEAD~2",
+					toRefOverride:     "HEAD~1",
+					parentDir:         filepath.Join(root, "package-3"),
+					matchDependencies: true,
+				},
+			},
+			[]string{"package-3"},
+		},
 		{
 			"all changed packages with parent dir exact match",
 			[]*TargetSelector{
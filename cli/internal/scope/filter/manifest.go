@@ -0,0 +1,128 @@
+package filter
+
+import (
+	"fmt"
+	"regexp"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+)
+
+// manifestOperator is how a manifestPredicate compares a package.json field to its value
+type manifestOperator string
+
+const (
+	// manifestEquals matches fields equal to the value
+	manifestEquals manifestOperator = "="
+	// manifestNotEquals matches fields not equal to the value
+	manifestNotEquals manifestOperator = "!="
+	// manifestContains matches arrays with an element equal to the value, objects
+	// with a key equal to the value, and strings containing the value
+	manifestContains manifestOperator = "~="
+)
+
+// manifestPredicate selects packages by a field in their package.json, e.g. `private!=true`,
+// `keywords~=frontend`, or `{private:false}`, which is the same as `private=false`. Nested
+// fields are separated by dots, e.g. `publishConfig.access=public`.
+type manifestPredicate struct {
+	field    []string
+	operator manifestOperator
+	value    string
+}
+
+// Package names can't contain "=", so a selector containing one is unambiguously a predicate
+var manifestPredicateRegex = regexp.MustCompile(`^([A-Za-z_$][\w$-]*(?:\.[A-Za-z_$][\w$-]*)*)(!=|~=|=)(.*)$`)
+
+// manifestBracesRegex matches the `{private:false}` form of `private=false`. Directory
+// selectors are in braces too, so the value can't contain a path separator, and a
+// directory with a ":" in its name can still be selected as `{./name:with-colon}`.
+var manifestBracesRegex = regexp.MustCompile(`^\{([A-Za-z_$][\w$-]*(?:\.[A-Za-z_$][\w$-]*)*):([^/\\{}]*)\}$`)
+
+// parseManifestPredicate returns the predicate in selector, or nil if selector isn't
+// a manifest predicate
+func parseManifestPredicate(selector string) *manifestPredicate {
+	if matches := manifestBracesRegex.FindStringSubmatch(selector); matches != nil {
+		return &manifestPredicate{
+			field:    strings.Split(matches[1], "."),
+			operator: manifestEquals,
+			value:    matches[2],
+		}
+	}
+	matches := manifestPredicateRegex.FindStringSubmatch(selector)
+	if matches == nil {
+		return nil
+	}
+	return &manifestPredicate{
+		field:    strings.Split(matches[1], "."),
+		operator: manifestOperator(matches[2]),
+		value:    matches[3],
+	}
+}
+
+// matches returns true if the given package's manifest satisfies the predicate
+func (mp *manifestPredicate) matches(pkg *fs.PackageJSON) bool {
+	value, ok := lookupManifestField(pkg.RawJSON, mp.field)
+	switch mp.operator {
+	case manifestEquals:
+		return mp.equals(value, ok)
+	case manifestNotEquals:
+		return !mp.equals(value, ok)
+	case manifestContains:
+		return ok && mp.contains(value)
+	}
+	return false
+}
+
+// equals compares a field to the predicate's value. Fields that aren't set are
+// equal to "false", since that is what boolean fields such as "private" default to.
+func (mp *manifestPredicate) equals(value interface{}, ok bool) bool {
+	if !ok {
+		return mp.value == "false"
+	}
+	return formatManifestValue(value) == mp.value
+}
+
+func (mp *manifestPredicate) contains(value interface{}) bool {
+	switch v := value.(type) {
+	case []interface{}:
+		for _, element := range v {
+			if formatManifestValue(element) == mp.value {
+				return true
+			}
+		}
+		return false
+	case map[string]interface{}:
+		_, ok := v[mp.value]
+		return ok
+	case string:
+		return strings.Contains(v, mp.value)
+	}
+	return false
+}
+
+func lookupManifestField(manifest map[string]interface{}, field []string) (interface{}, bool) {
+	var value interface{} = manifest
+	for _, key := range field {
+		object, ok := value.(map[string]interface{})
+		if !ok {
+			return nil, false
+		}
+		value, ok = object[key]
+		if !ok {
+			return nil, false
+		}
+	}
+	return value, true
+}
+
+// formatManifestValue renders a JSON value the way it would be written in a predicate
+func formatManifestValue(value interface{}) string {
+	switch v := value.(type) {
+	case nil:
+		return "null"
+	case string:
+		return v
+	default:
+		return fmt.Sprintf("%v", v)
+	}
+}
@@ -1,7 +1,7 @@
 package filter
 
 import (
-	"errors"
+	"fmt"
 	"path/filepath"
 	"regexp"
 	"strings"
@@ -16,13 +16,16 @@ type TargetSelector struct {
 	followProdDepsOnly  bool
 	parentDir           string
 	namePattern         string
+	manifestPredicate   *manifestPredicate
+	// hasTask selects the packages that have a script for this task
+	hasTask             string
 	fromRef             string
 	toRefOverride       string
 	raw                 string
 }
 
 func (ts *TargetSelector) IsValid() bool {
-	return ts.fromRef != "" || ts.parentDir != "" || ts.namePattern != ""
+	return ts.fromRef != "" || ts.parentDir != "" || ts.namePattern != "" || ts.manifestPredicate != nil || ts.hasTask != ""
 }
 
 // getToRef returns the git ref to use for upper bound of the comparison when finding changed
@@ -34,18 +37,22 @@ func (ts *TargetSelector) getToRef() string {
 	return ts.toRefOverride
 }
 
-var errCantMatchDependencies = errors.New("cannot use match dependencies without specifying either a directory or package")
-
 var targetSelectorRegex = regexp.MustCompile(`^([^.](?:[^{}[\]]*[^{}[\].])?)?(\{[^}]+\})?((?:\.{3})?\[[^\]]+\])?$`)
 
 // ParseTargetSelector is a function that returns pnpm compatible --filter command line flags
 func ParseTargetSelector(rawSelector string, prefix string) (TargetSelector, error) {
+	if rawSelector == "" {
+		return TargetSelector{}, &SelectorError{Message: "empty filter", Help: "pass a package name, a directory or a git range, e.g. --filter=my-app"}
+	}
 	exclude := false
 	firstChar := rawSelector[0]
 	selector := rawSelector
+	// offset is where selector starts within rawSelector
+	offset := 0
 	if firstChar == '!' {
 		selector = selector[1:]
 		exclude = true
+		offset++
 	}
 	excludeSelf := false
 	includeDependencies := strings.HasSuffix(selector, "...")
@@ -59,9 +66,43 @@ func ParseTargetSelector(rawSelector string, prefix string) (TargetSelector, err
 	includeDependents := strings.HasPrefix(selector, "...")
 	if includeDependents {
 		selector = selector[3:]
+		offset += 3
 		if strings.HasPrefix(selector, "^") {
 			excludeSelf = true
 			selector = selector[1:]
+			offset++
+		}
+	}
+
+	if strings.HasPrefix(selector, _hasTaskPrefix) {
+		task, err := parseHasTask(rawSelector, selector, offset)
+		if err != nil {
+			return TargetSelector{}, err
+		}
+		return TargetSelector{
+			exclude:             exclude,
+			excludeSelf:         excludeSelf,
+			includeDependencies: includeDependencies,
+			includeDependents:   includeDependents,
+			hasTask:             task,
+			raw:                 rawSelector,
+		}, nil
+	}
+
+	if predicate := parseManifestPredicate(selector); predicate != nil {
+		return TargetSelector{
+			exclude:             exclude,
+			excludeSelf:         excludeSelf,
+			includeDependencies: includeDependencies,
+			includeDependents:   includeDependents,
+			manifestPredicate:   predicate,
+			raw:                 rawSelector,
+		}, nil
+	}
+
+	if selector == "" || !isSelectorByLocation(selector) {
+		if err := checkSelectorSyntax(rawSelector, selector, offset); err != nil {
+			return TargetSelector{}, err
 		}
 	}
 
@@ -104,7 +145,13 @@ func ParseTargetSelector(rawSelector string, prefix string) (TargetSelector, err
 			fromRef = matches[0][3]
 			if strings.HasPrefix(fromRef, "...") {
 				if parentDir == "" && namePattern == "" {
-					return TargetSelector{}, errCantMatchDependencies
+					return TargetSelector{}, &SelectorError{
+						Selector: rawSelector,
+						Offset:   offset + len(matches[0][1]) + len(matches[0][2]),
+						Length:   3,
+						Message:  "cannot use match dependencies without specifying either a directory or package",
+						Help:     `put a package name or directory before "...[", e.g. my-app...[main]`,
+					}
 				}
 				preAddDepdencies = true
 				fromRef = fromRef[3:]
@@ -133,6 +180,33 @@ func ParseTargetSelector(rawSelector string, prefix string) (TargetSelector, err
 	}, nil
 }
 
+// _hasTaskPrefix starts a selector for the packages that have a given task, e.g. has:task(build)
+const _hasTaskPrefix = "has:"
+
+// parseHasTask returns the task named by a has:task(<name>) selector. selector is what is
+// left of raw once the leading "!" and the "..." and "^" around it were stripped, starting
+// at offset.
+func parseHasTask(raw string, selector string, offset int) (string, error) {
+	const help = `name the task in parentheses, e.g. has:task(build)`
+	rest := strings.TrimPrefix(selector, _hasTaskPrefix)
+	if !strings.HasPrefix(rest, "task(") {
+		return "", &SelectorError{Selector: raw, Offset: offset + len(_hasTaskPrefix), Length: len(rest), Message: `expected "task(" after "has:"`, Help: help}
+	}
+	name := strings.TrimPrefix(rest, "task(")
+	nameOffset := offset + len(selector) - len(name)
+	end := strings.IndexByte(name, ')')
+	if end < 0 {
+		return "", &SelectorError{Selector: raw, Offset: nameOffset - 1, Length: len(name) + 1, Message: `unclosed "("`, Help: help}
+	}
+	if end == 0 {
+		return "", &SelectorError{Selector: raw, Offset: nameOffset - 1, Length: 2, Message: "empty task name", Help: help}
+	}
+	if trailing := name[end+1:]; trailing != "" {
+		return "", &SelectorError{Selector: raw, Offset: nameOffset + end + 1, Length: len(trailing), Message: fmt.Sprintf("unexpected %q after the task", trailing), Help: `use "..." to include dependencies or dependents, e.g. has:task(build)...`}
+	}
+	return name[:end], nil
+}
+
 // isSelectorByLocation returns true if the selector is by filesystem location
 func isSelectorByLocation(rawSelector string) bool {
 	if rawSelector[0:1] != "." {
@@ -1,6 +1,7 @@
 package filter
 
 import (
+	"errors"
 	"path/filepath"
 	"reflect"
 	"testing"
@@ -312,6 +313,74 @@ func TestParseTargetSelector(t *testing.T) {
 			TargetSelector{},
 			true,
 		},
+		{
+			"private!=true",
+			args{"private!=true", "."},
+			TargetSelector{
+				manifestPredicate: &manifestPredicate{
+					field:    []string{"private"},
+					operator: manifestNotEquals,
+					value:    "true",
+				},
+			},
+			false,
+		},
+		{
+			"!publishConfig.access=public...",
+			args{"!publishConfig.access=public...", "."},
+			TargetSelector{
+				exclude:             true,
+				includeDependencies: true,
+				manifestPredicate: &manifestPredicate{
+					field:    []string{"publishConfig", "access"},
+					operator: manifestEquals,
+					value:    "public",
+				},
+			},
+			false,
+		},
+		{
+			"{private:false}",
+			args{"{private:false}", "."},
+			TargetSelector{
+				manifestPredicate: &manifestPredicate{
+					field:    []string{"private"},
+					operator: manifestEquals,
+					value:    "false",
+				},
+			},
+			false,
+		},
+		{
+			"...{publishConfig.access:public}",
+			args{"...{publishConfig.access:public}", "."},
+			TargetSelector{
+				includeDependents: true,
+				manifestPredicate: &manifestPredicate{
+					field:    []string{"publishConfig", "access"},
+					operator: manifestEquals,
+					value:    "public",
+				},
+			},
+			false,
+		},
+		{
+			"{./apps:old}",
+			args{"{./apps:old}", "."},
+			TargetSelector{
+				parentDir: "apps:old",
+			},
+			false,
+		},
+		{
+			"...has:task(storybook)",
+			args{"...has:task(storybook)", "."},
+			TargetSelector{
+				includeDependents: true,
+				hasTask:           "storybook",
+			},
+			false,
+		},
 	}
 	for _, tt := range tests {
 		t.Run(tt.name, func(t *testing.T) {
@@ -331,3 +400,94 @@ func TestParseTargetSelector(t *testing.T) {
 		})
 	}
 }
+
+func TestParseTargetSelectorErrors(t *testing.T) {
+	tests := []struct {
+		rawSelector string
+		want        string
+	}{
+		{
+			"foo{./apps",
+			`invalid filter "foo{./apps": unclosed "{"
+    foo{./apps
+       ^^^^^^^
+  help: close the directory with "}", e.g. {./apps/*}`,
+		},
+		{
+			"...foo]",
+			`invalid filter "...foo]": unexpected "]"
+    ...foo]
+          ^
+  help: a git range starts with "[", e.g. [main]`,
+		},
+		{
+			"!foo[]",
+			`invalid filter "!foo[]": empty git range
+    !foo[]
+        ^^
+  help: put a git ref between the brackets, e.g. [main] or [main...my-branch]`,
+		},
+		{
+			"[main]{./apps/*}",
+			`invalid filter "[main]{./apps/*}": unexpected "{./apps/*}" after the git range
+    [main]{./apps/*}
+          ^^^^^^^^^^
+  help: the package name and directory go before the git range, e.g. my-app{./apps/*}[main]`,
+		},
+		{
+			"^foo",
+			`invalid filter "^foo": unexpected "^"
+    ^foo
+    ^
+  help: "^" excludes the selected packages themselves and goes next to "...", e.g. ...^my-lib or my-app^...`,
+		},
+		{
+			"......[main]",
+			`invalid filter "......[main]": cannot use match dependencies without specifying either a directory or package
+    ......[main]
+       ^^^
+  help: put a package name or directory before "...[", e.g. my-app...[main]`,
+		},
+		{
+			"has:script(build)",
+			`invalid filter "has:script(build)": expected "task(" after "has:"
+    has:script(build)
+        ^^^^^^^^^^^^^
+  help: name the task in parentheses, e.g. has:task(build)`,
+		},
+		{
+			"!has:task()",
+			`invalid filter "!has:task()": empty task name
+    !has:task()
+             ^^
+  help: name the task in parentheses, e.g. has:task(build)`,
+		},
+		{
+			"has:task(build)[main]",
+			`invalid filter "has:task(build)[main]": unexpected "[main]" after the task
+    has:task(build)[main]
+                   ^^^^^^
+  help: use "..." to include dependencies or dependents, e.g. has:task(build)...`,
+		},
+	}
+	for _, tt := range tests {
+		t.Run(tt.rawSelector, func(t *testing.T) {
+			_, err := ParseTargetSelector(tt.rawSelector, ".")
+			var selectorErr *SelectorError
+			if !errors.As(err, &selectorErr) {
+				t.Fatalf("ParseTargetSelector() error = %v, want a SelectorError", err)
+			}
+			if err.Error() != tt.want {
+				t.Errorf("ParseTargetSelector() error =\n%v\nwant\n%v", err, tt.want)
+			}
+		})
+	}
+}
+
+func TestParseTargetSelectorBraceGlobs(t *testing.T) {
+	for _, rawSelector := range []string{"./apps/{web,docs}/*", "[HEAD@{1}]", "{./apps/*}[main]"} {
+		if _, err := ParseTargetSelector(rawSelector, "."); err != nil {
+			t.Errorf("ParseTargetSelector(%q) error = %v", rawSelector, err)
+		}
+	}
+}
@@ -0,0 +1,115 @@
+package filter
+
+import (
+	"fmt"
+	"strings"
+	"unicode/utf8"
+
+	"github.com/vercel/turborepo/cli/internal/errcode"
+)
+
+// SelectorError describes a malformed --filter expression. It renders the expression with
+// a caret under the part that couldn't be parsed, and a suggestion when there is one.
+type SelectorError struct {
+	// Selector is the filter expression as it was given
+	Selector string
+	// Offset and Length locate the offending part of Selector, in bytes
+	Offset int
+	Length int
+	// Message says what is wrong
+	Message string
+	// Help, if set, says how to fix it
+	Help string
+}
+
+var _ errcode.Coded = &SelectorError{}
+
+func (e *SelectorError) Error() string {
+	// Carets are lined up by character rather than by byte, for selectors that aren't ASCII
+	indent := utf8.RuneCountInString(e.Selector[:e.Offset])
+	width := utf8.RuneCountInString(e.Selector[e.Offset : e.Offset+e.Length])
+	if width == 0 {
+		width = 1
+	}
+	var b strings.Builder
+	fmt.Fprintf(&b, "invalid filter %q: %v\n", e.Selector, e.Message)
+	fmt.Fprintf(&b, "    %v\n", e.Selector)
+	fmt.Fprintf(&b, "    %v%v", strings.Repeat(" ", indent), strings.Repeat("^", width))
+	if e.Help != "" {
+		fmt.Fprintf(&b, "\n  help: %v", e.Help)
+	}
+	return b.String()
+}
+
+// ErrorCode returns the code of the error
+func (e *SelectorError) ErrorCode() errcode.Code {
+	return errcode.FilterSyntax
+}
+
+// checkSelectorSyntax finds the mistakes in selector that would otherwise make it a name
+// pattern that matches nothing, such as unbalanced brackets or a misplaced "^". selector
+// is what is left of raw once the leading "!" and the "..." and "^" around it were
+// stripped, starting at offset.
+func checkSelectorSyntax(raw string, selector string, offset int) error {
+	fail := func(at int, length int, message string, help string) error {
+		return &SelectorError{Selector: raw, Offset: offset + at, Length: length, Message: message, Help: help}
+	}
+	if selector == "" {
+		return fail(0, 0, "expected a package name, a directory or a git range", `e.g. my-app, {./apps/*} or [main]`)
+	}
+	// open is the offset of the "{" or "[" that hasn't been closed yet, or -1. Directories
+	// can hold brace globs, so depth counts the braces that are open within one.
+	open := -1
+	closer := byte(0)
+	depth := 0
+	for i := 0; i < len(selector); i++ {
+		c := selector[i]
+		if open >= 0 {
+			if closer == '}' && c == '{' {
+				depth++
+				continue
+			}
+			if c != closer {
+				continue
+			}
+			if depth > 0 {
+				depth--
+				continue
+			}
+			if i == open+1 {
+				if closer == '}' {
+					return fail(open, 2, "empty directory", `put a directory between the braces, e.g. {./apps/*}`)
+				}
+				return fail(open, 2, "empty git range", `put a git ref between the brackets, e.g. [main] or [main...my-branch]`)
+			}
+			open = -1
+			rest := selector[i+1:]
+			if closer == ']' && rest != "" {
+				return fail(i+1, len(rest), fmt.Sprintf("unexpected %q after the git range", rest), `the package name and directory go before the git range, e.g. my-app{./apps/*}[main]`)
+			}
+			if closer == '}' && rest != "" && !strings.HasPrefix(rest, "[") && !strings.HasPrefix(rest, "...[") {
+				return fail(i+1, len(rest), fmt.Sprintf("unexpected %q after the directory", rest), `only a git range can follow the directory, e.g. {./apps/*}[main]`)
+			}
+			continue
+		}
+		switch c {
+		case '{':
+			open, closer = i, '}'
+		case '[':
+			open, closer = i, ']'
+		case '}':
+			return fail(i, 1, `unexpected "}"`, `a directory starts with "{", e.g. {./apps/*}`)
+		case ']':
+			return fail(i, 1, `unexpected "]"`, `a git range starts with "[", e.g. [main]`)
+		case '^':
+			return fail(i, 1, `unexpected "^"`, `"^" excludes the selected packages themselves and goes next to "...", e.g. ...^my-lib or my-app^...`)
+		}
+	}
+	if open >= 0 {
+		if closer == '}' {
+			return fail(open, len(selector)-open, `unclosed "{"`, `close the directory with "}", e.g. {./apps/*}`)
+		}
+		return fail(open, len(selector)-open, `unclosed "["`, `close the git range with "]", e.g. [main]`)
+	}
+	return nil
+}
@@ -2,7 +2,6 @@ package scope
 
 import (
 	"fmt"
-	"os"
 	"path/filepath"
 	"strings"
 
@@ -12,10 +11,11 @@ import (
 	"github.com/spf13/pflag"
 	"github.com/vercel/turborepo/cli/internal/context"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
 	"github.com/vercel/turborepo/cli/internal/scm"
 	scope_filter "github.com/vercel/turborepo/cli/internal/scope/filter"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/util"
-	"github.com/vercel/turborepo/cli/internal/util/filter"
 )
 
 // LegacyFilter holds the options in use before the filter syntax. They have their own rules
@@ -51,6 +51,9 @@ type Opts struct {
 	GlobalDepPatterns []string
 	// Patterns are the filter patterns supplied to --filter on the commandline
 	FilterPatterns []string
+	// RecurseSubmodules is whether the files changed inside git submodules count as
+	// changed files, rather than only the submodules whose commits changed
+	RecurseSubmodules bool
 }
 
 var (
@@ -60,6 +63,10 @@ additional documentation and examples can be found in
 turbo's documentation https://turborepo.org/docs/reference/command-line-reference#--filter
 --filter can be specified multiple times. Packages that
 match any filter will be included.`
+	_recurseSubmodulesHelp = `Look inside checked-out git submodules whose commits changed
+when calculating changed files, so that the files that
+changed within them are attributed to the workspaces they
+are in and --ignore applies to them.`
 	_ignoreHelp    = `Files to ignore when calculating changed files (i.e. --since). Supports globs.`
 	_globalDepHelp = `Specify glob of global filesystem dependencies to be hashed. Useful for .env and files in the root directory.`
 )
@@ -69,6 +76,7 @@ func AddFlags(opts *Opts, flags *pflag.FlagSet) {
 	flags.StringArrayVar(&opts.FilterPatterns, "filter", nil, _filterHelp)
 	flags.StringArrayVar(&opts.IgnorePatterns, "ignore", nil, _ignoreHelp)
 	flags.StringArrayVar(&opts.GlobalDepPatterns, "global-deps", nil, _globalDepHelp)
+	flags.BoolVar(&opts.RecurseSubmodules, "recurse-submodules", false, _recurseSubmodulesHelp)
 	addLegacyFlags(&opts.LegacyFilter, flags)
 }
 
@@ -110,13 +118,15 @@ func (l *LegacyFilter) asFilterPatterns() []string {
 
 // ResolvePackages translates specified flags to a set of entry point packages for
 // the selected tasks. Returns the selected packages and whether or not the selected
-// packages represents a default "all packages".
-func ResolvePackages(opts *Opts, cwd string, scm scm.SCM, ctx *context.Context, tui cli.Ui, logger hclog.Logger) (util.Set, bool, error) {
+// packages represents a default "all packages". Relative directory filters are resolved
+// against invocationDir, the directory turbo was run from, if it is inside the repository.
+func ResolvePackages(opts *Opts, cwd string, invocationDir string, scm scm.SCM, ctx *context.Context, tui cli.Ui, logger hclog.Logger) (util.Set, bool, error) {
 	filterResolver := &scope_filter.Resolver{
 		Graph:                  &ctx.TopologicalGraph,
 		PackageInfos:           ctx.PackageInfos,
 		Cwd:                    cwd,
-		PackagesChangedInRange: opts.getPackageChangeFunc(scm, cwd, ctx.PackageInfos),
+		InvocationDir:          invocationDir,
+		PackagesChangedInRange: opts.getPackageChangeFunc(scm, cwd, ctx.PackageInfos, ctx.Catalogs, ctx.ExternalRepoRoots),
 	}
 	filterPatterns := opts.FilterPatterns
 	legacyFilterPatterns := opts.LegacyFilter.asFilterPatterns()
@@ -137,7 +147,7 @@ func ResolvePackages(opts *Opts, cwd string, scm scm.SCM, ctx *context.Context,
 	return filteredPkgs, isAllPackages, nil
 }
 
-func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, packageInfos map[interface{}]*fs.PackageJSON) scope_filter.PackagesChangedInRange {
+func (o *Opts) getPackageChangeFunc(repoSCM scm.SCM, cwd string, packageInfos map[interface{}]*fs.PackageJSON, catalogs packagemanager.Catalogs, externalRepoRoots []turbopath.AbsolutePath) scope_filter.PackagesChangedInRange {
 	return func(fromRef string, toRef string) (util.Set, error) {
 		// We could filter changed files at the git level, since it's possible
 		// that the changes we're interested in are scoped, but we need to handle
@@ -145,7 +155,8 @@ func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, packageInfos map[in
 		// scope changed files more deeply if we know there are no global dependencies.
 		var changedFiles []string
 		if fromRef != "" {
-			scmChangedFiles, err := scm.ChangedFiles(fromRef, toRef, true, cwd)
+			scm.RecurseSubmodules(repoSCM, o.RecurseSubmodules)
+			scmChangedFiles, err := repoSCM.ChangedFiles(fromRef, toRef, true, cwd)
 			if err != nil {
 				return nil, err
 			}
@@ -165,21 +176,40 @@ func (o *Opts) getPackageChangeFunc(scm scm.SCM, cwd string, packageInfos map[in
 			return nil, err
 		}
 		changedPkgs := getChangedPackages(filteredChangedFiles, packageInfos)
+		if fromRef != "" && catalogs != nil {
+			for pkg := range getCatalogChangedPackages(repoSCM, fromRef, toRef, cwd, filteredChangedFiles, catalogs, packageInfos) {
+				changedPkgs.Add(pkg)
+			}
+		}
+		if fromRef != "" {
+			for _, externalRoot := range externalRepoRoots {
+				// Like this repo, an external repo that isn't a git repo has no changes
+				externalSCM, err := scm.FromInRepo(externalRoot.ToString())
+				if err != nil && !errors.Is(err, scm.ErrFallback) {
+					return nil, err
+				}
+				scm.RecurseSubmodules(externalSCM, o.RecurseSubmodules)
+				externalChangedPkgs, err := getExternalRepoChangedPackages(o, externalSCM, fromRef, toRef, cwd, externalRoot, packageInfos)
+				if err != nil {
+					return nil, errors.Wrapf(err, "external repo %v", externalRoot)
+				}
+				for pkg := range externalChangedPkgs {
+					changedPkgs.Add(pkg)
+				}
+			}
+		}
 		return changedPkgs, nil
 	}
 }
 
 func repoGlobalFileHasChanged(opts *Opts, changedFiles []string) (bool, error) {
-	globalDepsGlob, err := filter.Compile(opts.GlobalDepPatterns)
-	if err != nil {
-		return false, errors.Wrap(err, "invalid global deps glob")
-	}
-
-	if globalDepsGlob != nil {
-		for _, file := range changedFiles {
-			if globalDepsGlob.Match(filepath.ToSlash(file)) {
-				return true, nil
-			}
+	for _, file := range changedFiles {
+		matches, err := matchesAnyGlob(file, opts.GlobalDepPatterns)
+		if err != nil {
+			return false, errors.Wrap(err, "invalid global deps glob")
+		}
+		if matches {
+			return true, nil
 		}
 	}
 	return false, nil
@@ -188,46 +218,99 @@ func repoGlobalFileHasChanged(opts *Opts, changedFiles []string) (bool, error) {
 func filterIgnoredFiles(opts *Opts, changedFiles []string) ([]string, error) {
 	// changedFiles is an array of repo-relative system paths.
 	// opts.IgnorePatterns is an array of unix-separator glob paths.
-	ignoreGlob, err := filter.Compile(opts.IgnorePatterns)
-	if err != nil {
-		return nil, errors.Wrap(err, "invalid ignore globs")
-	}
 	filteredChanges := []string{}
 	for _, file := range changedFiles {
-		// If we don't have anything to ignore, or if this file doesn't match the ignore pattern,
-		// keep it as a changed file.
-		if ignoreGlob == nil || !ignoreGlob.Match(filepath.ToSlash(file)) {
+		// If this file doesn't match any ignore pattern, keep it as a changed file.
+		ignored, err := matchesAnyGlob(file, opts.IgnorePatterns)
+		if err != nil {
+			return nil, errors.Wrap(err, "invalid ignore globs")
+		}
+		if !ignored {
 			filteredChanges = append(filteredChanges, file)
 		}
 	}
 	return filteredChanges, nil
 }
 
-func fileInPackage(changedFile string, packagePath string) bool {
-	// This whole method is basically this regex: /^.*\/?$/
-	// The regex is more-expensive, so we don't do it.
+// matchesAnyGlob reports whether the repo-relative system path file matches any of globs
+func matchesAnyGlob(file string, globs []string) (bool, error) {
+	path := turbopath.AnchoredSystemPathFromUpstream(file)
+	for _, glob := range globs {
+		matches, err := path.MatchGlob(glob, turbopath.GlobOptions{})
+		if err != nil {
+			return false, err
+		}
+		if matches {
+			return true, nil
+		}
+	}
+	return false, nil
+}
 
-	// If it has the prefix, it might be in the package.
-	if strings.HasPrefix(changedFile, packagePath) {
-		// Now we need to see if the prefix stopped at a reasonable boundary.
-		prefixLen := len(packagePath)
-		changedFileLen := len(changedFile)
+// _pnpmWorkspaceFile is where pnpm defines catalogs
+const _pnpmWorkspaceFile = "pnpm-workspace.yaml"
 
-		// Same path.
-		if prefixLen == changedFileLen {
-			return true
+// getCatalogChangedPackages finds the packages that reference a catalog entry whose version
+// changed. Like a lockfile change, this alters what those packages install without touching
+// any of their own files.
+func getCatalogChangedPackages(scm scm.SCM, fromRef string, toRef string, cwd string, changedFiles []string, catalogs packagemanager.Catalogs, packageInfos map[interface{}]*fs.PackageJSON) util.Set {
+	changedPackages := make(util.Set)
+	workspaceFileChanged := false
+	for _, file := range changedFiles {
+		if turbopath.AnchoredSystemPathFromUpstream(file).ToUnixPath().ToString() == _pnpmWorkspaceFile {
+			workspaceFileChanged = true
+			break
 		}
-
-		// We know changedFile is longer than packagePath.
-		// We can safely directly index into it.
-		// Look ahead one byte and see if it's the separator.
-		if changedFile[prefixLen] == os.PathSeparator {
-			return true
+	}
+	if !workspaceFileChanged {
+		return changedPackages
+	}
+	// A workspace file that didn't exist, or didn't parse, had no catalogs
+	contents, _ := scm.PreviousContent(fromRef, toRef, filepath.Join(cwd, _pnpmWorkspaceFile))
+	previous, err := packagemanager.ParsePnpmCatalogs(contents)
+	if err != nil {
+		previous = packagemanager.Catalogs{}
+	}
+	for pkgName, pkgInfo := range packageInfos {
+		for _, deps := range []map[string]string{pkgInfo.Dependencies, pkgInfo.DevDependencies, pkgInfo.OptionalDependencies} {
+			for name, version := range deps {
+				if catalogs.Resolve(name, version) != previous.Resolve(name, version) {
+					changedPackages.Add(pkgName)
+				}
+			}
 		}
 	}
+	return changedPackages
+}
+
+// getExternalRepoChangedPackages finds the workspaces of the external repo at externalRoot that
+// changed in the range, which is resolved in the external repo's own history. Its other files
+// don't change anything, since its root package isn't part of the graph.
+func getExternalRepoChangedPackages(opts *Opts, externalSCM scm.SCM, fromRef string, toRef string, cwd string, externalRoot turbopath.AbsolutePath, packageInfos map[interface{}]*fs.PackageJSON) (util.Set, error) {
+	changedFiles, err := externalSCM.ChangedFiles(fromRef, toRef, true, externalRoot.ToString())
+	if err != nil {
+		return nil, err
+	}
+	relativeRoot, err := filepath.Rel(cwd, externalRoot.ToString())
+	if err != nil {
+		return nil, err
+	}
+	// Make the changed files relative to this repo, like the directories of the workspaces
+	for i, file := range changedFiles {
+		changedFiles[i] = filepath.Join(relativeRoot, file)
+	}
+	filteredChangedFiles, err := filterIgnoredFiles(opts, changedFiles)
+	if err != nil {
+		return nil, err
+	}
+	changedPackages := getChangedPackages(filteredChangedFiles, packageInfos)
+	changedPackages.Delete(util.RootPkgName)
+	return changedPackages, nil
+}
 
-	// If it does not have the prefix, it's definitely not in the package.
-	return false
+func fileInPackage(changedFile turbopath.AnchoredSystemPath, packagePath turbopath.AnchoredSystemPath) bool {
+	relation := changedFile.RelationTo(packagePath)
+	return relation == turbopath.PathEqual || relation == turbopath.PathDescendant
 }
 
 func getChangedPackages(changedFiles []string, packageInfos map[interface{}]*fs.PackageJSON) util.Set {
@@ -235,7 +318,7 @@ func getChangedPackages(changedFiles []string, packageInfos map[interface{}]*fs.
 	for _, changedFile := range changedFiles {
 		found := false
 		for pkgName, pkgInfo := range packageInfos {
-			if pkgName != util.RootPkgName && fileInPackage(changedFile, pkgInfo.Dir.ToStringDuringMigration()) {
+			if pkgName != util.RootPkgName && fileInPackage(turbopath.AnchoredSystemPathFromUpstream(changedFile), pkgInfo.Dir) {
 				changedPackages.Add(pkgName)
 				found = true
 				break
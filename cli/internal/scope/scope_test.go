@@ -10,19 +10,29 @@ import (
 	"github.com/pyr-sh/dag"
 	"github.com/vercel/turborepo/cli/internal/context"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
 	"github.com/vercel/turborepo/cli/internal/ui"
 	"github.com/vercel/turborepo/cli/internal/util"
 )
 
 type mockSCM struct {
-	changed []string
+	changed  []string
+	contents map[string][]byte
 }
 
 func (m *mockSCM) ChangedFiles(_fromCommit string, _toCommit string, _includeUntracked bool, _relativeTo string) ([]string, error) {
 	return m.changed, nil
 }
 
+func (m *mockSCM) PreviousContent(_fromCommit string, _toCommit string, filePath string) ([]byte, error) {
+	contents, ok := m.contents[filePath]
+	if !ok {
+		return nil, fmt.Errorf("%v did not exist", filePath)
+	}
+	return contents, nil
+}
+
 func TestResolvePackages(t *testing.T) {
 	tui := ui.Default()
 	logger := hclog.Default()
@@ -234,7 +244,7 @@ func TestResolvePackages(t *testing.T) {
 				},
 				IgnorePatterns:    []string{tc.ignore},
 				GlobalDepPatterns: tc.globalDeps,
-			}, filepath.FromSlash("/dummy/repo/root"), scm, &context.Context{
+			}, filepath.FromSlash("/dummy/repo/root"), filepath.FromSlash("/dummy/repo/root"), scm, &context.Context{
 				PackageInfos:     packagesInfos,
 				PackageNames:     packageNames,
 				TopologicalGraph: graph,
@@ -255,3 +265,79 @@ func TestResolvePackages(t *testing.T) {
 		})
 	}
 }
+
+func Test_getCatalogChangedPackages(t *testing.T) {
+	cwd := filepath.FromSlash("/dummy/repo/root")
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web":    {Dependencies: map[string]string{"react": "catalog:"}},
+		"legacy": {Dependencies: map[string]string{"react": "catalog:legacy"}},
+		"docs":   {Dependencies: map[string]string{"react": "^18.0.0"}},
+	}
+	catalogs := packagemanager.Catalogs{
+		"default": {"react": "^18.2.0"},
+		"legacy":  {"react": "^17.0.2"},
+	}
+	scm := &mockSCM{
+		contents: map[string][]byte{
+			filepath.Join(cwd, "pnpm-workspace.yaml"): []byte("catalog:\n  react: ^18.1.0\ncatalogs:\n  legacy:\n    react: ^17.0.2\n"),
+		},
+	}
+
+	got := getCatalogChangedPackages(scm, "main", "HEAD", cwd, []string{"pnpm-workspace.yaml"}, catalogs, packageInfos)
+	if !reflect.DeepEqual(got, util.SetFromStrings([]string{"web"})) {
+		t.Errorf("getCatalogChangedPackages got %v, want [web]", got)
+	}
+
+	got = getCatalogChangedPackages(scm, "main", "HEAD", cwd, []string{filepath.FromSlash("packages/web/index.ts")}, catalogs, packageInfos)
+	if got.Len() != 0 {
+		t.Errorf("getCatalogChangedPackages got %v, want no packages when pnpm-workspace.yaml didn't change", got)
+	}
+}
+
+func Test_getExternalRepoChangedPackages(t *testing.T) {
+	cwd := filepath.FromSlash("/dummy/repo/root")
+	externalRoot := turbopath.AbsolutePath(filepath.FromSlash("/dummy/repo/design-system"))
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		util.RootPkgName: {},
+		"web":            {Dir: turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web"))},
+		"ui":             {Dir: turbopath.AnchoredSystemPath(filepath.FromSlash("../design-system/packages/ui"))},
+		"icons":          {Dir: turbopath.AnchoredSystemPath(filepath.FromSlash("../design-system/packages/icons"))},
+	}
+	// The external repo reports its changes relative to its own root
+	scm := &mockSCM{
+		changed: []string{
+			filepath.FromSlash("packages/ui/src/button.tsx"),
+			filepath.FromSlash("packages/icons/README.md"),
+			"package.json",
+		},
+	}
+	opts := &Opts{IgnorePatterns: []string{"../design-system/**/*.md"}}
+
+	got, err := getExternalRepoChangedPackages(opts, scm, "main", "HEAD", cwd, externalRoot, packageInfos)
+	if err != nil {
+		t.Fatalf("getExternalRepoChangedPackages: %v", err)
+	}
+	if !reflect.DeepEqual(got, util.SetFromStrings([]string{"ui"})) {
+		t.Errorf("getExternalRepoChangedPackages got %v, want [ui]", got)
+	}
+}
+
+func Test_filterIgnoredFiles(t *testing.T) {
+	changedFiles := []string{
+		"README.md",
+		filepath.FromSlash("docs/README.md"),
+		filepath.FromSlash("apps/web/src/index.ts"),
+		filepath.FromSlash("apps/web/src/index.test.ts"),
+	}
+	opts := &Opts{IgnorePatterns: []string{"*.md", "apps/*/src/**/*.test.ts"}}
+
+	got, err := filterIgnoredFiles(opts, changedFiles)
+	if err != nil {
+		t.Fatalf("filterIgnoredFiles: %v", err)
+	}
+	// * doesn't match across directories, and paths are matched with unix separators
+	want := []string{filepath.FromSlash("docs/README.md"), filepath.FromSlash("apps/web/src/index.ts")}
+	if !reflect.DeepEqual(got, want) {
+		t.Errorf("filterIgnoredFiles got %v, want %v", got, want)
+	}
+}
@@ -0,0 +1,73 @@
+package server
+
+import (
+	"fmt"
+	"sort"
+	"strings"
+	"sync"
+	"time"
+
+	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
+)
+
+// runMetrics aggregates the statistics that runs report to the daemon, for as long
+// as the daemon is up
+type runMetrics struct {
+	mu   sync.Mutex
+	runs uint64
+	// tasks and durations are keyed by task status
+	tasks     map[string]uint64
+	durations map[string]time.Duration
+}
+
+func newRunMetrics() *runMetrics {
+	return &runMetrics{
+		tasks:     make(map[string]uint64),
+		durations: make(map[string]time.Duration),
+	}
+}
+
+func (m *runMetrics) record(tasks []*turbodprotocol.TaskStats) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	m.runs++
+	for _, task := range tasks {
+		m.tasks[task.Status]++
+		m.durations[task.Status] += time.Duration(task.DurationMsec) * time.Millisecond
+	}
+}
+
+// prometheusText renders the aggregated statistics in the Prometheus text exposition format
+func (m *runMetrics) prometheusText() string {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+	statuses := make([]string, 0, len(m.tasks))
+	for status := range m.tasks {
+		statuses = append(statuses, status)
+	}
+	sort.Strings(statuses)
+
+	var b strings.Builder
+	b.WriteString("# HELP turbo_runs_total Runs that reported to the daemon.\n")
+	b.WriteString("# TYPE turbo_runs_total counter\n")
+	fmt.Fprintf(&b, "turbo_runs_total %v\n", m.runs)
+	b.WriteString("# HELP turbo_tasks_total Tasks that finished, by status.\n")
+	b.WriteString("# TYPE turbo_tasks_total counter\n")
+	for _, status := range statuses {
+		fmt.Fprintf(&b, "turbo_tasks_total{status=%q} %v\n", status, m.tasks[status])
+	}
+	b.WriteString("# HELP turbo_task_duration_seconds Time spent on tasks, by status.\n")
+	b.WriteString("# TYPE turbo_task_duration_seconds summary\n")
+	for _, status := range statuses {
+		fmt.Fprintf(&b, "turbo_task_duration_seconds_sum{status=%q} %v\n", status, m.durations[status].Seconds())
+		fmt.Fprintf(&b, "turbo_task_duration_seconds_count{status=%q} %v\n", status, m.tasks[status])
+	}
+	b.WriteString("# HELP turbo_cache_hit_ratio Fraction of built or cached tasks that were restored from the cache.\n")
+	b.WriteString("# TYPE turbo_cache_hit_ratio gauge\n")
+	hitRatio := 0.0
+	if attempted := m.tasks["built"] + m.tasks["cached"]; attempted > 0 {
+		hitRatio = float64(m.tasks["cached"]) / float64(attempted)
+	}
+	fmt.Fprintf(&b, "turbo_cache_hit_ratio %v\n", hitRatio)
+	return b.String()
+}
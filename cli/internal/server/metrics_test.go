@@ -0,0 +1,42 @@
+package server
+
+import (
+	"strings"
+	"testing"
+
+	"gotest.tools/v3/assert"
+
+	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
+)
+
+func TestRunMetrics(t *testing.T) {
+	m := newRunMetrics()
+	m.record([]*turbodprotocol.TaskStats{
+		{TaskId: "web#build", Status: "built", DurationMsec: 1500},
+		{TaskId: "ui#build", Status: "cached", DurationMsec: 100},
+	})
+	m.record([]*turbodprotocol.TaskStats{
+		{TaskId: "web#build", Status: "cached", DurationMsec: 200},
+		{TaskId: "ui#build", Status: "cached", DurationMsec: 200},
+	})
+	expected := strings.Join([]string{
+		"# HELP turbo_runs_total Runs that reported to the daemon.",
+		"# TYPE turbo_runs_total counter",
+		"turbo_runs_total 2",
+		"# HELP turbo_tasks_total Tasks that finished, by status.",
+		"# TYPE turbo_tasks_total counter",
+		`turbo_tasks_total{status="built"} 1`,
+		`turbo_tasks_total{status="cached"} 3`,
+		"# HELP turbo_task_duration_seconds Time spent on tasks, by status.",
+		"# TYPE turbo_task_duration_seconds summary",
+		`turbo_task_duration_seconds_sum{status="built"} 1.5`,
+		`turbo_task_duration_seconds_count{status="built"} 1`,
+		`turbo_task_duration_seconds_sum{status="cached"} 0.5`,
+		`turbo_task_duration_seconds_count{status="cached"} 3`,
+		"# HELP turbo_cache_hit_ratio Fraction of built or cached tasks that were restored from the cache.",
+		"# TYPE turbo_cache_hit_ratio gauge",
+		"turbo_cache_hit_ratio 0.75",
+		"",
+	}, "\n")
+	assert.Equal(t, m.prometheusText(), expected)
+}
@@ -0,0 +1,43 @@
+package server
+
+import "sync"
+
+// _maxPackageGraphs is how many package graphs the daemon keeps. Switching between a few
+// branches with different dependencies shouldn't evict the graph of the other branches.
+const _maxPackageGraphs = 8
+
+// packageGraphCache keeps the package graphs that clients resolved, keyed by a hash of the
+// files they were resolved from. The graphs are opaque to the daemon.
+type packageGraphCache struct {
+	mu     sync.Mutex
+	graphs map[string][]byte
+	// keys are in the order the graphs were stored, oldest first
+	keys []string
+}
+
+func newPackageGraphCache() *packageGraphCache {
+	return &packageGraphCache{
+		graphs: make(map[string][]byte),
+	}
+}
+
+func (c *packageGraphCache) get(key string) ([]byte, bool) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	graph, ok := c.graphs[key]
+	return graph, ok
+}
+
+// store keeps graph under key, dropping the oldest graph if there are too many
+func (c *packageGraphCache) store(key string, graph []byte) {
+	c.mu.Lock()
+	defer c.mu.Unlock()
+	if _, ok := c.graphs[key]; !ok {
+		c.keys = append(c.keys, key)
+	}
+	c.graphs[key] = graph
+	for len(c.keys) > _maxPackageGraphs {
+		delete(c.graphs, c.keys[0])
+		c.keys = c.keys[1:]
+	}
+}
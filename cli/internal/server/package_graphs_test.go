@@ -0,0 +1,35 @@
+package server
+
+import (
+	"fmt"
+	"testing"
+
+	"gotest.tools/v3/assert"
+)
+
+func TestPackageGraphCache(t *testing.T) {
+	c := newPackageGraphCache()
+	_, ok := c.get("a")
+	assert.Assert(t, !ok)
+
+	c.store("a", []byte("graph-a"))
+	graph, ok := c.get("a")
+	assert.Assert(t, ok)
+	assert.Equal(t, string(graph), "graph-a")
+
+	// Storing the same key again replaces the graph without counting twice
+	c.store("a", []byte("graph-a2"))
+	for i := 1; i < _maxPackageGraphs; i++ {
+		c.store(fmt.Sprintf("key-%v", i), []byte("graph"))
+	}
+	graph, ok = c.get("a")
+	assert.Assert(t, ok)
+	assert.Equal(t, string(graph), "graph-a2")
+
+	// The oldest graph is dropped once there are too many
+	c.store("b", []byte("graph-b"))
+	_, ok = c.get("a")
+	assert.Assert(t, !ok)
+	_, ok = c.get("b")
+	assert.Assert(t, ok)
+}
@@ -33,6 +33,9 @@ type Server struct {
 	repoRoot     turbopath.AbsolutePath
 	closerMu     sync.Mutex
 	closer       *closer
+	metrics      *runMetrics
+	// graphs are the package graphs that clients resolved, which the server only stores
+	graphs *packageGraphCache
 }
 
 // GRPCServer is the interface that the turbo server needs to the underlying
@@ -63,13 +66,13 @@ func (c *closer) close() {
 var _defaultCookieTimeout = 500 * time.Millisecond
 
 // New returns a new instance of Server
-func New(serverName string, logger hclog.Logger, repoRoot turbopath.AbsolutePath, turboVersion string, logFilePath turbopath.AbsolutePath) (*Server, error) {
+func New(serverName string, logger hclog.Logger, repoRoot turbopath.AbsolutePath, turboVersion string, logFilePath turbopath.AbsolutePath, fileWatcherBackend string) (*Server, error) {
 	cookieDir := fs.GetTurboDataDir().Join("cookies", serverName)
 	cookieJar, err := filewatcher.NewCookieJar(cookieDir, _defaultCookieTimeout)
 	if err != nil {
 		return nil, err
 	}
-	watcher, err := filewatcher.GetPlatformSpecificBackend(logger)
+	watcher, err := filewatcher.GetBackend(logger, fileWatcherBackend)
 	if err != nil {
 		return nil, err
 	}
@@ -82,6 +85,8 @@ func New(serverName string, logger hclog.Logger, repoRoot turbopath.AbsolutePath
 		started:      time.Now(),
 		logFilePath:  logFilePath,
 		repoRoot:     repoRoot,
+		metrics:      newRunMetrics(),
+		graphs:       newPackageGraphCache(),
 	}
 	server.watcher.AddClient(cookieJar)
 	server.watcher.AddClient(globWatcher)
@@ -184,3 +189,31 @@ func (s *Server) Status(ctx context.Context, req *turbodprotocol.StatusRequest)
 		},
 	}, nil
 }
+
+// RecordRunStats implements the RecordRunStats rpc from turbo.proto
+func (s *Server) RecordRunStats(ctx context.Context, req *turbodprotocol.RecordRunStatsRequest) (*turbodprotocol.RecordRunStatsResponse, error) {
+	s.metrics.record(req.Tasks)
+	return &turbodprotocol.RecordRunStatsResponse{}, nil
+}
+
+// GetMetrics implements the GetMetrics rpc from turbo.proto
+func (s *Server) GetMetrics(ctx context.Context, req *turbodprotocol.GetMetricsRequest) (*turbodprotocol.GetMetricsResponse, error) {
+	return &turbodprotocol.GetMetricsResponse{
+		Metrics: s.metrics.prometheusText(),
+	}, nil
+}
+
+// GetPackageGraph implements the GetPackageGraph rpc from turbo.proto
+func (s *Server) GetPackageGraph(ctx context.Context, req *turbodprotocol.GetPackageGraphRequest) (*turbodprotocol.GetPackageGraphResponse, error) {
+	graph, ok := s.graphs.get(req.Key)
+	return &turbodprotocol.GetPackageGraphResponse{
+		Found: ok,
+		Graph: graph,
+	}, nil
+}
+
+// StorePackageGraph implements the StorePackageGraph rpc from turbo.proto
+func (s *Server) StorePackageGraph(ctx context.Context, req *turbodprotocol.StorePackageGraphRequest) (*turbodprotocol.StorePackageGraphResponse, error) {
+	s.graphs.store(req.Key, req.Graph)
+	return &turbodprotocol.StorePackageGraphResponse{}, nil
+}
@@ -9,6 +9,7 @@ import (
 	"google.golang.org/grpc"
 	"gotest.tools/v3/assert"
 
+	"github.com/vercel/turborepo/cli/internal/filewatcher"
 	turbofs "github.com/vercel/turborepo/cli/internal/fs"
 	"github.com/vercel/turborepo/cli/internal/turbodprotocol"
 )
@@ -33,7 +34,7 @@ func TestDeleteRepoRoot(t *testing.T) {
 		stopped: make(chan struct{}),
 	}
 
-	s, err := New("testServer", logger, repoRoot, "some-version", "/log/file/path")
+	s, err := New("testServer", logger, repoRoot, "some-version", "/log/file/path", filewatcher.BackendNative)
 	assert.NilError(t, err, "New")
 	s.Register(grpcServer)
 
@@ -57,7 +58,7 @@ func TestShutdown(t *testing.T) {
 		stopped: make(chan struct{}),
 	}
 
-	s, err := New("testServer", logger, repoRoot, "some-version", "/log/file/path")
+	s, err := New("testServer", logger, repoRoot, "some-version", "/log/file/path", filewatcher.BackendNative)
 	assert.NilError(t, err, "New")
 	s.Register(grpcServer)
 
@@ -0,0 +1,245 @@
+// Package skew reports external packages that different workspaces of a
+// repository depend on at different versions.
+package skew
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+	"strings"
+
+	"github.com/Masterminds/semver"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/lockfile"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+// Drift is how far apart the versions of a skewed package are
+type Drift string
+
+const (
+	// DriftMajor means that some versions have different major versions
+	DriftMajor Drift = "major"
+	// DriftMinor means that all versions share a major version, but not a minor version
+	DriftMinor Drift = "minor"
+	// DriftPatch means that all versions share a major and a minor version
+	DriftPatch Drift = "patch"
+	// DriftUnknown means that some versions aren't semver, e.g. git dependencies
+	DriftUnknown Drift = "unknown"
+)
+
+// _driftOrder lists the kinds of drift from most to least severe
+var _driftOrder = map[Drift]int{
+	DriftMajor:   0,
+	DriftUnknown: 1,
+	DriftMinor:   2,
+	DriftPatch:   3,
+}
+
+// Skew is an external package that workspaces depend on at more than one version
+type Skew struct {
+	Package  string    `json:"package"`
+	Drift    Drift     `json:"drift"`
+	Versions []Version `json:"versions"`
+}
+
+// Version is one of the versions of a skewed package, along with the workspaces
+// that depend on it
+type Version struct {
+	Version    string   `json:"version"`
+	Workspaces []string `json:"workspaces"`
+}
+
+// Analyze finds the external packages that workspaces depend on at different versions.
+// Versions are the ones the lockfile resolves each workspace's dependency to, so two
+// workspaces asking for `^1.2.0` and `^1.3.0` are only reported if they actually get
+// different versions. When transitive is set, the whole lockfile closure of each
+// workspace is considered rather than only its direct dependencies.
+func Analyze(packageInfos map[interface{}]*fs.PackageJSON, lf lockfile.Lockfile, transitive bool) []Skew {
+	// package name -> resolved version -> workspaces
+	usages := make(map[string]map[string][]string)
+	for _, pkg := range packageInfos {
+		for name, versions := range resolveDeps(lf, pkg.UnresolvedExternalDeps, transitive) {
+			if usages[name] == nil {
+				usages[name] = make(map[string][]string)
+			}
+			for version := range versions {
+				usages[name][version] = append(usages[name][version], pkg.Name)
+			}
+		}
+	}
+
+	skews := []Skew{}
+	for name, byVersion := range usages {
+		if len(byVersion) < 2 {
+			continue
+		}
+		versions := make([]Version, 0, len(byVersion))
+		for version, workspaces := range byVersion {
+			sort.Strings(workspaces)
+			versions = append(versions, Version{Version: version, Workspaces: workspaces})
+		}
+		sortVersions(versions)
+		skews = append(skews, Skew{
+			Package:  name,
+			Drift:    driftBetween(versions),
+			Versions: versions,
+		})
+	}
+	sort.Slice(skews, func(i, j int) bool {
+		if skews[i].Drift != skews[j].Drift {
+			return _driftOrder[skews[i].Drift] < _driftOrder[skews[j].Drift]
+		}
+		return skews[i].Package < skews[j].Package
+	})
+	return skews
+}
+
+// resolveDeps returns the versions that the lockfile resolves the given dependencies to,
+// keyed by package name. The closure of a workspace can contain more than one version
+// of the same package.
+func resolveDeps(lf lockfile.Lockfile, deps map[string]string, transitive bool) map[string]map[string]bool {
+	resolved := make(map[string]map[string]bool)
+	seen := make(map[string]bool)
+	var visit func(deps map[string]string)
+	visit = func(deps map[string]string) {
+		for name, specifier := range deps {
+			key, version, ok := lf.ResolvePackage(name, specifier)
+			if !ok || seen[key] {
+				continue
+			}
+			seen[key] = true
+			if resolved[name] == nil {
+				resolved[name] = make(map[string]bool)
+			}
+			resolved[name][version] = true
+			if !transitive {
+				continue
+			}
+			if allDeps, ok := lf.AllDependencies(key); ok {
+				visit(allDeps)
+			}
+		}
+	}
+	visit(deps)
+	return resolved
+}
+
+// sortVersions orders versions from oldest to newest, with versions that aren't
+// semver at the end
+func sortVersions(versions []Version) {
+	sort.Slice(versions, func(i, j int) bool {
+		a, errA := semver.NewVersion(versions[i].Version)
+		b, errB := semver.NewVersion(versions[j].Version)
+		switch {
+		case errA == nil && errB == nil:
+			return a.LessThan(b)
+		case errA == nil || errB == nil:
+			return errA == nil
+		default:
+			return versions[i].Version < versions[j].Version
+		}
+	})
+}
+
+func driftBetween(versions []Version) Drift {
+	parsed := make([]*semver.Version, len(versions))
+	for i, version := range versions {
+		v, err := semver.NewVersion(version.Version)
+		if err != nil {
+			return DriftUnknown
+		}
+		parsed[i] = v
+	}
+	drift := DriftPatch
+	for _, v := range parsed[1:] {
+		if v.Major() != parsed[0].Major() {
+			return DriftMajor
+		}
+		if v.Minor() != parsed[0].Minor() {
+			drift = DriftMinor
+		}
+	}
+	return drift
+}
+
+type opts struct {
+	json       bool
+	transitive bool
+}
+
+// GetCmd returns the skew subcommand for use with cobra
+func GetCmd(helper *cmdutil.Helper) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:           "skew",
+		Short:         "Report external packages that workspaces depend on at different versions",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			skews, err := analyzeRepo(base, opts.transitive)
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			if err := report(base, skews, opts.json); err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&opts.json, "json", false, "Output the report in JSON format")
+	cmd.Flags().BoolVar(&opts.transitive, "transitive", false, "Include the dependencies of external dependencies")
+	return cmd
+}
+
+func analyzeRepo(base *cmdutil.CmdBase, transitive bool) ([]Skew, error) {
+	rootPackageJSON, err := fs.ReadPackageJSON(base.RepoRoot.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read package.json: %w", err)
+	}
+	ctx, err := context.New(context.WithGraph(base.RepoRoot, rootPackageJSON, cache.DefaultLocation(base.RepoRoot), base.RepoConfig.PackageManager()))
+	if err != nil {
+		return nil, errors.Wrap(err, "could not construct graph")
+	}
+	if ctx.Lockfile == nil {
+		return nil, errors.New("version skew can only be determined from a lockfile, and none was found")
+	}
+	return Analyze(ctx.PackageInfos, ctx.Lockfile, transitive), nil
+}
+
+func report(base *cmdutil.CmdBase, skews []Skew, asJSON bool) error {
+	if asJSON {
+		bytes, err := json.MarshalIndent(&struct {
+			Skews []Skew `json:"skews"`
+		}{
+			Skews: skews,
+		}, "", "  ")
+		if err != nil {
+			return errors.Wrap(err, "failed to render JSON")
+		}
+		base.UI.Output(string(bytes))
+		return nil
+	}
+	if len(skews) == 0 {
+		base.UI.Output("All workspaces use the same versions of their external dependencies")
+		return nil
+	}
+	for _, skew := range skews {
+		base.UI.Output(fmt.Sprintf("%s %s", ui.Bold(skew.Package), ui.Dim("("+string(skew.Drift)+" drift)")))
+		for _, version := range skew.Versions {
+			base.UI.Output(fmt.Sprintf("  %s  %s", version.Version, strings.Join(version.Workspaces, ", ")))
+		}
+	}
+	return nil
+}
@@ -0,0 +1,102 @@
+package skew
+
+import (
+	"io"
+	"strings"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/lockfile"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+// fakeLockfile resolves `name@specifier` to an entry keyed by `name@version`
+type fakeLockfile struct {
+	resolutions map[string]string
+	deps        map[string]map[string]string
+}
+
+var _ lockfile.Lockfile = &fakeLockfile{}
+
+func (l *fakeLockfile) ResolvePackage(name string, version string) (string, string, bool) {
+	resolved, ok := l.resolutions[name+"@"+version]
+	return name + "@" + resolved, resolved, ok
+}
+
+func (l *fakeLockfile) AllDependencies(key string) (map[string]string, bool) {
+	return l.deps[key], true
+}
+
+func (l *fakeLockfile) Subgraph(workspacePackages []turbopath.AnchoredSystemPath, packages []string) (lockfile.Lockfile, error) {
+	return l, nil
+}
+
+func (l *fakeLockfile) OmitDevDependencies(workspacePackages []turbopath.AnchoredSystemPath) lockfile.Lockfile {
+	return l
+}
+
+func (l *fakeLockfile) Encode(w io.Writer) error {
+	return nil
+}
+
+func (l *fakeLockfile) Patches() []turbopath.AnchoredUnixPath {
+	return nil
+}
+
+func summarize(skews []Skew) []string {
+	summary := []string{}
+	for _, skew := range skews {
+		versions := []string{}
+		for _, version := range skew.Versions {
+			versions = append(versions, version.Version+"="+strings.Join(version.Workspaces, ","))
+		}
+		summary = append(summary, skew.Package+" "+string(skew.Drift)+" "+strings.Join(versions, " "))
+	}
+	return summary
+}
+
+func TestAnalyze(t *testing.T) {
+	lf := &fakeLockfile{
+		resolutions: map[string]string{
+			"react@^17.0.0":  "17.0.2",
+			"react@^18.0.0":  "18.2.0",
+			"lodash@^4.17.0": "4.17.21",
+			"lodash@~4.16.0": "4.16.6",
+			"zod@^3.19.0":    "3.20.2",
+			"zod@^3.20.0":    "3.20.2",
+			"tslib@^2.4.0":   "2.4.1",
+			"tslib@~2.4.0":   "2.4.0",
+			"ui@github:ui":   "github:org/ui#abcd",
+			"ui@^1.0.0":      "1.0.0",
+		},
+		deps: map[string]map[string]string{
+			"react@18.2.0": {"tslib": "^2.4.0"},
+		},
+	}
+	packageInfos := map[interface{}]*fs.PackageJSON{
+		"web": {Name: "web", UnresolvedExternalDeps: map[string]string{
+			"react": "^18.0.0", "lodash": "^4.17.0", "zod": "^3.19.0", "ui": "^1.0.0",
+		}},
+		"docs": {Name: "docs", UnresolvedExternalDeps: map[string]string{
+			"react": "^17.0.0", "lodash": "~4.16.0", "zod": "^3.20.0", "tslib": "~2.4.0",
+		}},
+		"admin": {Name: "admin", UnresolvedExternalDeps: map[string]string{
+			"react": "^18.0.0", "ui": "github:ui",
+		}},
+	}
+
+	// zod resolves to the same version for both workspaces, and tslib is only
+	// skewed through react's dependencies
+	assert.DeepEqual(t, summarize(Analyze(packageInfos, lf, false)), []string{
+		"react major 17.0.2=docs 18.2.0=admin,web",
+		"ui unknown 1.0.0=web github:org/ui#abcd=admin",
+		"lodash minor 4.16.6=docs 4.17.21=web",
+	})
+	assert.DeepEqual(t, summarize(Analyze(packageInfos, lf, true)), []string{
+		"react major 17.0.2=docs 18.2.0=admin,web",
+		"ui unknown 1.0.0=web github:org/ui#abcd=admin",
+		"lodash minor 4.16.6=docs 4.17.21=web",
+		"tslib patch 2.4.0=docs 2.4.1=admin,web",
+	})
+}
@@ -5,6 +5,9 @@ package taskhash
 
 import (
 	"fmt"
+	"os"
+	"path"
+	"path/filepath"
 	"sort"
 	"strings"
 	"sync"
@@ -32,19 +35,66 @@ type Tracker struct {
 	globalHash          string
 	pipeline            fs.Pipeline
 	packageInfos        map[interface{}]*fs.PackageJSON
+	frameworks          *inference.Registry
 	mu                  sync.RWMutex
 	packageInputsHashes packageFileHashes
-	packageTaskHashes   map[string]string // taskID -> hash
+	packageTaskHashes   map[string]string            // taskID -> hash
+	envFileVars         map[string]map[string]string // taskID -> variables loaded from its env files
+	outputsHashes       map[string]string            // taskID -> hash of the outputs it produced
+	envOutputVars       map[string]map[string]string // taskID -> variables from the env output it produced
+	// typesOnlyDependencies maps a package to the package dependencies it only consumes types from
+	typesOnlyDependencies map[string]util.Set
+	// injectedDependencies maps a package to the package dependencies installed into it as copies
+	injectedDependencies map[string]util.Set
+	// nonUTF8Paths is what to do with input files whose names aren't valid UTF-8
+	nonUTF8Paths fs.NonUTF8PathPolicy
+	// normalizeLineEndings hashes text input files as if their line endings were LF
+	normalizeLineEndings bool
+	// skippedPaths are the repo-relative input files left out of package-inputs hashes
+	skippedPaths util.Set
+	// inputSizes are the sizes of each package-inputs combination, if RecordInputSizes was called
+	inputSizes map[packageFileHashKey]InputSize
+	// skipWorktreeFiles is the sparse checkout status of the repo, shared by all of its packages
+	skipWorktreeFiles *hashing.SkipWorktreeFiles
+}
+
+// TrackerOptions configures a Tracker
+type TrackerOptions struct {
+	RootNode     string
+	GlobalHash   string
+	Pipeline     fs.Pipeline
+	PackageInfos map[interface{}]*fs.PackageJSON
+	Frameworks   *inference.Registry
+	// TypesOnlyDependencies maps a package to the package dependencies it only consumes
+	// types from
+	TypesOnlyDependencies map[string]util.Set
+	// InjectedDependencies maps a package to the package dependencies installed into it
+	// as copies
+	InjectedDependencies map[string]util.Set
+	// NonUTF8Paths is what to do with input files whose names aren't valid UTF-8
+	NonUTF8Paths fs.NonUTF8PathPolicy
+	// NormalizeLineEndings hashes text input files as if their line endings were LF
+	NormalizeLineEndings bool
 }
 
 // NewTracker creates a tracker for package-inputs combinations and package-task combinations.
-func NewTracker(rootNode string, globalHash string, pipeline fs.Pipeline, packageInfos map[interface{}]*fs.PackageJSON) *Tracker {
+func NewTracker(opts *TrackerOptions) *Tracker {
 	return &Tracker{
-		rootNode:          rootNode,
-		globalHash:        globalHash,
-		pipeline:          pipeline,
-		packageInfos:      packageInfos,
-		packageTaskHashes: make(map[string]string),
+		rootNode:              opts.RootNode,
+		globalHash:            opts.GlobalHash,
+		pipeline:              opts.Pipeline,
+		packageInfos:          opts.PackageInfos,
+		frameworks:            opts.Frameworks,
+		packageTaskHashes:     make(map[string]string),
+		envFileVars:           make(map[string]map[string]string),
+		outputsHashes:         make(map[string]string),
+		envOutputVars:         make(map[string]map[string]string),
+		typesOnlyDependencies: opts.TypesOnlyDependencies,
+		injectedDependencies:  opts.InjectedDependencies,
+		nonUTF8Paths:          opts.NonUTF8Paths,
+		normalizeLineEndings:  opts.NormalizeLineEndings,
+		skippedPaths:          make(util.Set),
+		skipWorktreeFiles:     &hashing.SkipWorktreeFiles{},
 	}
 }
 
@@ -52,13 +102,67 @@ func NewTracker(rootNode string, globalHash string, pipeline fs.Pipeline, packag
 type packageFileSpec struct {
 	pkg    string
 	inputs []string
+	// excludeAttributes are the gitattributes of files to leave out when there are no inputs
+	excludeAttributes []string
+	// excludes are globs, relative to the package, of files to leave out of those that
+	// inputs match
+	excludes []string
 }
 
 func specFromPackageTask(packageTask *nodes.PackageTask) packageFileSpec {
 	return packageFileSpec{
-		pkg:    packageTask.PackageName,
-		inputs: packageTask.TaskDefinition.Inputs,
+		pkg:               packageTask.PackageName,
+		inputs:            packageTask.TaskDefinition.Inputs,
+		excludeAttributes: packageTask.TaskDefinition.ExcludeGitAttributes,
+	}
+}
+
+// _typesInputs are the globs, relative to a package, of the files that can affect its
+// types, unless the package sets "typesInputs" in the "turbo" key of its package.json
+var _typesInputs = []string{"**/*.d.cts", "**/*.d.mts", "**/*.d.ts", "tsconfig*.json"}
+
+// typesFileSpec returns the spec for the files of a package that can affect its types:
+// its declaration files, the file its "types" or "typings" field points at, its
+// tsconfig files, and its package.json. Dependents that only consume a package's types
+// depend on these files rather than on the package's tasks.
+func typesFileSpec(pkg *fs.PackageJSON) *packageFileSpec {
+	inputs := []string{"package.json"}
+	if pkg.LegacyTurboConfig != nil && len(pkg.LegacyTurboConfig.TypesInputs) > 0 {
+		inputs = append(inputs, pkg.LegacyTurboConfig.TypesInputs...)
+	} else {
+		inputs = append(inputs, _typesInputs...)
+	}
+	if entry, ok := typesEntry(pkg); ok {
+		inputs = append(inputs, entry)
+	}
+	return &packageFileSpec{
+		pkg:      pkg.Name,
+		inputs:   inputs,
+		excludes: []string{"node_modules/**"},
+	}
+}
+
+// typesEntry returns the package-relative path in the "types" or "typings" field of a
+// package's package.json, if it has one inside of the package
+func typesEntry(pkg *fs.PackageJSON) (string, bool) {
+	for _, field := range []string{"types", "typings"} {
+		entry, ok := pkg.RawJSON[field].(string)
+		if !ok || entry == "" {
+			continue
+		}
+		entry = path.Clean(filepath.ToSlash(entry))
+		if path.IsAbs(entry) || entry == ".." || strings.HasPrefix(entry, "../") {
+			return "", false
+		}
+		return entry, true
 	}
+	return "", false
+}
+
+// injectedFileSpec returns the spec for the files of a package that end up in the copies
+// of it that pnpm installs into its dependents
+func injectedFileSpec(pkg string) *packageFileSpec {
+	return &packageFileSpec{pkg: pkg}
 }
 
 // packageFileHashKey is a hashable representation of a packageFileSpec.
@@ -67,7 +171,18 @@ type packageFileHashKey string
 // hashes the inputs for a packageTask
 func (pfs packageFileSpec) ToKey() packageFileHashKey {
 	sort.Strings(pfs.inputs)
-	return packageFileHashKey(fmt.Sprintf("%v#%v", pfs.pkg, strings.Join(pfs.inputs, "!")))
+	key := fmt.Sprintf("%v#%v", pfs.pkg, strings.Join(pfs.inputs, "!"))
+	if len(pfs.inputs) == 0 && len(pfs.excludeAttributes) > 0 {
+		attributes := append([]string{}, pfs.excludeAttributes...)
+		sort.Strings(attributes)
+		key += "#" + strings.Join(attributes, "!")
+	}
+	if len(pfs.excludes) > 0 {
+		excludes := append([]string{}, pfs.excludes...)
+		sort.Strings(excludes)
+		key += "#-" + strings.Join(excludes, "!")
+	}
+	return packageFileHashKey(key)
 }
 
 func safeCompileIgnoreFile(filepath string) (*gitignore.GitIgnore, error) {
@@ -78,26 +193,75 @@ func safeCompileIgnoreFile(filepath string) (*gitignore.GitIgnore, error) {
 	return gitignore.CompileIgnoreLines([]string{}...), nil
 }
 
-func (pfs *packageFileSpec) hash(pkg *fs.PackageJSON, repoRoot turbopath.AbsolutePath) (string, error) {
+// files returns the hashes of the files matched by pfs, along with the repo-relative paths
+// of the files that nonUTF8Paths left out of them
+func (pfs *packageFileSpec) files(pkg *fs.PackageJSON, repoRoot turbopath.AbsolutePath, nonUTF8Paths fs.NonUTF8PathPolicy, normalizeLineEndings bool, skipWorktreeFiles *hashing.SkipWorktreeFiles) (map[turbopath.AnchoredUnixPath]string, []string, error) {
 	hashObject, pkgDepsErr := hashing.GetPackageDeps(repoRoot, &hashing.PackageDepsOptions{
-		PackagePath:   pkg.Dir,
-		InputPatterns: pfs.inputs,
+		PackagePath:          pkg.Dir,
+		InputPatterns:        pfs.inputs,
+		NormalizeLineEndings: normalizeLineEndings,
+		ExcludeAttributes:    pfs.excludeAttributes,
+		SkipWorktreeFiles:    skipWorktreeFiles,
 	})
 	if pkgDepsErr != nil {
-		manualHashObject, err := manuallyHashPackage(pkg, pfs.inputs, repoRoot)
+		manualHashObject, err := manuallyHashPackage(pkg, pfs.inputs, repoRoot, normalizeLineEndings)
 		if err != nil {
-			return "", err
+			return nil, nil, err
 		}
 		hashObject = manualHashObject
 	}
-	hashOfFiles, otherErr := fs.HashObject(hashObject)
-	if otherErr != nil {
-		return "", otherErr
+	for filePath := range hashObject {
+		for _, exclude := range pfs.excludes {
+			if excluded, err := doublestar.Match(exclude, filePath.ToString()); err != nil {
+				return nil, nil, err
+			} else if excluded {
+				delete(hashObject, filePath)
+				break
+			}
+		}
+	}
+	skipped := []string{}
+	for filePath := range hashObject {
+		repoRelativePath := filepath.Join(pkg.Dir.ToStringDuringMigration(), filePath.ToString())
+		allowed, err := nonUTF8Paths.Allow(repoRelativePath)
+		if err != nil {
+			return nil, nil, err
+		} else if !allowed {
+			delete(hashObject, filePath)
+			skipped = append(skipped, repoRelativePath)
+		}
 	}
-	return hashOfFiles, nil
+	return hashObject, skipped, nil
+}
+
+// InputSize is how much a package's inputs for a set of input globs add up to
+type InputSize struct {
+	Package      string
+	Inputs       []string
+	Files        int
+	Bytes        int64
+	Largest      string
+	LargestBytes int64
 }
 
-func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath turbopath.AbsolutePath) (map[turbopath.AnchoredUnixPath]string, error) {
+// measureInputs stats the files of a package-inputs combination
+func measureInputs(pfs *packageFileSpec, pkg *fs.PackageJSON, repoRoot turbopath.AbsolutePath, files map[turbopath.AnchoredUnixPath]string) (InputSize, error) {
+	size := InputSize{Package: pfs.pkg, Inputs: pfs.inputs, Files: len(files)}
+	for filePath := range files {
+		info, err := os.Stat(repoRoot.Join(pkg.Dir.ToStringDuringMigration(), filePath.ToSystemPath().ToString()).ToString())
+		if err != nil {
+			return InputSize{}, err
+		}
+		size.Bytes += info.Size()
+		if info.Size() > size.LargestBytes || (info.Size() == size.LargestBytes && filePath.ToString() < size.Largest) {
+			size.Largest = filePath.ToString()
+			size.LargestBytes = info.Size()
+		}
+	}
+	return size, nil
+}
+
+func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath turbopath.AbsolutePath, normalizeLineEndings bool) (map[turbopath.AnchoredUnixPath]string, error) {
 	hashObject := make(map[turbopath.AnchoredUnixPath]string)
 	// Instead of implementing all gitignore properly, we hack it. We only respect .gitignore in the root and in
 	// the directory of a package.
@@ -111,21 +275,27 @@ func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath turbopat
 		return nil, err
 	}
 
+	// Inputs are relative to the package and use forward slashes on every platform, so
+	// they are matched against package-relative unix paths
 	includePattern := ""
 	if len(inputs) > 0 {
-		includePattern = "{" + strings.Join(inputs, ",") + "}"
+		includePattern = filepath.ToSlash("{" + strings.Join(inputs, ",") + "}")
 	}
 
 	pathPrefix := rootPath.Join(pkg.Dir.ToStringDuringMigration()).ToString()
 	convertedPathPrefix := turbopath.AbsoluteSystemPathFromUpstream(pathPrefix)
-	fs.Walk(pathPrefix, func(name string, isDir bool) error {
+	err = fs.Walk(pathPrefix, func(name string, isDir bool) error {
 		convertedName := turbopath.AbsoluteSystemPathFromUpstream(name)
 		rootMatch := ignore.MatchesPath(convertedName.ToString())
 		otherMatch := ignorePkg.MatchesPath(convertedName.ToString())
 		if !rootMatch && !otherMatch {
 			if !isDir {
+				relativePath, err := convertedName.RelativeTo(convertedPathPrefix)
+				if err != nil {
+					return fmt.Errorf("File path cannot be made relative: %w", err)
+				}
 				if includePattern != "" {
-					val, err := doublestar.PathMatch(includePattern, convertedName.ToString())
+					val, err := doublestar.Match(includePattern, relativePath.ToUnixPath().ToString())
 					if err != nil {
 						return err
 					}
@@ -133,20 +303,18 @@ func manuallyHashPackage(pkg *fs.PackageJSON, inputs []string, rootPath turbopat
 						return nil
 					}
 				}
-				hash, err := fs.GitLikeHashFile(convertedName.ToString())
+				hash, err := fs.GitLikeHashFileWithLineEndings(convertedName.ToString(), normalizeLineEndings)
 				if err != nil {
 					return fmt.Errorf("could not hash file %v. \n%w", convertedName.ToString(), err)
 				}
-
-				relativePath, err := convertedName.RelativeTo(convertedPathPrefix)
-				if err != nil {
-					return fmt.Errorf("File path cannot be made relative: %w", err)
-				}
 				hashObject[relativePath.ToUnixPath()] = hash
 			}
 		}
 		return nil
 	})
+	if err != nil {
+		return nil, err
+	}
 	return hashObject, nil
 }
 
@@ -178,11 +346,34 @@ func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, r
 		}
 
 		pfs := &packageFileSpec{
-			pkg:    pkgName,
-			inputs: taskDefinition.Inputs,
+			pkg:               pkgName,
+			inputs:            taskDefinition.Inputs,
+			excludeAttributes: taskDefinition.ExcludeGitAttributes,
 		}
 
 		hashTasks.Add(pfs)
+		for _, dep := range th.typesOnlyDependencies[pkgName].UnsafeListOfStrings() {
+			depPkg, ok := th.packageInfos[dep]
+			if !ok {
+				return fmt.Errorf("cannot find package %v", dep)
+			}
+			hashTasks.Add(typesFileSpec(depPkg))
+		}
+		for _, dep := range th.injectedDependencies[pkgName].UnsafeListOfStrings() {
+			hashTasks.Add(injectedFileSpec(dep))
+		}
+
+		if len(taskDefinition.EnvFiles) > 0 {
+			pkg, ok := th.packageInfos[pkgName]
+			if !ok {
+				return fmt.Errorf("cannot find package %v", pkgName)
+			}
+			vars, err := loadEnvFileVars(repoRoot.Join(pkg.Dir.ToStringDuringMigration()), taskDefinition)
+			if err != nil {
+				return fmt.Errorf("failed to load env files for %v: %w", taskID, err)
+			}
+			th.envFileVars[taskID] = vars
+		}
 	}
 
 	hashes := make(map[packageFileHashKey]string)
@@ -196,13 +387,30 @@ func (th *Tracker) CalculateFileHashes(allTasks []dag.Vertex, workerCount int, r
 				if !ok {
 					return fmt.Errorf("cannot find package %v", packageFileSpec.pkg)
 				}
-				hash, err := packageFileSpec.hash(pkg, repoRoot)
+				files, skipped, err := packageFileSpec.files(pkg, repoRoot, th.nonUTF8Paths, th.normalizeLineEndings, th.skipWorktreeFiles)
+				if err != nil {
+					return err
+				}
+				hash, err := fs.HashFileHashes(files)
 				if err != nil {
 					return err
 				}
+				var size InputSize
+				if th.inputSizes != nil {
+					size, err = measureInputs(packageFileSpec, pkg, repoRoot, files)
+					if err != nil {
+						return err
+					}
+				}
 				th.mu.Lock()
 				pfsKey := packageFileSpec.ToKey()
 				hashes[pfsKey] = hash
+				if th.inputSizes != nil {
+					th.inputSizes[pfsKey] = size
+				}
+				for _, skippedPath := range skipped {
+					th.skippedPaths.Add(skippedPath)
+				}
 				th.mu.Unlock()
 			}
 			return nil
@@ -229,9 +437,117 @@ type taskHashInputs struct {
 	hashableEnvPairs     []string
 	globalHash           string
 	taskDependencyHashes []string
+	beforeHook           string
+	afterHook            string
+	envFilePairs         []string
+}
+
+// SkippedPaths returns the repo-relative input files that were left out of package-inputs
+// hashes because of their names, in sorted order
+func (th *Tracker) SkippedPaths() []string {
+	th.mu.RLock()
+	defer th.mu.RUnlock()
+	paths := th.skippedPaths.UnsafeListOfStrings()
+	sort.Strings(paths)
+	return paths
+}
+
+// RecordInputSizes makes CalculateFileHashes measure the files it hashes, for InputSizes
+func (th *Tracker) RecordInputSizes() {
+	th.inputSizes = make(map[packageFileHashKey]InputSize)
+}
+
+// InputSizes returns the sizes of the package-inputs combinations that were hashed, largest
+// first. It returns nil unless RecordInputSizes was called before CalculateFileHashes.
+func (th *Tracker) InputSizes() []InputSize {
+	th.mu.RLock()
+	defer th.mu.RUnlock()
+	if th.inputSizes == nil {
+		return nil
+	}
+	sizes := make([]InputSize, 0, len(th.inputSizes))
+	for _, size := range th.inputSizes {
+		sizes = append(sizes, size)
+	}
+	sort.Slice(sizes, func(i, j int) bool {
+		if sizes[i].Bytes != sizes[j].Bytes {
+			return sizes[i].Bytes > sizes[j].Bytes
+		}
+		if sizes[i].Package != sizes[j].Package {
+			return sizes[i].Package < sizes[j].Package
+		}
+		return strings.Join(sizes[i].Inputs, ",") < strings.Join(sizes[j].Inputs, ",")
+	})
+	return sizes
+}
+
+// loadEnvFileVars loads the variables from the env files of a task. When they are injected,
+// variables that are already set are left out: like dotenv, injection doesn't override them,
+// so their values in the env files never reach the task.
+func loadEnvFileVars(pkgDir turbopath.AbsolutePath, taskDefinition fs.TaskDefinition) (map[string]string, error) {
+	vars, err := env.LoadEnvFiles(pkgDir, taskDefinition.EnvFiles)
+	if err != nil {
+		return nil, err
+	}
+	if taskDefinition.InjectEnvFiles {
+		for k := range vars {
+			if _, ok := os.LookupEnv(k); ok {
+				delete(vars, k)
+			}
+		}
+	}
+	return vars, nil
 }
 
-func (th *Tracker) calculateDependencyHashes(dependencySet dag.Set) ([]string, error) {
+// EnvFileVars returns the variables loaded from the env files of the given task, which
+// CalculateFileHashes must have been called for. For tasks that inject their env files,
+// these are exactly the variables to inject.
+func (th *Tracker) EnvFileVars(taskID string) map[string]string {
+	return th.envFileVars[taskID]
+}
+
+// SetOutputsHash records the hash of the outputs that a task produced or restored from cache,
+// for use by dependent tasks that hash their dependencies by outputs.
+func (th *Tracker) SetOutputsHash(taskID string, hash string) {
+	th.mu.Lock()
+	th.outputsHashes[taskID] = hash
+	th.mu.Unlock()
+}
+
+// SetEnvOutputVars records the variables from the env output that a task produced or restored
+// from cache, for use by tasks that take their env inputs from it.
+func (th *Tracker) SetEnvOutputVars(taskID string, vars map[string]string) {
+	th.mu.Lock()
+	th.envOutputVars[taskID] = vars
+	th.mu.Unlock()
+}
+
+// EnvInputVars returns the variables that packageTask takes from the env outputs of the
+// tasks in its EnvInputsFrom. Variables from later tasks override the same variables from
+// earlier ones.
+func (th *Tracker) EnvInputVars(packageTask *nodes.PackageTask) map[string]string {
+	th.mu.RLock()
+	defer th.mu.RUnlock()
+	vars := make(map[string]string)
+	for _, taskID := range packageTask.TaskDefinition.EnvInputsFrom {
+		for k, v := range th.envOutputVars[taskID] {
+			vars[k] = v
+		}
+	}
+	return vars
+}
+
+// calculateDependencyHashes returns the hashes of the given dependencies of packageTask.
+// Dependencies in packages that packageTask only consumes types from contribute the hash of
+// their package's type files instead of their task hash. If packageTask hashes its
+// dependencies by outputs, dependencies with a recorded outputs hash contribute that
+// instead. Dependencies without one, for instance during a dry run, fall back to their
+// task hash. Packages that pnpm installs into packageTask's package as copies contribute the
+// hash of their files whether or not packageTask depends on any of their tasks, since the
+// copies change along with them. Tasks that packageTask takes env inputs from contribute the
+// variables of their recorded env output, and nothing extra if none is recorded.
+func (th *Tracker) calculateDependencyHashes(packageTask *nodes.PackageTask, dependencySet dag.Set) ([]string, error) {
+	pkgName := packageTask.PackageName
 	dependencyHashSet := make(util.Set)
 
 	rootPrefix := th.rootNode + util.TaskDelimiter
@@ -248,12 +564,44 @@ func (th *Tracker) calculateDependencyHashes(dependencySet dag.Set) ([]string, e
 		if strings.HasPrefix(dependencyTask, rootPrefix) {
 			continue
 		}
+		if dependencyPkg, _ := util.GetPackageTaskFromId(dependencyTask); th.typesOnlyDependencies[pkgName].Includes(dependencyPkg) {
+			pkg, ok := th.packageInfos[dependencyPkg]
+			if !ok {
+				return nil, fmt.Errorf("cannot find package %v", dependencyPkg)
+			}
+			typesHashKey := typesFileSpec(pkg).ToKey()
+			typesHash, ok := th.packageInputsHashes[typesHashKey]
+			if !ok {
+				return nil, fmt.Errorf("cannot find package-file hash for %v", typesHashKey)
+			}
+			dependencyHashSet.Add("types:" + typesHash)
+			continue
+		}
+		if packageTask.TaskDefinition.HashDependencyOutputs {
+			if outputsHash, ok := th.outputsHashes[dependencyTask]; ok {
+				dependencyHashSet.Add("outputs:" + outputsHash)
+				continue
+			}
+		}
 		dependencyHash, ok := th.packageTaskHashes[dependencyTask]
 		if !ok {
 			return nil, fmt.Errorf("missing hash for dependent task: %v", dependencyTask)
 		}
 		dependencyHashSet.Add(dependencyHash)
 	}
+	for _, injectedPkg := range th.injectedDependencies[pkgName].UnsafeListOfStrings() {
+		injectedHashKey := injectedFileSpec(injectedPkg).ToKey()
+		injectedHash, ok := th.packageInputsHashes[injectedHashKey]
+		if !ok {
+			return nil, fmt.Errorf("cannot find package-file hash for %v", injectedHashKey)
+		}
+		dependencyHashSet.Add("injected:" + injectedHash)
+	}
+	for _, envInputTask := range packageTask.TaskDefinition.EnvInputsFrom {
+		if vars, ok := th.envOutputVars[envInputTask]; ok {
+			dependencyHashSet.Add("envInputs:" + envInputTask + ":" + strings.Join(env.EnvFilePairs(vars), ","))
+		}
+	}
 	dependenciesHashList := dependencyHashSet.UnsafeListOfStrings()
 	sort.Strings(dependenciesHashList)
 	return dependenciesHashList, nil
@@ -272,14 +620,14 @@ func (th *Tracker) CalculateTaskHash(packageTask *nodes.PackageTask, dependencyS
 	}
 
 	var envPrefixes []string
-	framework := inference.InferFramework(packageTask.Pkg)
+	framework := th.frameworks.InferFramework(packageTask.Pkg)
 	if framework != nil && framework.EnvPrefix != "" {
 		envPrefixes = append(envPrefixes, framework.EnvPrefix)
 	}
 
 	hashableEnvPairs := env.GetHashableEnvPairs(packageTask.TaskDefinition.EnvVarDependencies, envPrefixes)
 	outputs := packageTask.HashableOutputs()
-	taskDependencyHashes, err := th.calculateDependencyHashes(dependencySet)
+	taskDependencyHashes, err := th.calculateDependencyHashes(packageTask, dependencySet)
 	if err != nil {
 		return "", err
 	}
@@ -292,6 +640,9 @@ func (th *Tracker) CalculateTaskHash(packageTask *nodes.PackageTask, dependencyS
 		hashableEnvPairs:     hashableEnvPairs,
 		globalHash:           th.globalHash,
 		taskDependencyHashes: taskDependencyHashes,
+		beforeHook:           packageTask.TaskDefinition.Before,
+		afterHook:            packageTask.TaskDefinition.After,
+		envFilePairs:         env.EnvFilePairs(th.EnvFileVars(packageTask.TaskID)),
 	})
 	if err != nil {
 		return "", fmt.Errorf("failed to hash task %v: %v", packageTask.TaskID, hash)
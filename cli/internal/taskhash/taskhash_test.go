@@ -3,11 +3,16 @@ package taskhash
 import (
 	"os"
 	"path/filepath"
+	"reflect"
+	"sort"
 	"strings"
 	"testing"
 
+	"github.com/pyr-sh/dag"
 	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/nodes"
 	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
 )
 
 func Test_manuallyHashPackage(t *testing.T) {
@@ -87,7 +92,7 @@ func Test_manuallyHashPackage(t *testing.T) {
 	pkg := &fs.PackageJSON{
 		Dir: pkgName,
 	}
-	hashes, err := manuallyHashPackage(pkg, []string{}, turbopath.AbsolutePath(repoRoot.ToString()))
+	hashes, err := manuallyHashPackage(pkg, []string{}, turbopath.AbsolutePath(repoRoot.ToString()), false)
 	if err != nil {
 		t.Fatalf("failed to calculate manual hashes: %v", err)
 	}
@@ -113,7 +118,7 @@ func Test_manuallyHashPackage(t *testing.T) {
 	}
 
 	count = 0
-	justFileHashes, err := manuallyHashPackage(pkg, []string{filepath.FromSlash("**/*file")}, turbopath.AbsolutePath(repoRoot.ToString()))
+	justFileHashes, err := manuallyHashPackage(pkg, []string{filepath.FromSlash("**/*file")}, turbopath.AbsolutePath(repoRoot.ToString()), false)
 	if err != nil {
 		t.Fatalf("failed to calculate manual hashes: %v", err)
 	}
@@ -136,3 +141,343 @@ func Test_manuallyHashPackage(t *testing.T) {
 		t.Errorf("found extra hashes in %v", hashes)
 	}
 }
+
+func Test_manuallyHashPackageLineEndings(t *testing.T) {
+	repoRoot := turbopath.AbsoluteSystemPathFromUpstream(t.TempDir())
+	files := map[string]string{
+		"libA/src/unix.txt":    "a\nb\n",
+		"libA/src/windows.txt": "a\r\nb\r\n",
+		"libA/src/binary.bin":  "\x00\r\n",
+		"libA/other.txt":       "a\nb\n",
+	}
+	for path, contents := range files {
+		filename := repoRoot.Join(filepath.FromSlash(path))
+		if err := fs.EnsureDir(filename.ToString()); err != nil {
+			t.Fatalf("failed to ensure directories for %v: %v", filename, err)
+		}
+		if err := os.WriteFile(filename.ToString(), []byte(contents), 0644); err != nil {
+			t.Fatalf("failed to write %v: %v", filename, err)
+		}
+	}
+	pkg := &fs.PackageJSON{Dir: turbopath.AnchoredSystemPath("libA")}
+
+	// Inputs are matched relative to the package
+	hashes, err := manuallyHashPackage(pkg, []string{"src/**"}, turbopath.AbsolutePath(repoRoot.ToString()), true)
+	if err != nil {
+		t.Fatalf("failed to calculate manual hashes: %v", err)
+	}
+	if len(hashes) != 3 {
+		t.Errorf("manuallyHashPackage got %v, want the 3 files in src", hashes)
+	}
+	if hashes["src/unix.txt"] != hashes["src/windows.txt"] {
+		t.Errorf("normalized hashes differ: %v and %v", hashes["src/unix.txt"], hashes["src/windows.txt"])
+	}
+	// Binary files are hashed as they are
+	if got, want := hashes["src/binary.bin"], "135cc60630e1201eb9509e0ff0a4a19746534810"; got != want {
+		t.Errorf("hash of binary file got %v, want %v", got, want)
+	}
+
+	hashes, err = manuallyHashPackage(pkg, []string{"src/**"}, turbopath.AbsolutePath(repoRoot.ToString()), false)
+	if err != nil {
+		t.Fatalf("failed to calculate manual hashes: %v", err)
+	}
+	if hashes["src/unix.txt"] == hashes["src/windows.txt"] {
+		t.Errorf("hashes without normalization should differ, both are %v", hashes["src/unix.txt"])
+	}
+}
+
+func Test_calculateDependencyHashesTypesOnly(t *testing.T) {
+	packageInfos := map[interface{}]*fs.PackageJSON{"ui": {Name: "ui"}}
+	packageInputsHashes := packageFileHashes{typesFileSpec(packageInfos["ui"]).ToKey(): "types-of-ui"}
+	packageTaskHashes := map[string]string{
+		"ui#build":    "build-of-ui",
+		"utils#build": "build-of-utils",
+	}
+	typesOnlyDependencies := map[string]util.Set{"web": util.SetFromStrings([]string{"ui"})}
+	th := &Tracker{
+		rootNode:              "___ROOT___",
+		packageInfos:          packageInfos,
+		packageInputsHashes:   packageInputsHashes,
+		packageTaskHashes:     packageTaskHashes,
+		typesOnlyDependencies: typesOnlyDependencies,
+	}
+	dependencySet := make(dag.Set)
+	dependencySet.Add("ui#build")
+	dependencySet.Add("utils#build")
+
+	webHashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "web", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-utils", "types:types-of-ui"}; !reflect.DeepEqual(webHashes, want) {
+		t.Errorf("dependency hashes of web, got %v want %v", webHashes, want)
+	}
+
+	docsHashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "docs", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-ui", "build-of-utils"}; !reflect.DeepEqual(docsHashes, want) {
+		t.Errorf("dependency hashes of docs, got %v want %v", docsHashes, want)
+	}
+}
+
+func Test_calculateDependencyHashesOutputs(t *testing.T) {
+	packageTaskHashes := map[string]string{
+		"ui#build":    "build-of-ui",
+		"utils#build": "build-of-utils",
+	}
+	th := &Tracker{
+		rootNode:          "___ROOT___",
+		packageTaskHashes: packageTaskHashes,
+		outputsHashes:     make(map[string]string),
+	}
+	th.SetOutputsHash("ui#build", "outputs-of-ui")
+	dependencySet := make(dag.Set)
+	dependencySet.Add("ui#build")
+	dependencySet.Add("utils#build")
+
+	outputsTask := &nodes.PackageTask{
+		PackageName:    "web",
+		TaskDefinition: &fs.TaskDefinition{HashDependencyOutputs: true},
+	}
+	outputsHashes, err := th.calculateDependencyHashes(outputsTask, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-utils", "outputs:outputs-of-ui"}; !reflect.DeepEqual(outputsHashes, want) {
+		t.Errorf("dependency hashes by outputs, got %v want %v", outputsHashes, want)
+	}
+
+	taskHashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "web", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-ui", "build-of-utils"}; !reflect.DeepEqual(taskHashes, want) {
+		t.Errorf("dependency hashes by task hash, got %v want %v", taskHashes, want)
+	}
+}
+
+func Test_calculateDependencyHashesInjected(t *testing.T) {
+	packageInputsHashes := packageFileHashes{injectedFileSpec("ui").ToKey(): "files-of-ui"}
+	packageTaskHashes := map[string]string{
+		"utils#build": "build-of-utils",
+	}
+	injectedDependencies := map[string]util.Set{"web": util.SetFromStrings([]string{"ui"})}
+	th := &Tracker{
+		rootNode:             "___ROOT___",
+		packageInputsHashes:  packageInputsHashes,
+		packageTaskHashes:    packageTaskHashes,
+		injectedDependencies: injectedDependencies,
+	}
+	// web's task doesn't depend on any of ui's tasks, but still receives a copy of ui
+	dependencySet := make(dag.Set)
+	dependencySet.Add("utils#build")
+
+	webHashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "web", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-utils", "injected:files-of-ui"}; !reflect.DeepEqual(webHashes, want) {
+		t.Errorf("dependency hashes of web, got %v want %v", webHashes, want)
+	}
+
+	docsHashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "docs", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-utils"}; !reflect.DeepEqual(docsHashes, want) {
+		t.Errorf("dependency hashes of docs, got %v want %v", docsHashes, want)
+	}
+}
+
+func Test_calculateDependencyHashesEnvInputs(t *testing.T) {
+	packageTaskHashes := map[string]string{
+		"codegen#build": "build-of-codegen",
+	}
+	th := &Tracker{
+		rootNode:          "___ROOT___",
+		packageTaskHashes: packageTaskHashes,
+		envOutputVars:     make(map[string]map[string]string),
+	}
+	dependencySet := make(dag.Set)
+	dependencySet.Add("codegen#build")
+	webTask := &nodes.PackageTask{
+		PackageName:    "web",
+		TaskDefinition: &fs.TaskDefinition{EnvInputsFrom: []string{"codegen#build"}},
+	}
+
+	// Without a recorded env output, for instance during a dry run, there is nothing to add
+	webHashes, err := th.calculateDependencyHashes(webTask, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-codegen"}; !reflect.DeepEqual(webHashes, want) {
+		t.Errorf("dependency hashes of web, got %v want %v", webHashes, want)
+	}
+
+	th.SetEnvOutputVars("codegen#build", map[string]string{"SCHEMA_VERSION": "2", "API_URL": "https://api.example.com"})
+	webHashes, err = th.calculateDependencyHashes(webTask, dependencySet)
+	if err != nil {
+		t.Fatalf("failed to calculate dependency hashes: %v", err)
+	}
+	if want := []string{"build-of-codegen", "envInputs:codegen#build:API_URL=https://api.example.com,SCHEMA_VERSION=2"}; !reflect.DeepEqual(webHashes, want) {
+		t.Errorf("dependency hashes of web, got %v want %v", webHashes, want)
+	}
+	if got := th.EnvInputVars(webTask); !reflect.DeepEqual(got, map[string]string{"SCHEMA_VERSION": "2", "API_URL": "https://api.example.com"}) {
+		t.Errorf("env input vars of web, got %v", got)
+	}
+}
+
+func Test_measureInputs(t *testing.T) {
+	repoRoot := turbopath.AbsoluteSystemPathFromUpstream(t.TempDir())
+	files := map[string]string{
+		"libA/package.json": "{}",
+		"libA/src/index.ts": "export const a = 1;",
+		"libA/src/big.ts":   strings.Repeat("a", 100),
+	}
+	for path, contents := range files {
+		filename := repoRoot.Join(filepath.FromSlash(path))
+		if err := fs.EnsureDir(filename.ToString()); err != nil {
+			t.Fatalf("failed to ensure directories for %v: %v", filename, err)
+		}
+		if err := os.WriteFile(filename.ToString(), []byte(contents), 0644); err != nil {
+			t.Fatalf("failed to write %v: %v", filename, err)
+		}
+	}
+	pkg := &fs.PackageJSON{Dir: turbopath.AnchoredSystemPath("libA")}
+	pfs := &packageFileSpec{pkg: "libA", inputs: []string{"src/**"}}
+	hashes := map[turbopath.AnchoredUnixPath]string{"src/index.ts": "", "src/big.ts": ""}
+
+	size, err := measureInputs(pfs, pkg, turbopath.AbsolutePath(repoRoot.ToString()), hashes)
+	if err != nil {
+		t.Fatalf("failed to measure inputs: %v", err)
+	}
+	expected := InputSize{Package: "libA", Inputs: []string{"src/**"}, Files: 2, Bytes: 119, Largest: "src/big.ts", LargestBytes: 100}
+	if !reflect.DeepEqual(size, expected) {
+		t.Errorf("measureInputs got %v, want %v", size, expected)
+	}
+}
+
+func Test_loadEnvFileVars(t *testing.T) {
+	pkgDir := turbopath.AbsolutePath(t.TempDir())
+	if err := os.WriteFile(pkgDir.Join(".env").ToString(), []byte("API_URL=https://example.com\nTURBO_TEST_SET_VAR=from-file"), 0644); err != nil {
+		t.Fatalf("failed to write .env: %v", err)
+	}
+	t.Setenv("TURBO_TEST_SET_VAR", "from-environment")
+
+	// Without injection the task reads the files itself, so every variable is hashed
+	vars, err := loadEnvFileVars(pkgDir, fs.TaskDefinition{EnvFiles: []string{".env"}})
+	if err != nil {
+		t.Fatalf("failed to load env files: %v", err)
+	}
+	if want := map[string]string{"API_URL": "https://example.com", "TURBO_TEST_SET_VAR": "from-file"}; !reflect.DeepEqual(vars, want) {
+		t.Errorf("env file vars got %v, want %v", vars, want)
+	}
+
+	// Injection doesn't override a variable that is already set, so its value in the file
+	// isn't hashed
+	vars, err = loadEnvFileVars(pkgDir, fs.TaskDefinition{EnvFiles: []string{".env"}, InjectEnvFiles: true})
+	if err != nil {
+		t.Fatalf("failed to load env files: %v", err)
+	}
+	if want := map[string]string{"API_URL": "https://example.com"}; !reflect.DeepEqual(vars, want) {
+		t.Errorf("injected env file vars got %v, want %v", vars, want)
+	}
+}
+
+func Test_typesFileSpec(t *testing.T) {
+	repoRoot := turbopath.AbsoluteSystemPathFromUpstream(t.TempDir())
+	writeFile := func(path string, contents string) {
+		t.Helper()
+		filename := repoRoot.Join(filepath.FromSlash(path))
+		if err := fs.EnsureDir(filename.ToString()); err != nil {
+			t.Fatalf("failed to ensure directories for %v: %v", filename, err)
+		}
+		if err := os.WriteFile(filename.ToString(), []byte(contents), 0644); err != nil {
+			t.Fatalf("failed to write %v: %v", filename, err)
+		}
+	}
+	writeFile("ui/package.json", `{"name": "ui", "types": "./src/types.ts"}`)
+	writeFile("ui/tsconfig.json", "{}")
+	writeFile("ui/src/types.ts", "export type Props = { label: string };")
+	writeFile("ui/src/button.tsx", "export const Button = () => null;")
+	writeFile("ui/src/index.ts", "export const label = 'button';")
+	writeFile("ui/dist/index.d.ts", "export declare const label: string;")
+	writeFile("ui/src/styles.css", "button {}")
+	pkg := &fs.PackageJSON{
+		Name:    "ui",
+		Dir:     turbopath.AnchoredSystemPath("ui"),
+		RawJSON: map[string]interface{}{"name": "ui", "types": "./src/types.ts"},
+	}
+	typesFiles := func() map[turbopath.AnchoredUnixPath]string {
+		t.Helper()
+		files, _, err := typesFileSpec(pkg).files(pkg, turbopath.AbsolutePath(repoRoot.ToString()), fs.NonUTF8PathsKeep, false, nil)
+		if err != nil {
+			t.Fatalf("failed to get types files: %v", err)
+		}
+		return files
+	}
+	// webHashes returns the dependency hashes of a task in web, which only consumes
+	// the types of ui
+	webHashes := func() []string {
+		t.Helper()
+		hash, err := fs.HashFileHashes(typesFiles())
+		if err != nil {
+			t.Fatalf("failed to hash types files: %v", err)
+		}
+		th := &Tracker{
+			rootNode:              "___ROOT___",
+			packageInfos:          map[interface{}]*fs.PackageJSON{"ui": pkg},
+			packageInputsHashes:   packageFileHashes{typesFileSpec(pkg).ToKey(): hash},
+			packageTaskHashes:     map[string]string{"ui#build": "build-of-ui"},
+			typesOnlyDependencies: map[string]util.Set{"web": util.SetFromStrings([]string{"ui"})},
+		}
+		dependencySet := make(dag.Set)
+		dependencySet.Add("ui#build")
+		hashes, err := th.calculateDependencyHashes(&nodes.PackageTask{PackageName: "web", TaskDefinition: &fs.TaskDefinition{}}, dependencySet)
+		if err != nil {
+			t.Fatalf("failed to calculate dependency hashes: %v", err)
+		}
+		return hashes
+	}
+
+	paths := []string{}
+	for path := range typesFiles() {
+		paths = append(paths, path.ToString())
+	}
+	sort.Strings(paths)
+	if want := []string{"dist/index.d.ts", "package.json", "src/types.ts", "tsconfig.json"}; !reflect.DeepEqual(paths, want) {
+		t.Errorf("types files got %v, want %v", paths, want)
+	}
+
+	before := webHashes()
+	// Runtime code, and anything else that isn't a declaration, doesn't affect the types
+	writeFile("ui/src/index.ts", "export const label = 'a button';")
+	writeFile("ui/src/button.tsx", "export const Button = () => <button />;")
+	writeFile("ui/src/styles.css", "button { color: red; }")
+	if after := webHashes(); !reflect.DeepEqual(after, before) {
+		t.Errorf("dependency hashes of web changed with a .ts body edit, got %v want %v", after, before)
+	}
+	// Declarations and the "types" entry do
+	writeFile("ui/dist/index.d.ts", "export declare const label: 'button';")
+	afterDeclaration := webHashes()
+	if reflect.DeepEqual(afterDeclaration, before) {
+		t.Errorf("dependency hashes of web didn't change with a declaration file")
+	}
+	writeFile("ui/src/types.ts", "export type Props = { label: string; disabled?: boolean };")
+	if after := webHashes(); reflect.DeepEqual(after, afterDeclaration) {
+		t.Errorf("dependency hashes of web didn't change with the \"types\" entry")
+	}
+
+	// "typesInputs" replaces the default globs
+	pkg.LegacyTurboConfig = &fs.TurboJSON{TypesInputs: []string{"src/button.tsx"}}
+	paths = []string{}
+	for path := range typesFiles() {
+		paths = append(paths, path.ToString())
+	}
+	sort.Strings(paths)
+	if want := []string{"package.json", "src/button.tsx", "src/types.ts"}; !reflect.DeepEqual(paths, want) {
+		t.Errorf("types files with typesInputs got %v, want %v", paths, want)
+	}
+}
@@ -1,6 +1,33 @@
 package turbopath
 
-import "path/filepath"
+import (
+	"path/filepath"
+	"runtime"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"golang.org/x/text/unicode/norm"
+)
+
+// PathRelation describes how two paths anchored at the same root relate to each other.
+type PathRelation int
+
+const (
+	// PathDisjoint means that neither path contains the other.
+	PathDisjoint PathRelation = iota
+	// PathEqual means that the paths are the same.
+	PathEqual
+	// PathAncestor means that the path contains the other path.
+	PathAncestor
+	// PathDescendant means that the path is contained by the other path.
+	PathDescendant
+)
+
+// GlobOptions controls how a path is matched against a glob.
+type GlobOptions struct {
+	// CaseInsensitive matches without regard to case.
+	CaseInsensitive bool
+}
 
 // AnchoredSystemPath is a path stemming from a specified root using system separators.
 type AnchoredSystemPath string
@@ -44,3 +71,36 @@ func (p AnchoredSystemPath) Join(additional ...RelativeSystemPath) AnchoredSyste
 	cast := RelativeSystemPathArray(additional)
 	return AnchoredSystemPath(filepath.Join(p.ToString(), filepath.Join(cast.ToStringArray()...)))
 }
+
+// RelationTo reports how this path relates to another path with the same anchor.
+// Paths are compared by segment, so "foo" is not an ancestor of "foobar".
+func (p AnchoredSystemPath) RelationTo(other AnchoredSystemPath) PathRelation {
+	self := filepath.Clean(p.ToString())
+	that := filepath.Clean(other.ToString())
+	switch {
+	case self == that:
+		return PathEqual
+	case self == "." || strings.HasPrefix(that, self+string(filepath.Separator)):
+		return PathAncestor
+	case that == "." || strings.HasPrefix(self, that+string(filepath.Separator)):
+		return PathDescendant
+	default:
+		return PathDisjoint
+	}
+}
+
+// MatchGlob reports whether this path matches the given glob, which uses unix separators
+// regardless of platform. On macOS, both are normalized to the same unicode form first, since
+// the filesystem may report decomposed names for what the user typed as precomposed ones.
+func (p AnchoredSystemPath) MatchGlob(glob string, opts GlobOptions) (bool, error) {
+	name := p.ToUnixPath().ToString()
+	if runtime.GOOS == "darwin" {
+		name = norm.NFC.String(name)
+		glob = norm.NFC.String(glob)
+	}
+	if opts.CaseInsensitive {
+		name = strings.ToLower(name)
+		glob = strings.ToLower(glob)
+	}
+	return doublestar.Match(glob, name)
+}
@@ -0,0 +1,58 @@
+package turbopath
+
+import (
+	"path/filepath"
+	"testing"
+)
+
+func TestAnchoredSystemPath_RelationTo(t *testing.T) {
+	tests := []struct {
+		name  string
+		path  string
+		other string
+		want  PathRelation
+	}{
+		{"equal", "apps/web", "apps/web", PathEqual},
+		{"ancestor", "apps", "apps/web", PathAncestor},
+		{"descendant", "apps/web/src/index.ts", "apps/web", PathDescendant},
+		{"shared prefix is not an ancestor", "apps/web", "apps/webapp", PathDisjoint},
+		{"siblings", "apps/web", "apps/docs", PathDisjoint},
+		{"anchor is an ancestor of everything", ".", "apps/web", PathAncestor},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			p := AnchoredSystemPath(filepath.FromSlash(tt.path))
+			other := AnchoredSystemPath(filepath.FromSlash(tt.other))
+			if got := p.RelationTo(other); got != tt.want {
+				t.Errorf("RelationTo() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
+
+func TestAnchoredSystemPath_MatchGlob(t *testing.T) {
+	tests := []struct {
+		name string
+		path string
+		glob string
+		opts GlobOptions
+		want bool
+	}{
+		{"matches with system separators", "apps/web/src/index.ts", "apps/*/src/**/*.ts", GlobOptions{}, true},
+		{"respects case by default", "apps/Web/index.ts", "apps/web/*.ts", GlobOptions{}, false},
+		{"case insensitive", "apps/Web/index.ts", "apps/web/*.ts", GlobOptions{CaseInsensitive: true}, true},
+		{"no match", "apps/web/index.js", "apps/web/*.ts", GlobOptions{}, false},
+	}
+	for _, tt := range tests {
+		t.Run(tt.name, func(t *testing.T) {
+			p := AnchoredSystemPath(filepath.FromSlash(tt.path))
+			got, err := p.MatchGlob(tt.glob, tt.opts)
+			if err != nil {
+				t.Fatalf("MatchGlob() error = %v", err)
+			}
+			if got != tt.want {
+				t.Errorf("MatchGlob() = %v, want %v", got, tt.want)
+			}
+		})
+	}
+}
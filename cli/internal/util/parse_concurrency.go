@@ -17,6 +17,12 @@ var (
 	_positiveInfinity = 1
 )
 
+// ParseConcurrency parses a concurrency given as a number, or as a percentage of
+// the available CPUs
+func ParseConcurrency(concurrencyRaw string) (int, error) {
+	return parseConcurrency(concurrencyRaw)
+}
+
 func parseConcurrency(concurrencyRaw string) (int, error) {
 	if strings.HasSuffix(concurrencyRaw, "%") {
 		if percent, err := strconv.ParseFloat(concurrencyRaw[:len(concurrencyRaw)-1], 64); err != nil {
@@ -0,0 +1,111 @@
+package util
+
+import (
+	"container/heap"
+	"sync"
+)
+
+// PrioritySemaphore is a semaphore that hands a freed slot to the waiter with the
+// highest priority, rather than to whichever waiter happens to be woken first.
+// Waiters with equal priority are served in the order they arrived.
+type PrioritySemaphore struct {
+	mu    sync.Mutex
+	limit int
+	// free is negative while the semaphore is shrinking and more slots are held
+	// than the limit allows
+	free    int
+	waiters waiterQueue
+	seq     uint64
+}
+
+// NewPrioritySemaphore creates a semaphore that allows up
+// to a given limit of simultaneous acquisitions
+func NewPrioritySemaphore(n int) *PrioritySemaphore {
+	if n <= 0 {
+		panic("semaphore with limit <=0")
+	}
+	return &PrioritySemaphore{limit: n, free: n}
+}
+
+// Acquire is used to acquire an available slot.
+// Blocks until available.
+func (s *PrioritySemaphore) Acquire(priority int64) {
+	s.mu.Lock()
+	if s.free > 0 && len(s.waiters) == 0 {
+		s.free--
+		s.mu.Unlock()
+		return
+	}
+	w := &waiter{
+		priority: priority,
+		seq:      s.seq,
+		ready:    make(chan struct{}),
+	}
+	s.seq++
+	heap.Push(&s.waiters, w)
+	s.mu.Unlock()
+	<-w.ready
+}
+
+// Release is used to return a slot. Acquire must
+// be called as a pre-condition.
+func (s *PrioritySemaphore) Release() {
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	if s.free >= 0 && len(s.waiters) > 0 {
+		// Hand the slot directly to the next waiter
+		w := heap.Pop(&s.waiters).(*waiter)
+		close(w.ready)
+		return
+	}
+	s.free++
+}
+
+// Resize changes the number of simultaneous acquisitions the semaphore allows.
+// Growing it wakes waiters right away. Shrinking it never interrupts holders,
+// the extra slots are retired as they are released.
+func (s *PrioritySemaphore) Resize(n int) {
+	if n <= 0 {
+		panic("semaphore with limit <=0")
+	}
+	s.mu.Lock()
+	defer s.mu.Unlock()
+	s.free += n - s.limit
+	s.limit = n
+	for s.free > 0 && len(s.waiters) > 0 {
+		s.free--
+		w := heap.Pop(&s.waiters).(*waiter)
+		close(w.ready)
+	}
+}
+
+type waiter struct {
+	priority int64
+	seq      uint64
+	ready    chan struct{}
+}
+
+// waiterQueue implements heap.Interface, ordering waiters by descending priority
+type waiterQueue []*waiter
+
+func (q waiterQueue) Len() int { return len(q) }
+
+func (q waiterQueue) Less(i, j int) bool {
+	if q[i].priority == q[j].priority {
+		return q[i].seq < q[j].seq
+	}
+	return q[i].priority > q[j].priority
+}
+
+func (q waiterQueue) Swap(i, j int) { q[i], q[j] = q[j], q[i] }
+
+func (q *waiterQueue) Push(x interface{}) { *q = append(*q, x.(*waiter)) }
+
+func (q *waiterQueue) Pop() interface{} {
+	old := *q
+	n := len(old)
+	w := old[n-1]
+	old[n-1] = nil
+	*q = old[:n-1]
+	return w
+}
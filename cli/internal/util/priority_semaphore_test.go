@@ -0,0 +1,98 @@
+package util
+
+import (
+	"reflect"
+	"sync"
+	"testing"
+	"time"
+)
+
+func TestPrioritySemaphore(t *testing.T) {
+	sema := NewPrioritySemaphore(1)
+	sema.Acquire(0)
+
+	var mu sync.Mutex
+	order := []int64{}
+	var wg sync.WaitGroup
+	priorities := []int64{1, 5, 3, 5, 2}
+	for _, priority := range priorities {
+		priority := priority
+		wg.Add(1)
+		go func() {
+			defer wg.Done()
+			sema.Acquire(priority)
+			mu.Lock()
+			order = append(order, priority)
+			mu.Unlock()
+			sema.Release()
+		}()
+	}
+	// Wait until every goroutine is queued, so that the order they run in only
+	// depends on their priority
+	deadline := time.Now().Add(5 * time.Second)
+	for {
+		sema.mu.Lock()
+		queued := len(sema.waiters)
+		sema.mu.Unlock()
+		if queued == len(priorities) {
+			break
+		}
+		if time.Now().After(deadline) {
+			t.Fatalf("only %v of %v waiters queued", queued, len(priorities))
+		}
+		time.Sleep(time.Millisecond)
+	}
+	sema.Release()
+	wg.Wait()
+
+	expected := []int64{5, 5, 3, 2, 1}
+	if !reflect.DeepEqual(order, expected) {
+		t.Errorf("got order %v, want %v", order, expected)
+	}
+	// Every slot has been returned
+	if sema.free != 1 {
+		t.Errorf("got %v free slots, want 1", sema.free)
+	}
+}
+
+func TestPrioritySemaphoreResize(t *testing.T) {
+	sema := NewPrioritySemaphore(2)
+	sema.Acquire(0)
+	sema.Acquire(0)
+
+	// Shrinking below the number of holders doesn't hand out slots until enough are released
+	sema.Resize(1)
+	acquired := make(chan struct{})
+	go func() {
+		sema.Acquire(0)
+		close(acquired)
+	}()
+	sema.Release()
+	select {
+	case <-acquired:
+		t.Fatal("acquired a slot while the semaphore was over its limit")
+	case <-time.After(20 * time.Millisecond):
+	}
+	sema.Release()
+	select {
+	case <-acquired:
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for a slot after shrinking")
+	}
+
+	// Growing wakes waiters right away
+	waiting := make(chan struct{})
+	go func() {
+		sema.Acquire(0)
+		close(waiting)
+	}()
+	sema.Resize(2)
+	select {
+	case <-waiting:
+	case <-time.After(5 * time.Second):
+		t.Fatal("timed out waiting for a slot after growing")
+	}
+	if sema.free != 0 {
+		t.Errorf("got %v free slots, want 0", sema.free)
+	}
+}
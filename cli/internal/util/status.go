@@ -1,6 +1,10 @@
 package util
 
-import "fmt"
+import (
+	"fmt"
+
+	"github.com/vercel/turborepo/cli/internal/errcode"
+)
 
 // CachingStatus represents the api server's perspective
 // on whether remote caching should be allowed
@@ -40,3 +44,8 @@ type CacheDisabledError struct {
 func (cd *CacheDisabledError) Error() string {
 	return cd.Message
 }
+
+// ErrorCode returns the code of the error
+func (cd *CacheDisabledError) ErrorCode() errcode.Code {
+	return errcode.CacheRemoteDisabled
+}
@@ -0,0 +1,193 @@
+package validate
+
+import (
+	"fmt"
+	"os"
+	"os/exec"
+	"path/filepath"
+	"sort"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/env"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// _dotEnvTemplateSuffixes mark .env files that are examples to be copied, rather
+// than files that are read by tasks
+var _dotEnvTemplateSuffixes = []string{".example", ".sample", ".template"}
+
+// checkGlobalEnvRedeclared reports task env vars that are already part of the global hash
+func checkGlobalEnvRedeclared(vc *validateContext) ([]Diagnostic, error) {
+	globalEnv := util.SetFromStrings(vc.turboJSON.GlobalEnv)
+	diagnostics := []Diagnostic{}
+	for _, taskName := range SortedTaskNames(vc.turboJSON.Pipeline) {
+		taskDefinition := vc.turboJSON.Pipeline[taskName]
+		for _, envVar := range taskDefinition.EnvVarDependencies {
+			if globalEnv.Includes(envVar) {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "global-env-redeclared",
+					Severity: SeverityWarning,
+					Task:     taskName,
+					Message:  fmt.Sprintf("%v is already in globalEnv, so also declaring it for this task has no effect", envVar),
+				})
+			}
+		}
+	}
+	return diagnostics, nil
+}
+
+// checkDotEnvInputs reports .env files that tasks are likely to read, but that aren't
+// included in any hash. These are usually ignored by git, so they are only hashed
+// when listed explicitly.
+func checkDotEnvInputs(vc *validateContext) ([]Diagnostic, error) {
+	diagnostics := []Diagnostic{}
+
+	rootDotEnvs, err := findDotEnvFiles(vc.repoRoot)
+	if err != nil {
+		return nil, err
+	}
+	for _, name := range rootDotEnvs {
+		matched, err := matchesAny(vc.turboJSON.GlobalDeps, name)
+		if err != nil {
+			return nil, err
+		}
+		if !matched {
+			diagnostics = append(diagnostics, Diagnostic{
+				Code:     "dotenv-not-hashed",
+				Severity: SeverityWarning,
+				Message:  fmt.Sprintf("%v is not in globalDependencies, so changes to it won't invalidate the cache", name),
+			})
+		}
+	}
+
+	for _, pkgName := range SortedPackageNames(vc.packageInfos) {
+		pkg := vc.packageInfos[pkgName]
+		if pkgName == util.RootPkgName {
+			continue
+		}
+		pkgDir := vc.repoRoot.Join(pkg.Dir.ToStringDuringMigration())
+		dotEnvs, err := findDotEnvFiles(pkgDir)
+		if err != nil {
+			return nil, err
+		}
+		if len(dotEnvs) == 0 {
+			continue
+		}
+		for _, task := range sortedScripts(pkg) {
+			taskDefinition, ok := vc.turboJSON.Pipeline.GetTaskDefinition(util.GetTaskId(pkgName, task))
+			if !ok {
+				continue
+			}
+			// Env files are hashed by their variables, whatever --env-mode they were loaded for
+			envFiles := env.ExpandEnvFiles(taskDefinition.EnvFiles, "*")
+			for _, name := range dotEnvs {
+				hashed, err := matchesAny(envFiles, name)
+				if err != nil {
+					return nil, err
+				}
+				if hashed {
+					continue
+				}
+				if len(taskDefinition.Inputs) > 0 {
+					hashed, err = matchesAny(taskDefinition.Inputs, name)
+					if err != nil {
+						return nil, err
+					}
+				} else {
+					// Without inputs, every file in the package that git knows about is hashed
+					hashed = !isGitIgnored(pkgDir, name)
+				}
+				if !hashed {
+					diagnostics = append(diagnostics, Diagnostic{
+						Code:     "dotenv-not-hashed",
+						Severity: SeverityWarning,
+						Package:  pkgName,
+						Task:     task,
+						Message:  fmt.Sprintf("%v is not included in this task's inputs, so changes to it won't invalidate the cache", name),
+					})
+				}
+			}
+		}
+	}
+	return diagnostics, nil
+}
+
+// findDotEnvFiles returns the names of the .env files directly inside dir
+func findDotEnvFiles(dir turbopath.AbsolutePath) ([]string, error) {
+	entries, err := os.ReadDir(dir.ToString())
+	if os.IsNotExist(err) {
+		return nil, nil
+	} else if err != nil {
+		return nil, err
+	}
+	names := []string{}
+	for _, entry := range entries {
+		name := entry.Name()
+		if entry.IsDir() || !strings.HasPrefix(name, ".env") || isDotEnvTemplate(name) {
+			continue
+		}
+		names = append(names, name)
+	}
+	return names, nil
+}
+
+func isDotEnvTemplate(name string) bool {
+	for _, suffix := range _dotEnvTemplateSuffixes {
+		if strings.HasSuffix(name, suffix) {
+			return true
+		}
+	}
+	return false
+}
+
+func matchesAny(globs []string, name string) (bool, error) {
+	for _, glob := range globs {
+		matched, err := doublestar.Match(filepath.ToSlash(glob), name)
+		if err != nil {
+			return false, fmt.Errorf("invalid glob %v: %w", glob, err)
+		} else if matched {
+			return true, nil
+		}
+	}
+	return false, nil
+}
+
+// isGitIgnored returns true if git is ignoring the given file. If git isn't
+// available, files are assumed to not be ignored.
+func isGitIgnored(dir turbopath.AbsolutePath, name string) bool {
+	cmd := exec.Command("git", "check-ignore", "--quiet", name)
+	cmd.Dir = dir.ToString()
+	return cmd.Run() == nil
+}
+
+// SortedTaskNames returns the names of the tasks in a pipeline, in order
+func SortedTaskNames(pipeline fs.Pipeline) []string {
+	names := make([]string, 0, len(pipeline))
+	for name := range pipeline {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
+
+// SortedPackageNames returns the names of the workspaces in packageInfos, in order
+func SortedPackageNames(packageInfos map[interface{}]*fs.PackageJSON) []string {
+	names := make([]string, 0, len(packageInfos))
+	for name := range packageInfos {
+		names = append(names, name.(string))
+	}
+	sort.Strings(names)
+	return names
+}
+
+func sortedScripts(pkg *fs.PackageJSON) []string {
+	names := make([]string, 0, len(pkg.Scripts))
+	for name := range pkg.Scripts {
+		names = append(names, name)
+	}
+	sort.Strings(names)
+	return names
+}
@@ -0,0 +1,88 @@
+package validate
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"gotest.tools/v3/assert"
+)
+
+func Test_checkGlobalEnvRedeclared(t *testing.T) {
+	vc := &validateContext{
+		turboJSON: &fs.TurboJSON{
+			GlobalEnv: []string{"CI", "API_URL"},
+			Pipeline: fs.Pipeline{
+				"build": fs.TaskDefinition{EnvVarDependencies: []string{"API_URL", "SENTRY_DSN"}},
+				"test":  fs.TaskDefinition{EnvVarDependencies: []string{"SENTRY_DSN"}},
+			},
+		},
+	}
+	diagnostics, err := checkGlobalEnvRedeclared(vc)
+	assert.NilError(t, err, "checkGlobalEnvRedeclared")
+	assert.Equal(t, len(diagnostics), 1)
+	assert.Equal(t, diagnostics[0].Code, "global-env-redeclared")
+	assert.Equal(t, diagnostics[0].Task, "build")
+}
+
+func Test_checkDotEnvInputs(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile := func(path string) {
+		t.Helper()
+		fullPath := filepath.Join(repoRoot.ToString(), filepath.FromSlash(path))
+		assert.NilError(t, os.MkdirAll(filepath.Dir(fullPath), 0755), "MkdirAll")
+		assert.NilError(t, os.WriteFile(fullPath, []byte("KEY=value"), 0644), "WriteFile")
+	}
+	writeFile(".env")
+	writeFile(".env.production")
+	writeFile(".env.example")
+	writeFile("apps/web/.env.local")
+	writeFile("apps/docs/.env.local")
+	writeFile("apps/admin/.env")
+	writeFile("apps/admin/.env.staging.local")
+
+	vc := &validateContext{
+		repoRoot: repoRoot,
+		turboJSON: &fs.TurboJSON{
+			GlobalDeps: []string{".env"},
+			Pipeline: fs.Pipeline{
+				"build":      fs.TaskDefinition{Inputs: []string{"src/**"}},
+				"docs#build": fs.TaskDefinition{Inputs: []string{"src/**", ".env*"}},
+				// Env files are hashed by their variables, so they don't need to be inputs
+				"admin#build": fs.TaskDefinition{Inputs: []string{"src/**"}, EnvFiles: []string{".env", ".env.{mode}.local"}},
+			},
+		},
+		packageInfos: map[interface{}]*fs.PackageJSON{
+			"web": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web")),
+				Scripts: map[string]string{"build": "next build", "lint": "eslint ."},
+			},
+			"docs": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("apps/docs")),
+				Scripts: map[string]string{"build": "next build"},
+			},
+			"admin": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("apps/admin")),
+				Scripts: map[string]string{"build": "next build"},
+			},
+		},
+	}
+	diagnostics, err := checkDotEnvInputs(vc)
+	assert.NilError(t, err, "checkDotEnvInputs")
+	assert.DeepEqual(t, diagnostics, []Diagnostic{
+		{
+			Code:     "dotenv-not-hashed",
+			Severity: SeverityWarning,
+			Message:  ".env.production is not in globalDependencies, so changes to it won't invalidate the cache",
+		},
+		{
+			Code:     "dotenv-not-hashed",
+			Severity: SeverityWarning,
+			Package:  "web",
+			Task:     "build",
+			Message:  ".env.local is not included in this task's inputs, so changes to it won't invalidate the cache",
+		},
+	})
+}
@@ -0,0 +1,202 @@
+package validate
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/doublestar"
+	"github.com/vercel/turborepo/cli/internal/util"
+)
+
+// checkMissingScripts reports pipeline entries that no workspace can run
+func checkMissingScripts(vc *validateContext) ([]Diagnostic, error) {
+	diagnostics := []Diagnostic{}
+	for _, taskName := range SortedTaskNames(vc.turboJSON.Pipeline) {
+		if util.IsPackageTask(taskName) {
+			pkgName, task := util.GetPackageTaskFromId(taskName)
+			pkg, ok := vc.packageInfos[pkgName]
+			if !ok {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "unknown-package",
+					Severity: SeverityError,
+					Task:     taskName,
+					Message:  fmt.Sprintf("there is no workspace named %v", pkgName),
+				})
+			} else if _, ok := pkg.Scripts[task]; !ok {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "missing-script",
+					Severity: SeverityWarning,
+					Package:  pkgName,
+					Task:     taskName,
+					Message:  fmt.Sprintf("%v has no %v script, so this task will never run", pkgName, task),
+				})
+			}
+			continue
+		}
+		found := false
+		for pkgName, pkg := range vc.packageInfos {
+			// Tasks without a workspace prefix never run in the root workspace
+			if pkgName == util.RootPkgName {
+				continue
+			}
+			if _, ok := pkg.Scripts[taskName]; ok {
+				found = true
+				break
+			}
+		}
+		if !found {
+			diagnostics = append(diagnostics, Diagnostic{
+				Code:     "missing-script",
+				Severity: SeverityWarning,
+				Task:     taskName,
+				Message:  fmt.Sprintf("no workspace has a %v script, so this task will never run", taskName),
+			})
+		}
+	}
+	return diagnostics, nil
+}
+
+// checkUnknownDependencies reports dependsOn entries that refer to tasks
+// that aren't in the pipeline
+func checkUnknownDependencies(vc *validateContext) ([]Diagnostic, error) {
+	pipeline := vc.turboJSON.Pipeline
+	diagnostics := []Diagnostic{}
+	for _, taskName := range SortedTaskNames(pipeline) {
+		taskDefinition := pipeline[taskName]
+		for _, dependency := range taskDefinition.TopologicalDependencies {
+			if !pipeline.HasTask(dependency) {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "unknown-dependency",
+					Severity: SeverityError,
+					Task:     taskName,
+					Message:  fmt.Sprintf("depends on ^%v, but %v is not in the pipeline", dependency, dependency),
+				})
+			}
+		}
+		for _, dependency := range taskDefinition.TaskDependencies {
+			if util.IsPackageTask(dependency) {
+				pkgName, _ := util.GetPackageTaskFromId(dependency)
+				if _, ok := vc.packageInfos[pkgName]; !ok {
+					diagnostics = append(diagnostics, Diagnostic{
+						Code:     "unknown-dependency",
+						Severity: SeverityError,
+						Task:     taskName,
+						Message:  fmt.Sprintf("depends on %v, but there is no workspace named %v", dependency, pkgName),
+					})
+					continue
+				}
+				if _, ok := pipeline.GetTaskDefinition(dependency); ok {
+					continue
+				}
+			} else if pipeline.HasTask(dependency) {
+				continue
+			}
+			diagnostics = append(diagnostics, Diagnostic{
+				Code:     "unknown-dependency",
+				Severity: SeverityError,
+				Task:     taskName,
+				Message:  fmt.Sprintf("depends on %v, but %v is not in the pipeline", dependency, dependency),
+			})
+		}
+	}
+	return diagnostics, nil
+}
+
+// checkShadowedEnv reports env vars declared for a task that a workspace-specific
+// entry silently drops. A `pkg#task` entry replaces the `task` entry entirely
+// for that workspace, rather than extending it.
+func checkShadowedEnv(vc *validateContext) ([]Diagnostic, error) {
+	pipeline := vc.turboJSON.Pipeline
+	diagnostics := []Diagnostic{}
+	for _, taskName := range SortedTaskNames(pipeline) {
+		if !util.IsPackageTask(taskName) {
+			continue
+		}
+		_, task := util.GetPackageTaskFromId(taskName)
+		generic, ok := pipeline[task]
+		if !ok {
+			continue
+		}
+		specificEnv := util.SetFromStrings(pipeline[taskName].EnvVarDependencies)
+		for _, envVar := range generic.EnvVarDependencies {
+			if !specificEnv.Includes(envVar) {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "env-shadowed",
+					Severity: SeverityWarning,
+					Task:     taskName,
+					Message:  fmt.Sprintf("%v is declared for %v, but not here, so it won't be part of this task's hash", envVar, task),
+				})
+			}
+		}
+	}
+	return diagnostics, nil
+}
+
+// checkOutputsMatchNothing reports tasks that have run before, according to their
+// log file, but whose outputs don't match any file on disk. This usually means
+// the outputs are misspelled, or relative to the wrong directory.
+func checkOutputsMatchNothing(vc *validateContext) ([]Diagnostic, error) {
+	diagnostics := []Diagnostic{}
+	for _, pkgName := range SortedPackageNames(vc.packageInfos) {
+		if pkgName == util.RootPkgName {
+			continue
+		}
+		pkg := vc.packageInfos[pkgName]
+		pkgDir := vc.repoRoot.Join(pkg.Dir.ToStringDuringMigration())
+		for _, task := range sortedScripts(pkg) {
+			taskDefinition, ok := vc.turboJSON.Pipeline.GetTaskDefinition(util.GetTaskId(pkgName, task))
+			if !ok {
+				continue
+			}
+			globs := inclusionGlobs(taskDefinition.Outputs)
+			if len(globs) == 0 {
+				continue
+			}
+			logFile := pkgDir.Join(".turbo", fmt.Sprintf("turbo-%v.log", task))
+			if !logFile.FileExists() {
+				continue
+			}
+			matched, err := anyGlobMatches(pkgDir.ToString(), globs)
+			if err != nil {
+				return nil, err
+			}
+			if !matched {
+				diagnostics = append(diagnostics, Diagnostic{
+					Code:     "outputs-match-nothing",
+					Severity: SeverityWarning,
+					Package:  pkgName,
+					Task:     task,
+					Message:  fmt.Sprintf("this task has run before, but none of its outputs (%v) match any files", strings.Join(globs, ", ")),
+				})
+			}
+		}
+	}
+	return diagnostics, nil
+}
+
+// inclusionGlobs returns the output globs that select files, dropping exclusions
+func inclusionGlobs(outputs []string) []string {
+	globs := []string{}
+	for _, output := range outputs {
+		if !strings.HasPrefix(output, "!") {
+			globs = append(globs, output)
+		}
+	}
+	return globs
+}
+
+func anyGlobMatches(dir string, globs []string) (bool, error) {
+	fsys := os.DirFS(dir)
+	for _, glob := range globs {
+		matches, err := doublestar.Glob(fsys, filepath.ToSlash(glob))
+		if err != nil {
+			return false, fmt.Errorf("invalid glob %v: %w", glob, err)
+		}
+		if len(matches) > 0 {
+			return true, nil
+		}
+	}
+	return false, nil
+}
@@ -0,0 +1,136 @@
+package validate
+
+import (
+	"os"
+	"path/filepath"
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/util"
+	"gotest.tools/v3/assert"
+)
+
+func diagnosticCodes(diagnostics []Diagnostic) []string {
+	codes := []string{}
+	for _, diagnostic := range diagnostics {
+		codes = append(codes, diagnostic.Code+" "+diagnostic.Task)
+	}
+	return codes
+}
+
+func Test_checkMissingScripts(t *testing.T) {
+	vc := &validateContext{
+		turboJSON: &fs.TurboJSON{
+			Pipeline: fs.Pipeline{
+				"build":         fs.TaskDefinition{},
+				"deploy":        fs.TaskDefinition{},
+				"web#test":      fs.TaskDefinition{},
+				"missing#build": fs.TaskDefinition{},
+			},
+		},
+		packageInfos: map[interface{}]*fs.PackageJSON{
+			util.RootPkgName: {Scripts: map[string]string{"deploy": "./deploy.sh"}},
+			"web":            {Scripts: map[string]string{"build": "next build"}},
+		},
+	}
+	diagnostics, err := checkMissingScripts(vc)
+	assert.NilError(t, err, "checkMissingScripts")
+	assert.DeepEqual(t, diagnosticCodes(diagnostics), []string{
+		"missing-script deploy",
+		"unknown-package missing#build",
+		"missing-script web#test",
+	})
+}
+
+func Test_checkUnknownDependencies(t *testing.T) {
+	vc := &validateContext{
+		turboJSON: &fs.TurboJSON{
+			Pipeline: fs.Pipeline{
+				"build": fs.TaskDefinition{
+					TopologicalDependencies: []string{"build", "codegen"},
+					TaskDependencies:        []string{"web#lint"},
+				},
+				"test": fs.TaskDefinition{
+					TaskDependencies: []string{"build", "typecheck", "ui#build", "missing#build"},
+				},
+				"web#lint": fs.TaskDefinition{},
+			},
+		},
+		packageInfos: map[interface{}]*fs.PackageJSON{
+			"web": {},
+			"ui":  {},
+		},
+	}
+	diagnostics, err := checkUnknownDependencies(vc)
+	assert.NilError(t, err, "checkUnknownDependencies")
+	assert.DeepEqual(t, diagnosticCodes(diagnostics), []string{
+		"unknown-dependency build",
+		"unknown-dependency test",
+		"unknown-dependency test",
+	})
+}
+
+func Test_checkShadowedEnv(t *testing.T) {
+	vc := &validateContext{
+		turboJSON: &fs.TurboJSON{
+			Pipeline: fs.Pipeline{
+				"build":      fs.TaskDefinition{EnvVarDependencies: []string{"API_URL", "SENTRY_DSN"}},
+				"web#build":  fs.TaskDefinition{EnvVarDependencies: []string{"API_URL"}},
+				"docs#build": fs.TaskDefinition{EnvVarDependencies: []string{"API_URL", "SENTRY_DSN"}},
+				"web#lint":   fs.TaskDefinition{},
+			},
+		},
+	}
+	diagnostics, err := checkShadowedEnv(vc)
+	assert.NilError(t, err, "checkShadowedEnv")
+	assert.Equal(t, len(diagnostics), 1)
+	assert.Equal(t, diagnostics[0].Code, "env-shadowed")
+	assert.Equal(t, diagnostics[0].Task, "web#build")
+}
+
+func Test_checkOutputsMatchNothing(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	writeFile := func(path string) {
+		t.Helper()
+		fullPath := filepath.Join(repoRoot.ToString(), filepath.FromSlash(path))
+		assert.NilError(t, os.MkdirAll(filepath.Dir(fullPath), 0755), "MkdirAll")
+		assert.NilError(t, os.WriteFile(fullPath, []byte("contents"), 0644), "WriteFile")
+	}
+	// web has built, and its outputs exist
+	writeFile("apps/web/.turbo/turbo-build.log")
+	writeFile("apps/web/dist/index.js")
+	// docs has built, but writes somewhere its outputs don't cover
+	writeFile("apps/docs/.turbo/turbo-build.log")
+	writeFile("apps/docs/out/index.html")
+	// ui has never built
+	writeFile("packages/ui/src/index.ts")
+
+	vc := &validateContext{
+		repoRoot: repoRoot,
+		turboJSON: &fs.TurboJSON{
+			Pipeline: fs.Pipeline{
+				"build": fs.TaskDefinition{Outputs: []string{"dist/**", "!dist/cache/**"}},
+			},
+		},
+		packageInfos: map[interface{}]*fs.PackageJSON{
+			"web": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("apps/web")),
+				Scripts: map[string]string{"build": "next build"},
+			},
+			"docs": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("apps/docs")),
+				Scripts: map[string]string{"build": "next build && next export"},
+			},
+			"ui": {
+				Dir:     turbopath.AnchoredSystemPath(filepath.FromSlash("packages/ui")),
+				Scripts: map[string]string{"build": "tsc"},
+			},
+		},
+	}
+	diagnostics, err := checkOutputsMatchNothing(vc)
+	assert.NilError(t, err, "checkOutputsMatchNothing")
+	assert.Equal(t, len(diagnostics), 1)
+	assert.Equal(t, diagnostics[0].Code, "outputs-match-nothing")
+	assert.Equal(t, diagnostics[0].Package, "docs")
+}
@@ -0,0 +1,27 @@
+package validate
+
+import (
+	"fmt"
+	"strings"
+
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+)
+
+// multipleLockfilesDiagnostic reports lockfiles from more than one package manager
+func multipleLockfilesDiagnostic(lockfiles []string, severity Severity) Diagnostic {
+	return Diagnostic{
+		Code:     "multiple-lockfiles",
+		Severity: severity,
+		Message:  fmt.Sprintf("found lockfiles for more than one package manager (%v). Delete the ones that aren't in use, or set the packageManager field in package.json or --package-manager", strings.Join(lockfiles, ", ")),
+	}
+}
+
+// checkMultipleLockfiles reports lockfiles from more than one package manager when the
+// package manager was settled some other way, since the other lockfiles are stale
+func checkMultipleLockfiles(vc *validateContext) ([]Diagnostic, error) {
+	lockfiles := packagemanager.FindLockfiles(vc.repoRoot)
+	if len(lockfiles) < 2 {
+		return []Diagnostic{}, nil
+	}
+	return []Diagnostic{multipleLockfilesDiagnostic(lockfiles, SeverityWarning)}, nil
+}
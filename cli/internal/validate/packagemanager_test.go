@@ -0,0 +1,24 @@
+package validate
+
+import (
+	"testing"
+
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"gotest.tools/v3/assert"
+)
+
+func Test_checkMultipleLockfiles(t *testing.T) {
+	repoRoot := fs.AbsolutePathFromUpstream(t.TempDir())
+	vc := &validateContext{repoRoot: repoRoot}
+	assert.NilError(t, repoRoot.Join("yarn.lock").WriteFile([]byte{}, 0644), "WriteFile")
+
+	diagnostics, err := checkMultipleLockfiles(vc)
+	assert.NilError(t, err, "checkMultipleLockfiles")
+	assert.DeepEqual(t, diagnostics, []Diagnostic{})
+
+	assert.NilError(t, repoRoot.Join("package-lock.json").WriteFile([]byte{}, 0644), "WriteFile")
+	diagnostics, err = checkMultipleLockfiles(vc)
+	assert.NilError(t, err, "checkMultipleLockfiles")
+	assert.DeepEqual(t, diagnosticCodes(diagnostics), []string{"multiple-lockfiles "})
+	assert.Equal(t, diagnostics[0].Severity, SeverityWarning)
+}
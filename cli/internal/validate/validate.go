@@ -0,0 +1,204 @@
+// Package validate checks a repository's turbo configuration for mistakes
+// without running any tasks.
+package validate
+
+import (
+	"encoding/json"
+	"fmt"
+	"sort"
+
+	"github.com/fatih/color"
+	"github.com/pkg/errors"
+	"github.com/spf13/cobra"
+	"github.com/vercel/turborepo/cli/internal/cache"
+	"github.com/vercel/turborepo/cli/internal/cmdutil"
+	"github.com/vercel/turborepo/cli/internal/context"
+	"github.com/vercel/turborepo/cli/internal/fs"
+	"github.com/vercel/turborepo/cli/internal/packagemanager"
+	"github.com/vercel/turborepo/cli/internal/turbopath"
+	"github.com/vercel/turborepo/cli/internal/ui"
+)
+
+// Severity is how serious a Diagnostic is
+type Severity string
+
+const (
+	// SeverityError is a misconfiguration that will cause incorrect behavior
+	SeverityError Severity = "error"
+	// SeverityWarning is a likely, but not certain, misconfiguration
+	SeverityWarning Severity = "warning"
+)
+
+// Diagnostic is a single problem found in the configuration
+type Diagnostic struct {
+	// Code is a stable identifier for the kind of problem
+	Code     string   `json:"code"`
+	Severity Severity `json:"severity"`
+	Message  string   `json:"message"`
+	// Package is the workspace the problem applies to, if any
+	Package string `json:"package,omitempty"`
+	// Task is the pipeline entry the problem applies to, if any
+	Task string `json:"task,omitempty"`
+}
+
+// Less orders diagnostics with errors first, then by package, task and code
+func (d Diagnostic) Less(other Diagnostic) bool {
+	if d.Severity != other.Severity {
+		return d.Severity == SeverityError
+	}
+	if d.Package != other.Package {
+		return d.Package < other.Package
+	}
+	if d.Task != other.Task {
+		return d.Task < other.Task
+	}
+	return d.Code < other.Code
+}
+
+// validateContext holds the parts of the repository that checks inspect
+type validateContext struct {
+	repoRoot     turbopath.AbsolutePath
+	turboJSON    *fs.TurboJSON
+	packageInfos map[interface{}]*fs.PackageJSON
+}
+
+// check inspects the repository and reports any problems it finds
+type check func(vc *validateContext) ([]Diagnostic, error)
+
+var _checks = []check{
+	checkGlobalEnvRedeclared,
+	checkDotEnvInputs,
+	checkMissingScripts,
+	checkUnknownDependencies,
+	checkShadowedEnv,
+	checkOutputsMatchNothing,
+	checkMultipleLockfiles,
+}
+
+type opts struct {
+	json bool
+}
+
+// GetCmd returns the validate subcommand for use with cobra
+func GetCmd(helper *cmdutil.Helper) *cobra.Command {
+	opts := &opts{}
+	cmd := &cobra.Command{
+		Use:           "validate",
+		Short:         "Check your turbo configuration for mistakes",
+		SilenceUsage:  true,
+		SilenceErrors: true,
+		RunE: func(cmd *cobra.Command, args []string) error {
+			base, err := helper.GetCmdBase(cmd.Flags())
+			if err != nil {
+				return err
+			}
+			diagnostics, err := validate(base.RepoRoot, base.RepoConfig.PackageManager())
+			if err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			if err := report(base, diagnostics, opts.json); err != nil {
+				base.LogError("%v", err)
+				return err
+			}
+			for _, diagnostic := range diagnostics {
+				if diagnostic.Severity == SeverityError {
+					return errors.New("configuration has errors")
+				}
+			}
+			return nil
+		},
+	}
+	cmd.Flags().BoolVar(&opts.json, "json", false, "Output diagnostics in JSON format")
+	return cmd
+}
+
+func validate(repoRoot turbopath.AbsolutePath, packageManagerOverride string) ([]Diagnostic, error) {
+	rootPackageJSON, err := fs.ReadPackageJSON(repoRoot.Join("package.json"))
+	if err != nil {
+		return nil, fmt.Errorf("failed to read package.json: %w", err)
+	}
+	turboJSON, err := fs.ReadTurboConfig(repoRoot, rootPackageJSON)
+	if err != nil {
+		return nil, err
+	}
+	ctx, err := context.New(context.WithGraph(repoRoot, rootPackageJSON, cache.DefaultLocation(repoRoot), packageManagerOverride))
+	var multipleLockfiles *packagemanager.MultipleLockfilesError
+	if errors.As(err, &multipleLockfiles) {
+		// Without a package manager there is no graph for the other checks to inspect
+		return []Diagnostic{multipleLockfilesDiagnostic(multipleLockfiles.Lockfiles, SeverityError)}, nil
+	}
+	if err != nil {
+		return nil, errors.Wrap(err, "could not construct graph")
+	}
+	vc := &validateContext{
+		repoRoot:     repoRoot,
+		turboJSON:    turboJSON,
+		packageInfos: ctx.PackageInfos,
+	}
+	return runChecks(vc, _checks)
+}
+
+func runChecks(vc *validateContext, checks []check) ([]Diagnostic, error) {
+	diagnostics := []Diagnostic{}
+	for _, check := range checks {
+		found, err := check(vc)
+		if err != nil {
+			return nil, err
+		}
+		diagnostics = append(diagnostics, found...)
+	}
+	sort.SliceStable(diagnostics, func(i, j int) bool {
+		return diagnostics[i].Less(diagnostics[j])
+	})
+	return diagnostics, nil
+}
+
+func report(base *cmdutil.CmdBase, diagnostics []Diagnostic, asJSON bool) error {
+	if asJSON {
+		bytes, err := json.MarshalIndent(&struct {
+			Diagnostics []Diagnostic `json:"diagnostics"`
+		}{
+			Diagnostics: diagnostics,
+		}, "", "  ")
+		if err != nil {
+			return errors.Wrap(err, "failed to render JSON")
+		}
+		base.UI.Output(string(bytes))
+		return nil
+	}
+	if len(diagnostics) == 0 {
+		base.UI.Output("No problems found")
+		return nil
+	}
+	for _, diagnostic := range diagnostics {
+		PrintDiagnostic(base, diagnostic)
+	}
+	return nil
+}
+
+// PrintDiagnostic prints a diagnostic as an error or a warning, depending on its severity
+func PrintDiagnostic(base *cmdutil.CmdBase, diagnostic Diagnostic) {
+	if diagnostic.Severity == SeverityError {
+		base.UI.Error(FormatDiagnostic(ui.ERROR_PREFIX, color.RedString(diagnostic.Message), diagnostic))
+	} else {
+		base.UI.Warn(FormatDiagnostic(ui.WARNING_PREFIX, color.YellowString(diagnostic.Message), diagnostic))
+	}
+}
+
+// FormatDiagnostic renders a line of output for a diagnostic: the prefix, where the problem
+// is, the message and the diagnostic's code
+func FormatDiagnostic(prefix string, message string, diagnostic Diagnostic) string {
+	location := diagnostic.Task
+	if diagnostic.Package != "" {
+		if location != "" {
+			location = diagnostic.Package + " " + location
+		} else {
+			location = diagnostic.Package
+		}
+	}
+	if location != "" {
+		location = ui.Bold(location) + ": "
+	}
+	return fmt.Sprintf("%s %s%s %s", prefix, location, message, ui.Dim("("+diagnostic.Code+")"))
+}
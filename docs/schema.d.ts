@@ -43,6 +43,52 @@ export interface Schema {
    * @default {}
    */
   remoteCache?: RemoteCache;
+
+  /**
+   * A list of repo-relative directories that task outputs are allowed to resolve to, even
+   * though they are outside of the task's workspace. By default, outputs globs that are
+   * absolute or use .. to reach outside of the workspace are rejected.
+   *
+   * @default []
+   */
+  allowedExternalOutputs?: string[];
+
+  /**
+   * Additional frameworks for turbo to detect in your workspaces. When a workspace
+   * depends on a framework's packages, environment variables starting with the
+   * framework's envPrefix are included in the hashes of that workspace's tasks.
+   *
+   * These are checked before turbo's built-in frameworks, so they can also be used to
+   * override how a built-in framework is detected.
+   *
+   * @default []
+   */
+  frameworks?: Framework[];
+}
+
+export interface Framework {
+  /**
+   * A unique name for the framework.
+   */
+  slug: string;
+
+  /**
+   * Environment variables starting with this prefix are included in task hashes.
+   */
+  envPrefix: string;
+
+  /**
+   * The dependencies that identify a workspace as using this framework.
+   */
+  dependencies: string[];
+
+  /**
+   * Use "all" to require every dependency to be present, or "some" to require at
+   * least one of them.
+   *
+   * @default all
+   */
+  dependencyMatch?: "all" | "some";
 }
 
 export interface Pipeline {
@@ -108,6 +154,23 @@ export interface Pipeline {
    * @default full
    */
   outputMode?: string;
+
+  /**
+   * A shell command to run before this task's script, in the same directory and
+   * with the same environment. If it exits with a non-zero code, the task fails
+   * and the script is not run.
+   *
+   * Changing this command will cause a cache miss.
+   */
+  before?: string;
+
+  /**
+   * A shell command to run after this task's script succeeds, in the same directory
+   * and with the same environment. If it exits with a non-zero code, the task fails.
+   *
+   * Changing this command will cause a cache miss.
+   */
+  after?: string;
 }
 
 export interface RemoteCache {
@@ -3,6 +3,7 @@ mod package_manager;
 use crate::package_manager::PackageManager;
 use anyhow::{anyhow, Result};
 use clap::{Parser, Subcommand};
+use serde::Deserialize;
 use std::env::current_exe;
 use std::path::{Path, PathBuf};
 
@@ -31,22 +32,56 @@ struct Args {
 /// flag into non-build commands.
 #[derive(Subcommand, Debug)]
 enum Command {
+    Artifacts,
     Bin,
     Completion,
+    /// Hidden commands that the scripts from `turbo completion` call back into.
+    /// They carry the command line being completed, which must not be mistaken
+    /// for a task.
+    #[clap(name = "__complete", hide = true)]
+    Complete,
+    #[clap(name = "__completeNoDesc", hide = true)]
+    CompleteNoDesc,
     Daemon,
+    Doctor,
+    Gen,
     Help,
+    Info,
     Link,
     Login,
     Logout,
+    Prefetch {
+        tasks: Vec<String>,
+    },
     Prune,
+    Skew,
     Unlink,
-    Run { tasks: Vec<String> },
+    Validate,
+    Run {
+        tasks: Vec<String>,
+    },
 }
 
+/// Environment variable that controls whether a run invoked from inside a
+/// workspace is automatically scoped to that workspace. Set to `false` or `0`
+/// to disable.
+const INFER_FILTER_ENV: &str = "TURBO_INFER_FILTER";
+
+/// Flag that turns off inferring a filter for a single run, like setting
+/// `TURBO_INFER_FILTER=false`. It's removed before the arguments reach turbo.
+const NO_INFER_FILTER_FLAG: &str = "--no-infer-filter";
+
+/// Flags that already select packages, so we must not add an inferred filter
+/// on top of them.
+const PACKAGE_SELECTION_FLAGS: [&str; 3] = ["--filter", "--scope", "--since"];
+
 #[derive(Debug)]
 struct RepoState {
     root: PathBuf,
     mode: RepoMode,
+    /// Name of the workspace containing the current directory, if turbo was
+    /// invoked from inside one rather than from the repo root.
+    workspace: Option<String>,
 }
 
 #[derive(Debug)]
@@ -55,6 +90,11 @@ enum RepoMode {
     MultiPackage,
 }
 
+#[derive(Debug, Deserialize)]
+struct PackageJsonName {
+    name: Option<String>,
+}
+
 extern "C" {
     pub fn nativeRunWithArgs(argc: c_int, argv: *mut *mut c_char) -> c_int;
 }
@@ -112,9 +152,15 @@ impl RepoState {
                 RepoMode::SinglePackage
             };
 
+            let workspace = match mode {
+                RepoMode::MultiPackage => Self::infer_workspace(root_path, current_dir),
+                RepoMode::SinglePackage => None,
+            };
+
             return Ok(Self {
                 root: root_path.to_path_buf(),
                 mode,
+                workspace,
             });
         }
 
@@ -140,6 +186,7 @@ impl RepoState {
                 return Ok(Self {
                     root: dir.to_path_buf(),
                     mode: RepoMode::MultiPackage,
+                    workspace: Self::infer_workspace(dir, current_dir),
                 });
             }
         }
@@ -155,8 +202,29 @@ impl RepoState {
         Ok(Self {
             root,
             mode: RepoMode::SinglePackage,
+            workspace: None,
         })
     }
+
+    /// Finds the name of the workspace that contains `current_dir`, i.e. the
+    /// nearest directory below `repo_root` that has a named `package.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `repo_root`: Root of the monorepo
+    /// * `current_dir`: Current working directory
+    ///
+    /// returns: Option<String>
+    ///
+    fn infer_workspace(repo_root: &Path, current_dir: &Path) -> Option<String> {
+        let package_json_text = current_dir
+            .ancestors()
+            .take_while(|dir| *dir != repo_root)
+            .find_map(|dir| fs::read_to_string(dir.join("package.json")).ok())?;
+        let package_json: PackageJsonName = serde_json::from_str(&package_json_text).ok()?;
+
+        package_json.name
+    }
 }
 
 /// Checks if either we have an explicit run command, i.e. `turbo run build`
@@ -176,6 +244,136 @@ fn is_run_command(clap_args: &Args) -> bool {
     is_explicit_run || is_implicit_run
 }
 
+/// Checks whether a run should be scoped to the workspace it was invoked
+/// from. This is on by default and can be turned off with `TURBO_INFER_FILTER`.
+/// We never infer a filter if the user already selected packages themselves,
+/// or if they set `--cwd`, since that already says where the repo root is.
+///
+/// # Arguments
+///
+/// * `args`: Arguments for turbo
+///
+/// returns: bool
+///
+fn should_infer_filter(args: &[String]) -> bool {
+    let is_enabled = !matches!(env::var(INFER_FILTER_ENV).as_deref(), Ok("false") | Ok("0"));
+    let has_package_selection = PACKAGE_SELECTION_FLAGS
+        .iter()
+        .any(|flag| has_turbo_flag(args, flag));
+
+    is_enabled && !has_package_selection && !has_turbo_flag(args, "--cwd")
+}
+
+/// Checks if a flag for turbo was passed, either on its own or with a value,
+/// ignoring anything after `--` since that is passed through to the task scripts.
+///
+/// # Arguments
+///
+/// * `args`: Arguments for turbo
+/// * `flag`: Flag to look for
+///
+/// returns: bool
+///
+fn has_turbo_flag(args: &[String], flag: &str) -> bool {
+    args.iter()
+        .take_while(|arg| *arg != "--")
+        .any(|arg| arg == flag || arg.starts_with(&format!("{}=", flag)))
+}
+
+/// Removes a flag that is only meant for the shim from the arguments for
+/// turbo, leaving anything after `--` alone.
+///
+/// # Arguments
+///
+/// * `args`: Arguments for turbo
+/// * `flag`: Flag to remove
+///
+/// returns: bool, whether the flag was passed
+///
+fn take_turbo_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    let before = args.len();
+    let mut index = 0;
+    args.retain(|arg| {
+        index += 1;
+        index > end || arg != flag
+    });
+
+    args.len() != before
+}
+
+/// Adds a flag for turbo, making sure it ends up before any `--` so that it
+/// isn't passed through to the task scripts.
+///
+/// # Arguments
+///
+/// * `args`: Arguments for turbo
+/// * `flag`: Flag to add
+///
+/// returns: ()
+///
+fn push_turbo_flag(args: &mut Vec<String>, flag: String) {
+    let position = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    args.insert(position, flag);
+}
+
+/// Adds the flags that a run needs given where in the repo it was invoked:
+/// `--single-package` for single package repos, `--cwd` pointing at the repo
+/// root when invoked from below it, and the inferred filter for the
+/// workspace it was invoked from.
+///
+/// Turbo treats `--cwd` as the repo root, and without it takes the directory
+/// it was run from to be the root, so a run from a nested workspace needs it
+/// whether or not a filter is inferred. Relative directory filters are still
+/// resolved from the directory turbo was run from.
+///
+/// # Arguments
+///
+/// * `args`: Arguments for turbo
+/// * `is_run`: Whether the arguments are for a run
+/// * `repo_state`: Repo that turbo was invoked in
+/// * `current_dir`: Directory turbo was invoked from
+///
+/// returns: Vec<String>
+///
+fn add_run_flags(
+    mut args: Vec<String>,
+    is_run: bool,
+    repo_state: &RepoState,
+    current_dir: &Path,
+) -> Vec<String> {
+    if !is_run {
+        return args;
+    }
+
+    if matches!(repo_state.mode, RepoMode::SinglePackage) {
+        push_turbo_flag(&mut args, "--single-package".to_string());
+    }
+
+    // Checked before adding `--cwd`, which would otherwise turn inferring off
+    let infer_filter = should_infer_filter(&args);
+
+    if !has_turbo_flag(&args, "--cwd") && repo_state.root != current_dir {
+        push_turbo_flag(&mut args, format!("--cwd={}", repo_state.root.display()));
+    }
+
+    // If we're inside a workspace, scope the run to that workspace and its
+    // dependencies.
+    if let Some(workspace) = &repo_state.workspace {
+        if infer_filter {
+            push_turbo_flag(&mut args, format!("--filter={}...", workspace));
+        }
+    }
+
+    args
+}
+
 /// Attempts to run correct turbo by finding nearest package.json,
 /// then finding local turbo installation. If the current binary is the local
 /// turbo installation, then we run current turbo. Otherwise we kick over to
@@ -221,10 +419,13 @@ fn main() -> Result<()> {
     let mut args: Vec<_> = env::args().skip(1).collect();
     let repo_state = RepoState::infer(&current_dir)?;
 
-    if matches!(repo_state.mode, RepoMode::SinglePackage) && is_run_command(&clap_args) {
-        args.push("--single-package".to_string());
+    if take_turbo_flag(&mut args, NO_INFER_FILTER_FLAG) {
+        // A local turbo that we hand the run over to must not infer a filter either
+        env::set_var(INFER_FILTER_ENV, "false");
     }
 
+    let args = add_run_flags(args, is_run_command(&clap_args), &repo_state, &current_dir);
+
     let exit_code = match run_correct_turbo(&repo_state.root, args) {
         Ok(exit_code) => exit_code,
         Err(e) => {
@@ -235,3 +436,149 @@ fn main() -> Result<()> {
 
     process::exit(exit_code)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_workspace() {
+        let repo_state = RepoState::infer(Path::new("../examples/basic/apps/web/pages")).unwrap();
+
+        assert_eq!(repo_state.root, PathBuf::from("../examples/basic"));
+        assert_eq!(repo_state.workspace, Some("web".to_string()));
+
+        let repo_state = RepoState::infer(Path::new("../examples/basic")).unwrap();
+        assert_eq!(repo_state.workspace, None);
+    }
+
+    #[test]
+    fn test_push_turbo_flag() {
+        let mut args = vec!["run".to_string(), "test".to_string()];
+        push_turbo_flag(&mut args, "--filter=web...".to_string());
+        assert_eq!(args, vec!["run", "test", "--filter=web..."]);
+
+        let mut args = vec![
+            "run".to_string(),
+            "test".to_string(),
+            "--".to_string(),
+            "--watch".to_string(),
+        ];
+        push_turbo_flag(&mut args, "--filter=web...".to_string());
+        assert_eq!(
+            args,
+            vec!["run", "test", "--filter=web...", "--", "--watch"]
+        );
+    }
+
+    #[test]
+    fn test_should_infer_filter() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+
+        assert!(should_infer_filter(&args(&["run", "build"])));
+        assert!(!should_infer_filter(&args(&[
+            "run",
+            "build",
+            "--filter=web"
+        ])));
+        assert!(!should_infer_filter(&args(&[
+            "run", "build", "--since", "main"
+        ])));
+        assert!(!should_infer_filter(&args(&[
+            "run",
+            "build",
+            "--cwd=../.."
+        ])));
+        assert!(!should_infer_filter(&args(&[
+            "run", "build", "--cwd", "../.."
+        ])));
+        // Arguments for the task scripts don't count
+        assert!(should_infer_filter(&args(&[
+            "run",
+            "build",
+            "--",
+            "--cwd=src"
+        ])));
+    }
+
+    #[test]
+    fn test_add_run_flags() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        let current_dir = Path::new("../examples/basic/apps/web");
+        let repo_state = RepoState::infer(current_dir).unwrap();
+
+        // An explicit relative filter from a nested workspace still runs
+        // against the whole repo, and is resolved from the workspace
+        assert_eq!(
+            add_run_flags(
+                args(&["run", "build", "--filter=../docs"]),
+                true,
+                &repo_state,
+                current_dir
+            ),
+            vec![
+                "run",
+                "build",
+                "--filter=../docs",
+                "--cwd=../examples/basic"
+            ]
+        );
+        assert_eq!(
+            add_run_flags(
+                args(&["run", "build", "--filter=./...", "--", "--watch"]),
+                true,
+                &repo_state,
+                current_dir
+            ),
+            vec![
+                "run",
+                "build",
+                "--filter=./...",
+                "--cwd=../examples/basic",
+                "--",
+                "--watch"
+            ]
+        );
+        // A root given with `--cwd` is left alone
+        assert_eq!(
+            add_run_flags(
+                args(&["run", "build", "--cwd=../.."]),
+                true,
+                &repo_state,
+                current_dir
+            ),
+            vec!["run", "build", "--cwd=../.."]
+        );
+        // Nothing is added from the repo root, or to other commands
+        let root = Path::new("../examples/basic");
+        let root_state = RepoState::infer(root).unwrap();
+        assert_eq!(
+            add_run_flags(
+                args(&["run", "build", "--filter=./apps/*"]),
+                true,
+                &root_state,
+                root
+            ),
+            vec!["run", "build", "--filter=./apps/*"]
+        );
+        assert_eq!(
+            add_run_flags(args(&["prune"]), false, &repo_state, current_dir),
+            vec!["prune"]
+        );
+    }
+
+    #[test]
+    fn test_take_turbo_flag() {
+        let mut args = vec![
+            "run".to_string(),
+            "build".to_string(),
+            "--no-infer-filter".to_string(),
+            "--".to_string(),
+            "--no-infer-filter".to_string(),
+        ];
+        assert!(take_turbo_flag(&mut args, NO_INFER_FILTER_FLAG));
+        assert_eq!(args, vec!["run", "build", "--", "--no-infer-filter"]);
+        assert!(!take_turbo_flag(&mut args, NO_INFER_FILTER_FLAG));
+        assert_eq!(args, vec!["run", "build", "--", "--no-infer-filter"]);
+    }
+}